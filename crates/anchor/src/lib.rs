@@ -0,0 +1,161 @@
+//! Anchor account codec backed by Jaguar.
+//!
+//! Anchor stores accounts as an 8-byte discriminator followed by the borsh
+//! encoded body. This crate keeps the discriminator and swaps the body for the
+//! Jaguar encoding, so a type can be used with `Account<'info, T>` like any
+//! other Anchor account.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use anchor_lang::prelude::*;
+//! use jaguar::{JaguarDeserialize, JaguarSerialize};
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, Clone)]
+//! pub struct Vault {
+//!     authority: [u8; 32],
+//!     amount: u64,
+//! }
+//!
+//! impl Discriminator for Vault {
+//!     const DISCRIMINATOR: &'static [u8] = &[211, 8, 232, 43, 2, 152, 117, 119];
+//! }
+//!
+//! jaguar_anchor::jaguar_account!(Vault);
+//! ```
+//!
+//! `Account<'info, T>` additionally requires an [`anchor_lang::Owner`]
+//! implementation pointing at your program id.
+
+use anchor_lang::{error::ErrorCode, Discriminator, Result};
+use jaguar::{JaguarDeserializeOwned, JaguarDeserializer, JaguarSerialize, JaguarSerializer};
+use std::io::Write;
+
+pub use anchor_lang;
+
+/// Writes `T::DISCRIMINATOR` followed by the Jaguar encoding of `value`.
+pub fn try_serialize<T, W>(value: &T, writer: &mut W) -> Result<()>
+where
+    T: Discriminator + JaguarSerialize,
+    W: Write,
+{
+    let mut ser = JaguarSerializer::new();
+    value.serialize(&mut ser).map_err(|_| ErrorCode::AccountDidNotSerialize)?;
+    writer
+        .write_all(T::DISCRIMINATOR)
+        .and_then(|_| writer.write_all(ser.data()))
+        .map_err(|_| ErrorCode::AccountDidNotSerialize)?;
+    Ok(())
+}
+
+/// Checks the discriminator and decodes the account body, advancing `buf`
+/// past the consumed bytes.
+pub fn try_deserialize<T>(buf: &mut &[u8]) -> Result<T>
+where
+    T: Discriminator + JaguarDeserializeOwned,
+{
+    let discriminator = T::DISCRIMINATOR;
+    if buf.len() < discriminator.len() {
+        return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+    }
+    if &buf[..discriminator.len()] != discriminator {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    try_deserialize_unchecked(buf)
+}
+
+/// Decodes the account body without checking the discriminator bytes.
+pub fn try_deserialize_unchecked<T>(buf: &mut &[u8]) -> Result<T>
+where
+    T: Discriminator + JaguarDeserializeOwned,
+{
+    let data = buf
+        .get(T::DISCRIMINATOR.len()..)
+        .ok_or(ErrorCode::AccountDiscriminatorNotFound)?;
+    let mut de = JaguarDeserializer::new(data);
+    let value = T::deserialize(&mut de).map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+    *buf = &data[de.position()..];
+    Ok(value)
+}
+
+/// Implements [`anchor_lang::AccountSerialize`] and
+/// [`anchor_lang::AccountDeserialize`] on top of Jaguar for each listed type.
+///
+/// Every type must implement [`anchor_lang::Discriminator`],
+/// `JaguarSerialize` and `JaguarDeserialize`.
+#[macro_export]
+macro_rules! jaguar_account {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl $crate::anchor_lang::AccountSerialize for $ty {
+                fn try_serialize<W: ::std::io::Write>(&self, writer: &mut W) -> $crate::anchor_lang::Result<()> {
+                    $crate::try_serialize(self, writer)
+                }
+            }
+
+            impl $crate::anchor_lang::AccountDeserialize for $ty {
+                fn try_deserialize(buf: &mut &[u8]) -> $crate::anchor_lang::Result<Self> {
+                    $crate::try_deserialize(buf)
+                }
+
+                fn try_deserialize_unchecked(buf: &mut &[u8]) -> $crate::anchor_lang::Result<Self> {
+                    $crate::try_deserialize_unchecked(buf)
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{error::Error, AccountDeserialize, AccountSerialize, Discriminator};
+    use jaguar::{JaguarDeserialize, JaguarSerialize};
+
+    #[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+    struct Vault {
+        authority: [u8; 32],
+        amount: u64,
+        bump: u8,
+    }
+
+    impl Discriminator for Vault {
+        const DISCRIMINATOR: &'static [u8] = &[1, 2, 3, 4, 5, 6, 7, 8];
+    }
+
+    crate::jaguar_account!(Vault);
+
+    #[test]
+    fn test_account_roundtrip() {
+        let vault = Vault {
+            authority: [4u8; 32],
+            amount: 1_000_000,
+            bump: 254,
+        };
+
+        let mut data = Vec::new();
+        vault.try_serialize(&mut data).unwrap();
+        assert_eq!(&data[..8], Vault::DISCRIMINATOR);
+
+        let mut buf = data.as_slice();
+        assert_eq!(Vault::try_deserialize(&mut buf).unwrap(), vault);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_discriminator_mismatch() {
+        let mut data = vec![0u8; 8];
+        data.extend_from_slice(&[4u8; 32]);
+        data.extend_from_slice(&[1, 0]);
+
+        let err = Vault::try_deserialize(&mut data.as_slice()).unwrap_err();
+        assert_eq!(err, Error::from(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch));
+
+        let err = Vault::try_deserialize(&mut &data[..4]).unwrap_err();
+        assert_eq!(err, Error::from(anchor_lang::error::ErrorCode::AccountDiscriminatorNotFound));
+
+        // zeroed account on init skips the discriminator check
+        assert!(Vault::try_deserialize_unchecked(&mut data.as_slice()).is_ok());
+    }
+}
@@ -0,0 +1,66 @@
+//! Off-chain encode and decode times for every shape in `jaguar_bench::shapes`.
+//!
+//! Run with `cargo bench -p jaguar-bench --bench codecs`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use jaguar_bench::shapes::{Account, Orders, Primitives};
+use jaguar_bench::{Codec, Sample};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Debug;
+
+const FORMATS: &[&str] = &["jaguar", "borsh", "bincode"];
+
+fn encode<T: Sample + Serialize>(value: &T, format: &str) -> Vec<u8> {
+    match format {
+        "jaguar" => jaguar_bench::encode(Codec::Jaguar, value),
+        "borsh" => jaguar_bench::encode(Codec::Borsh, value),
+        "bincode" => bincode::serialize(value).unwrap(),
+        _ => unreachable!(),
+    }
+}
+
+fn decode<T: Sample + DeserializeOwned>(data: &[u8], format: &str) -> T {
+    match format {
+        "jaguar" => jaguar_bench::decode(Codec::Jaguar, data).unwrap(),
+        "borsh" => jaguar_bench::decode(Codec::Borsh, data).unwrap(),
+        "bincode" => bincode::deserialize(data).unwrap(),
+        _ => unreachable!(),
+    }
+}
+
+fn bench_shape<T: Sample + Serialize + DeserializeOwned + PartialEq + Debug>(c: &mut Criterion, shape: &str) {
+    let value = T::sample();
+
+    let mut group = c.benchmark_group(format!("{}/serialize", shape));
+    for format in FORMATS {
+        group.bench_with_input(BenchmarkId::from_parameter(format), format, |b, format| {
+            b.iter(|| black_box(encode(black_box(&value), format)))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group(format!("{}/deserialize", shape));
+    for format in FORMATS {
+        let data = encode(&value, format);
+        assert_eq!(decode::<T>(&data, format), value, "{} round trip", format);
+        group.bench_with_input(BenchmarkId::from_parameter(format), format, |b, format| {
+            b.iter(|| black_box(decode::<T>(black_box(&data), format)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_account(c: &mut Criterion) {
+    bench_shape::<Account>(c, "account");
+}
+
+fn bench_primitives(c: &mut Criterion) {
+    bench_shape::<Primitives>(c, "primitives");
+}
+
+fn bench_orders(c: &mut Criterion) {
+    bench_shape::<Orders>(c, "orders");
+}
+
+criterion_group!(benches, bench_account, bench_primitives, bench_orders);
+criterion_main!(benches);
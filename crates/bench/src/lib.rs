@@ -0,0 +1,117 @@
+//! Encoding cost benchmarks for Jaguar against borsh and bincode.
+//!
+//! The same struct shapes are measured two ways:
+//!
+//! - off-chain, with the criterion benches in `benches/codecs.rs`
+//! - on-chain, with a program that decodes and re-encodes a [`Shape`] in the
+//!   chosen [`Codec`] and logs the compute units each step consumed, driven by
+//!   the program-test harness in `tests/cu.rs`
+//!
+//! `scripts/bench.sh` builds the program and runs both. The harness writes its
+//! results to `target/bench-cu.json` and fails when a Jaguar measurement grew
+//! since the previous run.
+//!
+//! Build with `no-entrypoint` to use [`instruction_data`] from another crate.
+
+#![allow(unexpected_cfgs)]
+
+pub mod processor;
+pub mod shapes;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use jaguar::{JaguarDeserializeOwned, JaguarSerialize};
+
+/// A format measured on-chain.
+///
+/// bincode is only compared off-chain, as programs rarely use it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Codec {
+    Jaguar,
+    Borsh,
+}
+
+impl Codec {
+    pub const ALL: [Codec; 2] = [Codec::Jaguar, Codec::Borsh];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Codec::Jaguar => "jaguar",
+            Codec::Borsh => "borsh",
+        }
+    }
+
+    pub fn from_u8(tag: u8) -> Option<Self> {
+        Self::ALL.get(tag as usize).copied()
+    }
+}
+
+/// A struct shape from [`shapes`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Shape {
+    Account,
+    Primitives,
+    Orders,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 3] = [Shape::Account, Shape::Primitives, Shape::Orders];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Shape::Account => "account",
+            Shape::Primitives => "primitives",
+            Shape::Orders => "orders",
+        }
+    }
+
+    pub fn from_u8(tag: u8) -> Option<Self> {
+        Self::ALL.get(tag as usize).copied()
+    }
+
+    /// The shape's sample value, encoded with `codec`.
+    pub fn encode_sample(self, codec: Codec) -> Vec<u8> {
+        match self {
+            Shape::Account => encode(codec, &shapes::Account::sample()),
+            Shape::Primitives => encode(codec, &shapes::Primitives::sample()),
+            Shape::Orders => encode(codec, &shapes::Orders::sample()),
+        }
+    }
+}
+
+/// A benchmarked value, encodable with every [`Codec`].
+pub trait Sample: JaguarSerialize + JaguarDeserializeOwned + BorshSerialize + BorshDeserialize {
+    fn sample() -> Self;
+}
+
+pub fn encode<T: Sample>(codec: Codec, value: &T) -> Vec<u8> {
+    match codec {
+        Codec::Jaguar => jaguar::serialize(value).unwrap(),
+        Codec::Borsh => borsh::to_vec(value).unwrap(),
+    }
+}
+
+pub fn decode<T: Sample>(codec: Codec, data: &[u8]) -> Option<T> {
+    match codec {
+        Codec::Jaguar => jaguar::deserialize_owned(data).ok(),
+        Codec::Borsh => borsh::from_slice(data).ok(),
+    }
+}
+
+/// Instruction data measuring `shape` in `codec`: `[codec][shape][payload]`.
+pub fn instruction_data(codec: Codec, shape: Shape) -> Vec<u8> {
+    let mut data = vec![codec as u8, shape as u8];
+    data.extend(shape.encode_sample(codec));
+    data
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+mod entrypoint {
+    use crate::processor::process_instruction;
+    use pinocchio::{default_allocator, default_panic_handler, program_entrypoint};
+
+    program_entrypoint!(process_instruction);
+    default_allocator!();
+    default_panic_handler!();
+}
@@ -0,0 +1,52 @@
+//! The on-chain half of the benches.
+
+use crate::{decode, encode, shapes, Codec, Sample, Shape};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult};
+use pinocchio_log::log;
+
+/// Decodes and re-encodes the payload of `[codec][shape][payload]`, logging
+/// the compute units of each step as `<codec> <shape> <step>: <cu>`.
+pub fn process_instruction(_program_id: &Pubkey, _accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [codec, shape, payload @ ..] = data else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let codec = Codec::from_u8(*codec).ok_or(ProgramError::InvalidInstructionData)?;
+    let shape = Shape::from_u8(*shape).ok_or(ProgramError::InvalidInstructionData)?;
+
+    match shape {
+        Shape::Account => run::<shapes::Account>(codec, shape, payload),
+        Shape::Primitives => run::<shapes::Primitives>(codec, shape, payload),
+        Shape::Orders => run::<shapes::Orders>(codec, shape, payload),
+    }
+}
+
+fn run<T: Sample>(codec: Codec, shape: Shape, payload: &[u8]) -> ProgramResult {
+    // The cost of reading the meter itself, subtracted from every step.
+    let (overhead, _) = measure(|| ());
+
+    let (cu, value) = measure(|| decode::<T>(codec, payload));
+    let value = value.ok_or(ProgramError::InvalidInstructionData)?;
+    log!("{} {} decode: {}", codec.name(), shape.name(), cu.saturating_sub(overhead));
+
+    let (cu, _) = measure(|| encode(codec, &value));
+    log!("{} {} encode: {}", codec.name(), shape.name(), cu.saturating_sub(overhead));
+    Ok(())
+}
+
+/// Runs `f`, returning the compute units it consumed.
+#[inline(always)]
+fn measure<R>(f: impl FnOnce() -> R) -> (u64, R) {
+    let before = remaining_compute_units();
+    let result = f();
+    (before - remaining_compute_units(), result)
+}
+
+#[inline(always)]
+fn remaining_compute_units() -> u64 {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        pinocchio::syscalls::sol_remaining_compute_units()
+    }
+    #[cfg(not(target_os = "solana"))]
+    0
+}
@@ -0,0 +1,130 @@
+//! Struct shapes shared by the on-chain and off-chain benches.
+
+use crate::Sample;
+use borsh::{BorshDeserialize, BorshSerialize};
+use jaguar::{JaguarDeserialize, JaguarSerialize};
+use serde::{Deserialize, Serialize};
+
+/// A typical program account: keys, a bump, a short memo and a few amounts.
+#[derive(
+    JaguarSerialize,
+    JaguarDeserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+pub struct Account {
+    pub authority: [u8; 32],
+    pub admin: [u8; 32],
+    pub bump: u8,
+    pub memo: String,
+    pub flags: (u16, u32),
+    pub offset: u64,
+    pub amount: u128,
+}
+
+impl Sample for Account {
+    fn sample() -> Self {
+        Account {
+            authority: [7; 32],
+            admin: [9; 32],
+            bump: 254,
+            memo: "this is a test".to_string(),
+            flags: (128, 25600),
+            offset: 392_000,
+            amount: 100_000_000_000_000,
+        }
+    }
+}
+
+/// One field of every fixed-width primitive.
+#[derive(
+    JaguarSerialize,
+    JaguarDeserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+pub struct Primitives {
+    pub a: u8,
+    pub b: u16,
+    pub c: u32,
+    pub d: u64,
+    pub e: i8,
+    pub f: i16,
+    pub g: i32,
+    pub h: i64,
+    pub k: bool,
+}
+
+impl Sample for Primitives {
+    fn sample() -> Self {
+        Primitives {
+            a: 42,
+            b: 12345,
+            c: 987654321,
+            d: 1234567890123456789,
+            e: -42,
+            f: -12345,
+            g: -987654321,
+            h: -1234567890123456789,
+            k: true,
+        }
+    }
+}
+
+#[derive(
+    JaguarSerialize,
+    JaguarDeserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+pub struct Order {
+    pub id: u64,
+    pub price: u64,
+    pub quantity: u32,
+    pub bid: bool,
+}
+
+/// A collection-heavy shape: an order book side.
+#[derive(
+    JaguarSerialize,
+    JaguarDeserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+pub struct Orders {
+    pub market: String,
+    pub orders: Vec<Order>,
+    pub slots: Vec<u64>,
+}
+
+impl Sample for Orders {
+    fn sample() -> Self {
+        Orders {
+            market: "SOL-PERP".to_string(),
+            orders: (0..16)
+                .map(|i| Order { id: 1_000 + i, price: 150_000 + i * 25, quantity: 10 * i as u32, bid: i % 2 == 0 })
+                .collect(),
+            slots: (0..16).map(|i| 310_000_000 + i * 3).collect(),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Compute unit usage of every codec on every shape, against the SBF build of
+//! the program.
+//!
+//! Run `scripts/bench.sh`, or build with
+//! `cargo build-sbf --manifest-path crates/bench/Cargo.toml` and then run
+//! `cargo test -p jaguar-bench --test cu -- --ignored --nocapture`.
+//!
+//! Results are written to `target/bench-cu.json`. When that file already
+//! exists, the run fails if a Jaguar step got more expensive and leaves the
+//! previous results in place; delete the file to accept the new numbers.
+
+use jaguar_bench::{instruction_data, Codec, Shape};
+use serde_json::{json, Map, Value};
+use solana_program_test::ProgramTest;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signer::Signer, transaction::Transaction};
+use std::{fs, path::PathBuf};
+
+const STEPS: [&str; 2] = ["decode", "encode"];
+
+/// The `<codec> <shape> <step>: <cu>` lines logged by the program.
+fn step_cu(logs: &[String], codec: Codec, shape: Shape, step: &str) -> u64 {
+    let prefix = format!("Program log: {} {} {}: ", codec.name(), shape.name(), step);
+    logs.iter()
+        .find_map(|line| line.strip_prefix(&prefix))
+        .unwrap_or_else(|| panic!("no `{}` line in {:?}", prefix, logs))
+        .parse()
+        .unwrap()
+}
+
+fn results_path() -> PathBuf {
+    // target/tmp
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).parent().unwrap().join("bench-cu.json")
+}
+
+#[tokio::test]
+#[ignore = "requires the program to be built with cargo build-sbf"]
+async fn report_cu() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("jaguar_bench", program_id, None);
+    let mut ctx = program_test.start_with_context().await;
+
+    let mut results = Map::new();
+    println!("{:<12}{:<8}{:>8}{:>10}{:>10}", "shape", "codec", "bytes", "decode", "encode");
+    for shape in Shape::ALL {
+        let mut codecs = Map::new();
+        for codec in Codec::ALL {
+            let data = instruction_data(codec, shape);
+            let size = data.len() - 2;
+            let instruction = Instruction { program_id, accounts: vec![], data };
+
+            let blockhash = ctx.get_new_latest_blockhash().await.unwrap();
+            let transaction =
+                Transaction::new_signed_with_payer(&[instruction], Some(&ctx.payer.pubkey()), &[&ctx.payer], blockhash);
+            let result = ctx
+                .banks_client
+                .process_transaction_with_metadata(transaction)
+                .await
+                .unwrap();
+            result.result.unwrap();
+            let logs = result.metadata.unwrap().log_messages;
+
+            let decode = step_cu(&logs, codec, shape, "decode");
+            let encode = step_cu(&logs, codec, shape, "encode");
+            println!("{:<12}{:<8}{:>8}{:>10}{:>10}", shape.name(), codec.name(), size, decode, encode);
+            codecs.insert(
+                codec.name().to_string(),
+                json!({ "size_bytes": size, "decode_cu": decode, "encode_cu": encode }),
+            );
+        }
+        results.insert(shape.name().to_string(), codecs.into());
+    }
+
+    let path = results_path();
+    if let Ok(previous) = fs::read(&path) {
+        let previous: Value = serde_json::from_slice(&previous).unwrap();
+        let mut regressions = Vec::new();
+        for shape in Shape::ALL {
+            for step in STEPS {
+                let key = format!("{}_cu", step);
+                let old = previous[shape.name()]["jaguar"][&key].as_u64();
+                let new = results[shape.name()]["jaguar"][&key].as_u64().unwrap();
+                if let Some(old) = old.filter(|&old| new > old) {
+                    regressions.push(format!("{} {}: {} -> {} CU", shape.name(), step, old, new));
+                }
+            }
+        }
+        assert!(
+            regressions.is_empty(),
+            "Jaguar got more expensive since the last run ({}):\n{}",
+            path.display(),
+            regressions.join("\n")
+        );
+    }
+
+    fs::write(&path, serde_json::to_string_pretty(&results).unwrap()).unwrap();
+    println!("wrote {}", path.display());
+}
@@ -0,0 +1,101 @@
+use jaguar_json::{decode, encode, format_data, parse_data, Encoding};
+use std::io::Read;
+use std::process::ExitCode;
+use std::{env, fs, io};
+
+const USAGE: &str = "\
+Decodes Jaguar-encoded data to JSON, or encodes JSON to Jaguar bytes.
+
+Usage:
+    jaguar decode --schema <file> [--base64] [<data>]
+    jaguar encode --schema <file> [--base64] [<json>]
+
+Options:
+    --schema <file>  JSON schema from `jaguar_json::schema::to_json`
+    --base64         Read (decode) or write (encode) base64 instead of hex
+
+<data> and <json> are read from stdin when omitted or `-`.";
+
+enum Command {
+    Decode,
+    Encode,
+}
+
+struct Args {
+    command: Command,
+    schema: String,
+    encoding: Encoding,
+    input: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let command = match args.next().as_deref() {
+        Some("decode") => Command::Decode,
+        Some("encode") => Command::Encode,
+        Some(other) => return Err(format!("unknown command `{}`", other)),
+        None => return Err("missing command".into()),
+    };
+    let mut schema = None;
+    let mut encoding = Encoding::Hex;
+    let mut input = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--schema" => schema = Some(args.next().ok_or("`--schema` needs a file")?),
+            "--base64" => encoding = Encoding::Base64,
+            "-" => {}
+            flag if flag.starts_with("--") => return Err(format!("unknown option `{}`", flag)),
+            _ if input.is_some() => return Err(format!("unexpected argument `{}`", arg)),
+            _ => input = Some(arg),
+        }
+    }
+    let schema = schema.ok_or("missing `--schema <file>`")?;
+    Ok(Args { command, schema, encoding, input })
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let schema = fs::read_to_string(&args.schema).map_err(|err| format!("{}: {}", args.schema, err))?;
+    let schema = jaguar_json::schema::from_json(&schema).map_err(|err| format!("{}: {}", args.schema, err))?;
+    let input = match args.input {
+        Some(input) => input,
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input).map_err(|err| err.to_string())?;
+            input
+        }
+    };
+
+    match args.command {
+        Command::Decode => {
+            let data = parse_data(&input, args.encoding)?;
+            let (value, len) = decode(&schema, &data)?;
+            println!("{:#}", value);
+            if len < data.len() {
+                eprintln!("warning: {} trailing bytes", data.len() - len);
+            }
+        }
+        Command::Encode => {
+            let value = serde_json::from_str(&input).map_err(|err| format!("invalid JSON: {}", err))?;
+            println!("{}", format_data(&encode(&schema, &value)?, args.encoding));
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    if args.is_empty() {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    }
+    match parse_args(args.into_iter()).and_then(run) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
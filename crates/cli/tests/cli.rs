@@ -0,0 +1,31 @@
+use jaguar::JaguarSchema;
+use std::process::Command;
+
+#[test]
+fn test_binary() {
+    let schema = std::env::temp_dir().join(format!("jaguar-cli-{}.json", std::process::id()));
+    std::fs::write(&schema, jaguar_json::schema::to_json(&<(u64, String)>::schema())).unwrap();
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_jaguar"))
+            .arg(args[0])
+            .arg("--schema")
+            .arg(&schema)
+            .args(&args[1..])
+            .output()
+            .unwrap();
+        let text = |bytes| String::from_utf8(bytes).unwrap();
+        (output.status.success(), text(output.stdout), text(output.stderr))
+    };
+
+    let (ok, stdout, _) = run(&["encode", r#"[300, "rent"]"#]);
+    assert!(ok);
+    assert_eq!(stdout, "ac020472656e74\n");
+    let (ok, stdout, stderr) = run(&["decode", "ac020472656e7400"]);
+    assert!(ok);
+    assert_eq!(stdout, "[\n  300,\n  \"rent\"\n]\n");
+    assert_eq!(stderr, "warning: 1 trailing bytes\n");
+    let (ok, _, stderr) = run(&["decode", "--base64", "rAI="]);
+    assert!(!ok);
+    assert_eq!(stderr, "error: buffer too small at byte 2\n");
+    std::fs::remove_file(&schema).unwrap();
+}
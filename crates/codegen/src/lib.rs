@@ -0,0 +1,60 @@
+//! Generates encoders and decoders for Jaguar types in other languages:
+//! TypeScript, JavaScript and Python.
+//!
+//! Types opt in with `#[derive(JaguarSchema)]`, and the generated code reads
+//! and writes exactly the bytes their `JaguarSerialize` and
+//! `JaguarDeserialize` implementations do. Generation usually runs from a
+//! build script, or from a small binary in the workspace defining the types.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```ignore
+//! use jaguar::JaguarSchema;
+//!
+//! let code = jaguar_codegen::typescript(&[Order::schema(), Fill::schema()]);
+//! std::fs::write("client/src/generated.ts", code)?;
+//! ```
+//!
+//! For every struct reachable from the given schemas, this emits an
+//! `interface`, `writeX`/`readX` functions working on a shared
+//! `JaguarWriter`/`JaguarReader`, and `encodeX`/`decodeX` convenience
+//! wrappers. Integers of up to 32 bits map to `number`, wider ones to `bigint`.
+//! [`python`] emits the equivalent dataclasses and `encode_x`/`decode_x`
+//! functions, e.g. for off-chain indexers.
+
+use jaguar::schema::{Schema, StructSchema};
+
+mod python;
+mod typescript;
+
+pub use python::python;
+pub use typescript::{javascript, typescript};
+
+/// Every struct reachable from `schemas`, each listed once.
+///
+/// # Panics
+///
+/// Panics if two different structs share a name.
+fn structs(schemas: &[Schema]) -> Vec<&StructSchema> {
+    fn visit<'s>(schema: &'s Schema, found: &mut Vec<&'s StructSchema>) {
+        match schema {
+            Schema::Array(_, inner) | Schema::Vec(inner) => visit(inner, found),
+            Schema::Tuple(items) => items.iter().for_each(|item| visit(item, found)),
+            Schema::Struct(def) => {
+                if let Some(existing) = found.iter().find(|existing| existing.name == def.name) {
+                    assert!(*existing == def, "two different structs are named `{}`", def.name);
+                    return;
+                }
+                found.push(def);
+                def.fields.iter().for_each(|field| visit(&field.schema, found));
+            }
+            _ => {}
+        }
+    }
+
+    let mut found = Vec::new();
+    schemas.iter().for_each(|schema| visit(schema, &mut found));
+    found
+}
@@ -0,0 +1,223 @@
+//! Python output, as dataclasses with module-level codec functions.
+
+use crate::structs;
+use jaguar::schema::{Schema, StructSchema};
+use std::fmt::Write;
+
+const RUNTIME: &str = include_str!("runtime.py");
+
+/// Python keywords that are valid Rust identifiers (or raw identifiers).
+const KEYWORDS: &[&str] = &[
+    "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else",
+    "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal", "not",
+    "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+/// Generates a Python module for every struct reachable from `schemas`.
+///
+/// Each struct becomes a `@dataclass` with `write_x`/`read_x` and
+/// `encode_x`/`decode_x` functions. Integers map to `int`, `[u8; N]` to
+/// `bytes`, and sequences to lists. Needs Python 3.7 or later.
+///
+/// # Panics
+///
+/// Panics if two different structs share a name.
+pub fn python(schemas: &[Schema]) -> String {
+    let mut out = String::from(RUNTIME);
+    for def in structs(schemas) {
+        out.push_str("\n\n");
+        emit_struct(&mut out, def);
+    }
+    out
+}
+
+/// A field name that is usable as a Python attribute.
+fn attr(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// `OrderBook` to `order_book`.
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if index > 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn emit_struct(out: &mut String, def: &StructSchema) {
+    let name = &def.name;
+    let snake = snake_case(name);
+
+    let _ = writeln!(out, "@dataclass\nclass {}:", name);
+    for field in &def.fields {
+        let _ = writeln!(out, "    {}: {}", attr(&field.name), py_type(&field.schema));
+    }
+    if def.fields.is_empty() {
+        out.push_str("    pass\n");
+    }
+
+    let _ = writeln!(out, "\n\ndef write_{}(w: JaguarWriter, v: {}) -> None:", snake, name);
+    let mut in_bits = false;
+    for field in &def.fields {
+        let value = format!("v.{}", attr(&field.name));
+        match field.bits {
+            Some(bits) => {
+                let _ = writeln!(out, "    w.bits({}, {})", value, bits);
+                in_bits = true;
+            }
+            None => {
+                if in_bits {
+                    out.push_str("    w.finish_bits()\n");
+                    in_bits = false;
+                }
+                let _ = writeln!(out, "    {}", write(&field.schema, &value, 0));
+            }
+        }
+    }
+    if in_bits {
+        out.push_str("    w.finish_bits()\n");
+    }
+    if def.reserve > 0 {
+        let _ = writeln!(out, "    w.padding({})", def.reserve);
+    }
+    if def.fields.is_empty() && def.reserve == 0 {
+        out.push_str("    pass\n");
+    }
+
+    let _ = writeln!(out, "\n\ndef read_{}(r: JaguarReader) -> {}:", snake, name);
+    let mut in_bits = false;
+    for (index, field) in def.fields.iter().enumerate() {
+        let value = match field.bits {
+            Some(bits) => {
+                in_bits = true;
+                read_bits(&field.schema, bits)
+            }
+            None => {
+                if in_bits {
+                    out.push_str("    r.finish_bits()\n");
+                    in_bits = false;
+                }
+                read(&field.schema)
+            }
+        };
+        let _ = writeln!(out, "    f{} = {}", index, value);
+    }
+    if in_bits {
+        out.push_str("    r.finish_bits()\n");
+    }
+    if def.reserve > 0 {
+        let _ = writeln!(out, "    r.skip({})", def.reserve);
+    }
+    let fields: Vec<String> = (0..def.fields.len()).map(|index| format!("f{}", index)).collect();
+    let _ = writeln!(out, "    return {}({})", name, fields.join(", "));
+
+    let _ = writeln!(out, "\n\ndef encode_{}(v: {}) -> bytes:", snake, name);
+    out.push_str("    w = JaguarWriter()\n");
+    let _ = writeln!(out, "    write_{}(w, v)", snake);
+    out.push_str("    return w.finish()\n");
+
+    let _ = writeln!(out, "\n\ndef decode_{}(data: bytes) -> {}:", snake, name);
+    let _ = writeln!(out, "    return read_{}(JaguarReader(data))", snake);
+}
+
+/// Expression writing `value` to `w`. `depth` keeps lambda parameters of
+/// nested sequences distinct.
+fn write(schema: &Schema, value: &str, depth: usize) -> String {
+    match schema {
+        Schema::U8 => format!("w.u8({})", value),
+        Schema::Bool => format!("w.boolean({})", value),
+        Schema::Varint { .. } => format!("w.varint({})", value),
+        Schema::SignedVarint { .. } => format!("w.signed_varint({})", value),
+        Schema::U128 => format!("w.u128({})", value),
+        Schema::I128 => format!("w.i128({})", value),
+        Schema::F32 => format!("w.f32({})", value),
+        Schema::F64 => format!("w.f64({})", value),
+        Schema::String => format!("w.string({})", value),
+        Schema::ByteArray(len) => format!("w.fixed_bytes({}, {})", value, len),
+        Schema::Array(len, inner) => {
+            let item = format!("x{}", depth);
+            format!("w.array({}, {}, lambda {}: {})", value, len, item, write(inner, &item, depth + 1))
+        }
+        Schema::Vec(inner) => {
+            let item = format!("x{}", depth);
+            format!("w.seq({}, lambda {}: {})", value, item, write(inner, &item, depth + 1))
+        }
+        Schema::DeltaVec => format!("w.delta({})", value),
+        Schema::NonZero(inner) => write(inner, value, depth),
+        Schema::Tuple(items) => {
+            let writes: Vec<String> = items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| write(item, &format!("{}[{}]", value, index), depth))
+                .collect();
+            format!("({},)", writes.join(", "))
+        }
+        Schema::Struct(def) => format!("write_{}(w, {})", snake_case(&def.name), value),
+    }
+}
+
+/// Expression reading a value from `r`.
+fn read(schema: &Schema) -> String {
+    match schema {
+        Schema::U8 => "r.u8()".into(),
+        Schema::Bool => "r.boolean()".into(),
+        Schema::Varint { bits } => format!("r.uint({})", bits),
+        Schema::SignedVarint { bits } => format!("r.sint({})", bits),
+        Schema::U128 => "r.u128()".into(),
+        Schema::I128 => "r.i128()".into(),
+        Schema::F32 => "r.f32()".into(),
+        Schema::F64 => "r.f64()".into(),
+        Schema::String => "r.string()".into(),
+        Schema::ByteArray(len) => format!("r.fixed_bytes({})", len),
+        Schema::Array(len, inner) => format!("r.array({}, lambda: {})", len, read(inner)),
+        Schema::Vec(inner) => format!("r.seq(lambda: {})", read(inner)),
+        Schema::DeltaVec => "r.delta()".into(),
+        Schema::NonZero(inner) => format!("r.non_zero({})", read(inner)),
+        Schema::Tuple(items) => {
+            let reads: Vec<String> = items.iter().map(read).collect();
+            format!("({},)", reads.join(", "))
+        }
+        Schema::Struct(def) => format!("read_{}(r)", snake_case(&def.name)),
+    }
+}
+
+/// Expression reading a `#[jaguar(bits = N)]` field.
+fn read_bits(schema: &Schema, bits: u32) -> String {
+    match schema {
+        Schema::Bool => format!("r.bits_bool({})", bits),
+        Schema::U8 => format!("r.bits_uint({}, 8)", bits),
+        Schema::Varint { bits: max } => format!("r.bits_uint({}, {})", bits, max),
+        other => panic!("{:?} cannot be bit-packed", other),
+    }
+}
+
+/// The type hint for a schema.
+fn py_type(schema: &Schema) -> String {
+    match schema {
+        Schema::U8 | Schema::Varint { .. } | Schema::SignedVarint { .. } | Schema::U128 | Schema::I128 => "int".into(),
+        Schema::F32 | Schema::F64 => "float".into(),
+        Schema::Bool => "bool".into(),
+        Schema::String => "str".into(),
+        Schema::ByteArray(_) => "bytes".into(),
+        Schema::Array(_, inner) | Schema::Vec(inner) => format!("List[{}]", py_type(inner)),
+        Schema::DeltaVec => "List[int]".into(),
+        Schema::NonZero(inner) => py_type(inner),
+        Schema::Tuple(items) => {
+            let items: Vec<String> = items.iter().map(py_type).collect();
+            format!("Tuple[{}]", items.join(", "))
+        }
+        Schema::Struct(def) => def.name.clone(),
+    }
+}
@@ -0,0 +1,246 @@
+# Generated by jaguar-codegen. Do not edit.
+
+from __future__ import annotations
+
+import struct
+from dataclasses import dataclass
+from typing import Callable, List, Optional, Tuple, TypeVar
+
+T = TypeVar("T")
+
+MASK_64 = (1 << 64) - 1
+MASK_128 = (1 << 128) - 1
+
+
+class JaguarError(ValueError):
+    pass
+
+
+class JaguarWriter:
+    def __init__(self) -> None:
+        self.buf = bytearray()
+        self.bit_acc = 0
+        self.bit_len = 0
+
+    def u8(self, v: int) -> None:
+        self.buf.append(v)
+
+    def boolean(self, v: bool) -> None:
+        self.buf.append(1 if v else 0)
+
+    def varint(self, v: int) -> None:
+        v &= MASK_64
+        while v >= 0x80:
+            self.buf.append((v & 0x7F) | 0x80)
+            v >>= 7
+        self.buf.append(v)
+
+    def signed_varint(self, v: int) -> None:
+        v = ((v + (1 << 63)) & MASK_64) - (1 << 63)
+        self.varint((v << 1) ^ (v >> 63))
+
+    def u128(self, v: int) -> None:
+        self.varint(v >> 64)
+        self.varint(v & MASK_64)
+
+    def i128(self, v: int) -> None:
+        v = ((v + (1 << 127)) & MASK_128) - (1 << 127)
+        self.u128(((v << 1) ^ (v >> 127)) & MASK_128)
+
+    def f32(self, v: float) -> None:
+        if not self._float_marker(v):
+            self.buf += struct.pack("<f", v)
+
+    def f64(self, v: float) -> None:
+        if not self._float_marker(v):
+            self.buf += struct.pack("<d", v)
+
+    def _float_marker(self, v: float) -> bool:
+        marker = 0 if v == 0 else 1 if v == 1 else 2 if v == -1 else 255
+        self.buf.append(marker)
+        return marker != 255
+
+    def string(self, v: str) -> None:
+        data = v.encode("utf-8")
+        self.varint(len(data))
+        self.buf += data
+
+    def fixed_bytes(self, v: bytes, length: int) -> None:
+        if len(v) != length:
+            raise JaguarError(f"expected {length} bytes, got {len(v)}")
+        self.buf += v
+
+    def padding(self, length: int) -> None:
+        self.buf += bytes(length)
+
+    def seq(self, items: List[T], write: Callable[[T], None]) -> None:
+        self.varint(len(items))
+        for item in items:
+            write(item)
+
+    def array(self, items: List[T], length: int, write: Callable[[T], None]) -> None:
+        if len(items) != length:
+            raise JaguarError(f"expected {length} elements, got {len(items)}")
+        self.seq(items, write)
+
+    def delta(self, items: List[int]) -> None:
+        self.varint(len(items))
+        prev = 0
+        for item in items:
+            self.varint(item - prev)
+            prev = item
+
+    def bits(self, v: int, width: int) -> None:
+        v = int(v)
+        if v < 0 or v >> width:
+            raise JaguarError(f"{v} does not fit in {width} bits")
+        self.bit_acc |= v << self.bit_len
+        self.bit_len += width
+        while self.bit_len >= 8:
+            self.buf.append(self.bit_acc & 0xFF)
+            self.bit_acc >>= 8
+            self.bit_len -= 8
+
+    def finish_bits(self) -> None:
+        if self.bit_len > 0:
+            self.buf.append(self.bit_acc)
+        self.bit_acc = 0
+        self.bit_len = 0
+
+    def finish(self) -> bytes:
+        return bytes(self.buf)
+
+
+class JaguarReader:
+    def __init__(self, data: bytes) -> None:
+        self.data = memoryview(data)
+        self.pos = 0
+        self.bit_acc = 0
+        self.bit_len = 0
+
+    def take(self, n: int) -> bytes:
+        if n > len(self.data) - self.pos:
+            raise JaguarError("unexpected end of input")
+        out = bytes(self.data[self.pos : self.pos + n])
+        self.pos += n
+        return out
+
+    def skip(self, n: int) -> None:
+        self.take(n)
+
+    def u8(self) -> int:
+        if self.pos >= len(self.data):
+            raise JaguarError("unexpected end of input")
+        self.pos += 1
+        return self.data[self.pos - 1]
+
+    def boolean(self) -> bool:
+        return self.u8() != 0
+
+    def varint(self) -> int:
+        result = 0
+        shift = 0
+        count = 0
+        while True:
+            byte = self.u8()
+            result |= (byte & 0x7F) << shift
+            if byte & 0x80 == 0:
+                return result & MASK_64
+            shift += 7
+            count += 1
+            if shift >= 64 or count > 9:
+                raise JaguarError("invalid varint")
+
+    def uint(self, bits: int) -> int:
+        return self.varint() & ((1 << bits) - 1)
+
+    def sint(self, bits: int) -> int:
+        x = self.varint()
+        v = (x >> 1) ^ -(x & 1)
+        return ((v + (1 << (bits - 1))) & ((1 << bits) - 1)) - (1 << (bits - 1))
+
+    def u128(self) -> int:
+        high = self.varint()
+        return (high << 64) | self.varint()
+
+    def i128(self) -> int:
+        x = self.u128()
+        return (x >> 1) ^ -(x & 1)
+
+    def f32(self) -> float:
+        marker = self._float_marker()
+        return marker if marker is not None else struct.unpack("<f", self.take(4))[0]
+
+    def f64(self) -> float:
+        marker = self._float_marker()
+        return marker if marker is not None else struct.unpack("<d", self.take(8))[0]
+
+    def _float_marker(self) -> Optional[float]:
+        marker = self.u8()
+        if marker == 255:
+            return None
+        if marker > 2:
+            raise JaguarError("invalid float marker")
+        return (0.0, 1.0, -1.0)[marker]
+
+    def string(self) -> str:
+        try:
+            return self.take(self.length()).decode("utf-8")
+        except UnicodeDecodeError as err:
+            raise JaguarError("invalid utf-8") from err
+
+    def fixed_bytes(self, length: int) -> bytes:
+        return self.take(length)
+
+    def length(self) -> int:
+        length = self.varint()
+        # every element takes at least one byte, unless it is an empty struct
+        if length > len(self.data) - self.pos:
+            raise JaguarError("length exceeds input")
+        return length
+
+    def seq(self, read: Callable[[], T]) -> List[T]:
+        return [read() for _ in range(self.length())]
+
+    def array(self, length: int, read: Callable[[], T]) -> List[T]:
+        items = self.seq(read)
+        if len(items) != length:
+            raise JaguarError(f"expected {length} elements, got {len(items)}")
+        return items
+
+    def delta(self) -> List[int]:
+        items = []
+        prev = 0
+        for _ in range(self.length()):
+            prev = (prev + self.varint()) & MASK_64
+            items.append(prev)
+        return items
+
+    def non_zero(self, value: int) -> int:
+        if value == 0:
+            raise JaguarError("zero in a non-zero integer")
+        return value
+
+    def bits(self, width: int) -> int:
+        while self.bit_len < width:
+            self.bit_acc |= self.u8() << self.bit_len
+            self.bit_len += 8
+        value = self.bit_acc & ((1 << width) - 1)
+        self.bit_acc >>= width
+        self.bit_len -= width
+        return value
+
+    def bits_uint(self, width: int, max_bits: int) -> int:
+        value = self.bits(width)
+        if value >> max_bits:
+            raise JaguarError(f"{value} does not fit in {max_bits} bits")
+        return value
+
+    def bits_bool(self, width: int) -> bool:
+        return self.bits_uint(width, 1) == 1
+
+    def finish_bits(self) -> None:
+        if self.bit_acc != 0:
+            raise JaguarError("non-zero padding bits")
+        self.bit_acc = 0
+        self.bit_len = 0
@@ -0,0 +1,299 @@
+// Generated by jaguar-codegen. Do not edit.
+
+const MAX_U64 = (1n << 64n) - 1n;
+
+export class JaguarError extends Error {}
+
+export class JaguarWriter {
+  buf«: Uint8Array» = new Uint8Array(64);
+  pos = 0;
+  bitAcc = 0n;
+  bitLen = 0n;
+
+  reserve(n«: number»)«: void» {
+    if (this.pos + n <= this.buf.length) return;
+    let len = this.buf.length * 2;
+    while (len < this.pos + n) len *= 2;
+    const buf = new Uint8Array(len);
+    buf.set(this.buf.subarray(0, this.pos));
+    this.buf = buf;
+  }
+
+  u8(v«: number»)«: void» {
+    this.reserve(1);
+    this.buf[this.pos++] = v;
+  }
+
+  bool(v«: boolean»)«: void» {
+    this.u8(v ? 1 : 0);
+  }
+
+  varint(v«: number | bigint»)«: void» {
+    let x = BigInt.asUintN(64, BigInt(v));
+    while (x >= 0x80n) {
+      this.u8(Number(x & 0x7fn) | 0x80);
+      x >>= 7n;
+    }
+    this.u8(Number(x));
+  }
+
+  signedVarint(v«: number | bigint»)«: void» {
+    const x = BigInt.asIntN(64, BigInt(v));
+    this.varint((x << 1n) ^ (x >> 63n));
+  }
+
+  u128(v«: bigint»)«: void» {
+    this.varint(v >> 64n);
+    this.varint(v & MAX_U64);
+  }
+
+  i128(v«: bigint»)«: void» {
+    const x = BigInt.asIntN(128, v);
+    this.u128(BigInt.asUintN(128, (x << 1n) ^ (x >> 127n)));
+  }
+
+  f32(v«: number»)«: void» {
+    if (this.floatMarker(v)) return;
+    this.reserve(4);
+    new DataView(this.buf.buffer).setFloat32(this.pos, v, true);
+    this.pos += 4;
+  }
+
+  f64(v«: number»)«: void» {
+    if (this.floatMarker(v)) return;
+    this.reserve(8);
+    new DataView(this.buf.buffer).setFloat64(this.pos, v, true);
+    this.pos += 8;
+  }
+
+  floatMarker(v«: number»)«: boolean» {
+    const marker = v === 0 ? 0 : v === 1 ? 1 : v === -1 ? 2 : 255;
+    this.u8(marker);
+    return marker !== 255;
+  }
+
+  string(v«: string»)«: void» {
+    const bytes = new TextEncoder().encode(v);
+    this.varint(bytes.length);
+    this.raw(bytes);
+  }
+
+  raw(v«: Uint8Array»)«: void» {
+    this.reserve(v.length);
+    this.buf.set(v, this.pos);
+    this.pos += v.length;
+  }
+
+  bytes(v«: Uint8Array», len«: number»)«: void» {
+    if (v.length !== len) throw new JaguarError(`expected ${len} bytes, got ${v.length}`);
+    this.raw(v);
+  }
+
+  padding(len«: number»)«: void» {
+    this.raw(new Uint8Array(len));
+  }
+
+  seq«<T>»(items«: T[]», write«: (item: T) => void»)«: void» {
+    this.varint(items.length);
+    for (const item of items) write(item);
+  }
+
+  array«<T>»(items«: T[]», len«: number», write«: (item: T) => void»)«: void» {
+    if (items.length !== len) throw new JaguarError(`expected ${len} elements, got ${items.length}`);
+    this.seq(items, write);
+  }
+
+  delta(items«: bigint[]»)«: void» {
+    let prev = 0n;
+    this.seq(items, (item) => {
+      this.varint(item - prev);
+      prev = item;
+    });
+  }
+
+  bits(v«: number | bigint | boolean», width«: number»)«: void» {
+    const x = BigInt(v);
+    if (x < 0n || x >> BigInt(width) !== 0n) throw new JaguarError(`${v} does not fit in ${width} bits`);
+    this.bitAcc |= x << this.bitLen;
+    this.bitLen += BigInt(width);
+    while (this.bitLen >= 8n) {
+      this.u8(Number(this.bitAcc & 0xffn));
+      this.bitAcc >>= 8n;
+      this.bitLen -= 8n;
+    }
+  }
+
+  finishBits()«: void» {
+    if (this.bitLen > 0n) this.u8(Number(this.bitAcc));
+    this.bitAcc = 0n;
+    this.bitLen = 0n;
+  }
+
+  finish()«: Uint8Array» {
+    return this.buf.slice(0, this.pos);
+  }
+}
+
+export class JaguarReader {
+  data«: Uint8Array»;
+  pos = 0;
+  bitAcc = 0n;
+  bitLen = 0n;
+
+  constructor(data«: Uint8Array») {
+    this.data = data;
+  }
+
+  take(n«: number»)«: Uint8Array» {
+    if (n > this.data.length - this.pos) throw new JaguarError("unexpected end of input");
+    const bytes = this.data.subarray(this.pos, this.pos + n);
+    this.pos += n;
+    return bytes;
+  }
+
+  skip(n«: number»)«: void» {
+    this.take(n);
+  }
+
+  u8()«: number» {
+    return this.take(1)[0];
+  }
+
+  bool()«: boolean» {
+    return this.u8() !== 0;
+  }
+
+  varint()«: bigint» {
+    let result = 0n;
+    let shift = 0n;
+    for (let count = 0; ; count++) {
+      const byte = this.u8();
+      result |= BigInt(byte & 0x7f) << shift;
+      if ((byte & 0x80) === 0) break;
+      shift += 7n;
+      if (shift >= 64n || count >= 9) throw new JaguarError("invalid varint");
+    }
+    return BigInt.asUintN(64, result);
+  }
+
+  signedVarint()«: bigint» {
+    const x = this.varint();
+    return BigInt.asIntN(64, (x >> 1n) ^ -(x & 1n));
+  }
+
+  uint(bits«: number»)«: number» {
+    return Number(BigInt.asUintN(bits, this.varint()));
+  }
+
+  int(bits«: number»)«: number» {
+    return Number(BigInt.asIntN(bits, this.signedVarint()));
+  }
+
+  u128()«: bigint» {
+    const high = this.varint();
+    return (high << 64n) | this.varint();
+  }
+
+  i128()«: bigint» {
+    const x = this.u128();
+    return BigInt.asIntN(128, (x >> 1n) ^ -(x & 1n));
+  }
+
+  f32()«: number» {
+    const marker = this.floatMarker();
+    if (marker !== undefined) return marker;
+    const bytes = this.take(4);
+    return new DataView(bytes.buffer, bytes.byteOffset, 4).getFloat32(0, true);
+  }
+
+  f64()«: number» {
+    const marker = this.floatMarker();
+    if (marker !== undefined) return marker;
+    const bytes = this.take(8);
+    return new DataView(bytes.buffer, bytes.byteOffset, 8).getFloat64(0, true);
+  }
+
+  floatMarker()«: number | undefined» {
+    switch (this.u8()) {
+      case 0:
+        return 0;
+      case 1:
+        return 1;
+      case 2:
+        return -1;
+      case 255:
+        return undefined;
+      default:
+        throw new JaguarError("invalid float marker");
+    }
+  }
+
+  string()«: string» {
+    const len = this.length();
+    return new TextDecoder("utf-8", { fatal: true }).decode(this.take(len));
+  }
+
+  bytes(len«: number»)«: Uint8Array» {
+    return this.take(len).slice();
+  }
+
+  length()«: number» {
+    const len = this.varint();
+    if (len > BigInt(this.data.length - this.pos)) {
+      // every element takes at least one byte, unless it is an empty struct
+      throw new JaguarError("length exceeds input");
+    }
+    return Number(len);
+  }
+
+  seq«<T>»(read«: () => T»)«: T[]» {
+    const len = this.length();
+    const items«: T[]» = [];
+    for (let i = 0; i < len; i++) items.push(read());
+    return items;
+  }
+
+  array«<T>»(len«: number», read«: () => T»)«: T[]» {
+    const items = this.seq(read);
+    if (items.length !== len) throw new JaguarError(`expected ${len} elements, got ${items.length}`);
+    return items;
+  }
+
+  delta()«: bigint[]» {
+    let prev = 0n;
+    return this.seq(() => (prev = BigInt.asUintN(64, prev + this.varint())));
+  }
+
+  nonZero«<T extends number | bigint>»(value«: T»)«: T» {
+    if (value == 0) throw new JaguarError("zero in a non-zero integer");
+    return value;
+  }
+
+  bits(width«: number»)«: bigint» {
+    const n = BigInt(width);
+    while (this.bitLen < n) {
+      this.bitAcc |= BigInt(this.u8()) << this.bitLen;
+      this.bitLen += 8n;
+    }
+    const value = this.bitAcc & ((1n << n) - 1n);
+    this.bitAcc >>= n;
+    this.bitLen -= n;
+    return value;
+  }
+
+  bitsUint(width«: number», max«: number»)«: number» {
+    const value = this.bits(width);
+    if (value >> BigInt(max) !== 0n) throw new JaguarError(`${value} does not fit in ${max} bits`);
+    return Number(value);
+  }
+
+  bitsBool(width«: number»)«: boolean» {
+    return this.bitsUint(width, 1) === 1;
+  }
+
+  finishBits()«: void» {
+    if (this.bitAcc !== 0n) throw new JaguarError("non-zero padding bits");
+    this.bitAcc = 0n;
+    this.bitLen = 0n;
+  }
+}
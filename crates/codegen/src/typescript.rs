@@ -0,0 +1,267 @@
+//! TypeScript and JavaScript output.
+//!
+//! Both share `runtime.ts`, in which type annotations are wrapped in `«»`:
+//! the TypeScript output keeps their contents, the JavaScript output drops
+//! them entirely.
+
+use crate::structs;
+use jaguar::schema::{Schema, StructSchema};
+use std::fmt::Write;
+
+const RUNTIME: &str = include_str!("runtime.ts");
+
+/// Generates a TypeScript module for every struct reachable from `schemas`.
+///
+/// The output uses `bigint` literals and so needs an ES2020 target.
+///
+/// # Panics
+///
+/// Panics if two different structs share a name.
+pub fn typescript(schemas: &[Schema]) -> String {
+    Emitter { types: true }.module(schemas)
+}
+
+/// Generates the same module as [`typescript`], as plain JavaScript.
+///
+/// # Panics
+///
+/// Panics if two different structs share a name.
+pub fn javascript(schemas: &[Schema]) -> String {
+    Emitter { types: false }.module(schemas)
+}
+
+struct Emitter {
+    types: bool,
+}
+
+impl Emitter {
+    fn module(&self, schemas: &[Schema]) -> String {
+        let mut out = self.runtime();
+        for def in structs(schemas) {
+            out.push('\n');
+            self.emit_struct(&mut out, def);
+        }
+        out
+    }
+
+    /// The runtime, with or without its annotations.
+    fn runtime(&self) -> String {
+        let mut out = String::with_capacity(RUNTIME.len());
+        let mut annotation = false;
+        for c in RUNTIME.chars() {
+            match c {
+                '«' => annotation = true,
+                '»' => annotation = false,
+                c if self.types || !annotation => out.push(c),
+                _ => {}
+            }
+        }
+        out
+    }
+
+    /// `text` in TypeScript output only.
+    fn ann<'t>(&self, text: &'t str) -> &'t str {
+        if self.types {
+            text
+        } else {
+            ""
+        }
+    }
+
+    fn emit_struct(&self, out: &mut String, def: &StructSchema) {
+        let name = &def.name;
+        if self.types {
+            let _ = writeln!(out, "export interface {} {{", name);
+            for field in &def.fields {
+                let _ = writeln!(out, "  {}: {};", field.name, ts_type(&field.schema));
+            }
+            let _ = writeln!(out, "}}\n");
+        }
+
+        let _ = writeln!(
+            out,
+            "export function write{name}(w{}, v{}){} {{",
+            self.ann(": JaguarWriter"),
+            self.ann(&format!(": {}", name)),
+            self.ann(": void"),
+        );
+        let mut in_bits = false;
+        for field in &def.fields {
+            let value = format!("v.{}", field.name);
+            match field.bits {
+                Some(bits) => {
+                    let _ = writeln!(out, "  w.bits({}, {});", value, bits);
+                    in_bits = true;
+                }
+                None => {
+                    if in_bits {
+                        out.push_str("  w.finishBits();\n");
+                        in_bits = false;
+                    }
+                    let _ = writeln!(out, "  {};", self.write(&field.schema, &value, 0));
+                }
+            }
+        }
+        if in_bits {
+            out.push_str("  w.finishBits();\n");
+        }
+        if def.reserve > 0 {
+            let _ = writeln!(out, "  w.padding({});", def.reserve);
+        }
+        out.push_str("}\n\n");
+
+        let _ = writeln!(
+            out,
+            "export function read{name}(r{}){} {{",
+            self.ann(": JaguarReader"),
+            self.ann(&format!(": {}", name)),
+        );
+        let mut in_bits = false;
+        for (index, field) in def.fields.iter().enumerate() {
+            let value = match field.bits {
+                Some(bits) => {
+                    in_bits = true;
+                    read_bits(&field.schema, bits)
+                }
+                None => {
+                    if in_bits {
+                        out.push_str("  r.finishBits();\n");
+                        in_bits = false;
+                    }
+                    self.read(&field.schema)
+                }
+            };
+            let _ = writeln!(out, "  const f{} = {};", index, value);
+        }
+        if in_bits {
+            out.push_str("  r.finishBits();\n");
+        }
+        if def.reserve > 0 {
+            let _ = writeln!(out, "  r.skip({});", def.reserve);
+        }
+        let fields: Vec<String> = def
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| format!("{}: f{}", field.name, index))
+            .collect();
+        let _ = writeln!(out, "  return {{ {} }};", fields.join(", "));
+        out.push_str("}\n\n");
+
+        let _ = writeln!(
+            out,
+            "export function encode{name}(v{}){} {{",
+            self.ann(&format!(": {}", name)),
+            self.ann(": Uint8Array"),
+        );
+        out.push_str("  const w = new JaguarWriter();\n");
+        let _ = writeln!(out, "  write{}(w, v);", name);
+        out.push_str("  return w.finish();\n}\n\n");
+
+        let _ = writeln!(
+            out,
+            "export function decode{name}(data{}){} {{",
+            self.ann(": Uint8Array"),
+            self.ann(&format!(": {}", name)),
+        );
+        let _ = writeln!(out, "  return read{}(new JaguarReader(data));", name);
+        out.push_str("}\n");
+    }
+
+    /// Expression writing `value` to `w`. `depth` keeps closure parameters
+    /// of nested sequences distinct.
+    fn write(&self, schema: &Schema, value: &str, depth: usize) -> String {
+        match schema {
+            Schema::U8 => format!("w.u8({})", value),
+            Schema::Bool => format!("w.bool({})", value),
+            Schema::Varint { .. } => format!("w.varint({})", value),
+            Schema::SignedVarint { .. } => format!("w.signedVarint({})", value),
+            Schema::U128 => format!("w.u128({})", value),
+            Schema::I128 => format!("w.i128({})", value),
+            Schema::F32 => format!("w.f32({})", value),
+            Schema::F64 => format!("w.f64({})", value),
+            Schema::String => format!("w.string({})", value),
+            Schema::ByteArray(len) => format!("w.bytes({}, {})", value, len),
+            Schema::Array(len, inner) => {
+                let item = format!("x{}", depth);
+                let write = self.write(inner, &item, depth + 1);
+                format!("w.array({}, {}, ({}) => {})", value, len, item, write)
+            }
+            Schema::Vec(inner) => {
+                let item = format!("x{}", depth);
+                let write = self.write(inner, &item, depth + 1);
+                format!("w.seq({}, ({}) => {})", value, item, write)
+            }
+            Schema::DeltaVec => format!("w.delta({})", value),
+            Schema::NonZero(inner) => self.write(inner, value, depth),
+            Schema::Tuple(items) => {
+                let writes: Vec<String> = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| self.write(item, &format!("{}[{}]", value, index), depth))
+                    .collect();
+                format!("({})", writes.join(", "))
+            }
+            Schema::Struct(def) => format!("write{}(w, {})", def.name, value),
+        }
+    }
+
+    /// Expression reading a value from `r`.
+    fn read(&self, schema: &Schema) -> String {
+        match schema {
+            Schema::U8 => "r.u8()".into(),
+            Schema::Bool => "r.bool()".into(),
+            Schema::Varint { bits } if *bits <= 32 => format!("r.uint({})", bits),
+            Schema::Varint { .. } => "r.varint()".into(),
+            Schema::SignedVarint { bits } if *bits <= 32 => format!("r.int({})", bits),
+            Schema::SignedVarint { .. } => "r.signedVarint()".into(),
+            Schema::U128 => "r.u128()".into(),
+            Schema::I128 => "r.i128()".into(),
+            Schema::F32 => "r.f32()".into(),
+            Schema::F64 => "r.f64()".into(),
+            Schema::String => "r.string()".into(),
+            Schema::ByteArray(len) => format!("r.bytes({})", len),
+            Schema::Array(len, inner) => format!("r.array({}, () => {})", len, self.read(inner)),
+            Schema::Vec(inner) => format!("r.seq(() => {})", self.read(inner)),
+            Schema::DeltaVec => "r.delta()".into(),
+            Schema::NonZero(inner) => format!("r.nonZero({})", self.read(inner)),
+            Schema::Tuple(items) => {
+                let reads: Vec<String> = items.iter().map(|item| self.read(item)).collect();
+                // without the assertion TypeScript infers an array of a union
+                format!("[{}]{}", reads.join(", "), self.ann(&format!(" as {}", ts_type(schema))))
+            }
+            Schema::Struct(def) => format!("read{}(r)", def.name),
+        }
+    }
+}
+
+/// Expression reading a `#[jaguar(bits = N)]` field.
+fn read_bits(schema: &Schema, bits: u32) -> String {
+    match schema {
+        Schema::Bool => format!("r.bitsBool({})", bits),
+        Schema::U8 => format!("r.bitsUint({}, 8)", bits),
+        Schema::Varint { bits: max } if *max <= 32 => format!("r.bitsUint({}, {})", bits, max),
+        Schema::Varint { .. } => format!("r.bits({})", bits),
+        other => panic!("{:?} cannot be bit-packed", other),
+    }
+}
+
+/// The TypeScript type a schema decodes to.
+fn ts_type(schema: &Schema) -> String {
+    match schema {
+        Schema::U8 | Schema::F32 | Schema::F64 => "number".into(),
+        Schema::Varint { bits } | Schema::SignedVarint { bits } if *bits <= 32 => "number".into(),
+        Schema::Varint { .. } | Schema::SignedVarint { .. } | Schema::U128 | Schema::I128 => "bigint".into(),
+        Schema::Bool => "boolean".into(),
+        Schema::String => "string".into(),
+        Schema::ByteArray(_) => "Uint8Array".into(),
+        Schema::Array(_, inner) | Schema::Vec(inner) => format!("{}[]", ts_type(inner)),
+        Schema::DeltaVec => "bigint[]".into(),
+        Schema::NonZero(inner) => ts_type(inner),
+        Schema::Tuple(items) => {
+            let items: Vec<String> = items.iter().map(ts_type).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Schema::Struct(def) => def.name.clone(),
+    }
+}
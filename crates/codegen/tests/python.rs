@@ -0,0 +1,102 @@
+use jaguar::{JaguarSchema, JaguarSerialize, JaguarSerializer};
+use std::process::Command;
+
+#[derive(JaguarSerialize, JaguarSchema)]
+struct TokenBalance {
+    mint: [u8; 4],
+    amount: u64,
+    decimals: u8,
+}
+
+#[derive(JaguarSerialize, JaguarSchema)]
+#[jaguar(reserve = 2)]
+struct IndexedAccount {
+    #[jaguar(bits = 1)]
+    r#in: bool,
+    #[jaguar(bits = 7)]
+    version: u8,
+    lamports: u64,
+    delta: i32,
+    rent: i64,
+    supply: u128,
+    debt: i128,
+    apy: f32,
+    price: f64,
+    owner: String,
+    balances: Vec<TokenBalance>,
+    weights: [u16; 2],
+    tags: Vec<(i8, String)>,
+    #[jaguar(delta)]
+    slots: Vec<u64>,
+    closed: bool,
+}
+
+#[test]
+fn test_python_output() {
+    let code = jaguar_codegen::python(&[IndexedAccount::schema()]);
+
+    assert!(code.contains("@dataclass\nclass TokenBalance:\n    mint: bytes\n    amount: int\n    decimals: int\n"));
+    assert!(code.contains("    in_: bool\n"));
+    assert!(code.contains("    tags: List[Tuple[int, str]]\n"));
+    assert!(code.contains("def write_indexed_account(w: JaguarWriter, v: IndexedAccount) -> None:"));
+    assert!(code.contains("    w.bits(v.in_, 1)\n    w.bits(v.version, 7)\n    w.finish_bits()\n"));
+    assert!(code.contains("def decode_token_balance(data: bytes) -> TokenBalance:"));
+    assert!(code.contains("    r.skip(2)\n"));
+}
+
+#[test]
+fn test_python_matches_rust() {
+    if Command::new("python3").arg("--version").output().is_err() {
+        eprintln!("python3 not found, skipping");
+        return;
+    }
+
+    let account = IndexedAccount {
+        r#in: true,
+        version: 100,
+        lamports: u64::MAX,
+        delta: i32::MIN,
+        rent: -5,
+        supply: 1 << 100,
+        debt: i128::MIN,
+        apy: -1.0,
+        price: 0.1,
+        owner: "Ωwner".into(),
+        balances: vec![TokenBalance { mint: [1, 2, 3, 4], amount: 300, decimals: 9 }],
+        weights: [0, u16::MAX],
+        tags: vec![(-128, "low".into())],
+        slots: vec![10, 5, 1 << 40],
+        closed: true,
+    };
+    let mut ser = JaguarSerializer::new();
+    account.serialize(&mut ser).unwrap();
+    let hex: String = ser.finish().iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    let dir = std::env::temp_dir().join(format!("jaguar-codegen-py-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("accounts.py"), jaguar_codegen::python(&[IndexedAccount::schema()])).unwrap();
+    std::fs::write(
+        dir.join("check.py"),
+        r#"
+import sys
+from accounts import decode_indexed_account, encode_indexed_account
+account = decode_indexed_account(bytes.fromhex(sys.argv[1]))
+print(encode_indexed_account(account).hex())
+print(account)
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new("python3").arg(dir.join("check.py")).arg(&hex).output().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    assert_eq!(lines.next().unwrap(), hex);
+    let repr = lines.next().unwrap();
+    assert!(repr.contains("in_=True, version=100, lamports=18446744073709551615, delta=-2147483648, rent=-5"));
+    assert!(repr.contains("supply=1267650600228229401496703205376, debt=-170141183460469231731687303715884105728, apy=-1.0, price=0.1, owner='Ωwner'"));
+    assert!(repr.contains("balances=[TokenBalance(mint=b'\\x01\\x02\\x03\\x04', amount=300, decimals=9)]"));
+    assert!(repr.contains("weights=[0, 65535], tags=[(-128, 'low')], slots=[10, 5, 1099511627776], closed=True"));
+}
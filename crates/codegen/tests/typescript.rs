@@ -0,0 +1,138 @@
+use jaguar::schema::{FieldSchema, Schema, StructSchema};
+use jaguar::{JaguarSchema, JaguarSerialize, JaguarSerializer};
+use std::process::Command;
+
+#[derive(JaguarSerialize, JaguarSchema)]
+struct Fill {
+    price: f64,
+    size: u64,
+}
+
+#[derive(JaguarSerialize, JaguarSchema)]
+#[jaguar(reserve = 4)]
+struct Order {
+    owner: [u8; 32],
+    side: u8,
+    #[jaguar(bits = 1)]
+    post_only: bool,
+    #[jaguar(bits = 3)]
+    tier: u8,
+    quantity: u16,
+    id: u32,
+    offset: i32,
+    pnl: i64,
+    notional: u128,
+    debt: i128,
+    leverage: f32,
+    label: String,
+    levels: [i16; 3],
+    fills: Vec<Fill>,
+    notes: Vec<(u8, String)>,
+    #[jaguar(delta)]
+    slots: Vec<u64>,
+    active: bool,
+}
+
+fn order() -> Order {
+    Order {
+        owner: [9; 32],
+        side: 1,
+        post_only: true,
+        tier: 5,
+        quantity: 40_000,
+        id: u32::MAX,
+        offset: -123_456,
+        pnl: i64::MIN,
+        notional: u128::MAX - 7,
+        debt: -(1 << 70),
+        leverage: 2.5,
+        label: "bid ü".into(),
+        levels: [-1, 0, 300],
+        fills: vec![Fill { price: 1.0, size: u64::MAX }, Fill { price: 101.25, size: 3 }],
+        notes: vec![(7, "first".into()), (0, String::new())],
+        slots: vec![300_000_000, 300_000_001, 299_999_999],
+        active: false,
+    }
+}
+
+#[test]
+fn test_schema_derive() {
+    assert_eq!(
+        Fill::schema(),
+        Schema::Struct(StructSchema {
+            name: "Fill".into(),
+            fields: vec![
+                FieldSchema { name: "price".into(), schema: Schema::F64, bits: None },
+                FieldSchema { name: "size".into(), schema: Schema::Varint { bits: 64 }, bits: None },
+            ],
+            reserve: 0,
+        })
+    );
+}
+
+#[test]
+fn test_typescript_output() {
+    let code = jaguar_codegen::typescript(&[Order::schema()]);
+
+    assert!(code.contains("export class JaguarWriter {"));
+    assert!(code.contains("  varint(v: number | bigint): void {"));
+    assert!(code.contains("export interface Fill {\n  price: number;\n  size: bigint;\n}"));
+    assert!(code.contains("  notional: bigint;\n"));
+    assert!(code.contains("  notes: [number, string][];\n"));
+    assert!(code.contains("export function encodeOrder(v: Order): Uint8Array {"));
+    assert!(code.contains("export function decodeOrder(data: Uint8Array): Order {"));
+    assert!(code.contains("  w.bits(v.post_only, 1);\n  w.bits(v.tier, 3);\n  w.finishBits();\n"));
+    assert!(code.contains("  w.padding(4);\n"));
+    // `Fill` is emitted once, even though it is reachable twice
+    let code = jaguar_codegen::typescript(&[Order::schema(), Fill::schema()]);
+    assert_eq!(code.matches("export function writeFill(").count(), 1);
+    assert!(!code.contains('«') && !code.contains('»'));
+}
+
+#[test]
+fn test_javascript_matches_rust() {
+    if Command::new("node").arg("--version").output().is_err() {
+        eprintln!("node not found, skipping");
+        return;
+    }
+
+    let order = order();
+    let mut ser = JaguarSerializer::new();
+    order.serialize(&mut ser).unwrap();
+    let data = ser.finish();
+
+    let dir = std::env::temp_dir().join(format!("jaguar-codegen-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let code = jaguar_codegen::javascript(&[Order::schema()]);
+    assert!(!code.contains(": number"));
+    std::fs::write(dir.join("order.mjs"), code).unwrap();
+    std::fs::write(
+        dir.join("check.mjs"),
+        r#"
+import { decodeOrder, encodeOrder } from "./order.mjs";
+const data = Uint8Array.from(Buffer.from(process.argv[2], "hex"));
+const order = decodeOrder(data);
+console.log(Buffer.from(encodeOrder(order)).toString("hex"));
+console.log(JSON.stringify(order, (_, v) => (typeof v === "bigint" ? v.toString() : v)));
+"#,
+    )
+    .unwrap();
+
+    let hex: String = data.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let output = Command::new("node").arg(dir.join("check.mjs")).arg(&hex).output().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    assert_eq!(lines.next().unwrap(), hex);
+    let json = lines.next().unwrap();
+    assert!(json.contains(r#""tier":5"#));
+    assert!(json.contains(r#""id":4294967295"#));
+    assert!(json.contains(r#""pnl":"-9223372036854775808""#));
+    assert!(json.contains(r#""notional":"340282366920938463463374607431768211448""#));
+    assert!(json.contains(r#""debt":"-1180591620717411303424""#));
+    assert!(json.contains(r#""label":"bid ü""#));
+    assert!(json.contains(r#""levels":[-1,0,300]"#));
+    assert!(json.contains(r#""slots":["300000000","300000001","299999999"]"#));
+}
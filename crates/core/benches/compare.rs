@@ -0,0 +1,214 @@
+//! Compares Jaguar against other binary formats on identical struct shapes.
+//!
+//! Run with `cargo bench -p jaguar --features bench-compare --bench compare`.
+//! Besides the usual criterion output, encoded sizes and mean timings for
+//! every format are written to `target/bench-compare.json`.
+
+use criterion::{black_box, criterion_group, BenchmarkId, Criterion};
+use jaguar::{JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer};
+use std::{fs, path::PathBuf};
+
+const FORMATS: &[&str] = &["jaguar", "borsh", "bincode", "postcard", "rkyv"];
+
+#[derive(
+    JaguarSerialize,
+    JaguarDeserialize,
+    borsh::BorshSerialize,
+    borsh::BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+#[archive(check_bytes)]
+struct SimpleStruct {
+    a: u8,
+    b: u16,
+    c: u32,
+    d: u64,
+    e: i8,
+    f: i16,
+    g: i32,
+    h: i64,
+    i: f32,
+    j: f64,
+    k: bool,
+}
+
+#[derive(
+    JaguarSerialize,
+    JaguarDeserialize,
+    borsh::BorshSerialize,
+    borsh::BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+#[archive(check_bytes)]
+struct ComplexStruct {
+    id: u64,
+    name: String,
+    values: Vec<u32>,
+    flags: Vec<bool>,
+    nested: Vec<SimpleStruct>,
+    metadata: Vec<(u8, String)>,
+}
+
+fn create_simple_struct() -> SimpleStruct {
+    SimpleStruct {
+        a: 42,
+        b: 12345,
+        c: 987654321,
+        d: 1234567890123456789,
+        e: -42,
+        f: -12345,
+        g: -987654321,
+        h: -1234567890123456789,
+        i: 3.25,
+        j: 2.5,
+        k: true,
+    }
+}
+
+fn create_complex_struct() -> ComplexStruct {
+    ComplexStruct {
+        id: 123456789,
+        name: "Test Struct".to_string(),
+        values: (0..100).collect(),
+        flags: (0..100).map(|i| i % 2 == 0).collect(),
+        nested: (0..10).map(|_| create_simple_struct()).collect(),
+        metadata: (0..5).map(|i| (i as u8, format!("item_{}", i))).collect(),
+    }
+}
+
+/// Encodes and decodes a struct shape with each format in [`FORMATS`].
+macro_rules! codecs {
+    ($ty:ty) => {
+        impl Codec for $ty {
+            fn encode(&self, format: &str) -> Vec<u8> {
+                match format {
+                    "jaguar" => {
+                        let mut ser = JaguarSerializer::new();
+                        self.serialize(&mut ser).unwrap();
+                        ser.finish()
+                    }
+                    "borsh" => borsh::to_vec(self).unwrap(),
+                    "bincode" => bincode::serialize(self).unwrap(),
+                    "postcard" => postcard::to_allocvec(self).unwrap(),
+                    "rkyv" => rkyv::to_bytes::<_, 1024>(self).unwrap().into_vec(),
+                    _ => unreachable!(),
+                }
+            }
+
+            fn decode(data: &[u8], format: &str) -> Self {
+                match format {
+                    "jaguar" => <$ty>::deserialize(&mut JaguarDeserializer::new(data)).unwrap(),
+                    "borsh" => borsh::from_slice(data).unwrap(),
+                    "bincode" => bincode::deserialize(data).unwrap(),
+                    "postcard" => postcard::from_bytes(data).unwrap(),
+                    "rkyv" => {
+                        let mut aligned = rkyv::AlignedVec::with_capacity(data.len());
+                        aligned.extend_from_slice(data);
+                        rkyv::from_bytes::<$ty>(&aligned).unwrap()
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    };
+}
+
+trait Codec: Sized {
+    fn encode(&self, format: &str) -> Vec<u8>;
+    fn decode(data: &[u8], format: &str) -> Self;
+}
+
+codecs!(SimpleStruct);
+codecs!(ComplexStruct);
+
+fn bench_shape<T: Codec + PartialEq + std::fmt::Debug>(c: &mut Criterion, shape: &str, value: T) {
+    let mut group = c.benchmark_group(format!("serialize_{}", shape));
+    for format in FORMATS {
+        group.bench_with_input(BenchmarkId::from_parameter(format), format, |b, format| {
+            b.iter(|| black_box(black_box(&value).encode(format)))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group(format!("deserialize_{}", shape));
+    for format in FORMATS {
+        let data = value.encode(format);
+        assert_eq!(T::decode(&data, format), value, "{} round trip", format);
+        group.bench_with_input(BenchmarkId::from_parameter(format), format, |b, format| {
+            b.iter(|| black_box(T::decode(black_box(&data), format)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_simple(c: &mut Criterion) {
+    bench_shape(c, "simple", create_simple_struct());
+}
+
+fn bench_complex(c: &mut Criterion) {
+    bench_shape(c, "complex", create_complex_struct());
+}
+
+criterion_group!(benches, bench_simple, bench_complex);
+
+/// Directory holding the bench executable's `target`.
+fn target_dir() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    // target/<profile>/deps/<bench>
+    exe.ancestors().nth(3).unwrap().to_path_buf()
+}
+
+/// Mean time in nanoseconds recorded by criterion for `group/format`.
+fn mean_ns(target: &std::path::Path, group: &str, format: &str) -> Option<f64> {
+    let path = target.join("criterion").join(group).join(format).join("new/estimates.json");
+    let estimates: serde_json::Value = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+    estimates["mean"]["point_estimate"].as_f64()
+}
+
+fn write_results() {
+    let target = target_dir();
+    let shapes = [
+        ("simple", Box::new(|f: &str| create_simple_struct().encode(f).len()) as Box<dyn Fn(&str) -> usize>),
+        ("complex", Box::new(|f: &str| create_complex_struct().encode(f).len())),
+    ];
+
+    let mut results = serde_json::Map::new();
+    for (shape, size) in &shapes {
+        let mut formats = serde_json::Map::new();
+        for format in FORMATS {
+            formats.insert(
+                format.to_string(),
+                serde_json::json!({
+                    "size_bytes": size(format),
+                    "serialize_ns": mean_ns(&target, &format!("serialize_{}", shape), format),
+                    "deserialize_ns": mean_ns(&target, &format!("deserialize_{}", shape), format),
+                }),
+            );
+        }
+        results.insert(shape.to_string(), formats.into());
+    }
+
+    let path = target.join("bench-compare.json");
+    fs::write(&path, serde_json::to_string_pretty(&results).unwrap()).unwrap();
+    println!("wrote {}", path.display());
+}
+
+fn main() {
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+    write_results();
+}
@@ -16,6 +16,20 @@ fn bench_deserialize_u32_slice(c: &mut Criterion) {
     });
 }
 
+fn bench_deserialize_u32_svb(c: &mut Criterion) {
+    let test_data: Vec<u32> = (0..10000).collect();
+    let mut ser = JaguarSerializer::new();
+    ser.write_u32_slice_svb(&test_data).unwrap();
+    let serialized = ser.finish();
+
+    c.bench_function("deserialize_u32_svb", |b| {
+        b.iter(|| {
+            let mut de = JaguarDeserializer::new(black_box(&serialized));
+            black_box(de.read_u32_vec_svb().unwrap())
+        })
+    });
+}
+
 fn bench_deserialize_u8_vec(c: &mut Criterion) {
     let data: Vec<u8> = (0..10000).map(|i| (i % 256) as u8).collect();
     let mut ser = JaguarSerializer::new();
@@ -169,6 +183,7 @@ criterion_group!(
     bench_deserialize_u8_vec,
     bench_deserialize_u16_vec,
     bench_deserialize_u32_slice,
+    bench_deserialize_u32_svb,
     bench_deserialize_u64_vec,
     bench_deserialize_i8_vec,
     bench_deserialize_i16_vec,
@@ -36,8 +36,8 @@ fn create_simple_struct() -> SimpleStruct {
         f: -12345,
         g: -987654321,
         h: -1234567890123456789,
-        i: 3.14159,
-        j: 2.71828,
+        i: 3.25,
+        j: 2.5,
         k: true,
     }
 }
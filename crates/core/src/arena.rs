@@ -0,0 +1,84 @@
+//! Arena-backed deserialization.
+//!
+//! Decoding a batch of records into `Vec`s and `String`s allocates for every
+//! one of them, which dominates decode-heavy workloads like indexers. With
+//! [`deserialize_in`], collections are `bumpalo` types allocated in a
+//! caller-provided [`Bump`] instead, so each allocation is a pointer bump and
+//! the whole batch is freed at once by resetting the arena.
+//!
+//! Any fully-owned [`JaguarDeserialize`](crate::JaguarDeserialize) type also
+//! implements [`JaguarDeserializeIn`], and `#[derive(JaguarDeserializeIn)]`
+//! covers structs mixing both. The encoding is unchanged, so arena types read
+//! the same bytes as their `alloc` counterparts.
+//!
+//! Requires the `bumpalo` feature.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use bumpalo::collections::{String, Vec};
+//! use bumpalo::Bump;
+//! use jaguar::JaguarDeserializeIn;
+//!
+//! #[derive(JaguarDeserializeIn)]
+//! struct Trade<'bump> {
+//!     market: String<'bump>,
+//!     fills: Vec<'bump, u64>,
+//! }
+//!
+//! let data = jaguar::serialize(&("SOL-PERP".to_owned(), vec![5u64, 7])).unwrap();
+//!
+//! let bump = Bump::new();
+//! let trade: Trade = jaguar::deserialize_in(&data, &bump).unwrap();
+//! assert_eq!(trade.market, "SOL-PERP");
+//! assert_eq!(trade.fills, [5, 7]);
+//! ```
+
+use crate::{JaguarDeserializeOwned, JaguarDeserializer, SerError};
+use bumpalo::collections::{String, Vec};
+use bumpalo::Bump;
+
+#[cfg(feature = "derive")]
+pub use jaguar_derive::JaguarDeserializeIn;
+
+/// Types that can be deserialized with their allocations placed in a
+/// [`Bump`] arena.
+pub trait JaguarDeserializeIn<'bump>: Sized {
+    fn deserialize_in(de: &mut JaguarDeserializer<'_>, bump: &'bump Bump) -> Result<Self, SerError>;
+}
+
+impl<'bump, T: JaguarDeserializeOwned> JaguarDeserializeIn<'bump> for T {
+    #[inline]
+    fn deserialize_in(de: &mut JaguarDeserializer<'_>, _bump: &'bump Bump) -> Result<Self, SerError> {
+        T::deserialize(de)
+    }
+}
+
+impl<'bump, T: JaguarDeserializeIn<'bump>> JaguarDeserializeIn<'bump> for Vec<'bump, T> {
+    #[inline]
+    fn deserialize_in(de: &mut JaguarDeserializer<'_>, bump: &'bump Bump) -> Result<Self, SerError> {
+        let len = de.read_seq_len::<T>()?;
+        let mut vec = Vec::with_capacity_in(de.capacity_hint(len), bump);
+        de.nested(|de| {
+            for _ in 0..len {
+                vec.push(T::deserialize_in(de, bump)?);
+            }
+            Ok(vec)
+        })
+    }
+}
+
+impl<'bump> JaguarDeserializeIn<'bump> for String<'bump> {
+    #[inline]
+    fn deserialize_in(de: &mut JaguarDeserializer<'_>, bump: &'bump Bump) -> Result<Self, SerError> {
+        Ok(String::from_str_in(de.read_str()?, bump))
+    }
+}
+
+/// Deserializes a value, allocating its `bumpalo` collections in `bump`.
+pub fn deserialize_in<'bump, T: JaguarDeserializeIn<'bump>>(data: &[u8], bump: &'bump Bump) -> Result<T, SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    T::deserialize_in(&mut de, bump)
+}
@@ -0,0 +1,192 @@
+//! Bit-level access for packed sub-byte fields.
+//!
+//! A [`BitWriter`] packs values of arbitrary bit width (1-bit flags, 3-bit
+//! enums, 5-bit counts) into shared bytes of the underlying serializer, least
+//! significant bit first. The final partial byte is zero-padded when the
+//! writer is finished or dropped, so byte-aligned writes can follow directly.
+//! [`BitReader`] reads the same layout back.
+
+use crate::{JaguarDeserializer, JaguarSerializer, SerError};
+
+/// Packs values of up to 64 bits into the bytes of a [`JaguarSerializer`].
+///
+/// Dropping the writer flushes the pending bits too, but a flush past the
+/// serializer's maximum size then loses them silently. Call
+/// [`BitWriter::finish`] to see that error.
+pub struct BitWriter<'s> {
+    ser: &'s mut JaguarSerializer,
+    acc: u128,
+    len: u32,
+}
+
+impl<'s> BitWriter<'s> {
+    #[inline]
+    pub fn new(ser: &'s mut JaguarSerializer) -> Self {
+        Self { ser, acc: 0, len: 0 }
+    }
+
+    /// Writes the low `bits` bits of `value`.
+    ///
+    /// Returns `InvalidData` if `value` does not fit in `bits` bits, and
+    /// `UnsupportedType` if `bits` is larger than 64.
+    #[inline]
+    pub fn write_bits(&mut self, value: u64, bits: u32) -> Result<(), SerError> {
+        if bits > 64 {
+            return Err(SerError::UnsupportedType);
+        }
+        if bits < 64 && value >> bits != 0 {
+            return Err(SerError::InvalidData);
+        }
+
+        self.acc |= (value as u128) << self.len;
+        self.len += bits;
+        while self.len >= 8 {
+            self.ser.write_u8(self.acc as u8)?;
+            self.acc >>= 8;
+            self.len -= 8;
+        }
+        Ok(())
+    }
+
+    /// Writes a single bit.
+    #[inline]
+    pub fn write_bool(&mut self, value: bool) -> Result<(), SerError> {
+        self.write_bits(value as u64, 1)
+    }
+
+    /// Zero-pads and writes any pending bits, returning to byte alignment.
+    #[inline]
+    pub fn finish(mut self) -> Result<(), SerError> {
+        self.flush()
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), SerError> {
+        if self.len > 0 {
+            self.ser.write_u8(self.acc as u8)?;
+            self.acc = 0;
+            self.len = 0;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for BitWriter<'_> {
+    fn drop(&mut self) {
+        // errors are only reported by `finish`, see `BitWriter`
+        let _ = self.flush();
+    }
+}
+
+/// Reads values packed by a [`BitWriter`].
+pub struct BitReader<'r, 'a> {
+    de: &'r mut JaguarDeserializer<'a>,
+    acc: u128,
+    len: u32,
+}
+
+impl<'r, 'a> BitReader<'r, 'a> {
+    #[inline]
+    pub fn new(de: &'r mut JaguarDeserializer<'a>) -> Self {
+        Self { de, acc: 0, len: 0 }
+    }
+
+    /// Reads a `bits`-bit value.
+    ///
+    /// Returns `UnsupportedType` if `bits` is larger than 64.
+    #[inline]
+    pub fn read_bits(&mut self, bits: u32) -> Result<u64, SerError> {
+        if bits > 64 {
+            return Err(SerError::UnsupportedType);
+        }
+        while self.len < bits {
+            self.acc |= (self.de.read_u8()? as u128) << self.len;
+            self.len += 8;
+        }
+
+        let value = (self.acc & ((1u128 << bits) - 1)) as u64;
+        self.acc >>= bits;
+        self.len -= bits;
+        Ok(value)
+    }
+
+    /// Reads a single bit.
+    #[inline]
+    pub fn read_bool(&mut self) -> Result<bool, SerError> {
+        Ok(self.read_bits(1)? == 1)
+    }
+
+    /// Returns to byte alignment, rejecting non-zero padding bits.
+    #[inline]
+    pub fn finish(self) -> Result<(), SerError> {
+        if self.acc != 0 {
+            return Err(SerError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl JaguarSerializer {
+    /// Starts packing sub-byte fields at the current position.
+    #[inline]
+    pub fn bit_writer(&mut self) -> BitWriter<'_> {
+        BitWriter::new(self)
+    }
+}
+
+impl<'a> JaguarDeserializer<'a> {
+    /// Starts reading sub-byte fields at the current position.
+    #[inline]
+    pub fn bit_reader(&mut self) -> BitReader<'_, 'a> {
+        BitReader::new(self)
+    }
+}
+
+/// A field that can be packed with `#[jaguar(bits = N)]`.
+///
+/// Derive it with `#[derive(JaguarBitfield)]` for fieldless enums, packed as
+/// their discriminant, and for structs whose fields all have `bits`, packed
+/// into one value of at most 63 bits in field order.
+pub trait BitField: Sized {
+    fn to_bits(&self) -> u64;
+
+    /// Converts a decoded value back, returning `InvalidData` if it is out of
+    /// range for `Self`.
+    fn from_bits(bits: u64) -> Result<Self, SerError>;
+}
+
+impl BitField for bool {
+    #[inline]
+    fn to_bits(&self) -> u64 {
+        *self as u64
+    }
+
+    #[inline]
+    fn from_bits(bits: u64) -> Result<Self, SerError> {
+        match bits {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(SerError::InvalidData),
+        }
+    }
+}
+
+macro_rules! impl_bit_field {
+    ($($t:ty),*) => {
+        $(
+            impl BitField for $t {
+                #[inline]
+                fn to_bits(&self) -> u64 {
+                    *self as u64
+                }
+
+                #[inline]
+                fn from_bits(bits: u64) -> Result<Self, SerError> {
+                    <$t>::try_from(bits).map_err(|_| SerError::InvalidData)
+                }
+            }
+        )*
+    };
+}
+
+impl_bit_field!(u8, u16, u32, u64);
@@ -0,0 +1,95 @@
+//! Integration with the `bytes` crate.
+//!
+//! Tokio-based services pass messages around as [`Bytes`] and read sockets
+//! into [`BytesMut`]. With this feature, a serializer can write into a
+//! `BytesMut`'s allocation and hand the result back as `Bytes` without
+//! copying it, and `Bytes`/`BytesMut` fields encode like `Vec<u8>`.
+//!
+//! Decoding needs nothing extra: `Bytes` derefs to `&[u8]`, so
+//! `#[jaguar(borrow)]` fields point straight into it, and
+//! [`Bytes::slice_ref`] turns such a field into a `Bytes` sharing the same
+//! allocation. Decoding a `Bytes` field copies it.
+//!
+//! Requires the `bytes` feature.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use bytes::BytesMut;
+//! use jaguar::{JaguarDeserialize, JaguarSerialize, JaguarSerializer};
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize)]
+//! struct Packet<'a> {
+//!     id: u32,
+//!     #[jaguar(borrow)]
+//!     body: &'a [u8],
+//! }
+//!
+//! let mut ser = JaguarSerializer::from_bytes_mut(BytesMut::with_capacity(64));
+//! Packet { id: 7, body: b"hello" }.serialize(&mut ser).unwrap();
+//! let data = ser.finish_bytes();
+//!
+//! let packet: Packet = jaguar::deserialize(&data).unwrap();
+//! let body = data.slice_ref(packet.body);
+//! assert_eq!(body, &b"hello"[..]);
+//! ```
+
+use crate::{JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+use alloc::vec::Vec;
+use ::bytes::{Bytes, BytesMut};
+
+impl JaguarSerializer {
+    /// Creates a serializer writing into `buf`'s allocation. Its contents
+    /// are discarded.
+    #[inline]
+    pub fn from_bytes_mut(mut buf: BytesMut) -> Self {
+        buf.clear();
+        let mut ser = Self::with_capacity(0);
+        ser.buffer = Vec::from(buf);
+        ser
+    }
+
+    /// Finalizes and returns the serialized data as [`Bytes`], without
+    /// copying it.
+    #[inline]
+    pub fn finish_bytes(self) -> Bytes {
+        Bytes::from(self.finish())
+    }
+
+    /// Finalizes and returns the serialized data as [`BytesMut`], without
+    /// copying it.
+    #[inline]
+    pub fn finish_bytes_mut(self) -> BytesMut {
+        BytesMut::from(self.finish_bytes())
+    }
+}
+
+impl JaguarSerialize for Bytes {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_bytes(self)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for Bytes {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(Bytes::copy_from_slice(de.read_bytes()?))
+    }
+}
+
+impl JaguarSerialize for BytesMut {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_bytes(self)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for BytesMut {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(BytesMut::from(de.read_bytes()?))
+    }
+}
@@ -0,0 +1,149 @@
+//! Deserializing from input split across several byte slices.
+//!
+//! Scatter/gather network reads and account data split over several accounts
+//! arrive as a list of slices. [`ChainedDeserializer`] decodes values from
+//! such a list without concatenating it first: a value inside one slice is
+//! decoded in place, and only a value that straddles a boundary is copied,
+//! together with the slices it spans, into a scratch buffer.
+//!
+//! A straddling value is retried each time the buffered bytes double, so one
+//! spanning many small slices is decoded a logarithmic number of times, not
+//! once per slice.
+//!
+//! Decoded values can't borrow from the input, since they may come from the
+//! scratch buffer, so this works with [`JaguarDeserializeOwned`] types.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::chained::ChainedDeserializer;
+//!
+//! let data = jaguar::serialize(&(300u32, (String::from("split"), 7u8))).unwrap();
+//! let (head, tail) = data.split_at(4);
+//! let slices = [head, tail];
+//!
+//! let mut de = ChainedDeserializer::new(&slices);
+//! assert_eq!(de.deserialize::<u32>().unwrap(), 300);
+//! assert_eq!(de.deserialize::<String>().unwrap(), "split");
+//! assert_eq!(de.deserialize::<u8>().unwrap(), 7);
+//! assert!(!de.has_data());
+//! ```
+
+use crate::{JaguarDeserializeOwned, JaguarDeserializer, SerError};
+use alloc::vec::Vec;
+
+/// Reads values across a list of byte slices, in order.
+pub struct ChainedDeserializer<'a> {
+    slices: &'a [&'a [u8]],
+    /// Index of the slice holding the next byte.
+    index: usize,
+    /// Offset of the next byte within `slices[index]`.
+    offset: usize,
+    /// Bytes consumed so far, across every slice.
+    pos: usize,
+    scratch: Vec<u8>,
+}
+
+impl<'a> ChainedDeserializer<'a> {
+    /// Creates a deserializer reading `slices` as one continuous input.
+    pub fn new(slices: &'a [&'a [u8]]) -> Self {
+        Self { slices, index: 0, offset: 0, pos: 0, scratch: Vec::new() }
+    }
+
+    /// Returns the number of bytes consumed so far.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the number of bytes left in every slice.
+    pub fn remaining(&self) -> usize {
+        let rest: usize = self.slices.iter().skip(self.index).map(|slice| slice.len()).sum();
+        rest - self.offset
+    }
+
+    /// Returns `true` if any bytes are left.
+    #[inline]
+    pub fn has_data(&self) -> bool {
+        self.remaining() > 0
+    }
+
+    /// Deserializes the next value.
+    ///
+    /// On error nothing is consumed, so the position still points at the
+    /// start of the failing value.
+    pub fn deserialize<T: JaguarDeserializeOwned>(&mut self) -> Result<T, SerError> {
+        self.skip_empty();
+        let current = match self.slices.get(self.index) {
+            Some(slice) => &slice[self.offset..],
+            None => &[],
+        };
+        let mut de = JaguarDeserializer::new(current);
+        match T::deserialize(&mut de) {
+            Ok(value) => {
+                self.advance(de.position());
+                return Ok(value);
+            }
+            Err(SerError::BufferTooSmall) if self.index + 1 < self.slices.len() => {}
+            Err(err) => return Err(err),
+        }
+
+        // the value runs past this slice, so join it with the following ones
+        // until it decodes, retrying once the joined bytes have doubled
+        self.scratch.clear();
+        self.scratch.extend_from_slice(current);
+        let mut tried = current.len();
+        for next in self.index + 1..self.slices.len() {
+            self.scratch.extend_from_slice(self.slices[next]);
+            if self.scratch.len() < 2 * tried && next + 1 < self.slices.len() {
+                continue;
+            }
+            tried = self.scratch.len();
+            let mut de = JaguarDeserializer::new(&self.scratch);
+            match T::deserialize(&mut de) {
+                Ok(value) => {
+                    let len = de.position();
+                    self.advance(len);
+                    return Ok(value);
+                }
+                Err(SerError::BufferTooSmall) if next + 1 < self.slices.len() => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Err(SerError::BufferTooSmall)
+    }
+
+    /// Skips `len` bytes.
+    pub fn skip(&mut self, len: usize) -> Result<(), SerError> {
+        if len > self.remaining() {
+            return Err(SerError::BufferTooSmall);
+        }
+        self.advance(len);
+        Ok(())
+    }
+
+    /// Moves past slices with nothing left to read.
+    fn skip_empty(&mut self) {
+        while self.slices.get(self.index).is_some_and(|slice| self.offset == slice.len()) {
+            self.index += 1;
+            self.offset = 0;
+        }
+    }
+
+    /// Consumes `len` bytes, which must be available.
+    fn advance(&mut self, mut len: usize) {
+        self.pos += len;
+        while len > 0 {
+            let available = self.slices[self.index].len() - self.offset;
+            if len < available {
+                self.offset += len;
+                return;
+            }
+            len -= available;
+            self.index += 1;
+            self.offset = 0;
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! CRC-32 checksums for payloads sent over lossy links.
+//!
+//! [`JaguarSerializer::finish_with_checksum`](crate::JaguarSerializer::finish_with_checksum)
+//! appends the [`crc32`] of the payload as 4 little-endian bytes, and
+//! [`JaguarDeserializer::new_verified`](crate::JaguarDeserializer::new_verified)
+//! checks and strips it, failing with `ChecksumMismatch` on corruption.
+//!
+//! The checksum is the common CRC-32 (IEEE 802.3, as in zlib and Ethernet), so
+//! the other end of a serial or radio link can usually compute it in hardware.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::{JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+//!
+//! let mut ser = JaguarSerializer::new();
+//! 1_000u32.serialize(&mut ser).unwrap();
+//! let mut data = ser.finish_with_checksum().unwrap();
+//! assert_eq!(data.len(), 2 + 4);
+//!
+//! let mut de = JaguarDeserializer::new_verified(&data).unwrap();
+//! assert_eq!(u32::deserialize(&mut de).unwrap(), 1_000);
+//!
+//! data[0] ^= 1;
+//! assert_eq!(JaguarDeserializer::new_verified(&data).err(), Some(SerError::ChecksumMismatch));
+//! ```
+
+/// Length of the checksum trailer in bytes.
+pub const CHECKSUM_LEN: usize = 4;
+
+const TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Computes the CRC-32 (IEEE) of `data`.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc = TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
@@ -0,0 +1,126 @@
+//! Slot, epoch and timestamp newtypes for validator tooling.
+//!
+//! A single [`Slot`] or [`Epoch`] encodes as a varint, like the `u64` it wraps.
+//! Histories of them are usually monotonic, so they get the delta encoding of
+//! [`JaguarSerializer::write_u64_slice_delta`]: through
+//! `write_slot_history`/`read_slot_history`, or in derives with
+//! `#[jaguar(with = "jaguar::clock::History")]`.
+//!
+//! A [`UnixTimestamp`] encodes as a zigzag varint, like the `i64` it wraps, so
+//! current dates take 5 bytes instead of 8.
+
+use crate::{JaguarCodec, JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+use alloc::vec::Vec;
+
+macro_rules! clock_type {
+    ($(#[$doc:meta])* $name:ident, $write:ident, $read:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub u64);
+
+        impl From<u64> for $name {
+            #[inline]
+            fn from(value: u64) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for u64 {
+            #[inline]
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl JaguarSerialize for $name {
+            #[inline]
+            fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+                ser.write_varint(self.0)
+            }
+        }
+
+        impl<'a> JaguarDeserialize<'a> for $name {
+            #[inline]
+            fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+                de.read_varint().map(Self)
+            }
+        }
+
+        impl JaguarCodec<Vec<$name>> for History {
+            #[inline]
+            fn encode(value: &Vec<$name>, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+                ser.$write(value)
+            }
+
+            #[inline]
+            fn decode(de: &mut JaguarDeserializer<'_>) -> Result<Vec<$name>, SerError> {
+                de.$read()
+            }
+        }
+
+        impl JaguarSerializer {
+            #[doc = concat!("Writes a history of [`", stringify!($name), "`]s as varint deltas.")]
+            #[inline]
+            pub fn $write(&mut self, history: &[$name]) -> Result<(), SerError> {
+                self.write_delta(history.iter().map(|value| value.0))
+            }
+        }
+
+        impl<'a> JaguarDeserializer<'a> {
+            #[doc = concat!("Reads a history of [`", stringify!($name), "`]s written as varint deltas.")]
+            #[inline]
+            pub fn $read(&mut self) -> Result<Vec<$name>, SerError> {
+                Ok(self.read_u64_vec_delta()?.into_iter().map($name).collect())
+            }
+        }
+    };
+}
+
+/// Delta [`JaguarCodec`] for `Vec<Slot>` and `Vec<Epoch>` fields.
+pub struct History;
+
+clock_type!(
+    /// A Solana slot number.
+    Slot,
+    write_slot_history,
+    read_slot_history
+);
+
+clock_type!(
+    /// A Solana epoch number.
+    Epoch,
+    write_epoch_history,
+    read_epoch_history
+);
+
+/// Seconds since the Unix epoch, as in Solana's `Clock::unix_timestamp`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnixTimestamp(pub i64);
+
+impl From<i64> for UnixTimestamp {
+    #[inline]
+    fn from(value: i64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<UnixTimestamp> for i64 {
+    #[inline]
+    fn from(value: UnixTimestamp) -> Self {
+        value.0
+    }
+}
+
+impl JaguarSerialize for UnixTimestamp {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_signed_varint(self.0)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for UnixTimestamp {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        i64::deserialize(de).map(Self)
+    }
+}
@@ -0,0 +1,309 @@
+//! Borsh-compatible encodings for fields of otherwise Jaguar-encoded structs.
+//!
+//! Borsh stores integers as fixed-width little-endian values and prefixes
+//! strings and vectors with a `u32` length. Marking a field with
+//! `#[jaguar(compat = "borsh")]` encodes just that field this way, which
+//! allows byte-precise hybrid layouts while migrating existing accounts.
+//!
+//! [`COption`] reproduces the SPL token program's optional fields, so structs
+//! mirroring token accounts and mints decode without hand-written impls.
+//!
+//! Types from other crates that derive `BorshSerialize` and
+//! `BorshDeserialize`, such as embedded legacy state, can instead be marked
+//! `#[jaguar(repr = "borsh")]`, which hands the field to the `borsh` crate
+//! itself through [`BorshRepr`]. Requires the `borsh` feature.
+
+use crate::{JaguarCodec, JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+/// A type with a borsh wire layout.
+pub trait BorshLayout: Sized {
+    fn write_borsh(&self, ser: &mut JaguarSerializer) -> Result<(), SerError>;
+    fn read_borsh(de: &mut JaguarDeserializer<'_>) -> Result<Self, SerError>;
+}
+
+/// [`JaguarCodec`] for any [`BorshLayout`] type, used by
+/// `#[jaguar(compat = "borsh")]`.
+pub struct Borsh;
+
+impl<T: BorshLayout> JaguarCodec<T> for Borsh {
+    #[inline]
+    fn encode(value: &T, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        value.write_borsh(ser)
+    }
+
+    #[inline]
+    fn decode(de: &mut JaguarDeserializer<'_>) -> Result<T, SerError> {
+        T::read_borsh(de)
+    }
+}
+
+/// [`JaguarCodec`] encoding a field through the `borsh` crate's own
+/// `BorshSerialize` and `BorshDeserialize`, used by
+/// `#[jaguar(repr = "borsh")]`. The field keeps exactly the bytes borsh gives
+/// it.
+///
+/// Decoding fails with `InvalidData` wherever borsh fails, including on
+/// truncated input.
+#[cfg(feature = "borsh")]
+pub struct BorshRepr;
+
+#[cfg(feature = "borsh")]
+impl<T: borsh::BorshSerialize + borsh::BorshDeserialize> JaguarCodec<T> for BorshRepr {
+    fn encode(value: &T, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        let mut writer = BorshWriter { ser, error: None };
+        match value.serialize(&mut writer) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(writer.error.unwrap_or(SerError::InvalidData)),
+        }
+    }
+
+    fn decode(de: &mut JaguarDeserializer<'_>) -> Result<T, SerError> {
+        let mut rest = de.remaining_bytes();
+        let len = rest.len();
+        let value = T::deserialize(&mut rest).map_err(|_| SerError::InvalidData)?;
+        de.skip(len - rest.len())?;
+        Ok(value)
+    }
+}
+
+/// Adapts a serializer to `borsh::io::Write`, keeping the serializer's own
+/// error since borsh's can't carry it.
+#[cfg(feature = "borsh")]
+struct BorshWriter<'s> {
+    ser: &'s mut JaguarSerializer,
+    error: Option<SerError>,
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::io::Write for BorshWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> borsh::io::Result<usize> {
+        match self.ser.write_raw(buf) {
+            Ok(()) => Ok(buf.len()),
+            Err(err) => {
+                self.error = Some(err);
+                Err(borsh::io::ErrorKind::WriteZero.into())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> borsh::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[inline]
+fn read_len(de: &mut JaguarDeserializer<'_>) -> Result<usize, SerError> {
+    Ok(u32::read_borsh(de)? as usize)
+}
+
+#[inline]
+fn write_len(ser: &mut JaguarSerializer, len: usize) -> Result<(), SerError> {
+    let len = u32::try_from(len).map_err(|_| SerError::InvalidLength)?;
+    len.write_borsh(ser)
+}
+
+macro_rules! impl_borsh_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl BorshLayout for $t {
+                #[inline]
+                fn write_borsh(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+                    ser.write_raw(&self.to_le_bytes())
+                }
+
+                #[inline]
+                fn read_borsh(de: &mut JaguarDeserializer<'_>) -> Result<Self, SerError> {
+                    let bytes = de.read_array_ref::<{ mem::size_of::<$t>() }>()?;
+                    Ok(<$t>::from_le_bytes(*bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_borsh_primitive!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl BorshLayout for bool {
+    #[inline]
+    fn write_borsh(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_bool(*self)
+    }
+
+    #[inline]
+    fn read_borsh(de: &mut JaguarDeserializer<'_>) -> Result<Self, SerError> {
+        match de.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(SerError::InvalidData),
+        }
+    }
+}
+
+impl BorshLayout for String {
+    #[inline]
+    fn write_borsh(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        write_len(ser, self.len())?;
+        ser.write_raw(self.as_bytes())
+    }
+
+    #[inline]
+    fn read_borsh(de: &mut JaguarDeserializer<'_>) -> Result<Self, SerError> {
+        let len = read_len(de)?;
+        core::str::from_utf8(de.take_bytes(len)?)
+            .map(String::from)
+            .map_err(|_| SerError::InvalidData)
+    }
+}
+
+impl<T: BorshLayout> BorshLayout for Vec<T> {
+    #[inline]
+    fn write_borsh(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        write_len(ser, self.len())?;
+        for item in self {
+            item.write_borsh(ser)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn read_borsh(de: &mut JaguarDeserializer<'_>) -> Result<Self, SerError> {
+        let len = u32::read_borsh(de)?;
+        let len = de.check_seq_len::<T>(len.into())?;
+        let mut vec = Vec::with_capacity(de.capacity_hint(len));
+        for _ in 0..len {
+            vec.push(T::read_borsh(de)?);
+        }
+        Ok(vec)
+    }
+}
+
+impl<T: BorshLayout> BorshLayout for Option<T> {
+    #[inline]
+    fn write_borsh(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        match self {
+            Some(value) => {
+                ser.write_u8(1)?;
+                value.write_borsh(ser)
+            }
+            None => ser.write_u8(0),
+        }
+    }
+
+    #[inline]
+    fn read_borsh(de: &mut JaguarDeserializer<'_>) -> Result<Self, SerError> {
+        match de.read_u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::read_borsh(de)?)),
+            _ => Err(SerError::InvalidData),
+        }
+    }
+}
+
+impl<const N: usize> BorshLayout for [u8; N] {
+    #[inline]
+    fn write_borsh(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_raw(self)
+    }
+
+    #[inline]
+    fn read_borsh(de: &mut JaguarDeserializer<'_>) -> Result<Self, SerError> {
+        de.read_array_ref::<N>().copied()
+    }
+}
+
+/// An optional value in the SPL `COption` layout: a little-endian `u32` tag
+/// (`0` or `1`) followed by the value in its [`BorshLayout`].
+///
+/// The value is written even when absent, as `T::default()`, so the field
+/// always has the same size. `COption<[u8; 32]>` is the 36-byte optional
+/// pubkey of token accounts and mints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum COption<T> {
+    #[default]
+    None,
+    Some(T),
+}
+
+impl<T> COption<T> {
+    #[inline]
+    pub fn is_some(&self) -> bool {
+        matches!(self, COption::Some(_))
+    }
+
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        !self.is_some()
+    }
+
+    #[inline]
+    pub fn as_ref(&self) -> COption<&T> {
+        match self {
+            COption::Some(value) => COption::Some(value),
+            COption::None => COption::None,
+        }
+    }
+}
+
+impl<T> From<Option<T>> for COption<T> {
+    #[inline]
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => COption::Some(value),
+            None => COption::None,
+        }
+    }
+}
+
+impl<T> From<COption<T>> for Option<T> {
+    #[inline]
+    fn from(value: COption<T>) -> Self {
+        match value {
+            COption::Some(value) => Some(value),
+            COption::None => None,
+        }
+    }
+}
+
+impl<T: BorshLayout + Default> BorshLayout for COption<T> {
+    #[inline]
+    fn write_borsh(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        match self {
+            COption::Some(value) => {
+                1u32.write_borsh(ser)?;
+                value.write_borsh(ser)
+            }
+            COption::None => {
+                0u32.write_borsh(ser)?;
+                T::default().write_borsh(ser)
+            }
+        }
+    }
+
+    #[inline]
+    fn read_borsh(de: &mut JaguarDeserializer<'_>) -> Result<Self, SerError> {
+        let tag = u32::read_borsh(de)?;
+        let value = T::read_borsh(de)?;
+        match tag {
+            0 => Ok(COption::None),
+            1 => Ok(COption::Some(value)),
+            _ => Err(SerError::InvalidData),
+        }
+    }
+}
+
+impl<T: BorshLayout + Default> JaguarSerialize for COption<T> {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        self.write_borsh(ser)
+    }
+}
+
+impl<'a, T: BorshLayout + Default> JaguarDeserialize<'a> for COption<T> {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Self::read_borsh(de)
+    }
+}
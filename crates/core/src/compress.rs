@@ -0,0 +1,62 @@
+//! LZ4-compressed payloads for off-chain storage.
+//!
+//! Account snapshots and archives are dominated by repeated keys and zero
+//! padding that Jaguar alone doesn't shrink. [`serialize_compressed`] encodes
+//! a value as usual, then compresses the bytes with LZ4, which decompresses at
+//! memory speed, so reads stay cheap.
+//!
+//! Requires the `lz4` feature, which implies `std`.
+//!
+//! ```text
+//! Format:
+//! [uncompressed length: varint][LZ4 block: bytes]
+//! ```
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! let snapshot = vec![[0u8; 32]; 100];
+//! let data = jaguar::serialize_compressed(&snapshot).unwrap();
+//! assert!(data.len() < 100);
+//!
+//! let decoded: Vec<[u8; 32]> = jaguar::deserialize_compressed(&data).unwrap();
+//! assert_eq!(decoded, snapshot);
+//! ```
+
+use crate::{JaguarDeserializeOwned, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+use alloc::vec::Vec;
+
+/// LZ4 can't expand input by more than this, which bounds the allocation a
+/// corrupt length prefix can cause.
+const MAX_RATIO: usize = 255;
+
+/// Serializes `value` and compresses the result.
+pub fn serialize_compressed<T: JaguarSerialize + ?Sized>(value: &T) -> Result<Vec<u8>, SerError> {
+    let mut ser = JaguarSerializer::new();
+    value.serialize(&mut ser)?;
+    let data = ser.finish();
+
+    let mut out = JaguarSerializer::with_capacity(lz4_flex::block::get_maximum_output_size(data.len()) + 10);
+    out.write_varint(data.len() as u64)?;
+    out.write_raw(&lz4_flex::block::compress(&data))?;
+    Ok(out.finish())
+}
+
+/// Decompresses data written by [`serialize_compressed`] and deserializes it.
+///
+/// Corrupt compressed data fails with `InvalidData`.
+pub fn deserialize_compressed<T: JaguarDeserializeOwned>(data: &[u8]) -> Result<T, SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    let len = de.read_varint()?;
+    let block = de.take_rest();
+    if len > block.len().saturating_mul(MAX_RATIO) as u64 {
+        return Err(SerError::InvalidLength);
+    }
+    let payload = lz4_flex::block::decompress(block, len as usize).map_err(|_| SerError::InvalidData)?;
+    if payload.len() != len as usize {
+        return Err(SerError::InvalidData);
+    }
+    crate::deserialize_owned(&payload)
+}
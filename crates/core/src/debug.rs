@@ -0,0 +1,347 @@
+//! Human-readable dumps of encoded data.
+//!
+//! Encoded values carry no field names or types, so instruction data and
+//! account contents are opaque bytes in a debugger or an explorer. Given the
+//! [`Schema`] of the type they hold, [`dump`] prints them field by field in
+//! RON syntax, and [`dump_json`] as JSON for tools that parse the output.
+//!
+//! Byte arrays and `Vec<u8>` print as lists of numbers. Decoding stops at the
+//! first error, which prints as `<error: ...>` (`{"error": "..."}` in JSON)
+//! in place of the value that failed, so everything before it is still
+//! shown. [`dump`] also notes any bytes left after the value.
+//!
+//! Requires the `std` feature.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::{JaguarSchema, JaguarSerialize};
+//!
+//! #[derive(JaguarSerialize, JaguarSchema)]
+//! struct Transfer {
+//!     amount: u64,
+//!     memo: String,
+//!     accounts: Vec<[u8; 2]>,
+//! }
+//!
+//! let data = jaguar::serialize(&Transfer { amount: 300, memo: "rent".into(), accounts: vec![[1, 2]] }).unwrap();
+//! assert_eq!(
+//!     jaguar::debug::dump(&data, &Transfer::schema()),
+//!     "Transfer(\n    amount: 300,\n    memo: \"rent\",\n    accounts: [[1, 2]],\n)"
+//! );
+//! assert_eq!(
+//!     jaguar::debug::dump_json(&data[..4], &Transfer::schema()),
+//!     "{\n  \"amount\": 300,\n  \"memo\": {\"error\": \"buffer too small at byte 2\"}\n}"
+//! );
+//! ```
+
+use crate::bits::BitField;
+use crate::schema::{FieldSchema, Schema, StructSchema};
+use crate::{JaguarDeserialize, JaguarDeserializer, SerError};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Prints `data`, holding a value with layout `schema`, in RON syntax.
+pub fn dump(data: &[u8], schema: &Schema) -> String {
+    let (value, trailing) = decode(data, schema);
+    let mut printer = Printer { out: String::new(), json: false };
+    printer.value(&value, 0);
+    if trailing > 0 {
+        // Writing to a `String` can't fail.
+        let _ = write!(printer.out, "\n// {} trailing bytes", trailing);
+    }
+    printer.out
+}
+
+/// Prints `data`, holding a value with layout `schema`, as JSON. Struct names
+/// and trailing bytes are left out, and tuples become arrays.
+pub fn dump_json(data: &[u8], schema: &Schema) -> String {
+    let (value, _) = decode(data, schema);
+    let mut printer = Printer { out: String::new(), json: true };
+    printer.value(&value, 0);
+    printer.out
+}
+
+/// A decoded value, keeping the names the schema gives it.
+enum Value<'s> {
+    UInt(u128),
+    Int(i128),
+    F32(f32),
+    F64(f64),
+    Bool(bool),
+    Str(String),
+    Bytes(Vec<u8>),
+    List(Vec<Value<'s>>),
+    Tuple(Vec<Value<'s>>),
+    Struct(&'s str, Vec<(&'s str, Value<'s>)>),
+    /// Where decoding failed, with the offset of the value.
+    Error(SerError, usize),
+}
+
+/// Decodes as much of `data` as it can, returning the value and the number
+/// of bytes after it.
+fn decode<'s>(data: &[u8], schema: &'s Schema) -> (Value<'s>, usize) {
+    let mut decoder = Decoder { de: JaguarDeserializer::new(data), failed: false };
+    let value = decoder.value(schema);
+    let trailing = if decoder.failed { 0 } else { decoder.de.remaining() };
+    (value, trailing)
+}
+
+struct Decoder<'a> {
+    de: JaguarDeserializer<'a>,
+    /// Set by the first error, after which nothing more is decoded.
+    failed: bool,
+}
+
+impl Decoder<'_> {
+    fn value<'s>(&mut self, schema: &'s Schema) -> Value<'s> {
+        let start = self.de.position();
+        self.try_value(schema).unwrap_or_else(|err| {
+            self.failed = true;
+            Value::Error(err, start)
+        })
+    }
+
+    fn try_value<'s>(&mut self, schema: &'s Schema) -> Result<Value<'s>, SerError> {
+        let de = &mut self.de;
+        Ok(match schema {
+            Schema::U8 => Value::UInt(de.read_u8()?.into()),
+            Schema::Bool => Value::Bool(bool::deserialize(de)?),
+            Schema::Varint { bits: 16 } => Value::UInt(u16::deserialize(de)?.into()),
+            Schema::Varint { bits: 32 } => Value::UInt(u32::deserialize(de)?.into()),
+            Schema::Varint { .. } => Value::UInt(u64::deserialize(de)?.into()),
+            Schema::SignedVarint { bits: 8 } => Value::Int(i8::deserialize(de)?.into()),
+            Schema::SignedVarint { bits: 16 } => Value::Int(i16::deserialize(de)?.into()),
+            Schema::SignedVarint { bits: 32 } => Value::Int(i32::deserialize(de)?.into()),
+            Schema::SignedVarint { .. } => Value::Int(i64::deserialize(de)?.into()),
+            Schema::U128 => Value::UInt(u128::deserialize(de)?),
+            Schema::I128 => Value::Int(i128::deserialize(de)?),
+            Schema::F32 => Value::F32(f32::deserialize(de)?),
+            Schema::F64 => Value::F64(f64::deserialize(de)?),
+            Schema::String => Value::Str(String::deserialize(de)?),
+            Schema::ByteArray(len) => {
+                let bytes = de.peek_bytes(*len)?.to_vec();
+                de.skip(*len)?;
+                Value::Bytes(bytes)
+            }
+            Schema::Array(len, inner) => {
+                if de.read_varint()? != *len as u64 {
+                    return Err(SerError::InvalidLength);
+                }
+                Value::List(self.items(inner, *len as u64)?)
+            }
+            Schema::Vec(inner) if **inner == Schema::U8 => Value::Bytes(de.read_bytes()?.to_vec()),
+            Schema::Vec(inner) => {
+                let len = de.read_varint()?;
+                Value::List(self.items(inner, len)?)
+            }
+            Schema::DeltaVec => Value::List(de.read_u64_vec_delta()?.into_iter().map(|v| Value::UInt(v.into())).collect()),
+            Schema::NonZero(inner) => match self.try_value(inner)? {
+                Value::UInt(0) | Value::Int(0) => return Err(SerError::InvalidData),
+                value => value,
+            },
+            Schema::Tuple(schemas) => {
+                let mut items = Vec::with_capacity(schemas.len());
+                for schema in schemas {
+                    items.push(self.value(schema));
+                    if self.failed {
+                        break;
+                    }
+                }
+                Value::Tuple(items)
+            }
+            Schema::Struct(def) => self.structure(def)?,
+        })
+    }
+
+    fn items<'s>(&mut self, schema: &'s Schema, len: u64) -> Result<Vec<Value<'s>>, SerError> {
+        let len = self.de.check_len(len, schema.is_zero_sized())?;
+        let mut items = Vec::new();
+        for _ in 0..len {
+            items.push(self.value(schema));
+            if self.failed {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn structure<'s>(&mut self, def: &'s StructSchema) -> Result<Value<'s>, SerError> {
+        let mut values = Vec::with_capacity(def.fields.len());
+        let mut fields = def.fields.iter().peekable();
+        while let Some(first) = fields.next() {
+            let Some(bits) = first.bits else {
+                values.push((first.name.as_str(), self.value(&first.schema)));
+                if self.failed {
+                    return Ok(Value::Struct(&def.name, values));
+                }
+                continue;
+            };
+
+            let start = self.de.position();
+            let mut group = Vec::new();
+            let mut reader = self.de.bit_reader();
+            let mut read = |field: &'s FieldSchema, bits: u32| -> Result<(), SerError> {
+                let raw = reader.read_bits(bits)?;
+                let value = match field.schema {
+                    Schema::Bool => Value::Bool(bool::from_bits(raw)?),
+                    _ => Value::UInt(raw.into()),
+                };
+                group.push((field.name.as_str(), value));
+                Ok(())
+            };
+            let mut result = read(first, bits);
+            while let Some(next) = fields.next_if(|next| next.bits.is_some()) {
+                result = result.and_then(|()| read(next, next.bits.unwrap_or_default()));
+            }
+            if let Err(err) = result.and_then(|()| reader.finish()) {
+                self.failed = true;
+                values.push((first.name.as_str(), Value::Error(err, start)));
+                return Ok(Value::Struct(&def.name, values));
+            }
+            values.extend(group);
+        }
+        self.de.skip(def.reserve)?;
+        Ok(Value::Struct(&def.name, values))
+    }
+}
+
+struct Printer {
+    out: String,
+    json: bool,
+}
+
+impl Printer {
+    fn value(&mut self, value: &Value<'_>, indent: usize) {
+        // Writing to a `String` can't fail.
+        let _ = match value {
+            Value::UInt(value) => write!(self.out, "{}", value),
+            Value::Int(value) => write!(self.out, "{}", value),
+            Value::Bool(value) => write!(self.out, "{}", value),
+            Value::F32(value) => self.float(*value as f64, format_args!("{:?}", value)),
+            Value::F64(value) => self.float(*value, format_args!("{:?}", value)),
+            Value::Str(value) => {
+                self.string(value);
+                Ok(())
+            }
+            Value::Bytes(bytes) => {
+                self.out.push('[');
+                for (i, byte) in bytes.iter().enumerate() {
+                    let _ = write!(self.out, "{}{}", if i == 0 { "" } else { ", " }, byte);
+                }
+                self.out.push(']');
+                Ok(())
+            }
+            Value::List(items) => {
+                self.seq(('[', ']'), items.iter().map(|item| (None, item)), indent);
+                Ok(())
+            }
+            Value::Tuple(items) => {
+                let delims = if self.json { ('[', ']') } else { ('(', ')') };
+                self.seq(delims, items.iter().map(|item| (None, item)), indent);
+                Ok(())
+            }
+            Value::Struct(name, fields) => {
+                let delims = if self.json {
+                    ('{', '}')
+                } else {
+                    self.out.push_str(name);
+                    ('(', ')')
+                };
+                self.seq(delims, fields.iter().map(|(name, value)| (Some(*name), value)), indent);
+                Ok(())
+            }
+            Value::Error(err, pos) if self.json => {
+                self.out.push_str("{\"error\": ");
+                self.string(&alloc::format!("{} at byte {}", err, pos));
+                self.out.push('}');
+                Ok(())
+            }
+            Value::Error(err, pos) => write!(self.out, "<error: {} at byte {}>", err, pos),
+        };
+    }
+
+    /// Writes a float, quoting NaN and infinities in JSON, which has no
+    /// literals for them.
+    fn float(&mut self, value: f64, text: core::fmt::Arguments<'_>) -> core::fmt::Result {
+        if self.json && !value.is_finite() {
+            write!(self.out, "\"{}\"", text)
+        } else {
+            self.out.write_fmt(text)
+        }
+    }
+
+    fn string(&mut self, value: &str) {
+        if !self.json {
+            let _ = write!(self.out, "{:?}", value);
+            return;
+        }
+        self.out.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => self.out.push_str("\\\""),
+                '\\' => self.out.push_str("\\\\"),
+                '\n' => self.out.push_str("\\n"),
+                c if (c as u32) < 0x20 => {
+                    let _ = write!(self.out, "\\u{:04x}", c as u32);
+                }
+                c => self.out.push(c),
+            }
+        }
+        self.out.push('"');
+    }
+
+    /// Writes a list, tuple or struct. Sequences of plain values fit on one
+    /// line, anything else gets a line per item.
+    fn seq<'v, 's: 'v>(
+        &mut self,
+        (open, close): (char, char),
+        items: impl ExactSizeIterator<Item = (Option<&'v str>, &'v Value<'s>)> + Clone,
+        indent: usize,
+    ) {
+        self.out.push(open);
+        let inline = items.clone().all(|(name, value)| name.is_none() && !value.is_nested());
+        let step = if self.json { 2 } else { 4 };
+        let count = items.len();
+        for (i, (name, value)) in items.enumerate() {
+            if inline {
+                if i > 0 {
+                    self.out.push_str(", ");
+                }
+            } else {
+                self.out.push('\n');
+                self.out.extend(core::iter::repeat_n(' ', indent + step));
+            }
+            match name {
+                Some(name) if self.json => {
+                    self.string(name);
+                    self.out.push_str(": ");
+                }
+                Some(name) => {
+                    self.out.push_str(name);
+                    self.out.push_str(": ");
+                }
+                None => {}
+            }
+            self.value(value, indent + step);
+            // RON allows a trailing comma, JSON doesn't
+            if !inline && (!self.json || i + 1 < count) {
+                self.out.push(',');
+            }
+        }
+        if !inline && count > 0 {
+            self.out.push('\n');
+            self.out.extend(core::iter::repeat_n(' ', indent));
+        }
+        self.out.push(close);
+    }
+}
+
+impl Value<'_> {
+    fn is_nested(&self) -> bool {
+        matches!(self, Value::List(_) | Value::Tuple(_) | Value::Struct(..))
+    }
+}
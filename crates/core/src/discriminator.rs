@@ -0,0 +1,213 @@
+//! 8-byte type discriminators for account data.
+//!
+//! A program that decodes any account it is handed as any layout that fits
+//! can be tricked into reading one account type as another. Prefixing each
+//! account with its type's [`JaguarAccount::DISCRIMINATOR`] closes that hole
+//! without Anchor: [`serialize_with_discriminator`] writes the tag and
+//! [`deserialize_checked`] refuses data carrying any other tag, failing with
+//! `DiscriminatorMismatch`.
+//!
+//! Discriminators are the first 8 bytes of `sha256("account:<Name>")`, as in
+//! Anchor, so the two can read each other's account headers. They are
+//! constants, computed at compile time by the const [`discriminator_of`].
+//! `#[derive(JaguarAccount)]` names the type as written in its definition,
+//! or as set with `#[jaguar(name = "...")]` for types renamed since their
+//! accounts were created. Generic types implement [`JaguarAccount`] by hand,
+//! with a distinct name for each instantiation.
+//!
+//! Events logged for indexers are tagged the same way with their
+//! [`JaguarEvent::DISCRIMINATOR`], from `sha256("event:<Name>")` as in
+//! Anchor, by [`serialize_event`] and [`deserialize_event`].
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::{
+//!     deserialize_checked, serialize_with_discriminator, JaguarAccount, JaguarDeserialize, JaguarSerialize, SerError,
+//! };
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, JaguarAccount, Debug, PartialEq)]
+//! struct Vault {
+//!     amount: u64,
+//! }
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, JaguarAccount, Debug, PartialEq)]
+//! struct Config {
+//!     fee: u64,
+//! }
+//!
+//! let data = serialize_with_discriminator(&Vault { amount: 5 }).unwrap();
+//! assert_eq!(data[..8], Vault::DISCRIMINATOR);
+//! assert_eq!(Vault::DISCRIMINATOR, jaguar::discriminator_of("Vault"));
+//! assert_eq!(deserialize_checked::<Vault>(&data).unwrap(), Vault { amount: 5 });
+//! assert_eq!(deserialize_checked::<Config>(&data).err(), Some(SerError::DiscriminatorMismatch));
+//! ```
+
+use crate::{JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+use alloc::vec::Vec;
+
+/// Length of a discriminator in bytes.
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+/// A type stored in accounts behind a discriminator.
+pub trait JaguarAccount {
+    /// The tag written before the type's data, usually [`discriminator_of`]
+    /// its name.
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN];
+}
+
+/// A type logged as an event behind a discriminator.
+pub trait JaguarEvent {
+    /// The tag written before the event's data, usually
+    /// [`event_discriminator_of`] its name.
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN];
+}
+
+/// Returns the discriminator of `name`: the first 8 bytes of
+/// `sha256("account:" + name)`.
+pub const fn discriminator_of(name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    tag_of(b"account:", name)
+}
+
+/// Returns the event discriminator of `name`: the first 8 bytes of
+/// `sha256("event:" + name)`.
+pub const fn event_discriminator_of(name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    tag_of(b"event:", name)
+}
+
+/// Returns the first 8 bytes of `sha256(namespace + name)`, the tag shared
+/// by account and event discriminators and envelope type tags.
+pub(crate) const fn tag_of(namespace: &[u8], name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    let hash = sha256(namespace, name.as_bytes());
+    let mut out = [0u8; DISCRIMINATOR_LEN];
+    let mut i = 0;
+    while i < DISCRIMINATOR_LEN {
+        out[i] = hash[i];
+        i += 1;
+    }
+    out
+}
+
+/// Serializes `value` behind the discriminator of `T`.
+pub fn serialize_with_discriminator<T: JaguarSerialize + JaguarAccount>(value: &T) -> Result<Vec<u8>, SerError> {
+    let mut ser = JaguarSerializer::new();
+    ser.write_raw(&T::DISCRIMINATOR)?;
+    value.serialize(&mut ser)?;
+    Ok(ser.finish())
+}
+
+/// Deserializes a `T` written by [`serialize_with_discriminator`], failing
+/// with `DiscriminatorMismatch` if `data` starts with any other discriminator.
+pub fn deserialize_checked<'a, T: JaguarDeserialize<'a> + JaguarAccount>(data: &'a [u8]) -> Result<T, SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    if *de.read_array_ref::<DISCRIMINATOR_LEN>()? != T::DISCRIMINATOR {
+        return Err(SerError::DiscriminatorMismatch);
+    }
+    de.decode_traced::<T>()
+}
+
+/// Serializes `event` behind the event discriminator of `T`.
+pub fn serialize_event<T: JaguarSerialize + JaguarEvent>(event: &T) -> Result<Vec<u8>, SerError> {
+    let mut ser = JaguarSerializer::new();
+    ser.write_raw(&T::DISCRIMINATOR)?;
+    event.serialize(&mut ser)?;
+    Ok(ser.finish())
+}
+
+/// Deserializes a `T` written by [`serialize_event`], failing with
+/// `DiscriminatorMismatch` if `data` is tagged as any other event.
+pub fn deserialize_event<'a, T: JaguarDeserialize<'a> + JaguarEvent>(data: &'a [u8]) -> Result<T, SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    if *de.read_array_ref::<DISCRIMINATOR_LEN>()? != T::DISCRIMINATOR {
+        return Err(SerError::DiscriminatorMismatch);
+    }
+    de.decode_traced::<T>()
+}
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5, 0xd807aa98,
+    0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786,
+    0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8,
+    0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13,
+    0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819,
+    0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a,
+    0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+    0xc67178f2,
+];
+
+/// SHA-256 of `prefix` followed by `data`, usable in constants.
+const fn sha256(prefix: &[u8], data: &[u8]) -> [u8; 32] {
+    let len = prefix.len() + data.len();
+    // The message, a 0x80 byte and the 8-byte bit length, rounded up to
+    // whole 64-byte blocks.
+    let blocks = (len + 9).div_ceil(64);
+
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+    let mut block = 0;
+    while block < blocks {
+        let mut w = [0u32; 64];
+        let mut i = 0;
+        while i < 64 {
+            let pos = block * 64 + i;
+            let byte = if pos < prefix.len() {
+                prefix[pos]
+            } else if pos < len {
+                data[pos - prefix.len()]
+            } else if pos == len {
+                0x80
+            } else if pos >= blocks * 64 - 8 {
+                ((len as u64 * 8) >> ((blocks * 64 - 1 - pos) * 8)) as u8
+            } else {
+                0
+            };
+            w[i / 4] |= (byte as u32) << ((3 - i % 4) * 8);
+            i += 1;
+        }
+        let mut i = 16;
+        while i < 64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+            i += 1;
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        let mut i = 0;
+        while i < 64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+            i += 1;
+        }
+        let round = [a, b, c, d, e, f, g, h];
+        let mut i = 0;
+        while i < 8 {
+            state[i] = state[i].wrapping_add(round[i]);
+            i += 1;
+        }
+        block += 1;
+    }
+
+    let mut out = [0u8; 32];
+    let mut i = 0;
+    while i < 32 {
+        out[i] = (state[i / 4] >> ((3 - i % 4) * 8)) as u8;
+        i += 1;
+    }
+    out
+}
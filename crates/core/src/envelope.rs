@@ -0,0 +1,128 @@
+//! Versioned, type-tagged messages.
+//!
+//! A queue carrying several message types needs to know what each message
+//! is before decoding it, and a consumer running an older build needs to
+//! notice messages written under a newer format instead of misreading them.
+//! An [`Envelope`] carries both: the format version, the
+//! [`JaguarMessage::TYPE_TAG`] of the payload's type, and the payload itself.
+//!
+//! Type tags are the first 8 bytes of `sha256("message:<Name>")`, from
+//! [`message_tag_of`]. `#[derive(JaguarMessage)]` hashes the type's name,
+//! or the one set with `#[jaguar(name = "...")]`, so the tag survives
+//! moving or renaming the type. Generic types implement [`JaguarMessage`] by
+//! hand, with a distinct name for each instantiation.
+//!
+//! [`Envelope::wrap`] encodes a value into an envelope. Decoding an
+//! `Envelope` borrows its payload, so a consumer can route on
+//! [`Envelope::is`] before paying for the decode, and
+//! [`Envelope::unwrap_checked`] fails with `VersionMismatch` or
+//! `DiscriminatorMismatch` rather than decoding the wrong thing.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::{Envelope, JaguarDeserialize, JaguarMessage, JaguarSerialize, SerError};
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, JaguarMessage, Debug, PartialEq)]
+//! struct Deposit {
+//!     amount: u64,
+//! }
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, JaguarMessage, Debug, PartialEq)]
+//! #[jaguar(name = "Withdrawal")]
+//! struct Withdraw {
+//!     amount: u64,
+//! }
+//!
+//! let data = Envelope::wrap(&Deposit { amount: 5 }).unwrap();
+//!
+//! let envelope: Envelope = jaguar::deserialize(&data).unwrap();
+//! assert!(envelope.is::<Deposit>());
+//! assert_eq!(envelope.unwrap_checked::<Deposit>().unwrap(), Deposit { amount: 5 });
+//! assert_eq!(envelope.unwrap_checked::<Withdraw>().err(), Some(SerError::DiscriminatorMismatch));
+//! ```
+
+use crate::discriminator::{tag_of, DISCRIMINATOR_LEN};
+use crate::{JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError, FORMAT_VERSION};
+use alloc::vec::Vec;
+
+/// Version written into new envelopes.
+pub const ENVELOPE_VERSION: u8 = FORMAT_VERSION as u8;
+
+/// A type carried in [`Envelope`]s, told apart from the other message types
+/// by its tag.
+pub trait JaguarMessage {
+    /// The tag written into envelopes holding this type, usually
+    /// [`message_tag_of`] its name.
+    const TYPE_TAG: [u8; DISCRIMINATOR_LEN];
+}
+
+/// Returns the type tag of `name`: the first 8 bytes of
+/// `sha256("message:" + name)`.
+pub const fn message_tag_of(name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    tag_of(b"message:", name)
+}
+
+/// A message tagged with its format version and payload type.
+///
+/// Encoded as the version byte, the 8-byte type tag, then the payload as
+/// length-prefixed bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Envelope<'a> {
+    /// Format version the payload was written under.
+    pub version: u8,
+    /// [`JaguarMessage::TYPE_TAG`] of the payload's type.
+    pub type_tag: [u8; DISCRIMINATOR_LEN],
+    /// The encoded payload.
+    pub payload: &'a [u8],
+}
+
+impl<'a> Envelope<'a> {
+    /// Serializes `value` inside an envelope tagged with the current version
+    /// and the type tag of `T`.
+    pub fn wrap<T: JaguarSerialize + JaguarMessage>(value: &T) -> Result<Vec<u8>, SerError> {
+        let payload = crate::serialize(value)?;
+        crate::serialize(&Envelope { version: ENVELOPE_VERSION, type_tag: T::TYPE_TAG, payload: &payload })
+    }
+
+    /// Returns whether the payload is tagged as a `T`.
+    #[inline]
+    pub fn is<T: JaguarMessage + ?Sized>(&self) -> bool {
+        self.type_tag == T::TYPE_TAG
+    }
+
+    /// Deserializes the payload as a `T`, failing with `VersionMismatch` if
+    /// it was written under another version and `DiscriminatorMismatch` if
+    /// it is tagged as another type.
+    pub fn unwrap_checked<T: JaguarDeserialize<'a> + JaguarMessage>(&self) -> Result<T, SerError> {
+        if self.version != ENVELOPE_VERSION {
+            return Err(SerError::VersionMismatch);
+        }
+        if !self.is::<T>() {
+            return Err(SerError::DiscriminatorMismatch);
+        }
+        crate::deserialize(self.payload)
+    }
+}
+
+impl JaguarSerialize for Envelope<'_> {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_u8(self.version)?;
+        ser.write_raw(&self.type_tag)?;
+        ser.write_bytes(self.payload)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for Envelope<'a> {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(Envelope {
+            version: de.read_u8()?,
+            type_tag: *de.read_array_ref::<DISCRIMINATOR_LEN>()?,
+            payload: de.read_bytes()?,
+        })
+    }
+}
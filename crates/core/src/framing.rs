@@ -0,0 +1,367 @@
+//! Length-prefixed message framing for byte streams.
+//!
+//! TCP sockets and serial links deliver bytes, not messages, and a single
+//! read can end anywhere inside one. Each frame here is a varint length
+//! followed by that many bytes of Jaguar-encoded message, the same layout as
+//! a [section](crate::JaguarSerializer::begin_section).
+//!
+//! [`write_frame`] and [`FrameDecoder`] do the framing without any I/O, so
+//! they work on `no_std` devices fed by a UART or DMA buffer. With the `std`
+//! feature, [`FramedWriter`] and [`FramedReader`] wrap `std::io` streams, and
+//! with the `async` feature, [`AsyncFramedWriter`] and [`AsyncFramedReader`]
+//! wrap `tokio::io` ones.
+//!
+//! A frame longer than the decoder's maximum fails with `InvalidLength`
+//! before any of it is buffered. After an error the stream can't be
+//! resynchronized, as the start of the next frame is unknown.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::framing::{write_frame, FrameDecoder};
+//! use jaguar::JaguarSerializer;
+//!
+//! let mut ser = JaguarSerializer::new();
+//! write_frame(&mut ser, &300u64).unwrap();
+//! write_frame(&mut ser, &"ping".to_string()).unwrap();
+//! let wire = ser.finish();
+//!
+//! let mut decoder = FrameDecoder::new();
+//! decoder.push(&wire[..4]);
+//! assert_eq!(decoder.decode::<u64>().unwrap(), Some(300));
+//! assert_eq!(decoder.decode::<String>().unwrap(), None);
+//! decoder.push(&wire[4..]);
+//! assert_eq!(decoder.decode::<String>().unwrap().as_deref(), Some("ping"));
+//! ```
+
+use crate::{JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+use alloc::vec::Vec;
+
+/// The largest frame a new [`FrameDecoder`] accepts, 1 MiB.
+pub const DEFAULT_MAX_FRAME_LEN: usize = 1 << 20;
+
+/// Writes `value` as one frame.
+#[inline]
+pub fn write_frame<T: JaguarSerialize + ?Sized>(ser: &mut JaguarSerializer, value: &T) -> Result<(), SerError> {
+    let section = ser.begin_section()?;
+    value.serialize(ser)?;
+    ser.end_section(section)
+}
+
+/// Splits a stream of bytes, arriving in arbitrary pieces, into frames.
+#[derive(Debug, Clone)]
+pub struct FrameDecoder {
+    buf: Vec<u8>,
+    start: usize,
+    max_len: usize,
+}
+
+impl Default for FrameDecoder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameDecoder {
+    /// Creates a decoder accepting frames up to [`DEFAULT_MAX_FRAME_LEN`].
+    #[inline]
+    pub fn new() -> Self {
+        Self { buf: Vec::new(), start: 0, max_len: DEFAULT_MAX_FRAME_LEN }
+    }
+
+    /// Sets the largest frame body accepted, in bytes.
+    #[inline]
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Appends bytes received from the stream.
+    pub fn push(&mut self, bytes: &[u8]) {
+        if self.start > 0 {
+            self.buf.drain(..self.start);
+            self.start = 0;
+        }
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Returns the number of received bytes not yet returned in a frame.
+    #[inline]
+    pub fn buffered(&self) -> usize {
+        self.buf.len() - self.start
+    }
+
+    /// Returns the body of the next frame, or `None` if it hasn't fully
+    /// arrived yet.
+    pub fn next_frame(&mut self) -> Result<Option<&[u8]>, SerError> {
+        let mut de = JaguarDeserializer::new(&self.buf[self.start..]);
+        let len = match de.read_varint() {
+            Ok(len) => len,
+            Err(SerError::BufferTooSmall) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        if len > self.max_len as u64 {
+            return Err(SerError::InvalidLength);
+        }
+        let len = len as usize;
+        if de.remaining() < len {
+            return Ok(None);
+        }
+        let body = self.start + de.position();
+        self.start = body + len;
+        Ok(Some(&self.buf[body..self.start]))
+    }
+
+    /// Decodes the next frame, or returns `None` if it hasn't fully arrived
+    /// yet.
+    #[inline]
+    pub fn decode<'a, T: JaguarDeserialize<'a>>(&'a mut self) -> Result<Option<T>, SerError> {
+        match self.next_frame()? {
+            Some(frame) => crate::deserialize(frame).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::io::{FramedReader, FramedWriter};
+
+#[cfg(feature = "async")]
+pub use self::async_io::{AsyncFramedReader, AsyncFramedWriter};
+
+#[cfg(feature = "std")]
+mod io {
+    use super::{write_frame, FrameDecoder};
+    use crate::{JaguarDeserializeOwned, JaguarSerialize, JaguarSerializer};
+    use std::io::{self, Read, Write};
+
+    /// Writes framed messages to a `std::io::Write`.
+    ///
+    /// Each message is encoded into a reused buffer, then written with a
+    /// single `write_all`.
+    pub struct FramedWriter<W> {
+        inner: W,
+        ser: JaguarSerializer,
+    }
+
+    impl<W: Write> FramedWriter<W> {
+        /// Wraps `inner`.
+        #[inline]
+        pub fn new(inner: W) -> Self {
+            Self { inner, ser: JaguarSerializer::new() }
+        }
+
+        /// Writes `value` as one frame.
+        pub fn write<T: JaguarSerialize + ?Sized>(&mut self, value: &T) -> io::Result<()> {
+            self.ser.reset();
+            write_frame(&mut self.ser, value)?;
+            self.inner.write_all(self.ser.data())
+        }
+
+        /// Flushes the underlying writer.
+        #[inline]
+        pub fn flush(&mut self) -> io::Result<()> {
+            self.inner.flush()
+        }
+
+        #[inline]
+        pub fn get_ref(&self) -> &W {
+            &self.inner
+        }
+
+        #[inline]
+        pub fn get_mut(&mut self) -> &mut W {
+            &mut self.inner
+        }
+
+        #[inline]
+        pub fn into_inner(self) -> W {
+            self.inner
+        }
+    }
+
+    /// Reads framed messages from a `std::io::Read`, however the bytes are
+    /// split across reads.
+    pub struct FramedReader<R> {
+        inner: R,
+        decoder: FrameDecoder,
+    }
+
+    impl<R: Read> FramedReader<R> {
+        /// Wraps `inner`, accepting frames up to
+        /// [`DEFAULT_MAX_FRAME_LEN`](super::DEFAULT_MAX_FRAME_LEN).
+        #[inline]
+        pub fn new(inner: R) -> Self {
+            Self::with_decoder(inner, FrameDecoder::new())
+        }
+
+        /// Wraps `inner`, splitting frames with `decoder`.
+        #[inline]
+        pub fn with_decoder(inner: R, decoder: FrameDecoder) -> Self {
+            Self { inner, decoder }
+        }
+
+        /// Reads the next message, or `None` if the stream ended cleanly
+        /// between frames.
+        ///
+        /// A stream ending partway through a frame fails with
+        /// `UnexpectedEof`, and a malformed frame with `InvalidData`.
+        pub fn read<T: JaguarDeserializeOwned>(&mut self) -> io::Result<Option<T>> {
+            let mut chunk = [0u8; 4096];
+            loop {
+                if let Some(value) = self.decoder.decode()? {
+                    return Ok(Some(value));
+                }
+                let read = match self.inner.read(&mut chunk) {
+                    Ok(read) => read,
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => return Err(err),
+                };
+                if read == 0 {
+                    return match self.decoder.buffered() {
+                        0 => Ok(None),
+                        _ => Err(io::ErrorKind::UnexpectedEof.into()),
+                    };
+                }
+                self.decoder.push(&chunk[..read]);
+            }
+        }
+
+        #[inline]
+        pub fn get_ref(&self) -> &R {
+            &self.inner
+        }
+
+        #[inline]
+        pub fn get_mut(&mut self) -> &mut R {
+            &mut self.inner
+        }
+
+        /// Returns the underlying reader. Bytes already read past the last
+        /// returned frame are lost.
+        #[inline]
+        pub fn into_inner(self) -> R {
+            self.inner
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+mod async_io {
+    use super::{write_frame, FrameDecoder};
+    use crate::{JaguarDeserializeOwned, JaguarSerialize, JaguarSerializer};
+    use std::io;
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+    /// Writes framed messages to a `tokio::io::AsyncWrite`.
+    ///
+    /// Each message is encoded into a reused buffer, then written with a
+    /// single `write_all`.
+    pub struct AsyncFramedWriter<W> {
+        inner: W,
+        ser: JaguarSerializer,
+    }
+
+    impl<W: AsyncWrite + Unpin> AsyncFramedWriter<W> {
+        /// Wraps `inner`.
+        #[inline]
+        pub fn new(inner: W) -> Self {
+            Self { inner, ser: JaguarSerializer::new() }
+        }
+
+        /// Writes `value` as one frame.
+        pub async fn write<T: JaguarSerialize + ?Sized>(&mut self, value: &T) -> io::Result<()> {
+            self.ser.reset();
+            write_frame(&mut self.ser, value)?;
+            self.inner.write_all(self.ser.data()).await
+        }
+
+        /// Flushes the underlying writer.
+        #[inline]
+        pub async fn flush(&mut self) -> io::Result<()> {
+            self.inner.flush().await
+        }
+
+        #[inline]
+        pub fn get_ref(&self) -> &W {
+            &self.inner
+        }
+
+        #[inline]
+        pub fn get_mut(&mut self) -> &mut W {
+            &mut self.inner
+        }
+
+        #[inline]
+        pub fn into_inner(self) -> W {
+            self.inner
+        }
+    }
+
+    /// Reads framed messages from a `tokio::io::AsyncRead`, however the bytes
+    /// are split across reads.
+    pub struct AsyncFramedReader<R> {
+        inner: R,
+        decoder: FrameDecoder,
+    }
+
+    impl<R: AsyncRead + Unpin> AsyncFramedReader<R> {
+        /// Wraps `inner`, accepting frames up to
+        /// [`DEFAULT_MAX_FRAME_LEN`](super::DEFAULT_MAX_FRAME_LEN).
+        #[inline]
+        pub fn new(inner: R) -> Self {
+            Self::with_decoder(inner, FrameDecoder::new())
+        }
+
+        /// Wraps `inner`, splitting frames with `decoder`.
+        #[inline]
+        pub fn with_decoder(inner: R, decoder: FrameDecoder) -> Self {
+            Self { inner, decoder }
+        }
+
+        /// Reads the next message, or `None` if the stream ended cleanly
+        /// between frames.
+        ///
+        /// A stream ending partway through a frame fails with
+        /// `UnexpectedEof`, and a malformed frame with `InvalidData`.
+        ///
+        /// This is cancel safe: bytes are buffered as soon as they are read,
+        /// so it can be raced in `tokio::select!` without losing a message.
+        pub async fn read<T: JaguarDeserializeOwned>(&mut self) -> io::Result<Option<T>> {
+            let mut chunk = [0u8; 4096];
+            loop {
+                if let Some(value) = self.decoder.decode()? {
+                    return Ok(Some(value));
+                }
+                let read = self.inner.read(&mut chunk).await?;
+                if read == 0 {
+                    return match self.decoder.buffered() {
+                        0 => Ok(None),
+                        _ => Err(io::ErrorKind::UnexpectedEof.into()),
+                    };
+                }
+                self.decoder.push(&chunk[..read]);
+            }
+        }
+
+        #[inline]
+        pub fn get_ref(&self) -> &R {
+            &self.inner
+        }
+
+        #[inline]
+        pub fn get_mut(&mut self) -> &mut R {
+            &mut self.inner
+        }
+
+        /// Returns the underlying reader. Bytes already read past the last
+        /// returned frame are lost.
+        #[inline]
+        pub fn into_inner(self) -> R {
+            self.inner
+        }
+    }
+}
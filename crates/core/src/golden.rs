@@ -0,0 +1,142 @@
+//! Golden vectors: committed encodings that pin the wire format.
+//!
+//! Data written on-chain today has to decode with every later release, so
+//! encodings can't change by accident. A golden fixture records the bytes a
+//! value encodes to, along with the [`FORMAT_VERSION`] they were written
+//! under. [`encode_golden`] produces one to commit next to the tests, and
+//! [`verify_golden`] checks that the value still encodes to exactly those
+//! bytes and that the bytes still decode, in canonical mode, to the value.
+//!
+//! Fixtures are text, so changes to them show up in review:
+//!
+//! ```text
+//! jaguar-golden 1
+//! ac02 0568656c6c6f
+//! ```
+//!
+//! The first line is the format version. The rest is the encoding in hex,
+//! with whitespace ignored.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::golden::{encode_golden, verify_golden, GoldenError};
+//!
+//! let fixture = encode_golden(&(300u32, String::from("hello"))).unwrap();
+//! assert_eq!(fixture, "jaguar-golden 1\nac020568656c6c6f\n");
+//!
+//! assert_eq!(verify_golden(&fixture, &(300u32, String::from("hello"))), Ok(()));
+//! assert!(matches!(
+//!     verify_golden(&fixture, &(301u32, String::from("hello"))),
+//!     Err(GoldenError::Mismatch { .. })
+//! ));
+//! ```
+
+use crate::{deserialize_canonical, JaguarDeserializeOwned, JaguarSerialize, SerError, FORMAT_VERSION};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+const HEADER: &str = "jaguar-golden ";
+
+/// Why a value no longer matches its golden fixture.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GoldenError {
+    /// The fixture isn't a header line followed by hex bytes.
+    Malformed,
+    /// The fixture was recorded under another format version.
+    Version(u32),
+    /// The value failed to serialize.
+    Encode(SerError),
+    /// The value encodes to different bytes than the fixture's.
+    Mismatch { expected: Vec<u8>, actual: Vec<u8> },
+    /// The fixture's bytes failed to deserialize.
+    Decode(SerError),
+    /// The fixture's bytes deserialize to a different value.
+    Changed,
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::Malformed => f.write_str("malformed golden fixture"),
+            GoldenError::Version(version) => {
+                write!(f, "fixture is format version {}, expected {}", version, FORMAT_VERSION)
+            }
+            GoldenError::Encode(err) => write!(f, "failed to serialize: {}", err),
+            GoldenError::Mismatch { expected, actual } => {
+                write!(f, "encoding changed: expected {}, got {}", Hex(expected), Hex(actual))
+            }
+            GoldenError::Decode(err) => write!(f, "failed to deserialize fixture: {}", err),
+            GoldenError::Changed => f.write_str("fixture deserializes to a different value"),
+        }
+    }
+}
+
+impl core::error::Error for GoldenError {}
+
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+/// Encodes `value` as a golden fixture under the current [`FORMAT_VERSION`].
+pub fn encode_golden<T: JaguarSerialize>(value: &T) -> Result<String, SerError> {
+    let data = crate::serialize(value)?;
+    let mut fixture = String::with_capacity(HEADER.len() + 4 + data.len() * 2);
+    // Writing to a `String` can't fail.
+    let _ = writeln!(fixture, "{}{}\n{}", HEADER, FORMAT_VERSION, Hex(&data));
+    Ok(fixture)
+}
+
+/// Checks that `value` encodes to exactly the bytes in `fixture`, and that
+/// they decode back to `value` in canonical mode.
+pub fn verify_golden<T>(fixture: &str, value: &T) -> Result<(), GoldenError>
+where
+    T: JaguarSerialize + JaguarDeserializeOwned + PartialEq,
+{
+    let expected = parse(fixture)?;
+    let actual = crate::serialize(value).map_err(GoldenError::Encode)?;
+    if actual != expected {
+        return Err(GoldenError::Mismatch { expected, actual });
+    }
+    let decoded: T = deserialize_canonical(&expected).map_err(GoldenError::Decode)?;
+    if decoded != *value {
+        return Err(GoldenError::Changed);
+    }
+    Ok(())
+}
+
+/// Returns the bytes recorded in `fixture`.
+fn parse(fixture: &str) -> Result<Vec<u8>, GoldenError> {
+    let (header, hex) = fixture.split_once('\n').unwrap_or((fixture, ""));
+    let version = header
+        .trim_end()
+        .strip_prefix(HEADER)
+        .and_then(|version| version.parse().ok())
+        .ok_or(GoldenError::Malformed)?;
+    if version != FORMAT_VERSION {
+        return Err(GoldenError::Version(version));
+    }
+
+    let digits: Vec<u8> = hex.bytes().filter(|byte| !byte.is_ascii_whitespace()).collect();
+    if !digits.len().is_multiple_of(2) {
+        return Err(GoldenError::Malformed);
+    }
+    digits
+        .chunks_exact(2)
+        .map(|pair| match (nibble(pair[0]), nibble(pair[1])) {
+            (Some(high), Some(low)) => Ok(high << 4 | low),
+            _ => Err(GoldenError::Malformed),
+        })
+        .collect()
+}
+
+fn nibble(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|value| value as u8)
+}
@@ -0,0 +1,98 @@
+//! Field order of structs derived with `#[jaguar(optimize_layout)]`.
+//!
+//! Fields normally encode in declaration order, so a lazy view reaching a
+//! field has to skip every variable-length field declared before it. With
+//! `optimize_layout`, the derives encode fields grouped by [`FieldClass`]:
+//! bit-packed fields, then fixed-size ones, then varint scalars, then
+//! everything else, keeping declaration order within each group. Every field
+//! ahead of the first scalar then sits at a constant offset.
+//!
+//! The grouping only looks at field types by name (`u8`, `bool`, `[u8; N]`,
+//! the integer and float primitives...) and at `#[jaguar(fixed)]`, so aliases
+//! and other types count as variable. Since a new field can land ahead of existing ones, such structs
+//! can't be extended by appending fields, and `default_on_eof` is rejected.
+//!
+//! `JaguarSerialize` implements [`JaguarLayout`] for these structs. Commit
+//! the [`manifest`] next to the tests, so any change to the encoded order
+//! shows up in review.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::layout::{manifest, FieldClass, JaguarLayout};
+//! use jaguar::{JaguarDeserialize, JaguarSerialize};
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+//! #[jaguar(optimize_layout)]
+//! struct Vault {
+//!     name: String,
+//!     amount: u64,
+//!     owner: [u8; 32],
+//!     bump: u8,
+//! }
+//!
+//! assert_eq!(Vault::LAYOUT[0].name, "owner");
+//! assert_eq!(Vault::LAYOUT[2].class, FieldClass::Scalar);
+//! assert_eq!(manifest::<Vault>(), "fixed owner\nfixed bump\nscalar amount\nvariable name\n");
+//!
+//! let vault = Vault { name: "main".into(), amount: 5, owner: [1; 32], bump: 255 };
+//! let data = jaguar::serialize(&vault).unwrap();
+//! assert_eq!(data[..32], [1; 32]);
+//! assert_eq!(jaguar::deserialize::<Vault>(&data).unwrap(), vault);
+//! ```
+
+use alloc::string::String;
+
+/// The groups `optimize_layout` orders fields by, in encoding order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FieldClass {
+    /// A `#[jaguar(bits = N)]` field, packed with the others.
+    Bits,
+    /// A field that always encodes to the same number of bytes.
+    Fixed,
+    /// A varint-encoded integer or a float, with a small upper bound.
+    Scalar,
+    /// Anything else, such as strings, vectors and nested structs.
+    Variable,
+}
+
+impl FieldClass {
+    /// The name used in [`manifest`].
+    pub fn name(self) -> &'static str {
+        match self {
+            FieldClass::Bits => "bits",
+            FieldClass::Fixed => "fixed",
+            FieldClass::Scalar => "scalar",
+            FieldClass::Variable => "variable",
+        }
+    }
+}
+
+/// A field of a [`JaguarLayout`] struct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutField {
+    /// The field's name, or `_N` for tuple struct fields.
+    pub name: &'static str,
+    pub class: FieldClass,
+}
+
+/// The encoded field order of a struct derived with `optimize_layout`.
+pub trait JaguarLayout {
+    /// Every field, in the order it is encoded.
+    const LAYOUT: &'static [LayoutField];
+}
+
+/// Renders the layout of `T` as text, one `<class> <name>` line per field
+/// in encoding order.
+pub fn manifest<T: JaguarLayout + ?Sized>() -> String {
+    let mut out = String::new();
+    for field in T::LAYOUT {
+        out.push_str(field.class.name());
+        out.push(' ');
+        out.push_str(field.name);
+        out.push('\n');
+    }
+    out
+}
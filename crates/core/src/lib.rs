@@ -1,11 +1,26 @@
 #![no_std]
+#![cfg_attr(feature = "safe", forbid(unsafe_code))]
 
 extern crate alloc;
 
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 use alloc::string::String;
+use core::marker::{PhantomData, PhantomPinned};
 use core::mem;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32,
+    NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+#[cfg(not(feature = "safe"))]
 use core::ptr;
+use core::time::Duration;
 
 #[cfg(feature = "derive")]
 pub use jaguar_derive::*;
@@ -13,12 +28,173 @@ pub use jaguar_derive::*;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+pub mod bits;
+#[cfg(feature = "bytes")]
+pub mod bytes;
+pub mod chained;
+pub mod checksum;
+pub mod clock;
+pub mod compat;
+#[cfg(feature = "lz4")]
+pub mod compress;
+#[cfg(feature = "std")]
+pub mod debug;
+pub mod discriminator;
+pub mod envelope;
+pub mod framing;
+pub mod golden;
+pub mod layout;
+#[cfg(feature = "std")]
+pub mod pool;
+#[cfg(not(feature = "safe"))]
+pub mod pod;
+pub mod prefix;
+pub mod records;
+#[cfg(feature = "registry")]
+pub mod registry;
+pub mod schema;
+pub mod streams;
+pub mod view;
+
+#[cfg(feature = "solana")]
+pub mod solana;
+
+#[cfg(feature = "bumpalo")]
+pub use arena::{deserialize_in, JaguarDeserializeIn};
+#[cfg(feature = "lz4")]
+pub use compress::{deserialize_compressed, serialize_compressed};
+pub use discriminator::{
+    deserialize_checked, deserialize_event, discriminator_of, event_discriminator_of, serialize_event,
+    serialize_with_discriminator, JaguarAccount, JaguarEvent,
+};
+pub use envelope::{message_tag_of, Envelope, JaguarMessage};
+pub use prefix::LenPrefix;
+pub use schema::JaguarSchema;
+pub use view::JaguarView;
+
+/// Version of the wire format described in `SPEC.md`.
+///
+/// Encodings of existing types only change when this is bumped, which only
+/// happens in a major release. New types and opt-in encodings don't bump it.
+/// The [`golden`] fixtures pin every encoding to the version they were
+/// recorded under.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Re-exports used by derive-generated code.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
+    #[cfg(feature = "bumpalo")]
+    pub use bumpalo::Bump;
+}
+
+/// Errors returned while serializing or deserializing.
+///
+/// Variants with richer diagnostics may be added in minor releases, so code
+/// that needs to stay compatible should match on [`SerError::kind`] or
+/// [`SerError::code`] instead of the variants themselves.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum SerError {
+    /// The input ended early, or the output hit its size limit.
     BufferTooSmall,
+    /// The input is not a valid encoding, or a value cannot be encoded.
     InvalidData,
+    /// A length prefix does not match what the type expects.
     InvalidLength,
+    /// The operation is not supported for this type or width.
     UnsupportedType,
+    /// Values are nested deeper than the deserializer's maximum depth.
+    DepthLimitExceeded,
+    /// The checksum trailer does not match the payload.
+    ChecksumMismatch,
+    /// The data starts with another type's discriminator.
+    DiscriminatorMismatch,
+    /// The data was written under another format version.
+    VersionMismatch,
+    /// Bytes remain after the value, which the reader expected to end the
+    /// input.
+    TrailingBytes,
+}
+
+/// The category of a [`SerError`].
+///
+/// Unlike `SerError` this is exhaustive: new errors always fall into one of
+/// these kinds, so matching on it never breaks across minor releases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Ran out of input or output space.
+    BufferTooSmall,
+    /// The bytes, or the value being written, are malformed.
+    InvalidData,
+    /// The requested encoding is not supported.
+    Unsupported,
+}
+
+impl SerError {
+    /// Returns the category of this error.
+    #[inline]
+    pub const fn kind(&self) -> ErrorKind {
+        match self {
+            SerError::BufferTooSmall => ErrorKind::BufferTooSmall,
+            SerError::InvalidData
+            | SerError::InvalidLength
+            | SerError::DepthLimitExceeded
+            | SerError::ChecksumMismatch
+            | SerError::DiscriminatorMismatch
+            | SerError::VersionMismatch
+            | SerError::TrailingBytes => ErrorKind::InvalidData,
+            SerError::UnsupportedType => ErrorKind::Unsupported,
+        }
+    }
+
+    /// Returns a numeric code identifying this error, e.g. for
+    /// `ProgramError::Custom`.
+    ///
+    /// Codes are never reused or renumbered. New variants get new codes.
+    #[inline]
+    pub const fn code(&self) -> u32 {
+        match self {
+            SerError::BufferTooSmall => 1,
+            SerError::InvalidData => 2,
+            SerError::InvalidLength => 3,
+            SerError::UnsupportedType => 4,
+            SerError::DepthLimitExceeded => 5,
+            SerError::ChecksumMismatch => 6,
+            SerError::DiscriminatorMismatch => 7,
+            SerError::VersionMismatch => 8,
+            SerError::TrailingBytes => 9,
+        }
+    }
+}
+
+impl core::fmt::Display for SerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let message = match self {
+            SerError::BufferTooSmall => "buffer too small",
+            SerError::InvalidData => "invalid data",
+            SerError::InvalidLength => "invalid length",
+            SerError::UnsupportedType => "unsupported type",
+            SerError::DepthLimitExceeded => "depth limit exceeded",
+            SerError::ChecksumMismatch => "checksum mismatch",
+            SerError::DiscriminatorMismatch => "discriminator mismatch",
+            SerError::VersionMismatch => "version mismatch",
+            SerError::TrailingBytes => "trailing bytes",
+        };
+        f.write_str(message)
+    }
+}
+
+impl core::error::Error for SerError {}
+
+#[cfg(feature = "std")]
+impl From<SerError> for std::io::Error {
+    fn from(err: SerError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
 }
 
 /// Compact binary serializer, optimized for resource-constrained environments like
@@ -29,7 +205,7 @@ pub enum SerError {
 /// ### Usage
 /// 
 /// ```rust
-/// use jaguar::{JaguarSerializer, JaguarDeserializer};
+/// use jaguar::{JaguarSerialize, JaguarSerializer};
 ///
 /// let pubkey = [1u8; 32];
 ///
@@ -43,18 +219,25 @@ pub enum SerError {
 /// ### Derive Usage
 /// 
 /// ```rust
+/// use jaguar::{JaguarSerialize, JaguarDeserialize, JaguarSerializer};
+///
 /// #[derive(JaguarSerialize, JaguarDeserialize)]
 /// struct MyStruct {
 ///     pubkey: [u8; 32],
 /// }
 /// 
-/// let my_struct = MyStruct { pubkey };
+/// let my_struct = MyStruct { pubkey: [1u8; 32] };
 /// let mut ser = JaguarSerializer::new();
-/// my_struct.serialize(&mut ser).unwrap().finish();
+/// my_struct.serialize(&mut ser).unwrap();
+/// let data = ser.finish();
 /// ```
 pub struct JaguarSerializer {
     buffer: Vec<u8>,
     pos: usize,
+    strings: Option<BTreeMap<String, u64>>,
+    max_size: usize,
+    growth: Growth,
+    float_dictionary: bool,
 }
 
 /// Deserializer for raw bytes initially serialized by JaguarSerializer.
@@ -68,9 +251,10 @@ pub struct JaguarSerializer {
 /// 
 /// let data = vec![1, 2, 3];
 /// let mut ser = JaguarSerializer::new();
-/// ser.write_u8_slice(&data);
-/// let deser = JaguarDeserializer::new(&ser.finish());
-/// let data = deser.read_u8_slice().unwrap();
+/// ser.write_u8_slice(&data).unwrap();
+/// let bytes = ser.finish();
+/// let mut deser = JaguarDeserializer::new(&bytes);
+/// let data = deser.read_u8_vec().unwrap();
 /// ```
 /// 
 /// --------
@@ -78,20 +262,175 @@ pub struct JaguarSerializer {
 /// ### Derive Usage
 /// 
 /// ```rust
+/// use jaguar::{JaguarSerialize, JaguarDeserialize, JaguarSerializer, JaguarDeserializer};
+///
 /// #[derive(JaguarSerialize, JaguarDeserialize)]
 /// struct MyStruct {
 ///     data: [u8; 3],
 /// }
 /// 
-/// let my_struct = MyStruct { data };
+/// let my_struct = MyStruct { data: [1, 2, 3] };
 /// let mut ser = JaguarSerializer::new();
-/// my_struct.serialize(&mut ser).unwrap().finish();
-/// let deser = JaguarDeserializer::new(&ser.finish());
-/// let data = deser.read_u8_slice().unwrap();
+/// my_struct.serialize(&mut ser).unwrap();
+/// let bytes = ser.finish();
+/// let mut deser = JaguarDeserializer::new(&bytes);
+/// let decoded = MyStruct::deserialize(&mut deser).unwrap();
 /// ```
 pub struct JaguarDeserializer<'a> {
     data: &'a [u8],
     pos: usize,
+    strings: Option<Vec<&'a str>>,
+    canonical: bool,
+    float_dictionary: bool,
+    depth: u32,
+    max_depth: u32,
+    #[cfg(not(feature = "safe"))]
+    trusted: bool,
+}
+
+impl Default for JaguarSerializer {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How a [`JaguarSerializer`] grows its buffer once the initial capacity is
+/// used up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Growth {
+    /// Grow to exactly the size needed. Keeps the footprint minimal, at the
+    /// cost of a reallocation on every write past the capacity.
+    Exact,
+    /// Double the buffer, so writes stay amortized constant time.
+    #[default]
+    Double,
+}
+
+/// Configures a [`JaguarSerializer`], see [`JaguarSerializer::builder`].
+#[derive(Debug, Clone)]
+pub struct SerializerBuilder {
+    capacity: usize,
+    max_size: usize,
+    growth: Growth,
+}
+
+impl SerializerBuilder {
+    /// Sets the initial capacity, 1024 bytes by default. It is clamped to the
+    /// maximum size.
+    #[inline]
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Caps the serialized size, and the memory the buffer may take, at
+    /// `max_size` bytes. See [`JaguarSerializer::with_max_size`].
+    #[inline]
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Sets how the buffer grows, [`Growth::Double`] by default.
+    #[inline]
+    pub fn growth(mut self, growth: Growth) -> Self {
+        self.growth = growth;
+        self
+    }
+
+    /// Creates the configured serializer.
+    #[inline]
+    pub fn build(self) -> JaguarSerializer {
+        JaguarSerializer {
+            growth: self.growth,
+            ..JaguarSerializer::with_capacity(self.capacity.min(self.max_size)).with_max_size(self.max_size)
+        }
+    }
+}
+
+/// Generates the `*_packed` and `*_raw` slice writers for integer types.
+/// 
+/// Packed slices store each element as it is encoded on its own, a varint
+/// (zigzag for signed types). Raw slices store native-endian bytes, which
+/// is a plain copy but always takes the full width.
+macro_rules! int_slice_writes {
+    ($($t:ty => $packed:ident, $raw:ident;)*) => {
+        $(
+            #[doc = concat!("Writes a slice of `", stringify!($t), "` as varints, one per element.")]
+            #[inline]
+            pub fn $packed(&mut self, slice: &[$t]) -> Result<(), SerError> {
+                self.write_varint(slice.len() as u64)?;
+                for value in slice {
+                    value.serialize(self)?;
+                }
+                Ok(())
+            }
+
+            #[doc = concat!("Writes a slice of `", stringify!($t), "` as raw native-endian bytes.")]
+            #[inline]
+            pub fn $raw(&mut self, slice: &[$t]) -> Result<(), SerError> {
+                const WIDTH: usize = mem::size_of::<$t>();
+                self.write_varint(slice.len() as u64)?;
+                self.ensure_space(slice.len().saturating_mul(WIDTH))?;
+                #[cfg(feature = "safe")]
+                for value in slice {
+                    self.put_bytes(&value.to_ne_bytes());
+                }
+                #[cfg(not(feature = "safe"))]
+                self.put_bytes(unsafe { core::slice::from_raw_parts(slice.as_ptr().cast(), slice.len() * WIDTH) });
+                Ok(())
+            }
+        )*
+    };
+}
+
+/// Generates the readers matching `int_slice_writes`.
+macro_rules! int_slice_reads {
+    ($($t:ty => $packed_vec:ident, $packed_into:ident, $raw_vec:ident, $raw_into:ident;)*) => {
+        $(
+            #[doc = concat!("Reads a vector of `", stringify!($t), "` written as varints.")]
+            #[inline]
+            pub fn $packed_vec(&mut self) -> Result<Vec<$t>, SerError> {
+                let mut vec = Vec::new();
+                self.$packed_into(&mut vec)?;
+                Ok(vec)
+            }
+
+            #[doc = concat!("Reads a vector of `", stringify!($t), "` written as varints into `buf`, replacing its")]
+            /// contents but keeping its allocation.
+            #[inline]
+            pub fn $packed_into(&mut self, buf: &mut Vec<$t>) -> Result<(), SerError> {
+                let len = self.read_varint()? as usize;
+                buf.clear();
+                buf.reserve(self.capacity_hint(len));
+                for _ in 0..len {
+                    buf.push(<$t>::deserialize(self)?);
+                }
+                Ok(())
+            }
+
+            #[doc = concat!("Reads a vector of `", stringify!($t), "` written as raw bytes.")]
+            #[inline]
+            pub fn $raw_vec(&mut self) -> Result<Vec<$t>, SerError> {
+                let mut vec = Vec::new();
+                self.$raw_into(&mut vec)?;
+                Ok(vec)
+            }
+
+            #[doc = concat!("Reads a vector of `", stringify!($t), "` written as raw bytes into `buf`, replacing its")]
+            /// contents but keeping its allocation.
+            #[inline]
+            pub fn $raw_into(&mut self, buf: &mut Vec<$t>) -> Result<(), SerError> {
+                const WIDTH: usize = mem::size_of::<$t>();
+                let len = self.read_varint()? as usize;
+                let bytes = self.take_bytes(len.checked_mul(WIDTH).ok_or(SerError::InvalidLength)?)?;
+                buf.clear();
+                buf.extend(bytes.chunks_exact(WIDTH).map(|chunk| <$t>::from_ne_bytes(chunk.try_into().unwrap())));
+                Ok(())
+            }
+        )*
+    };
 }
 
 impl JaguarSerializer {
@@ -107,9 +446,47 @@ impl JaguarSerializer {
         Self {
             buffer: Vec::with_capacity(capacity),
             pos: 0,
+            max_size: usize::MAX,
+            growth: Growth::Double,
+            strings: None,
+            float_dictionary: false,
         }
     }
 
+    /// Configures a serializer's capacity, maximum size and growth strategy,
+    /// e.g. for devices that need a hard memory cap.
+    /// 
+    /// The buffer never grows past the maximum size: a write that would need
+    /// more fails with `BufferTooSmall` instead.
+    /// 
+    /// --------
+    /// 
+    /// ### Usage
+    /// 
+    /// ```rust
+    /// use jaguar::{Growth, JaguarSerializer, SerError};
+    ///
+    /// let mut ser = JaguarSerializer::builder().capacity(16).max_size(64).growth(Growth::Exact).build();
+    /// ser.write_raw(&[7; 40]).unwrap();
+    /// assert_eq!(ser.write_raw(&[7; 40]), Err(SerError::BufferTooSmall));
+    /// assert_eq!(ser.finish().len(), 40);
+    /// ```
+    #[inline]
+    pub fn builder() -> SerializerBuilder {
+        SerializerBuilder { capacity: 1024, max_size: usize::MAX, growth: Growth::Double }
+    }
+
+    /// Caps the total serialized size at `max_size` bytes, e.g. the 10 KiB
+    /// account limit or the 1232-byte transaction limit.
+    /// 
+    /// Any write that would exceed it fails with `BufferTooSmall` as soon as
+    /// the budget runs out, so a value may be left partially written.
+    #[inline]
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
     /// Finalizes and returns the serialized data.
     /// 
     /// This truncates the internal buffer to the actual size of the
@@ -120,80 +497,217 @@ impl JaguarSerializer {
         self.buffer
     }
 
+    /// Finalizes the serialized data with a CRC-32 of it appended, to be
+    /// read with [`JaguarDeserializer::new_verified`].
+    /// 
+    /// Fails with `BufferTooSmall` if the 4-byte trailer would pass the
+    /// maximum size.
+    #[inline]
+    pub fn finish_with_checksum(mut self) -> Result<Vec<u8>, SerError> {
+        let crc = checksum::crc32(self.data());
+        self.write_raw(&crc.to_le_bytes())?;
+        Ok(self.finish())
+    }
+
+    /// Finalizes the serialized data without consuming the serializer.
+    /// 
+    /// Like `finish`, this returns exactly the serialized data, but borrowed
+    /// and without truncating the buffer, so writing may continue afterwards
+    /// into the space already allocated.
+    #[inline]
+    pub fn finish_ref(&self) -> &[u8] {
+        self.data()
+    }
+
+    /// Appends the serialized data to `out` and resets the serializer, keeping
+    /// its buffer for the next message.
+    #[inline]
+    pub fn finish_into(&mut self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.data());
+        self.reset();
+    }
+
+    /// Takes the serialized data and resets the serializer.
+    /// 
+    /// Unlike `finish`, the serializer (and its settings) stays usable, but the
+    /// buffer moves out with the data, so the next write allocates a new one.
+    /// Use [`Self::finish_into`] to reuse the allocation instead.
+    #[inline]
+    pub fn take(&mut self) -> Vec<u8> {
+        let mut buffer = mem::take(&mut self.buffer);
+        buffer.truncate(self.pos);
+        self.reset();
+        buffer
+    }
+
+    /// Discards all serialized data while keeping the allocated capacity.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.reset();
+    }
+
     /// Returns a slice containing the currently serialized data.
     #[inline]
     pub fn data(&self) -> &[u8] {
         &self.buffer[..self.pos]
     }
 
+    /// Returns the number of bytes written so far.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
     /// Resets the serializer to its initial state, allowing reuse.
     #[inline]
     pub fn reset(&mut self) {
         self.pos = 0;
+        if let Some(table) = self.strings.as_mut() {
+            table.clear();
+        }
+    }
+
+    /// Enables the string table mode.
+    /// 
+    /// Every string written afterwards is prefixed with a varint tag: `0`
+    /// followed by the string itself the first time it is seen, or `index + 1`
+    /// referencing an earlier occurrence. Payloads full of duplicate keys only
+    /// pay for each distinct string once. The reader must call
+    /// [`JaguarDeserializer::enable_string_table`] at the same position.
+    #[inline]
+    pub fn enable_string_table(&mut self) {
+        self.strings.get_or_insert_with(BTreeMap::new);
+    }
+
+    /// Enables the extended float dictionary.
+    /// 
+    /// Floats written afterwards also get one-byte markers for `0.5`, `2.0`,
+    /// `10.0`, NaN and the infinities, and values exactly representable as
+    /// half-precision floats are stored in 2 bytes instead of 4 or 8. NaN
+    /// payloads are not kept. Older readers reject the new markers, so the
+    /// reader must call [`JaguarDeserializer::enable_float_dictionary`].
+    #[inline]
+    pub fn enable_float_dictionary(&mut self) {
+        self.float_dictionary = true;
     }
 
+    /// Grows the buffer to fit `needed` more bytes, failing if that would
+    /// exceed the maximum size.
     #[inline]
-    fn ensure_space(&mut self, needed: usize) {
-        let required = self.pos + needed;
+    fn ensure_space(&mut self, needed: usize) -> Result<(), SerError> {
+        let required = self.pos.saturating_add(needed);
+        if required > self.max_size {
+            return Err(SerError::BufferTooSmall);
+        }
         if self.buffer.len() < required {
-            self.buffer.resize(required.max(self.buffer.len() * 2), 0);
+            let len = match self.growth {
+                Growth::Exact => required,
+                Growth::Double => required.max(self.buffer.len() * 2).min(self.max_size),
+            };
+            // reserve exactly, so the allocation stays within the maximum size
+            self.buffer.reserve_exact(len - self.buffer.len());
+            self.buffer.resize(len, 0);
+        }
+        Ok(())
+    }
+
+    /// Copies `bytes` to the current position.
+    /// 
+    /// Callers must `ensure_space` first: without the `safe` feature this
+    /// skips bounds checks.
+    #[inline]
+    fn put_bytes(&mut self, bytes: &[u8]) {
+        let end = self.pos + bytes.len();
+        #[cfg(feature = "safe")]
+        self.buffer[self.pos..end].copy_from_slice(bytes);
+        #[cfg(not(feature = "safe"))]
+        unsafe {
+            let dest = self.buffer.as_mut_ptr().add(self.pos);
+            ptr::copy_nonoverlapping(bytes.as_ptr(), dest, bytes.len());
         }
+        self.pos = end;
     }
 
+    /// Writes one byte at the current position, see [`Self::put_bytes`].
     #[inline]
-    unsafe fn write_bytes_unchecked(&mut self, bytes: &[u8]) {
-        let dest = self.buffer.as_mut_ptr().add(self.pos);
-        ptr::copy_nonoverlapping(bytes.as_ptr(), dest, bytes.len());
-        self.pos += bytes.len();
+    fn put_u8(&mut self, byte: u8) {
+        #[cfg(feature = "safe")]
+        {
+            self.buffer[self.pos] = byte;
+        }
+        #[cfg(not(feature = "safe"))]
+        unsafe {
+            *self.buffer.as_mut_ptr().add(self.pos) = byte;
+        }
+        self.pos += 1;
     }
 
     /// Writes a single byte to the serialized output.
     #[inline]
     pub fn write_u8(&mut self, value: u8) -> Result<(), SerError> {
-        self.ensure_space(1);
-        unsafe {
-            *self.buffer.as_mut_ptr().add(self.pos) = value;
-            self.pos += 1;
-        }
+        self.ensure_space(1)?;
+        self.put_u8(value);
         Ok(())
     }
 
     /// Writes a slice of booleans as a bit-packed sequence.
     pub fn write_bool_slice(&mut self, slice: &[bool]) -> Result<(), SerError> {
         self.write_varint(slice.len() as u64)?;
-        let bytes_needed = (slice.len() + 7) / 8;
-        self.ensure_space(bytes_needed);
+        self.write_bitmap(slice)
+    }
+
+    /// Writes a slice of booleans as a bitmap or as runs, whichever is
+    /// smaller.
+    ///
+    /// A mode byte follows the length: `0` for the bitmap written by
+    /// [`Self::write_bool_slice`], `1` for the first value followed by the
+    /// varint length of each run of equal values. Mostly-false flag vectors
+    /// shrink to a few bytes.
+    ///
+    /// Runs are only used while they decode to fewer than 4096 elements per
+    /// byte, which bounds what readers allocate for a short input.
+    pub fn write_bool_slice_rle(&mut self, slice: &[bool]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        if !use_runs(slice.len(), 1 + rle_len(slice, 0), slice.len().div_ceil(8)) {
+            self.write_u8(RLE_MODE_PLAIN)?;
+            return self.write_bitmap(slice);
+        }
+        self.write_u8(RLE_MODE_RUNS)?;
+        self.write_u8(slice[0] as u8)?;
+        for run in slice.chunk_by(|a, b| a == b) {
+            self.write_varint(run.len() as u64)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the bit-packed body of [`Self::write_bool_slice`].
+    fn write_bitmap(&mut self, slice: &[bool]) -> Result<(), SerError> {
+        let bytes_needed = slice.len().div_ceil(8);
+        self.ensure_space(bytes_needed)?;
         
-        let mut byte = 0u8;
-        let _bit = 0;
         let mut pos = 0;
         
         while pos + 8 <= slice.len() {
             let chunk = &slice[pos..pos + 8];
-            byte = 0;
+            let mut byte = 0u8;
             for (i, &b) in chunk.iter().enumerate() {
                 if b {
                     byte |= 1 << i;
                 }
             }
-            unsafe {
-                *self.buffer.as_mut_ptr().add(self.pos) = byte;
-            }
-            self.pos += 1;
+            self.put_u8(byte);
             pos += 8;
         }
         
         if pos < slice.len() {
-            byte = 0;
+            let mut byte = 0u8;
             for (i, &b) in slice[pos..].iter().enumerate() {
                 if b {
                     byte |= 1 << i;
                 }
             }
-            unsafe {
-                *self.buffer.as_mut_ptr().add(self.pos) = byte;
-            }
-            self.pos += 1;
+            self.put_u8(byte);
         }
         
         Ok(())
@@ -201,24 +715,99 @@ impl JaguarSerializer {
 
     /// Varint encoding for unsigned integers.
     #[inline]
-    pub fn write_varint(&mut self, mut value: u64) -> Result<(), SerError> {
-        self.ensure_space(10);
-        unsafe {
-            let mut ptr = self.buffer.as_mut_ptr().add(self.pos);
-            // common case: value < 128
-            if value < 0x80 {
-                *ptr = value as u8;
-                self.pos += 1;
-                return Ok(());
-            }
-            while value >= 0x80 {
-                *ptr = (value as u8) | 0x80;
-                ptr = ptr.add(1);
-                value >>= 7;
-                self.pos += 1;
-            }
-            *ptr = value as u8;
-            self.pos += 1;
+    pub fn write_varint(&mut self, value: u64) -> Result<(), SerError> {
+        self.ensure_space(varint_len(value))?;
+        self.put_varint(value);
+        Ok(())
+    }
+
+    /// Writes a varint at the current position, see [`Self::put_bytes`].
+    #[inline]
+    fn put_varint(&mut self, mut value: u64) {
+        // common case: value < 128
+        if value < 0x80 {
+            self.put_u8(value as u8);
+            return;
+        }
+        while value >= 0x80 {
+            self.put_u8((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.put_u8(value as u8);
+    }
+
+    /// Starts a run of writes bounded by `max` bytes in total, checking for
+    /// space once instead of per write. Returns `None` if `max` bytes would
+    /// exceed the maximum size, in which case the caller falls back to the
+    /// checked writes.
+    /// 
+    /// Used by derived code for runs of adjacent fixed-bound fields.
+    #[doc(hidden)]
+    #[inline]
+    pub fn __coalesce(&mut self, max: usize) -> Option<Coalesced<'_>> {
+        self.ensure_space(max).ok()?;
+        Some(Coalesced { ser: self, budget: max })
+    }
+
+    /// Opens a length-prefixed section at the current position.
+    /// 
+    /// Everything written until the matching [`Self::end_section`] becomes the
+    /// section body, encoded exactly like [`Self::write_bytes`], so readers can
+    /// skip it without knowing its contents. Sections nest, and must be closed
+    /// in reverse order of opening.
+    ///
+    /// The string table is suspended until the section is closed, so strings
+    /// in the body are written inline and the body decodes on its own, see
+    /// [`JaguarDeserializer::read_section`].
+    #[inline]
+    pub fn begin_section(&mut self) -> Result<Section, SerError> {
+        // one byte covers bodies under 128 bytes, longer ones are shifted
+        self.ensure_space(1)?;
+        self.put_u8(0);
+        Ok(Section { start: self.pos, strings: self.strings.take() })
+    }
+
+    /// Closes `section`, backpatching its length prefix.
+    #[inline]
+    pub fn end_section(&mut self, section: Section) -> Result<(), SerError> {
+        let start = section.start;
+        self.strings = section.strings;
+        if start > self.pos {
+            return Err(SerError::InvalidData);
+        }
+
+        let mut prefix = [0u8; 10];
+        let width = encode_varint((self.pos - start) as u64, &mut prefix);
+        let shift = width - 1;
+        if shift > 0 {
+            self.ensure_space(shift)?;
+            self.buffer.copy_within(start..self.pos, start + shift);
+            self.pos += shift;
+        }
+        self.buffer[start - 1..start + shift].copy_from_slice(&prefix[..width]);
+        Ok(())
+    }
+
+    /// Writes map entries in ascending key order, so equal maps always encode
+    /// to the same bytes whatever order `entries` yields them in.
+    /// 
+    /// The format is a varint entry count followed by alternating keys and
+    /// values. Duplicate keys fail with `InvalidData`.
+    pub fn write_map_sorted<'m, K, V, I>(&mut self, entries: I) -> Result<(), SerError>
+    where
+        K: JaguarSerialize + Ord + 'm,
+        V: JaguarSerialize + 'm,
+        I: IntoIterator<Item = (&'m K, &'m V)>,
+    {
+        let mut entries: Vec<(&K, &V)> = entries.into_iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        if entries.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(SerError::InvalidData);
+        }
+        self.write_varint(entries.len() as u64)?;
+        for (key, value) in entries {
+            key.serialize(self)?;
+            value.serialize(self)?;
         }
         Ok(())
     }
@@ -243,995 +832,5381 @@ impl JaguarSerializer {
     /// 
     /// This optimizes for common float values (0.0, 1.0, -1.0),
     /// using a single byte marker. All other values are stored in full IEEE-754
-    /// format with a marker byte.
+    /// format with a marker byte. See [`Self::enable_float_dictionary`] for
+    /// more markers.
     #[inline]
     pub fn write_f32(&mut self, value: f32) -> Result<(), SerError> {
-        if value == 0.0 {
-            return self.write_u8(0);
-        } else if value == 1.0 {
-            return self.write_u8(1);
-        } else if value == -1.0 {
-            return self.write_u8(2);
-        }
-        
-        self.write_u8(255)?; 
-        self.ensure_space(4);
-        unsafe {
-            let bytes = mem::transmute::<f32, [u8; 4]>(value);
-            self.write_bytes_unchecked(&bytes);
-        }
+        let code = float_code(value as f64, self.float_dictionary);
+        self.ensure_space(code.len(4))?;
+        self.put_float(code, value.to_ne_bytes());
         Ok(())
     }
 
+    /// Writes an encoded float at the current position, see
+    /// [`Self::put_bytes`].
+    #[inline]
+    fn put_float<const N: usize>(&mut self, code: FloatCode, bytes: [u8; N]) {
+        match code {
+            FloatCode::Marker(marker) => self.put_u8(marker),
+            FloatCode::Half(bits) => {
+                self.put_u8(HALF_MARKER);
+                self.put_bytes(&bits.to_le_bytes());
+            }
+            FloatCode::Full => {
+                self.put_u8(255);
+                self.put_bytes(&bytes);
+            }
+        }
+    }
+
     /// Writes a 64-bit float.
     /// 
     /// Similar to write_f32, but for double-precision floats.
     #[inline]
     pub fn write_f64(&mut self, value: f64) -> Result<(), SerError> {
-        if value == 0.0 {
-            return self.write_u8(0);
-        } else if value == 1.0 {
-            return self.write_u8(1);
-        } else if value == -1.0 {
-            return self.write_u8(2);
-        }
-        
-        self.write_u8(255)?;
-        self.ensure_space(8);
-        unsafe {
-            let bytes = mem::transmute::<f64, [u8; 8]>(value);
-            self.write_bytes_unchecked(&bytes);
-        }
+        let code = float_code(value, self.float_dictionary);
+        self.ensure_space(code.len(8))?;
+        self.put_float(code, value.to_ne_bytes());
         Ok(())
     }
 
-    /// Writes a string as a length-prefixed UTF-8 byte sequence.
+    /// Writes a 64-byte ed25519 signature as raw bytes, without a length prefix.
     #[inline]
-    pub fn write_str(&mut self, s: &str) -> Result<(), SerError> {
-        let bytes = s.as_bytes();
-        self.write_varint(bytes.len() as u64)?;
-        self.ensure_space(bytes.len());
-        unsafe {
-            self.write_bytes_unchecked(bytes);
-        }
+    pub fn write_signature(&mut self, signature: &[u8; 64]) -> Result<(), SerError> {
+        self.ensure_space(64)?;
+        self.put_bytes(signature);
         Ok(())
     }
 
-    /// Writes a byte slice as a length-prefixed sequence.
+    /// Writes an unsigned integer as `N` little-endian bytes, for `N` from 1
+    /// to 8, with no continuation bits.
+    /// 
+    /// Fails with `InvalidData` if `value` doesn't fit in `N` bytes, and with
+    /// `InvalidLength` for any other `N`.
     #[inline]
-    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), SerError> {
-        self.write_varint(bytes.len() as u64)?;
-        self.ensure_space(bytes.len());
-        unsafe {
-            self.write_bytes_unchecked(bytes);
+    pub fn write_uint_le<const N: usize>(&mut self, value: u64) -> Result<(), SerError> {
+        if N == 0 || N > 8 {
+            return Err(SerError::InvalidLength);
+        }
+        if N < 8 && value >> (N * 8) != 0 {
+            return Err(SerError::InvalidData);
         }
+        self.ensure_space(N)?;
+        self.put_bytes(&value.to_le_bytes()[..N]);
         Ok(())
     }
 
-    /// Writes a slice of 32-bit integers.
+    /// Writes a 24-bit unsigned integer as 3 little-endian bytes, see
+    /// [`Self::write_uint_le`].
     #[inline]
-    pub fn write_u32_slice(&mut self, slice: &[u32]) -> Result<(), SerError> {
-        self.write_varint(slice.len() as u64)?;
-        let bytes_needed = slice.len() * 4;
-        self.ensure_space(bytes_needed);
-        unsafe {
-            let dest = self.buffer.as_mut_ptr().add(self.pos);
-            ptr::copy_nonoverlapping(slice.as_ptr() as *const u8, dest, bytes_needed);
-            self.pos += bytes_needed;
-        }
-        Ok(())
+    pub fn write_u24(&mut self, value: u32) -> Result<(), SerError> {
+        self.write_uint_le::<3>(value as u64)
     }
 
-    /// Writes a vector of strings, each encoded as a length-prefixed UTF-8 sequence.
+    /// Writes a 48-bit unsigned integer as 6 little-endian bytes, see
+    /// [`Self::write_uint_le`].
     #[inline]
-    pub fn write_string_vec(&mut self, vec: &[String]) -> Result<(), SerError> {
-        self.write_varint(vec.len() as u64)?;
-        for s in vec {
-            self.write_str(s)?;
-        }
-        Ok(())
+    pub fn write_u48(&mut self, value: u64) -> Result<(), SerError> {
+        self.write_uint_le::<6>(value)
     }
 
-    /// Writes a slice of 8-bit integers.
+    /// Writes a slice of 24-bit unsigned integers, 3 little-endian bytes each.
     #[inline]
-    pub fn write_u8_slice(&mut self, slice: &[u8]) -> Result<(), SerError> {
-        self.write_varint(slice.len() as u64)?;
-        self.ensure_space(slice.len());
-        unsafe {
-            self.write_bytes_unchecked(slice);
+    pub fn write_u24_slice(&mut self, slice: &[u32]) -> Result<(), SerError> {
+        // check every value first, so a failed write leaves nothing behind
+        if slice.iter().any(|&value| value >> 24 != 0) {
+            return Err(SerError::InvalidData);
+        }
+        let len = slice.len() as u64;
+        self.ensure_space(varint_len(len).saturating_add(slice.len().saturating_mul(3)))?;
+        self.put_varint(len);
+        for &value in slice {
+            self.put_bytes(&value.to_le_bytes()[..3]);
         }
         Ok(())
     }
 
-    /// Writes a slice of 16-bit integers using varlen encoding.
+    /// Writes a slice of 48-bit unsigned integers, 6 little-endian bytes each.
     #[inline]
-    pub fn write_u16_slice(&mut self, slice: &[u16]) -> Result<(), SerError> {
-        self.write_varint(slice.len() as u64)?;
+    pub fn write_u48_slice(&mut self, slice: &[u64]) -> Result<(), SerError> {
+        // check every value first, so a failed write leaves nothing behind
+        if slice.iter().any(|&value| value >> 48 != 0) {
+            return Err(SerError::InvalidData);
+        }
+        let len = slice.len() as u64;
+        self.ensure_space(varint_len(len).saturating_add(slice.len().saturating_mul(6)))?;
+        self.put_varint(len);
         for &value in slice {
-            self.write_varint(value as u64)?;
+            self.put_bytes(&value.to_le_bytes()[..6]);
         }
         Ok(())
     }
 
-    /// Writes a slice of 64-bit integers using varlen encoding.
+    /// Writes `len` zero bytes, e.g. to reserve space for future fields.
     #[inline]
-    pub fn write_u64_slice(&mut self, slice: &[u64]) -> Result<(), SerError> {
-        self.write_varint(slice.len() as u64)?;
-        for &value in slice {
-            self.write_varint(value)?;
-        }
+    pub fn write_padding(&mut self, len: usize) -> Result<(), SerError> {
+        self.ensure_space(len)?;
+        self.buffer[self.pos..self.pos + len].fill(0);
+        self.pos += len;
         Ok(())
     }
 
-    /// Writes a slice of signed 8-bit integers.
+    /// Writes a string as a length-prefixed UTF-8 byte sequence.
     #[inline]
-    pub fn write_i8_slice(&mut self, slice: &[i8]) -> Result<(), SerError> {
-        self.write_varint(slice.len() as u64)?;
-        for &value in slice {
-            self.write_signed_varint(value as i64)?;
+    pub fn write_str(&mut self, s: &str) -> Result<(), SerError> {
+        if let Some(table) = self.strings.as_mut() {
+            if let Some(&index) = table.get(s) {
+                return self.write_varint(index + 1);
+            }
+            let index = table.len() as u64;
+            table.insert(String::from(s), index);
+            self.write_varint(0)?;
         }
+        let bytes = s.as_bytes();
+        self.write_varint(bytes.len() as u64)?;
+        self.ensure_space(bytes.len())?;
+        self.put_bytes(bytes);
         Ok(())
     }
 
-    /// Writes a slice of signed 16-bit integers.
+    /// Writes a byte slice as a length-prefixed sequence.
     #[inline]
-    pub fn write_i16_slice(&mut self, slice: &[i16]) -> Result<(), SerError> {
-        self.write_varint(slice.len() as u64)?;
-        for &value in slice {
-            self.write_signed_varint(value as i64)?;
-        }
-        Ok(())
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), SerError> {
+        self.write_bytes_with::<prefix::Varint>(bytes)
     }
 
-    /// Writes a slice of signed 64-bit integers.
+    /// Writes a byte slice with the length prefix `P`.
     #[inline]
-    pub fn write_i64_slice(&mut self, slice: &[i64]) -> Result<(), SerError> {
-        self.write_varint(slice.len() as u64)?;
-        for &value in slice {
-            self.write_signed_varint(value)?;
-        }
+    pub fn write_bytes_with<P: LenPrefix>(&mut self, bytes: &[u8]) -> Result<(), SerError> {
+        P::write_len(self, bytes.len())?;
+        self.ensure_space(bytes.len())?;
+        self.put_bytes(bytes);
         Ok(())
     }
 
-    /// Writes a slice of 32-bit floats.
+    /// Writes a string with the length prefix `P`. The string table is not
+    /// used.
     #[inline]
-    pub fn write_f32_slice(&mut self, slice: &[f32]) -> Result<(), SerError> {
-        self.write_varint(slice.len() as u64)?;
-        for &value in slice {
-            self.write_f32(value)?;
-        }
-        Ok(())
+    pub fn write_str_with<P: LenPrefix>(&mut self, s: &str) -> Result<(), SerError> {
+        self.write_bytes_with::<P>(s.as_bytes())
     }
 
-    /// Writes a slice of 64-bit floats.
+    /// Writes a sequence with the length prefix `P`.
     #[inline]
-    pub fn write_f64_slice(&mut self, slice: &[f64]) -> Result<(), SerError> {
-        self.write_varint(slice.len() as u64)?;
-        for &value in slice {
-            self.write_f64(value)?;
+    pub fn write_seq_with<P: LenPrefix, T: JaguarSerialize>(&mut self, items: &[T]) -> Result<(), SerError> {
+        P::write_len(self, items.len())?;
+        for item in items {
+            item.serialize(self)?;
         }
         Ok(())
     }
-}
-
-impl<'a> JaguarDeserializer<'a> {
-    /// Creates a new deserializer from a byte slice.
-    #[inline]
-    pub fn new(data: &'a [u8]) -> Self {
-        Self { data, pos: 0 }
-    }
 
-    /// Returns `true` if there is more data to read.
-    #[inline]
-    pub fn has_data(&self) -> bool {
-        self.pos < self.data.len()
+    /// Writes `len` items from an iterator as a sequence, encoded exactly like
+    /// a `Vec` of them, without collecting the items first.
+    /// 
+    /// Fails with `InvalidLength` if the iterator yields more or fewer than
+    /// `len` items. Extra items are not consumed past the first one.
+    /// 
+    /// ```rust
+    /// use jaguar::JaguarSerializer;
+    /// use std::collections::HashMap;
+    ///
+    /// let balances = HashMap::from([("alice", 5u64)]);
+    /// let mut ser = JaguarSerializer::new();
+    /// ser.write_seq(balances.len(), balances.values().map(|b| b * 1_000)).unwrap();
+    /// assert_eq!(ser.finish(), jaguar::serialize(&vec![5_000u64]).unwrap());
+    /// ```
+    pub fn write_seq<I>(&mut self, len: usize, items: I) -> Result<(), SerError>
+    where
+        I: IntoIterator,
+        I::Item: JaguarSerialize,
+    {
+        self.write_varint(len as u64)?;
+        let mut written = 0;
+        for item in items {
+            if written == len {
+                return Err(SerError::InvalidLength);
+            }
+            item.serialize(self)?;
+            written += 1;
+        }
+        if written != len {
+            return Err(SerError::InvalidLength);
+        }
+        Ok(())
     }
 
-    /// Returns the current read position in the data.
+    /// Writes every item from an iterator that knows its length, see
+    /// [`Self::write_seq`].
     #[inline]
-    pub fn position(&self) -> usize {
-        self.pos
+    pub fn write_seq_unsized<I>(&mut self, items: I) -> Result<(), SerError>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        I::Item: JaguarSerialize,
+    {
+        let items = items.into_iter();
+        self.write_seq(items.len(), items)
     }
 
-    /// Reads a single byte from the input.
+    /// Writes `bytes` as-is, without a length prefix.
+    /// 
+    /// The reader has to know the length some other way, e.g. because the
+    /// bytes fill the rest of the container (see
+    /// [`JaguarDeserializer::take_rest`]).
     #[inline]
-    pub fn read_u8(&mut self) -> Result<u8, SerError> {
-        if self.pos >= self.data.len() {
-            return Err(SerError::BufferTooSmall);
-        }
-        let value = self.data[self.pos];
-        self.pos += 1;
-        Ok(value)
+    pub fn write_raw(&mut self, bytes: &[u8]) -> Result<(), SerError> {
+        self.ensure_space(bytes.len())?;
+        self.put_bytes(bytes);
+        Ok(())
     }
 
-    /// Reads a vector of 32-bit integers.
+    /// Writes a slice of 32-bit integers as raw bytes, the same as
+    /// [`Self::write_u32_slice_raw`].
+    /// 
+    /// The other integer slice writers without a suffix are packed. This one
+    /// stays raw so existing data keeps decoding, so prefer the explicit
+    /// `_packed`/`_raw` names in new code.
     #[inline]
-    pub fn read_u32_vec(&mut self) -> Result<Vec<u32>, SerError> {
-        let len = self.read_varint()? as usize;
-        let bytes_needed = len * 4;
-        if self.pos + bytes_needed > self.data.len() {
-            return Err(SerError::BufferTooSmall);
+    pub fn write_u32_slice(&mut self, slice: &[u32]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        let bytes_needed = slice.len() * 4;
+        self.ensure_space(bytes_needed)?;
+        #[cfg(feature = "safe")]
+        for value in slice {
+            self.put_bytes(&value.to_ne_bytes());
         }
-        
-        let mut vec = Vec::with_capacity(len);
+        #[cfg(not(feature = "safe"))]
         unsafe {
-            vec.set_len(len);
-            ptr::copy_nonoverlapping(
-                self.data.as_ptr().add(self.pos),
-                vec.as_mut_ptr() as *mut u8,
-                bytes_needed
-            );
+            let dest = self.buffer.as_mut_ptr().add(self.pos);
+            ptr::copy_nonoverlapping(slice.as_ptr() as *const u8, dest, bytes_needed);
+            self.pos += bytes_needed;
         }
-        self.pos += bytes_needed;
-        Ok(vec)
+        Ok(())
     }
 
-    /// Reads a vector of booleans.
+    /// Writes a vector of strings, each encoded as a length-prefixed UTF-8 sequence.
     #[inline]
-    pub fn read_bool_vec(&mut self) -> Result<Vec<bool>, SerError> {
-        let len = self.read_varint()? as usize;
-        let bytes_needed = (len + 7) / 8;
-        if self.pos + bytes_needed > self.data.len() {
-            return Err(SerError::BufferTooSmall);
-        }
-        
-        let mut vec = Vec::with_capacity(len);
-        let mut pos = 0;
-    
-        while pos + 8 <= len {
-            let byte = self.data[self.pos];
-            self.pos += 1;
-            for i in 0..8 {
-                vec.push((byte & (1 << i)) != 0);
-            }
-            pos += 8;
-        }
-        
-        if pos < len {
-            let byte = self.data[self.pos];
-            self.pos += 1;
-            for i in 0..(len - pos) {
-                vec.push((byte & (1 << i)) != 0);
-            }
+    pub fn write_string_vec(&mut self, vec: &[String]) -> Result<(), SerError> {
+        self.write_varint(vec.len() as u64)?;
+        for s in vec {
+            self.write_str(s)?;
         }
-        
-        Ok(vec)
+        Ok(())
     }
 
-    /// Deserialization for fixed-size arrays of primitive types.
+    /// Writes a slice of 8-bit integers.
     #[inline]
-    pub fn read_fixed_array<T: Copy, const N: usize>(&mut self) -> Result<[T; N], SerError> {
-        let bytes_needed = N * mem::size_of::<T>();
-        if self.pos + bytes_needed > self.data.len() {
-            return Err(SerError::BufferTooSmall);
+    pub fn write_u8_slice(&mut self, slice: &[u8]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        self.ensure_space(slice.len())?;
+        self.put_bytes(slice);
+        Ok(())
+    }
+
+    /// Writes a slice of 8-bit integers as raw bytes or as runs, whichever is
+    /// smaller.
+    ///
+    /// A mode byte follows the length: `0` for the bytes written by
+    /// [`Self::write_u8_slice`], `1` for each run of equal values as the
+    /// value followed by the varint length of the run, used under the same
+    /// limit as in [`Self::write_bool_slice_rle`].
+    pub fn write_u8_slice_rle(&mut self, slice: &[u8]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        if !use_runs(slice.len(), rle_len(slice, 1), slice.len()) {
+            self.write_u8(RLE_MODE_PLAIN)?;
+            self.ensure_space(slice.len())?;
+            self.put_bytes(slice);
+            return Ok(());
         }
-        
-        let mut result = [unsafe { mem::zeroed() }; N];
-        unsafe {
-            ptr::copy_nonoverlapping(
-                self.data.as_ptr().add(self.pos),
-                result.as_mut_ptr() as *mut u8,
-                bytes_needed
-            );
+        self.write_u8(RLE_MODE_RUNS)?;
+        for run in slice.chunk_by(|a, b| a == b) {
+            self.write_u8(run[0])?;
+            self.write_varint(run.len() as u64)?;
         }
-        self.pos += bytes_needed;
-        Ok(result)
+        Ok(())
     }
 
-    /// Varint decoding for unsigned integers.
+    /// Writes a slice of 16-bit integers using varlen encoding.
     #[inline]
-    pub fn read_varint(&mut self) -> Result<u64, SerError> {
-        let mut result = 0u64;
-        let mut shift = 0;
-        let mut count = 0;
-        loop {
-            if self.pos >= self.data.len() {
-                return Err(SerError::BufferTooSmall);
-            }
-            let byte = self.data[self.pos];
-            self.pos += 1;
-            result |= ((byte & 0x7F) as u64) << shift;
-            if byte & 0x80 == 0 {
-                break;
-            }
-            shift += 7;
-            count += 1;
-            if shift >= 64 || count > 9 {
-                return Err(SerError::InvalidData);
-            }
+    pub fn write_u16_slice(&mut self, slice: &[u16]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        for &value in slice {
+            self.write_varint(value as u64)?;
         }
-        Ok(result)
+        Ok(())
     }
 
-    /// Reads a varlen encoded signed integer.
+    /// Writes a slice of 64-bit integers using varlen encoding.
     #[inline]
-    pub fn read_signed_varint(&mut self) -> Result<i64, SerError> {
-        let encoded = self.read_varint()?;
-        Ok(((encoded >> 1) as i64) ^ (-((encoded & 1) as i64)))
+    pub fn write_u64_slice(&mut self, slice: &[u64]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        for &value in slice {
+            self.write_varint(value)?;
+        }
+        Ok(())
     }
 
-    /// Reads a boolean value.
+    /// Writes a slice of 64-bit integers using delta encoding.
+    /// 
+    /// The first value is stored as a varint, followed by the varint-encoded
+    /// difference between each value and its predecessor. Monotonic sequences
+    /// like timestamps or slot numbers shrink to a byte or two per element.
+    /// Deltas wrap, so non-monotonic input still roundtrips (just less compactly).
     #[inline]
-    pub fn read_bool(&mut self) -> Result<bool, SerError> {
-        Ok(self.read_u8()? != 0)
+    pub fn write_u64_slice_delta(&mut self, slice: &[u64]) -> Result<(), SerError> {
+        self.write_delta(slice.iter().copied())
     }
 
-    /// Reads a 32-bit float.
+    /// Writes `values` like [`Self::write_u64_slice_delta`], for newtypes
+    /// over `u64`.
     #[inline]
-    pub fn read_f32(&mut self) -> Result<f32, SerError> {
-        let marker = self.read_u8()?;
-        match marker {
-            0 => Ok(0.0),
-            1 => Ok(1.0),
-            2 => Ok(-1.0),
-            255 => {
-                if self.pos + 4 > self.data.len() {
-                    return Err(SerError::BufferTooSmall);
-                }
-                unsafe {
-                    let bytes = ptr::read_unaligned(self.data.as_ptr().add(self.pos) as *const [u8; 4]);
-                    self.pos += 4;
-                    Ok(mem::transmute::<[u8; 4], f32>(bytes))
-                }
-            }
-            _ => Err(SerError::InvalidData),
+    pub(crate) fn write_delta(&mut self, values: impl ExactSizeIterator<Item = u64>) -> Result<(), SerError> {
+        self.write_varint(values.len() as u64)?;
+        let mut prev = 0u64;
+        for value in values {
+            self.write_varint(value.wrapping_sub(prev))?;
+            prev = value;
         }
+        Ok(())
     }
 
-    /// Reads a 64-bit float.
+    /// Writes a slice of 32-bit integers in the Stream VByte layout.
+    /// 
+    /// Each element takes 1 to 4 little-endian bytes, with the byte counts
+    /// packed two bits apiece into control bytes ahead of the data. Readers
+    /// decode four elements per control byte rather than branching on every
+    /// byte like varints, at about the size of
+    /// [`Self::write_u32_slice_packed`] for small values.
     #[inline]
-    pub fn read_f64(&mut self) -> Result<f64, SerError> {
-        let marker = self.read_u8()?;
-        match marker {
-            0 => Ok(0.0),
-            1 => Ok(1.0),
-            2 => Ok(-1.0),
-            255 => {
-                if self.pos + 8 > self.data.len() {
-                    return Err(SerError::BufferTooSmall);
-                }
-                unsafe {
-                    let bytes = ptr::read_unaligned(self.data.as_ptr().add(self.pos) as *const [u8; 8]);
-                    self.pos += 8;
-                    Ok(mem::transmute::<[u8; 8], f64>(bytes))
-                }
+    pub fn write_u32_slice_svb(&mut self, slice: &[u32]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        let data_len: usize = slice.iter().map(|&value| svb_len(value)).sum();
+        self.ensure_space(slice.len().div_ceil(4) + data_len)?;
+        for chunk in slice.chunks(4) {
+            let mut control = 0u8;
+            for (i, &value) in chunk.iter().enumerate() {
+                control |= ((svb_len(value) - 1) as u8) << (i * 2);
             }
-            _ => Err(SerError::InvalidData),
+            self.put_u8(control);
+        }
+        for &value in slice {
+            self.put_bytes(&value.to_le_bytes()[..svb_len(value)]);
         }
+        Ok(())
     }
 
-    /// Reads a length-prefixed UTF-8 string.
+    /// Writes a slice of signed 8-bit integers.
     #[inline]
-    pub fn read_str(&mut self) -> Result<&'a str, SerError> {
-        let len = self.read_varint()? as usize;
-        if self.pos + len > self.data.len() {
-            return Err(SerError::BufferTooSmall);
-        }
-        
-        let slice = &self.data[self.pos..self.pos + len];
-        self.pos += len;
-        
-        #[cfg(feature = "std")]
-        {
-            std::str::from_utf8(slice).map_err(|_| SerError::InvalidData)
-        }
-        #[cfg(not(feature = "std"))]
-        {
-            core::str::from_utf8(slice).map_err(|_| SerError::InvalidData)
+    pub fn write_i8_slice(&mut self, slice: &[i8]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        for &value in slice {
+            self.write_signed_varint(value as i64)?;
         }
+        Ok(())
     }
 
-    /// Reads a length-prefixed byte slice.
+    /// Writes a slice of signed 16-bit integers.
     #[inline]
-    pub fn read_bytes(&mut self) -> Result<&'a [u8], SerError> {
-        let len = self.read_varint()? as usize;
-        if self.pos + len > self.data.len() {
-            return Err(SerError::BufferTooSmall);
+    pub fn write_i16_slice(&mut self, slice: &[i16]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        for &value in slice {
+            self.write_signed_varint(value as i64)?;
         }
-        
-        let slice = &self.data[self.pos..self.pos + len];
-        self.pos += len;
-        Ok(slice)
+        Ok(())
     }
 
-    /// Reads a vector of strings.
+    /// Writes a slice of signed 32-bit integers.
     #[inline]
-    pub fn read_string_vec(&mut self) -> Result<Vec<String>, SerError> {
-        #[cfg(not(feature = "std"))]
-        {
-            return Err(SerError::UnsupportedType);
-        }
-        #[cfg(feature = "std")]
-        {
-            let len = self.read_varint()? as usize;
-            let mut vec = Vec::with_capacity(len);
-            
-            for _ in 0..len {
-                vec.push(self.read_str()?.to_string());
-            }
-            
-            Ok(vec)
+    pub fn write_i32_slice(&mut self, slice: &[i32]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        for &value in slice {
+            self.write_signed_varint(value as i64)?;
         }
+        Ok(())
     }
 
-    /// Reads a vector of 8-bit integers.
+    /// Writes a slice of signed 64-bit integers.
     #[inline]
-    pub fn read_u8_vec(&mut self) -> Result<Vec<u8>, SerError> {
-        let len = self.read_varint()? as usize;
-        if self.pos + len > self.data.len() {
-            return Err(SerError::BufferTooSmall);
-        }
-        let mut vec = Vec::with_capacity(len);
-        unsafe {
-            vec.set_len(len);
-            ptr::copy_nonoverlapping(
-                self.data.as_ptr().add(self.pos),
-                vec.as_mut_ptr(),
-                len
-            );
+    pub fn write_i64_slice(&mut self, slice: &[i64]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        for &value in slice {
+            self.write_signed_varint(value)?;
         }
-        self.pos += len;
-        Ok(vec)
+        Ok(())
     }
 
-    /// Reads a vector of 16-bit integers.
+    /// Writes a slice of signed 128-bit integers.
     #[inline]
-    pub fn read_u16_vec(&mut self) -> Result<Vec<u16>, SerError> {
-        let len = self.read_varint()? as usize;
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            vec.push(self.read_varint()? as u16);
+    pub fn write_i128_slice(&mut self, slice: &[i128]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        for value in slice {
+            value.serialize(self)?;
         }
-        Ok(vec)
+        Ok(())
     }
 
-    /// Reads a vector of 64-bit integers.
+    /// Writes a slice of 32-bit floats.
     #[inline]
-    pub fn read_u64_vec(&mut self) -> Result<Vec<u64>, SerError> {
-        let len = self.read_varint()? as usize;
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            vec.push(self.read_varint()?);
+    pub fn write_f32_slice(&mut self, slice: &[f32]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        for &value in slice {
+            self.write_f32(value)?;
         }
-        Ok(vec)
+        Ok(())
     }
 
-    /// Reads a vector of signed 8-bit integers.
+    /// Writes a slice of 64-bit floats.
     #[inline]
-    pub fn read_i8_vec(&mut self) -> Result<Vec<i8>, SerError> {
-        let len = self.read_varint()? as usize;
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            vec.push(self.read_signed_varint()? as i8);
+    pub fn write_f64_slice(&mut self, slice: &[f64]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        for &value in slice {
+            self.write_f64(value)?;
         }
-        Ok(vec)
+        Ok(())
     }
 
-    /// Reads a vector of signed 16-bit integers.
+    /// Writes a slice of 32-bit floats as raw IEEE-754 bytes, without the
+    /// per-element marker.
+    /// 
+    /// Larger than [`Self::write_f32_slice`] for data full of `0.0` and `1.0`,
+    /// but a straight copy, so faster and smaller for dense values.
     #[inline]
-    pub fn read_i16_vec(&mut self) -> Result<Vec<i16>, SerError> {
-        let len = self.read_varint()? as usize;
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            vec.push(self.read_signed_varint()? as i16);
+    pub fn write_f32_slice_raw(&mut self, slice: &[f32]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        self.ensure_space(slice.len().saturating_mul(4))?;
+        #[cfg(feature = "safe")]
+        for value in slice {
+            self.put_bytes(&value.to_ne_bytes());
         }
-        Ok(vec)
+        #[cfg(not(feature = "safe"))]
+        self.put_bytes(unsafe { core::slice::from_raw_parts(slice.as_ptr().cast(), slice.len() * 4) });
+        Ok(())
     }
 
-    /// Reads a vector of signed 64-bit integers.
+    /// Writes a slice of 64-bit floats as raw IEEE-754 bytes, see
+    /// [`Self::write_f32_slice_raw`].
     #[inline]
-    pub fn read_i64_vec(&mut self) -> Result<Vec<i64>, SerError> {
-        let len = self.read_varint()? as usize;
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            vec.push(self.read_signed_varint()?);
+    pub fn write_f64_slice_raw(&mut self, slice: &[f64]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        self.ensure_space(slice.len().saturating_mul(8))?;
+        #[cfg(feature = "safe")]
+        for value in slice {
+            self.put_bytes(&value.to_ne_bytes());
         }
-        Ok(vec)
+        #[cfg(not(feature = "safe"))]
+        self.put_bytes(unsafe { core::slice::from_raw_parts(slice.as_ptr().cast(), slice.len() * 8) });
+        Ok(())
     }
 
-    /// Reads a vector of 32-bit floats.
+    int_slice_writes! {
+        u16 => write_u16_slice_packed, write_u16_slice_raw;
+        u32 => write_u32_slice_packed, write_u32_slice_raw;
+        u64 => write_u64_slice_packed, write_u64_slice_raw;
+        i16 => write_i16_slice_packed, write_i16_slice_raw;
+        i32 => write_i32_slice_packed, write_i32_slice_raw;
+        i64 => write_i64_slice_packed, write_i64_slice_raw;
+    }
+}
+
+impl<'a> JaguarDeserializer<'a> {
+    /// The maximum nesting depth of a new deserializer.
+    pub const DEFAULT_MAX_DEPTH: u32 = 32;
+
+    /// The longest sequence of zero-sized elements, such as `Vec<()>`, that
+    /// decodes. They take no input, so the input length can't bound them.
+    pub const MAX_ZERO_SIZED_LEN: u64 = 1 << 16;
+
+    /// Creates a new deserializer from a byte slice.
     #[inline]
-    pub fn read_f32_vec(&mut self) -> Result<Vec<f32>, SerError> {
-        let len = self.read_varint()? as usize;
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            vec.push(self.read_f32()?);
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            strings: None,
+            canonical: false,
+            float_dictionary: false,
+            depth: 0,
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            #[cfg(not(feature = "safe"))]
+            trusted: false,
         }
-        Ok(vec)
     }
 
-    /// Reads a vector of 64-bit floats.
+    /// Creates a deserializer that skips bounds checks when reading bytes and
+    /// varints, for input that is known to be valid, such as account data
+    /// written by the same program or CPI data from a trusted caller.
+    /// 
+    /// Only available without the `safe` feature. Length prefixes, UTF-8 and
+    /// depth are still checked, and errors other than `BufferTooSmall` are
+    /// still returned.
+    /// 
+    /// # Safety
+    /// 
+    /// Every read must stay within `data`: the bytes have to be a complete
+    /// encoding, produced by `JaguarSerializer`, of exactly the values read
+    /// back. Reading past the end of `data` is undefined behavior.
+    #[cfg(not(feature = "safe"))]
     #[inline]
-    pub fn read_f64_vec(&mut self) -> Result<Vec<f64>, SerError> {
-        let len = self.read_varint()? as usize;
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            vec.push(self.read_f64()?);
+    pub unsafe fn new_unchecked(data: &'a [u8]) -> Self {
+        Self { trusted: true, ..Self::new(data) }
+    }
+
+    /// Creates a deserializer over data written with
+    /// [`JaguarSerializer::finish_with_checksum`], after checking and
+    /// stripping the CRC-32 trailer.
+    /// 
+    /// Fails with `ChecksumMismatch` if the data was corrupted, or
+    /// `BufferTooSmall` if it is too short to hold a trailer.
+    #[inline]
+    pub fn new_verified(data: &'a [u8]) -> Result<Self, SerError> {
+        let split = data.len().checked_sub(checksum::CHECKSUM_LEN).ok_or(SerError::BufferTooSmall)?;
+        let (payload, trailer) = data.split_at(split);
+        let expected = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        if checksum::crc32(payload) != expected {
+            return Err(SerError::ChecksumMismatch);
         }
-        Ok(vec)
+        Ok(Self::new(payload))
     }
-}
 
-pub trait JaguarSerialize {
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError>;
-}
+    /// Returns `true` if there is more data to read.
+    #[inline]
+    pub fn has_data(&self) -> bool {
+        self.pos < self.data.len()
+    }
 
-pub trait JaguarDeserialize<'a>: Sized {
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError>;
-}
+    /// Returns `true` if every byte has been read, the opposite of
+    /// [`Self::has_data`].
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        !self.has_data()
+    }
 
-impl JaguarSerialize for u8 {
+    /// Ends decoding, failing with `TrailingBytes` if any input is left
+    /// unread.
     #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_u8(*self)
+    pub fn finish(self) -> Result<(), SerError> {
+        if self.has_data() {
+            return Err(SerError::TrailingBytes);
+        }
+        Ok(())
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for u8 {
+    /// Enables the string table mode, matching
+    /// [`JaguarSerializer::enable_string_table`].
     #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        de.read_u8()
+    pub fn enable_string_table(&mut self) {
+        self.strings.get_or_insert_with(Vec::new);
     }
-}
 
-impl JaguarSerialize for u32 {
+    /// Enables the extended float dictionary, matching
+    /// [`JaguarSerializer::enable_float_dictionary`]. Without it, the extra
+    /// markers fail with `InvalidData`.
     #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_varint(*self as u64)
+    pub fn enable_float_dictionary(&mut self) {
+        self.float_dictionary = true;
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for u32 {
+    /// Enables canonical mode, in which any encoding `JaguarSerializer` would
+    /// not have produced fails with `InvalidData`.
+    /// 
+    /// This rejects non-minimal varints, integers out of range for their
+    /// type, bools other than `0` and `1`, floats written in full that have a
+    /// shorter encoding, non-zero padding bits, and strings repeated inline
+    /// while the string table is enabled. Use it when the encoded bytes are
+    /// hashed or signed, so every value has exactly one valid encoding.
     #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        Ok(de.read_varint()? as u32)
+    pub fn enable_canonical(&mut self) {
+        self.canonical = true;
     }
-}
 
-impl JaguarSerialize for i32 {
+    /// Sets how deeply sequences, maps and derived structs may nest before
+    /// decoding fails with `DepthLimitExceeded`. Defaults to
+    /// [`Self::DEFAULT_MAX_DEPTH`].
+    /// 
+    /// Each level of nesting costs stack frames, so untrusted input with
+    /// thousands of nested `Vec`s could otherwise overflow the stack, which
+    /// on Solana's 4 KiB BPF frames happens quickly.
     #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_signed_varint(*self as i64)
+    pub fn set_max_depth(&mut self, max_depth: u32) {
+        self.max_depth = max_depth;
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for i32 {
+    /// Runs `f` one nesting level deeper, failing with `DepthLimitExceeded`
+    /// instead if that passes the maximum depth.
+    /// 
+    /// Container impls call this around decoding their contents. Custom impls
+    /// of recursive types should do the same.
     #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        Ok(de.read_signed_varint()? as i32)
+    pub fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, SerError>) -> Result<T, SerError> {
+        if self.depth >= self.max_depth {
+            return Err(SerError::DepthLimitExceeded);
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
     }
-}
 
-impl JaguarSerialize for String {
+    /// Returns the current read position in the data.
     #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_str(self)
+    pub fn position(&self) -> usize {
+        self.pos
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for String {
+    /// Deserializes a `T` for one of the top-level entry points, reporting a
+    /// failure through `tracing` with the type and the offset decoding stopped
+    /// at, when the `tracing` feature is enabled.
     #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        Ok(alloc::string::String::from(de.read_str()?))
+    pub(crate) fn decode_traced<T: JaguarDeserialize<'a>>(&mut self) -> Result<T, SerError> {
+        let result = T::deserialize(self);
+        #[cfg(feature = "tracing")]
+        if let Err(err) = &result {
+            tracing::warn!(
+                type_name = core::any::type_name::<T>(),
+                offset = self.pos,
+                len = self.data.len(),
+                error = ?err,
+                "jaguar: failed to decode",
+            );
+        }
+        result
     }
-}
 
-pub fn serialize<T: JaguarSerialize>(value: &T) -> Result<Vec<u8>, SerError> {
-    let mut ser = JaguarSerializer::new();
-    value.serialize(&mut ser)?;
-    Ok(ser.finish())
-}
+    /// Returns the number of unread bytes.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
 
-pub fn deserialize<'a, T: JaguarDeserialize<'a>>(data: &'a [u8]) -> Result<T, SerError> {
-    let mut de = JaguarDeserializer::new(data);
-    T::deserialize(&mut de)
-}
+    /// Moves the read position to `pos`, which may be anywhere up to the end
+    /// of the input. Fails with `BufferTooSmall` past the end.
+    /// 
+    /// The string table is left as is, so with it enabled prefer
+    /// [`Self::mark`] and [`Self::rewind`].
+    #[inline]
+    pub fn seek(&mut self, pos: usize) -> Result<(), SerError> {
+        if pos > self.data.len() {
+            return Err(SerError::BufferTooSmall);
+        }
+        self.pos = pos;
+        Ok(())
+    }
 
-impl JaguarSerialize for u128 {
+    /// Records the current position, to return to with [`Self::rewind`].
     #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        let high = (*self >> 64) as u64;
-        let low = *self as u64;
-        ser.write_varint(high)?;
-        ser.write_varint(low)
+    pub fn mark(&self) -> Mark {
+        Mark { pos: self.pos, strings: self.strings.as_ref().map_or(0, Vec::len) }
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for u128 {
+    /// Returns to `mark`, forgetting any strings added to the table since, so
+    /// a decoder can retry another layout after a failed parse.
+    /// 
+    /// --------
+    /// 
+    /// ### Usage
+    /// 
+    /// ```rust
+    /// use jaguar::{JaguarDeserialize, JaguarDeserializer};
+    ///
+    /// #[derive(JaguarDeserialize)]
+    /// struct Legacy {
+    ///     owner: [u8; 4],
+    /// }
+    ///
+    /// #[derive(JaguarDeserialize)]
+    /// struct Current {
+    ///     owner: [u8; 4],
+    ///     fee_bps: u16,
+    /// }
+    ///
+    /// let data = [1, 2, 3, 4];
+    /// let mut de = JaguarDeserializer::new(&data);
+    /// let mark = de.mark();
+    /// let fee_bps = match Current::deserialize(&mut de) {
+    ///     Ok(current) => current.fee_bps,
+    ///     Err(_) => {
+    ///         de.rewind(mark);
+    ///         Legacy::deserialize(&mut de).map(|_| 0).unwrap()
+    ///     }
+    /// };
+    /// assert_eq!(fee_bps, 0);
+    /// ```
     #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        let high = de.read_varint()?;
-        let low = de.read_varint()?;
-        Ok(((high as u128) << 64) | (low as u128))
+    pub fn rewind(&mut self, mark: Mark) {
+        self.pos = mark.pos.min(self.data.len());
+        if let Some(table) = self.strings.as_mut() {
+            table.truncate(mark.strings);
+        }
     }
-}
 
-impl JaguarSerialize for u16 {
+    /// Advances past `len` bytes without reading them.
     #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_varint(*self as u64)
+    pub fn skip(&mut self, len: usize) -> Result<(), SerError> {
+        self.take_bytes(len).map(drop)
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for u16 {
+    /// Borrows the next `len` bytes and advances past them.
     #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        Ok(de.read_varint()? as u16)
+    pub(crate) fn take_bytes(&mut self, len: usize) -> Result<&'a [u8], SerError> {
+        let slice = self.peek_bytes(len)?;
+        self.pos += len;
+        Ok(slice)
     }
-}
 
-impl JaguarSerialize for u64 {
+    /// Bounds a decoded element count by the unread input, so a corrupt length
+    /// can't trigger a huge up-front allocation.
     #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_varint(*self)
+    pub(crate) fn capacity_hint(&self, len: usize) -> usize {
+        len.min(self.remaining())
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for u64 {
+    /// Reads the varint element count of a sequence of `T`, checked with
+    /// [`Self::check_seq_len`].
     #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        de.read_varint()
+    pub(crate) fn read_seq_len<T>(&mut self) -> Result<usize, SerError> {
+        let len = self.read_varint()?;
+        self.check_seq_len::<T>(len)
     }
-}
 
-impl JaguarSerialize for i8 {
+    /// Fails unless the unread input could hold `len` elements of `T`, see
+    /// [`Self::check_len`].
     #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_signed_varint(*self as i64)
+    pub(crate) fn check_seq_len<T>(&self, len: u64) -> Result<usize, SerError> {
+        self.check_len(len, mem::size_of::<T>() == 0)
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for i8 {
+    /// Checks a sequence length read from the input before decoding its
+    /// elements, for decoders driven by a runtime [`schema::Schema`].
+    ///
+    /// Fails with `BufferTooSmall` if the unread input can't hold `len`
+    /// elements, since every element takes at least a byte. Zero-sized
+    /// elements take none, so they fail with `InvalidLength` past
+    /// [`Self::MAX_ZERO_SIZED_LEN`] instead, and a corrupt length can't keep
+    /// the decoder looping.
     #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        Ok(de.read_signed_varint()? as i8)
+    pub fn check_len(&self, len: u64, zero_sized: bool) -> Result<usize, SerError> {
+        if zero_sized {
+            if len > Self::MAX_ZERO_SIZED_LEN {
+                return Err(SerError::InvalidLength);
+            }
+        } else if len > self.remaining() as u64 {
+            return Err(SerError::BufferTooSmall);
+        }
+        Ok(len as usize)
     }
-}
 
-impl JaguarSerialize for i16 {
+    /// Reads a single byte from the input.
     #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_signed_varint(*self as i64)
+    pub fn read_u8(&mut self) -> Result<u8, SerError> {
+        #[cfg(not(feature = "safe"))]
+        if self.trusted {
+            // SAFETY: the caller of `new_unchecked` guarantees reads stay in bounds
+            let value = unsafe { *self.data.get_unchecked(self.pos) };
+            self.pos += 1;
+            return Ok(value);
+        }
+        if self.pos >= self.data.len() {
+            return Err(SerError::BufferTooSmall);
+        }
+        let value = self.data[self.pos];
+        self.pos += 1;
+        Ok(value)
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for i16 {
+    /// Borrows every remaining byte without advancing, see [`Self::take_rest`].
     #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        Ok(de.read_signed_varint()? as i16)
+    pub fn remaining_bytes(&self) -> &'a [u8] {
+        let data: &'a [u8] = self.data;
+        &data[self.pos..]
     }
-}
 
-impl JaguarSerialize for i64 {
+    /// Splits off the next `len` bytes as a separate deserializer and advances
+    /// past them, so a nested decoder can't read beyond its window.
+    /// 
+    /// The child shares canonical mode, the float dictionary and the depth
+    /// limit, counting from the current depth, but starts without the string
+    /// table, so windows are decoded on their own. It always checks bounds,
+    /// even when `self` was created with `new_unchecked`.
+    /// 
+    /// --------
+    /// 
+    /// ### Usage
+    /// 
+    /// ```rust
+    /// use jaguar::{JaguarDeserialize, JaguarDeserializer, SerError};
+    ///
+    /// // a one byte length, then a body that must hold exactly one `u32`
+    /// let data = [2, 0xAC, 0x02, 7];
+    /// let mut de = JaguarDeserializer::new(&data);
+    /// let len = de.read_u8()? as usize;
+    /// let mut body = de.take(len)?;
+    /// assert_eq!(u32::deserialize(&mut body)?, 300);
+    /// assert!(body.is_empty());
+    /// assert_eq!(de.read_u8()?, 7);
+    /// # Ok::<(), SerError>(())
+    /// ```
     #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_signed_varint(*self)
+    pub fn take(&mut self, len: usize) -> Result<JaguarDeserializer<'a>, SerError> {
+        let window = self.take_bytes(len)?;
+        Ok(Self {
+            canonical: self.canonical,
+            float_dictionary: self.float_dictionary,
+            depth: self.depth,
+            max_depth: self.max_depth,
+            ..Self::new(window)
+        })
+    }
+
+    /// Borrows every remaining byte and advances to the end of the input.
+    #[inline]
+    pub fn take_rest(&mut self) -> &'a [u8] {
+        let data: &'a [u8] = self.data;
+        let rest = &data[self.pos..];
+        self.pos = data.len();
+        rest
+    }
+
+    /// Returns the next byte without advancing.
+    #[inline]
+    pub fn peek_u8(&self) -> Result<u8, SerError> {
+        self.data.get(self.pos).copied().ok_or(SerError::BufferTooSmall)
+    }
+
+    /// Decodes the next varint without advancing, e.g. to inspect a
+    /// discriminator before dispatching to the matching decoder.
+    #[inline]
+    pub fn peek_varint(&mut self) -> Result<u64, SerError> {
+        let pos = self.pos;
+        let value = self.read_varint();
+        self.pos = pos;
+        value
+    }
+
+    /// Borrows the next `len` bytes without advancing.
+    #[inline]
+    pub fn peek_bytes(&self, len: usize) -> Result<&'a [u8], SerError> {
+        #[cfg(not(feature = "safe"))]
+        if self.trusted {
+            let data: &'a [u8] = self.data;
+            // SAFETY: the caller of `new_unchecked` guarantees reads stay in bounds
+            return Ok(unsafe { data.get_unchecked(self.pos..self.pos + len) });
+        }
+        if len > self.remaining() {
+            return Err(SerError::BufferTooSmall);
+        }
+        let data: &'a [u8] = self.data;
+        Ok(&data[self.pos..self.pos + len])
+    }
+
+    /// Reads a vector of 32-bit integers written as raw bytes, see
+    /// [`JaguarSerializer::write_u32_slice`].
+    #[inline]
+    pub fn read_u32_vec(&mut self) -> Result<Vec<u32>, SerError> {
+        let len = self.read_varint()? as usize;
+        let bytes_needed = len.checked_mul(4).ok_or(SerError::InvalidLength)?;
+        if bytes_needed > self.remaining() {
+            return Err(SerError::BufferTooSmall);
+        }
+        
+        #[cfg(feature = "safe")]
+        let vec = self.data[self.pos..self.pos + bytes_needed]
+            .chunks_exact(4)
+            .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect();
+        #[cfg(not(feature = "safe"))]
+        let vec = {
+            let mut vec = Vec::<u32>::with_capacity(len);
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    self.data.as_ptr().add(self.pos),
+                    vec.as_mut_ptr() as *mut u8,
+                    bytes_needed
+                );
+                vec.set_len(len);
+            }
+            vec
+        };
+        self.pos += bytes_needed;
+        Ok(vec)
+    }
+
+    /// Reads a vector of 32-bit integers into `buf`, replacing its contents but
+    /// keeping its allocation.
+    #[inline]
+    pub fn read_u32_into(&mut self, buf: &mut Vec<u32>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        let bytes_needed = len.checked_mul(4).ok_or(SerError::InvalidLength)?;
+        let bytes = self.take_bytes(bytes_needed)?;
+        buf.clear();
+        buf.extend(
+            bytes
+                .chunks_exact(4)
+                .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])),
+        );
+        Ok(())
+    }
+
+    /// Reads a vector of booleans.
+    #[inline]
+    pub fn read_bool_vec(&mut self) -> Result<Vec<bool>, SerError> {
+        let mut vec = Vec::new();
+        self.read_bool_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a bit-packed vector of booleans into `buf`, replacing its
+    /// contents but keeping its allocation.
+    #[inline]
+    pub fn read_bool_into(&mut self, buf: &mut Vec<bool>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        self.read_bitmap_into(len, buf)
+    }
+
+    /// Reads a vector of booleans written by
+    /// [`JaguarSerializer::write_bool_slice_rle`].
+    #[inline]
+    pub fn read_bool_vec_rle(&mut self) -> Result<Vec<bool>, SerError> {
+        let mut vec = Vec::new();
+        self.read_bool_rle_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a bitmap- or run-length-encoded vector of booleans into `buf`,
+    /// replacing its contents but keeping its allocation.
+    pub fn read_bool_rle_into(&mut self, buf: &mut Vec<bool>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        match self.read_u8()? {
+            RLE_MODE_PLAIN => {
+                self.read_bitmap_into(len, buf)?;
+                if self.canonical && use_runs(len, 1 + rle_len(buf, 0), len.div_ceil(8)) {
+                    return Err(SerError::InvalidData);
+                }
+                Ok(())
+            }
+            RLE_MODE_RUNS if len > 0 => {
+                self.check_rle_len(len)?;
+                let start = self.pos;
+                let mut value = match self.read_u8()? {
+                    0 => false,
+                    1 => true,
+                    _ => return Err(SerError::InvalidData),
+                };
+                buf.clear();
+                buf.reserve(self.capacity_hint(len));
+                while buf.len() < len {
+                    let run = self.read_run(len - buf.len())?;
+                    buf.resize(buf.len() + run, value);
+                    value = !value;
+                }
+                if self.canonical && !use_runs(len, self.pos - start, len.div_ceil(8)) {
+                    return Err(SerError::InvalidData);
+                }
+                Ok(())
+            }
+            _ => Err(SerError::InvalidData),
+        }
+    }
+
+    /// Reads the bit-packed body of a `len`-element bool vector into `buf`.
+    fn read_bitmap_into(&mut self, len: usize, buf: &mut Vec<bool>) -> Result<(), SerError> {
+        let bytes_needed = len.div_ceil(8);
+        if bytes_needed > self.remaining() {
+            return Err(SerError::BufferTooSmall);
+        }
+        
+        buf.clear();
+        buf.reserve(len);
+        let mut pos = 0;
+    
+        while pos + 8 <= len {
+            let byte = self.data[self.pos];
+            self.pos += 1;
+            for i in 0..8 {
+                buf.push((byte & (1 << i)) != 0);
+            }
+            pos += 8;
+        }
+        
+        if pos < len {
+            let byte = self.data[self.pos];
+            self.pos += 1;
+            if self.canonical && byte >> (len - pos) != 0 {
+                return Err(SerError::InvalidData);
+            }
+            for i in 0..(len - pos) {
+                buf.push((byte & (1 << i)) != 0);
+            }
+        }
+        
+        Ok(())
+    }
+
+    /// Deserialization for fixed-size arrays of primitive types.
+    /// 
+    /// Elements are decoded one by one with [`FixedWidth::from_ne_slice`],
+    /// never from uninitialized or zeroed memory, so any type implementing
+    /// [`FixedWidth`] can be read this way.
+    #[inline]
+    pub fn read_fixed_array<T: FixedWidth, const N: usize>(&mut self) -> Result<[T; N], SerError> {
+        let bytes_needed = N * T::WIDTH;
+        if bytes_needed > self.remaining() {
+            return Err(SerError::BufferTooSmall);
+        }
+        
+        let bytes = &self.data[self.pos..self.pos + bytes_needed];
+        let result = core::array::from_fn(|i| T::from_ne_slice(&bytes[i * T::WIDTH..]));
+        self.pos += bytes_needed;
+        Ok(result)
+    }
+
+    /// Borrows a fixed-size byte array directly from the input, without copying.
+    #[inline]
+    pub fn read_array_ref<const N: usize>(&mut self) -> Result<&'a [u8; N], SerError> {
+        self.take_bytes(N)?.try_into().map_err(|_| SerError::InvalidLength)
+    }
+
+    /// Borrows a 64-byte ed25519 signature written by
+    /// [`JaguarSerializer::write_signature`].
+    #[inline]
+    pub fn read_signature_ref(&mut self) -> Result<&'a [u8; 64], SerError> {
+        self.read_array_ref::<64>()
+    }
+
+    /// Reads an unsigned integer written by
+    /// [`JaguarSerializer::write_uint_le`] as `N` little-endian bytes.
+    #[inline]
+    pub fn read_uint_le<const N: usize>(&mut self) -> Result<u64, SerError> {
+        if N == 0 || N > 8 {
+            return Err(SerError::InvalidLength);
+        }
+        let mut buf = [0u8; 8];
+        buf[..N].copy_from_slice(self.take_bytes(N)?);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    /// Reads a 24-bit unsigned integer written by
+    /// [`JaguarSerializer::write_u24`].
+    #[inline]
+    pub fn read_u24(&mut self) -> Result<u32, SerError> {
+        Ok(self.read_uint_le::<3>()? as u32)
+    }
+
+    /// Reads a 48-bit unsigned integer written by
+    /// [`JaguarSerializer::write_u48`].
+    #[inline]
+    pub fn read_u48(&mut self) -> Result<u64, SerError> {
+        self.read_uint_le::<6>()
+    }
+
+    /// Reads a vector of 24-bit unsigned integers written by
+    /// [`JaguarSerializer::write_u24_slice`].
+    #[inline]
+    pub fn read_u24_vec(&mut self) -> Result<Vec<u32>, SerError> {
+        let len = self.read_varint()? as usize;
+        let bytes = self.take_bytes(len.checked_mul(3).ok_or(SerError::InvalidLength)?)?;
+        Ok(bytes.chunks_exact(3).map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], 0])).collect())
+    }
+
+    /// Reads a vector of 48-bit unsigned integers written by
+    /// [`JaguarSerializer::write_u48_slice`].
+    #[inline]
+    pub fn read_u48_vec(&mut self) -> Result<Vec<u64>, SerError> {
+        let len = self.read_varint()? as usize;
+        let bytes = self.take_bytes(len.checked_mul(6).ok_or(SerError::InvalidLength)?)?;
+        Ok(bytes
+            .chunks_exact(6)
+            .map(|chunk| {
+                let mut buf = [0u8; 8];
+                buf[..6].copy_from_slice(chunk);
+                u64::from_le_bytes(buf)
+            })
+            .collect())
+    }
+
+    /// Varint decoding for unsigned integers.
+    #[inline]
+    pub fn read_varint(&mut self) -> Result<u64, SerError> {
+        let mut result = 0u64;
+        let mut shift = 0;
+        let mut count = 0;
+        loop {
+            let byte = self.read_u8()?;
+            result |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                // a trailing zero group, or bits shifted out of a 64-bit value
+                if self.canonical && ((count > 0 && byte == 0) || (shift == 63 && byte > 1)) {
+                    return Err(SerError::InvalidData);
+                }
+                break;
+            }
+            shift += 7;
+            count += 1;
+            if shift >= 64 || count > 9 {
+                return Err(SerError::InvalidData);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Reads a varlen encoded signed integer.
+    #[inline]
+    pub fn read_signed_varint(&mut self) -> Result<i64, SerError> {
+        let encoded = self.read_varint()?;
+        Ok(((encoded >> 1) as i64) ^ (-((encoded & 1) as i64)))
+    }
+
+    /// Reads a varint holding a `bits`-bit integer. Wider values are left for
+    /// the caller to truncate, except in canonical mode.
+    #[inline]
+    fn read_varint_bits(&mut self, bits: u32) -> Result<u64, SerError> {
+        let value = self.read_varint()?;
+        if self.canonical && value >> bits != 0 {
+            return Err(SerError::InvalidData);
+        }
+        Ok(value)
+    }
+
+    /// Signed counterpart of [`Self::read_varint_bits`].
+    #[inline]
+    fn read_signed_varint_bits(&mut self, bits: u32) -> Result<i64, SerError> {
+        let value = self.read_signed_varint()?;
+        if self.canonical && !(-(1 << (bits - 1))..1 << (bits - 1)).contains(&value) {
+            return Err(SerError::InvalidData);
+        }
+        Ok(value)
+    }
+
+    /// Reads a boolean value.
+    #[inline]
+    pub fn read_bool(&mut self) -> Result<bool, SerError> {
+        match self.read_u8()? {
+            byte if self.canonical && byte > 1 => Err(SerError::InvalidData),
+            byte => Ok(byte != 0),
+        }
+    }
+
+    /// Reads a 32-bit float.
+    #[inline]
+    pub fn read_f32(&mut self) -> Result<f32, SerError> {
+        let marker = self.read_u8()?;
+        read_float(self, marker, |de| Ok(f32::from_ne_bytes(*de.read_array_ref::<4>()?)), f64::from, |value| value as f32)
+    }
+
+    /// Reads a 64-bit float.
+    #[inline]
+    pub fn read_f64(&mut self) -> Result<f64, SerError> {
+        let marker = self.read_u8()?;
+        read_float(self, marker, |de| Ok(f64::from_ne_bytes(*de.read_array_ref::<8>()?)), |value| value, |value| value)
+    }
+
+    /// Reads a length-prefixed UTF-8 string.
+    #[inline]
+    pub fn read_str(&mut self) -> Result<&'a str, SerError> {
+        if self.strings.is_some() {
+            let tag = self.read_varint()? as usize;
+            if tag > 0 {
+                return self.strings.as_ref()
+                    .and_then(|table| table.get(tag - 1).copied())
+                    .ok_or(SerError::InvalidData);
+            }
+        }
+
+        let len = self.read_varint()? as usize;
+        let slice = self.take_bytes(len)?;
+        let s = core::str::from_utf8(slice).map_err(|_| SerError::InvalidData)?;
+        if let Some(table) = self.strings.as_mut() {
+            if self.canonical && table.contains(&s) {
+                return Err(SerError::InvalidData);
+            }
+            table.push(s);
+        }
+        Ok(s)
+    }
+
+    /// Reads a sequence written like a `Vec<T>`, returning an iterator that
+    /// decodes one element per step.
+    /// 
+    /// Nothing is allocated, so a program can search or filter a long list,
+    /// and stop early, for only the compute of the elements it looks at. Use
+    /// [`Seq::finish`] to skip past the remaining elements before reading
+    /// anything after the sequence.
+    /// 
+    /// ```rust
+    /// use jaguar::JaguarDeserializer;
+    ///
+    /// let data = jaguar::serialize(&(vec![3u64, 40, 500], 9u8)).unwrap();
+    /// let mut de = JaguarDeserializer::new(&data);
+    /// let mut seq = de.read_seq::<u64>().unwrap();
+    /// assert_eq!(seq.find(|amount| amount.as_ref().is_ok_and(|a| *a > 10)), Some(Ok(40)));
+    /// seq.finish().unwrap();
+    /// assert_eq!(de.read_u8().unwrap(), 9);
+    /// ```
+    #[inline]
+    pub fn read_seq<T: JaguarDeserialize<'a>>(&mut self) -> Result<Seq<'_, 'a, T>, SerError> {
+        let len = self.read_seq_len::<T>()? as u64;
+        if self.depth >= self.max_depth {
+            return Err(SerError::DepthLimitExceeded);
+        }
+        self.depth += 1;
+        Ok(Seq { de: self, remaining: len, _marker: PhantomData })
+    }
+
+    /// Reads a length-prefixed UTF-8 string as an iterator of `&str` chunks.
+    /// 
+    /// Each chunk is at most `chunk_size` bytes (unless a single character is
+    /// wider) and is validated lazily as the iterator advances, so very long
+    /// strings never need to be validated or copied in one piece.
+    #[inline]
+    pub fn read_str_chunks(&mut self, chunk_size: usize) -> Result<StrChunks<'a>, SerError> {
+        if chunk_size == 0 {
+            return Err(SerError::InvalidLength);
+        }
+        let data = self.read_bytes()?;
+        Ok(StrChunks { data, chunk_size })
+    }
+
+    /// Reads a length-prefixed byte slice.
+    #[inline]
+    pub fn read_bytes(&mut self) -> Result<&'a [u8], SerError> {
+        self.read_bytes_with::<prefix::Varint>()
+    }
+
+    /// Borrows a byte slice written with the length prefix `P`.
+    #[inline]
+    pub fn read_bytes_with<P: LenPrefix>(&mut self) -> Result<&'a [u8], SerError> {
+        match P::read_len(self)? {
+            Some(len) => self.take_bytes(len),
+            None => Ok(self.take_rest()),
+        }
+    }
+
+    /// Borrows a string written with the length prefix `P`.
+    #[inline]
+    pub fn read_str_with<P: LenPrefix>(&mut self) -> Result<&'a str, SerError> {
+        core::str::from_utf8(self.read_bytes_with::<P>()?).map_err(|_| SerError::InvalidData)
+    }
+
+    /// Reads a sequence written with the length prefix `P`.
+    #[inline]
+    pub fn read_vec_with<P: LenPrefix, T: JaguarDeserialize<'a>>(&mut self) -> Result<Vec<T>, SerError> {
+        let mut vec = Vec::new();
+        let len = match P::read_len(self)? {
+            Some(len) => Some(self.check_seq_len::<T>(len as u64)?),
+            None => None,
+        };
+        self.nested(|de| {
+            match len {
+                Some(len) => {
+                    vec.reserve(de.capacity_hint(len));
+                    for _ in 0..len {
+                        vec.push(T::deserialize(de)?);
+                    }
+                }
+                None => {
+                    while de.has_data() {
+                        let start = de.pos;
+                        vec.push(T::deserialize(de)?);
+                        // zero-sized elements would never reach the end
+                        if de.pos == start {
+                            return Err(SerError::InvalidData);
+                        }
+                    }
+                }
+            }
+            Ok(vec)
+        })
+    }
+
+    /// Reads a section written with [`JaguarSerializer::begin_section`],
+    /// returning a deserializer over just its body.
+    /// 
+    /// The outer deserializer moves past the whole section, whether or not the
+    /// body is read. The body starts without the string table, since
+    /// [`JaguarSerializer::begin_section`] writes its strings inline, but the
+    /// current and maximum depth, canonical mode and the float dictionary
+    /// setting are shared.
+    #[inline]
+    pub fn read_section(&mut self) -> Result<JaguarDeserializer<'a>, SerError> {
+        let mut section = JaguarDeserializer::new(self.read_bytes()?);
+        section.depth = self.depth;
+        section.max_depth = self.max_depth;
+        section.canonical = self.canonical;
+        section.float_dictionary = self.float_dictionary;
+        #[cfg(not(feature = "safe"))]
+        {
+            section.trusted = self.trusted;
+        }
+        Ok(section)
+    }
+
+    /// Skips over a section without reading its body.
+    #[inline]
+    pub fn skip_section(&mut self) -> Result<(), SerError> {
+        self.read_bytes().map(drop)
+    }
+
+    /// Reads map entries written by [`JaguarSerializer::write_map_sorted`],
+    /// failing with `InvalidData` unless the keys are strictly ascending.
+    pub fn read_map_sorted<K, V>(&mut self) -> Result<Vec<(K, V)>, SerError>
+    where
+        K: JaguarDeserialize<'a> + Ord,
+        V: JaguarDeserialize<'a>,
+    {
+        let len = self.read_varint()? as usize;
+        let mut entries: Vec<(K, V)> = Vec::with_capacity(self.capacity_hint(len));
+        self.nested(|de| {
+            for _ in 0..len {
+                let key = K::deserialize(de)?;
+                if entries.last().is_some_and(|(last, _)| *last >= key) {
+                    return Err(SerError::InvalidData);
+                }
+                entries.push((key, V::deserialize(de)?));
+            }
+            Ok(entries)
+        })
+    }
+
+    /// Reads a vector of strings.
+    #[inline]
+    pub fn read_string_vec(&mut self) -> Result<Vec<String>, SerError> {
+        #[cfg(not(feature = "std"))]
+        {
+            Err(SerError::UnsupportedType)
+        }
+        #[cfg(feature = "std")]
+        {
+            let len = self.read_varint()? as usize;
+            let mut vec = Vec::with_capacity(self.capacity_hint(len));
+            
+            for _ in 0..len {
+                vec.push(String::from(self.read_str()?));
+            }
+            
+            Ok(vec)
+        }
+    }
+
+    /// Reads a vector of 8-bit integers.
+    #[inline]
+    pub fn read_u8_vec(&mut self) -> Result<Vec<u8>, SerError> {
+        let len = self.read_varint()? as usize;
+        Ok(self.take_bytes(len)?.to_vec())
+    }
+
+    /// Reads a vector of 8-bit integers into `buf`, replacing its contents but
+    /// keeping its allocation.
+    #[inline]
+    pub fn read_u8_into(&mut self, buf: &mut Vec<u8>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        let bytes = self.take_bytes(len)?;
+        buf.clear();
+        buf.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Reads a vector of 8-bit integers written by
+    /// [`JaguarSerializer::write_u8_slice_rle`].
+    #[inline]
+    pub fn read_u8_vec_rle(&mut self) -> Result<Vec<u8>, SerError> {
+        let mut vec = Vec::new();
+        self.read_u8_rle_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a raw or run-length-encoded vector of 8-bit integers into `buf`,
+    /// replacing its contents but keeping its allocation.
+    pub fn read_u8_rle_into(&mut self, buf: &mut Vec<u8>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        buf.clear();
+        match self.read_u8()? {
+            RLE_MODE_PLAIN => {
+                buf.extend_from_slice(self.take_bytes(len)?);
+                if self.canonical && use_runs(len, rle_len(buf, 1), len) {
+                    return Err(SerError::InvalidData);
+                }
+                Ok(())
+            }
+            RLE_MODE_RUNS => {
+                self.check_rle_len(len)?;
+                let start = self.pos;
+                buf.reserve(self.capacity_hint(len));
+                let mut prev = None;
+                while buf.len() < len {
+                    let value = self.read_u8()?;
+                    if self.canonical && prev == Some(value) {
+                        return Err(SerError::InvalidData);
+                    }
+                    let run = self.read_run(len - buf.len())?;
+                    buf.resize(buf.len() + run, value);
+                    prev = Some(value);
+                }
+                if self.canonical && !use_runs(len, self.pos - start, len) {
+                    return Err(SerError::InvalidData);
+                }
+                Ok(())
+            }
+            _ => Err(SerError::InvalidData),
+        }
+    }
+
+    /// Rejects run-length-encoded slices claiming more elements than the
+    /// rest of the input could hold under [`RLE_MAX_RATIO`].
+    fn check_rle_len(&self, len: usize) -> Result<(), SerError> {
+        if len / RLE_MAX_RATIO >= self.remaining() {
+            return Err(SerError::InvalidLength);
+        }
+        Ok(())
+    }
+
+    /// Reads the length of a run, which must be nonzero and at most `left`.
+    fn read_run(&mut self, left: usize) -> Result<usize, SerError> {
+        let run = self.read_varint()?;
+        if run == 0 || run > left as u64 {
+            return Err(SerError::InvalidData);
+        }
+        Ok(run as usize)
+    }
+
+    /// Fills `buf` with the next `buf.len()` raw bytes, without a length prefix.
+    #[inline]
+    pub fn read_exact_into(&mut self, buf: &mut [u8]) -> Result<(), SerError> {
+        buf.copy_from_slice(self.take_bytes(buf.len())?);
+        Ok(())
+    }
+
+    /// Reads a vector of 16-bit integers.
+    #[inline]
+    pub fn read_u16_vec(&mut self) -> Result<Vec<u16>, SerError> {
+        let mut vec = Vec::new();
+        self.read_u16_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a vector of 16-bit integers into `buf`, replacing its contents but
+    /// keeping its allocation.
+    #[inline]
+    pub fn read_u16_into(&mut self, buf: &mut Vec<u16>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        buf.clear();
+        buf.reserve(self.capacity_hint(len));
+        for _ in 0..len {
+            buf.push(self.read_varint_bits(16)? as u16);
+        }
+        Ok(())
+    }
+
+    /// Reads a vector of 64-bit integers.
+    #[inline]
+    pub fn read_u64_vec(&mut self) -> Result<Vec<u64>, SerError> {
+        let mut vec = Vec::new();
+        self.read_u64_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a vector of 64-bit integers into `buf`, replacing its contents but
+    /// keeping its allocation.
+    #[inline]
+    pub fn read_u64_into(&mut self, buf: &mut Vec<u64>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        buf.clear();
+        buf.reserve(self.capacity_hint(len));
+        for _ in 0..len {
+            buf.push(self.read_varint()?);
+        }
+        Ok(())
+    }
+
+    /// Reads a vector of delta-encoded 64-bit integers.
+    #[inline]
+    pub fn read_u64_vec_delta(&mut self) -> Result<Vec<u64>, SerError> {
+        let mut vec = Vec::new();
+        self.read_u64_delta_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a vector of delta-encoded 64-bit integers into `buf`, replacing
+    /// its contents but keeping its allocation.
+    #[inline]
+    pub fn read_u64_delta_into(&mut self, buf: &mut Vec<u64>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        buf.clear();
+        buf.reserve(self.capacity_hint(len));
+        let mut prev = 0u64;
+        for _ in 0..len {
+            prev = prev.wrapping_add(self.read_varint()?);
+            buf.push(prev);
+        }
+        Ok(())
+    }
+
+    /// Reads a vector of 32-bit integers written by
+    /// [`JaguarSerializer::write_u32_slice_svb`].
+    #[inline]
+    pub fn read_u32_vec_svb(&mut self) -> Result<Vec<u32>, SerError> {
+        let mut vec = Vec::new();
+        self.read_u32_svb_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a Stream VByte vector of 32-bit integers into `buf`, replacing
+    /// its contents but keeping its allocation.
+    #[inline]
+    pub fn read_u32_svb_into(&mut self, buf: &mut Vec<u32>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        let controls = self.take_bytes(len.div_ceil(4))?;
+        // unused slots in the last control byte are masked off and then
+        // subtracted, since an all-zero slot still counts as one byte
+        let tail = (4 - len % 4) % 4;
+        let last = controls.last().map_or(0, |&c| c & (0xFF >> (tail * 2)));
+        if self.canonical && controls.last().is_some_and(|&c| c != last) {
+            return Err(SerError::InvalidData);
+        }
+        let data_len = match controls.split_last() {
+            Some((_, full)) => full.iter().map(|&c| svb_chunk_len(c)).sum::<usize>() + svb_chunk_len(last) - tail,
+            None => 0,
+        };
+        let data = self.take_bytes(data_len)?;
+
+        buf.clear();
+        buf.reserve(len);
+        let mut at = 0;
+        for (i, &control) in controls.iter().enumerate() {
+            for slot in 0..(len - i * 4).min(4) {
+                let width = ((control >> (slot * 2)) & 0b11) as usize + 1;
+                let value = match data.get(at..at + 4) {
+                    // a full word is in bounds, so load it and mask off the excess
+                    Some(word) => {
+                        u32::from_le_bytes([word[0], word[1], word[2], word[3]]) & (u32::MAX >> ((4 - width) * 8))
+                    }
+                    None => {
+                        let mut bytes = [0u8; 4];
+                        bytes[..width].copy_from_slice(&data[at..at + width]);
+                        u32::from_le_bytes(bytes)
+                    }
+                };
+                if self.canonical && svb_len(value) != width {
+                    return Err(SerError::InvalidData);
+                }
+                buf.push(value);
+                at += width;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a vector of signed 8-bit integers.
+    #[inline]
+    pub fn read_i8_vec(&mut self) -> Result<Vec<i8>, SerError> {
+        let mut vec = Vec::new();
+        self.read_i8_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a vector of signed 8-bit integers into `buf`, replacing its contents but
+    /// keeping its allocation.
+    #[inline]
+    pub fn read_i8_into(&mut self, buf: &mut Vec<i8>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        buf.clear();
+        buf.reserve(self.capacity_hint(len));
+        for _ in 0..len {
+            buf.push(self.read_signed_varint_bits(8)? as i8);
+        }
+        Ok(())
+    }
+
+    /// Reads a vector of signed 16-bit integers.
+    #[inline]
+    pub fn read_i16_vec(&mut self) -> Result<Vec<i16>, SerError> {
+        let mut vec = Vec::new();
+        self.read_i16_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a vector of signed 16-bit integers into `buf`, replacing its contents but
+    /// keeping its allocation.
+    #[inline]
+    pub fn read_i16_into(&mut self, buf: &mut Vec<i16>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        buf.clear();
+        buf.reserve(self.capacity_hint(len));
+        for _ in 0..len {
+            buf.push(self.read_signed_varint_bits(16)? as i16);
+        }
+        Ok(())
+    }
+
+    /// Reads a vector of signed 32-bit integers.
+    #[inline]
+    pub fn read_i32_vec(&mut self) -> Result<Vec<i32>, SerError> {
+        let mut vec = Vec::new();
+        self.read_i32_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a vector of signed 32-bit integers into `buf`, replacing its contents but
+    /// keeping its allocation.
+    #[inline]
+    pub fn read_i32_into(&mut self, buf: &mut Vec<i32>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        buf.clear();
+        buf.reserve(self.capacity_hint(len));
+        for _ in 0..len {
+            buf.push(self.read_signed_varint_bits(32)? as i32);
+        }
+        Ok(())
+    }
+
+    /// Reads a vector of signed 64-bit integers.
+    #[inline]
+    pub fn read_i64_vec(&mut self) -> Result<Vec<i64>, SerError> {
+        let mut vec = Vec::new();
+        self.read_i64_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a vector of signed 64-bit integers into `buf`, replacing its contents but
+    /// keeping its allocation.
+    #[inline]
+    pub fn read_i64_into(&mut self, buf: &mut Vec<i64>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        buf.clear();
+        buf.reserve(self.capacity_hint(len));
+        for _ in 0..len {
+            buf.push(self.read_signed_varint()?);
+        }
+        Ok(())
+    }
+
+    /// Reads a vector of signed 128-bit integers.
+    #[inline]
+    pub fn read_i128_vec(&mut self) -> Result<Vec<i128>, SerError> {
+        let mut vec = Vec::new();
+        self.read_i128_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a vector of signed 128-bit integers into `buf`, replacing its
+    /// contents but keeping its allocation.
+    #[inline]
+    pub fn read_i128_into(&mut self, buf: &mut Vec<i128>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        buf.clear();
+        buf.reserve(self.capacity_hint(len));
+        for _ in 0..len {
+            buf.push(i128::deserialize(self)?);
+        }
+        Ok(())
+    }
+
+    /// Reads a vector of 32-bit floats.
+    #[inline]
+    pub fn read_f32_vec(&mut self) -> Result<Vec<f32>, SerError> {
+        let mut vec = Vec::new();
+        self.read_f32_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a vector of 32-bit floats into `buf`, replacing its contents but
+    /// keeping its allocation.
+    #[inline]
+    pub fn read_f32_into(&mut self, buf: &mut Vec<f32>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        buf.clear();
+        buf.reserve(self.capacity_hint(len));
+        for _ in 0..len {
+            buf.push(self.read_f32()?);
+        }
+        Ok(())
+    }
+
+    /// Reads a vector of 32-bit floats written by
+    /// [`JaguarSerializer::write_f32_slice_raw`].
+    #[inline]
+    pub fn read_f32_vec_raw(&mut self) -> Result<Vec<f32>, SerError> {
+        let mut vec = Vec::new();
+        self.read_f32_into_raw(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Raw counterpart of [`Self::read_f32_into`].
+    #[inline]
+    pub fn read_f32_into_raw(&mut self, buf: &mut Vec<f32>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        let bytes = self.take_bytes(len.checked_mul(4).ok_or(SerError::InvalidLength)?)?;
+        buf.clear();
+        buf.extend(bytes.chunks_exact(4).map(|chunk| f32::from_ne_bytes(chunk.try_into().unwrap())));
+        Ok(())
+    }
+
+    /// Reads a vector of 64-bit floats written by
+    /// [`JaguarSerializer::write_f64_slice_raw`].
+    #[inline]
+    pub fn read_f64_vec_raw(&mut self) -> Result<Vec<f64>, SerError> {
+        let mut vec = Vec::new();
+        self.read_f64_into_raw(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Raw counterpart of [`Self::read_f64_into`].
+    #[inline]
+    pub fn read_f64_into_raw(&mut self, buf: &mut Vec<f64>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        let bytes = self.take_bytes(len.checked_mul(8).ok_or(SerError::InvalidLength)?)?;
+        buf.clear();
+        buf.extend(bytes.chunks_exact(8).map(|chunk| f64::from_ne_bytes(chunk.try_into().unwrap())));
+        Ok(())
+    }
+
+    /// Reads a vector of 64-bit floats.
+    #[inline]
+    pub fn read_f64_vec(&mut self) -> Result<Vec<f64>, SerError> {
+        let mut vec = Vec::new();
+        self.read_f64_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Reads a vector of 64-bit floats into `buf`, replacing its contents but
+    /// keeping its allocation.
+    #[inline]
+    pub fn read_f64_into(&mut self, buf: &mut Vec<f64>) -> Result<(), SerError> {
+        let len = self.read_varint()? as usize;
+        buf.clear();
+        buf.reserve(self.capacity_hint(len));
+        for _ in 0..len {
+            buf.push(self.read_f64()?);
+        }
+        Ok(())
+    }
+
+    int_slice_reads! {
+        u16 => read_u16_vec_packed, read_u16_into_packed, read_u16_vec_raw, read_u16_into_raw;
+        u32 => read_u32_vec_packed, read_u32_into_packed, read_u32_vec_raw, read_u32_into_raw;
+        u64 => read_u64_vec_packed, read_u64_into_packed, read_u64_vec_raw, read_u64_into_raw;
+        i16 => read_i16_vec_packed, read_i16_into_packed, read_i16_vec_raw, read_i16_into_raw;
+        i32 => read_i32_vec_packed, read_i32_into_packed, read_i32_vec_raw, read_i32_into_raw;
+        i64 => read_i64_vec_packed, read_i64_into_packed, read_i64_vec_raw, read_i64_into_raw;
+    }
+}
+
+/// A saved read position, see [`JaguarDeserializer::mark`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mark {
+    pos: usize,
+    strings: usize,
+}
+
+/// An open section, see [`JaguarSerializer::begin_section`].
+#[derive(Debug)]
+#[must_use = "sections must be closed with `end_section`"]
+pub struct Section {
+    start: usize,
+    strings: Option<BTreeMap<String, u64>>,
+}
+
+/// A run of writes sharing one space check, see
+/// [`JaguarSerializer::__coalesce`].
+/// 
+/// Every write first claims its worst-case size from the reserved budget.
+/// The claims are constants, so once inlined into derived code the checks
+/// fold away, but a mismatched reservation still panics instead of writing
+/// out of bounds.
+#[doc(hidden)]
+pub struct Coalesced<'s> {
+    ser: &'s mut JaguarSerializer,
+    budget: usize,
+}
+
+impl Coalesced<'_> {
+    #[inline]
+    fn claim(&mut self, max: usize) {
+        assert!(max <= self.budget, "coalesced write exceeds its reservation");
+        self.budget -= max;
+    }
+
+    #[inline]
+    pub fn u8(&mut self, value: u8) {
+        self.claim(1);
+        self.ser.put_u8(value);
+    }
+
+    #[inline]
+    pub fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    #[inline]
+    fn varint(&mut self, value: u64, max: usize) {
+        self.claim(max);
+        self.ser.put_varint(value);
+    }
+
+    #[inline]
+    fn signed_varint(&mut self, value: i64, max: usize) {
+        self.varint(((value << 1) ^ (value >> 63)) as u64, max);
+    }
+
+    #[inline]
+    pub fn u16(&mut self, value: u16) {
+        self.varint(value as u64, 3);
+    }
+
+    #[inline]
+    pub fn u32(&mut self, value: u32) {
+        self.varint(value as u64, 5);
+    }
+
+    #[inline]
+    pub fn u64(&mut self, value: u64) {
+        self.varint(value, 10);
+    }
+
+    #[inline]
+    pub fn usize(&mut self, value: usize) {
+        self.varint(value as u64, 10);
+    }
+
+    #[inline]
+    pub fn i8(&mut self, value: i8) {
+        self.signed_varint(value as i64, 2);
+    }
+
+    #[inline]
+    pub fn i16(&mut self, value: i16) {
+        self.signed_varint(value as i64, 3);
+    }
+
+    #[inline]
+    pub fn i32(&mut self, value: i32) {
+        self.signed_varint(value as i64, 5);
+    }
+
+    #[inline]
+    pub fn i64(&mut self, value: i64) {
+        self.signed_varint(value, 10);
+    }
+
+    #[inline]
+    pub fn isize(&mut self, value: isize) {
+        self.signed_varint(value as i64, 10);
+    }
+
+    #[inline]
+    pub fn f32(&mut self, value: f32) {
+        self.claim(5);
+        self.ser.put_float(float_code(value as f64, self.ser.float_dictionary), value.to_ne_bytes());
+    }
+
+    #[inline]
+    pub fn f64(&mut self, value: f64) {
+        self.claim(9);
+        self.ser.put_float(float_code(value, self.ser.float_dictionary), value.to_ne_bytes());
+    }
+
+    #[inline]
+    pub fn bytes<const N: usize>(&mut self, value: &[u8; N]) {
+        self.claim(N);
+        self.ser.put_bytes(value);
+    }
+}
+
+/// How a float is written, see [`JaguarSerializer::write_f32`].
+#[derive(Clone, Copy, PartialEq)]
+enum FloatCode {
+    /// A one-byte marker.
+    Marker(u8),
+    /// [`HALF_MARKER`] followed by the value as a little-endian IEEE-754
+    /// half-precision float.
+    Half(u16),
+    /// `255` followed by the value's native-endian bytes.
+    Full,
+}
+
+impl FloatCode {
+    /// Encoded length, for a float of `width` bytes.
+    #[inline]
+    fn len(self, width: usize) -> usize {
+        match self {
+            FloatCode::Marker(_) => 1,
+            FloatCode::Half(_) => 3,
+            FloatCode::Full => 1 + width,
+        }
+    }
+}
+
+/// Marker of a half-precision float in the extended float dictionary.
+const HALF_MARKER: u8 = 254;
+
+/// Values with markers `3..` in the extended float dictionary; NaN is marker
+/// 6 and is matched separately.
+const FLOAT_DICTIONARY: [f64; 6] = [0.5, 2.0, 10.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY];
+
+/// Picks the shortest encoding of `value`, using the markers of
+/// [`JaguarSerializer::enable_float_dictionary`] if `dictionary` is set.
+#[inline]
+fn float_code(value: f64, dictionary: bool) -> FloatCode {
+    if value == 0.0 {
+        return FloatCode::Marker(0);
+    } else if value == 1.0 {
+        return FloatCode::Marker(1);
+    } else if value == -1.0 {
+        return FloatCode::Marker(2);
+    } else if !dictionary {
+        return FloatCode::Full;
+    }
+
+    if value.is_nan() {
+        return FloatCode::Marker(6);
+    }
+    if let Some(index) = FLOAT_DICTIONARY.iter().position(|&entry| entry == value) {
+        return FloatCode::Marker(3 + index as u8);
+    }
+    match f16_bits(value) {
+        Some(bits) => FloatCode::Half(bits),
+        None => FloatCode::Full,
+    }
+}
+
+/// Reads the float following `marker`, the counterpart of [`float_code`].
+/// `read_full` reads the bytes after a `255` marker as they were written, so
+/// `f32` NaN payloads survive; `widen` and `narrow` convert to and from
+/// `f64` for the markers, whose values are exact in either width.
+#[inline]
+fn read_float<F: Copy>(
+    de: &mut JaguarDeserializer<'_>,
+    marker: u8,
+    read_full: impl FnOnce(&mut JaguarDeserializer<'_>) -> Result<F, SerError>,
+    widen: fn(F) -> f64,
+    narrow: fn(f64) -> F,
+) -> Result<F, SerError> {
+    let (value, code) = match marker {
+        0 => return Ok(narrow(0.0)),
+        1 => return Ok(narrow(1.0)),
+        2 => return Ok(narrow(-1.0)),
+        3..=8 if de.float_dictionary => return Ok(narrow(FLOAT_DICTIONARY[marker as usize - 3])),
+        HALF_MARKER if de.float_dictionary => {
+            let bits = u16::from_le_bytes(*de.read_array_ref::<2>()?);
+            (narrow(f16_value(bits)), FloatCode::Half(bits))
+        }
+        255 => (read_full(de)?, FloatCode::Full),
+        _ => return Err(SerError::InvalidData),
+    };
+    if de.canonical && float_code(widen(value), de.float_dictionary) != code {
+        return Err(SerError::InvalidData);
+    }
+    Ok(value)
+}
+
+/// The bits of `value` as a half-precision float, if it is exactly
+/// representable as one. Zero, infinities and NaN have markers, so return
+/// `None`.
+#[inline]
+fn f16_bits(value: f64) -> Option<u16> {
+    let bits = value.to_bits();
+    let sign = ((bits >> 48) & 0x8000) as u16;
+    let exponent = ((bits >> 52) & 0x7FF) as i32 - 1023;
+    let mantissa = bits & ((1 << 52) - 1);
+    if !value.is_finite() || value == 0.0 {
+        return None;
+    }
+
+    if (-14..=15).contains(&exponent) {
+        // normal: the 10 high mantissa bits must hold all of it
+        if mantissa & ((1 << 42) - 1) != 0 {
+            return None;
+        }
+        Some(sign | (((exponent + 15) as u16) << 10) | (mantissa >> 42) as u16)
+    } else if (-24..-14).contains(&exponent) {
+        // subnormal: value = m * 2^-24 with m < 1024
+        let full = mantissa | (1 << 52);
+        let shift = 52 - (exponent + 24);
+        if full & ((1 << shift) - 1) != 0 {
+            return None;
+        }
+        Some(sign | (full >> shift) as u16)
+    } else {
+        None
+    }
+}
+
+/// The value of the half-precision float `bits`.
+#[inline]
+fn f16_value(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1F) as i32;
+    let mantissa = (bits & 0x3FF) as f64;
+    let magnitude = match exponent {
+        0 => mantissa * pow2(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1024.0 + mantissa) * pow2(exponent - 25),
+    };
+    sign * magnitude
+}
+
+/// `2^exponent`, for the small exponents of half-precision floats.
+#[inline]
+fn pow2(exponent: i32) -> f64 {
+    f64::from_bits(((exponent + 1023) as u64) << 52)
+}
+
+/// Mode byte of `*_rle` slices stored as a bitmap or raw bytes.
+const RLE_MODE_PLAIN: u8 = 0;
+/// Mode byte of `*_rle` slices stored as runs of equal values.
+const RLE_MODE_RUNS: u8 = 1;
+/// Run-length-encoded slices decode to fewer than this many elements per
+/// encoded byte, which bounds the allocation a corrupt run can cause.
+const RLE_MAX_RATIO: usize = 4096;
+
+/// Whether a `len`-element `*_rle` slice is written as runs, given its size
+/// as runs and as a bitmap or raw bytes: only when runs are smaller, and
+/// within [`RLE_MAX_RATIO`].
+fn use_runs(len: usize, runs_len: usize, plain_len: usize) -> bool {
+    runs_len < plain_len && len / RLE_MAX_RATIO < runs_len
+}
+
+/// Size of `slice` encoded as runs, each taking `value_len` bytes for its
+/// value plus a varint length.
+fn rle_len<T: PartialEq>(slice: &[T], value_len: usize) -> usize {
+    slice.chunk_by(|a, b| a == b).map(|run| value_len + varint_len(run.len() as u64)).sum()
+}
+
+/// Number of bytes `value` takes in a Stream VByte slice.
+#[inline]
+fn svb_len(value: u32) -> usize {
+    (4 - value.leading_zeros() as usize / 8).max(1)
+}
+
+/// Total data bytes of the four elements described by a Stream VByte
+/// control byte.
+#[inline]
+fn svb_chunk_len(control: u8) -> usize {
+    let c = control as usize;
+    (c & 0b11) + (c >> 2 & 0b11) + (c >> 4 & 0b11) + (c >> 6) + 4
+}
+
+/// Number of bytes in the varint encoding of `value`.
+#[inline]
+fn varint_len(value: u64) -> usize {
+    (64 - (value | 1).leading_zeros() as usize).div_ceil(7)
+}
+
+/// Encodes `value` as a varint into `buf`, returning the number of bytes used.
+#[inline]
+fn encode_varint(mut value: u64, buf: &mut [u8; 10]) -> usize {
+    let mut len = 0;
+    while value >= 0x80 {
+        buf[len] = (value as u8) | 0x80;
+        value >>= 7;
+        len += 1;
+    }
+    buf[len] = value as u8;
+    len + 1
+}
+
+pub trait JaguarSerialize {
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError>;
+}
+
+pub trait JaguarDeserialize<'a>: Sized {
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError>;
+
+    /// Walks the encoding of `Self` without constructing it, failing exactly
+    /// when [`Self::deserialize`] would.
+    /// 
+    /// Defaults to decoding and dropping the value. Types that allocate
+    /// override this to skip the allocation.
+    #[inline]
+    fn validate(de: &mut JaguarDeserializer<'a>) -> Result<(), SerError> {
+        Self::deserialize(de).map(drop)
+    }
+}
+
+/// Primitive numeric types with a fixed-width representation, readable with
+/// [`JaguarDeserializer::read_fixed_array`] and encoded little-endian by
+/// [`Fixed`].
+/// 
+/// Implementing it needs no `unsafe`: a newtype over a primitive, or any
+/// other `Copy` type with a byte layout of its own, opts into those paths by
+/// converting from and to bytes itself.
+pub trait FixedWidth: Copy {
+    const WIDTH: usize;
+
+    /// Decodes a value from the first `WIDTH` bytes of `bytes`.
+    fn from_ne_slice(bytes: &[u8]) -> Self;
+
+    /// Decodes a little-endian value from the first `WIDTH` bytes of `bytes`.
+    fn from_le_slice(bytes: &[u8]) -> Self;
+
+    /// Writes the value little-endian into `out[..WIDTH]`.
+    fn write_le(self, out: &mut [u8]);
+}
+
+macro_rules! impl_fixed_width {
+    ($($t:ty),*) => {
+        $(
+            impl FixedWidth for $t {
+                const WIDTH: usize = mem::size_of::<$t>();
+
+                #[inline]
+                fn from_ne_slice(bytes: &[u8]) -> Self {
+                    let mut buf = [0u8; mem::size_of::<$t>()];
+                    buf.copy_from_slice(&bytes[..Self::WIDTH]);
+                    <$t>::from_ne_bytes(buf)
+                }
+
+                #[inline]
+                fn from_le_slice(bytes: &[u8]) -> Self {
+                    let mut buf = [0u8; mem::size_of::<$t>()];
+                    buf.copy_from_slice(&bytes[..Self::WIDTH]);
+                    <$t>::from_le_bytes(buf)
+                }
+
+                #[inline]
+                fn write_le(self, out: &mut [u8]) {
+                    out[..Self::WIDTH].copy_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_width!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+/// A type that can be deserialized from input of any lifetime, i.e. one that
+/// never borrows from the data it was decoded from.
+/// 
+/// This is implemented automatically, and is easier to name in generic code
+/// than `for<'a> JaguarDeserialize<'a>`.
+pub trait JaguarDeserializeOwned: for<'a> JaguarDeserialize<'a> {}
+
+impl<T> JaguarDeserializeOwned for T where T: for<'a> JaguarDeserialize<'a> {}
+
+/// A custom low-level encoding for values of type `T`.
+/// 
+/// Codecs let downstream crates add their own encodings without touching the
+/// serializer internals, and plug into derives with `#[jaguar(with = "Codec")]`.
+/// A module with free `serialize` and `deserialize` functions of the same
+/// shapes plugs in with `#[jaguar(module = "path")]` instead.
+/// 
+/// --------
+/// 
+/// ### Usage
+/// 
+/// ```rust
+/// use jaguar::{JaguarCodec, JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+///
+/// /// Stores a `u32` as four little-endian bytes instead of a varint.
+/// struct FixedU32;
+///
+/// impl JaguarCodec<u32> for FixedU32 {
+///     fn encode(value: &u32, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+///         value.to_le_bytes().serialize(ser)
+///     }
+///
+///     fn decode(de: &mut JaguarDeserializer<'_>) -> Result<u32, SerError> {
+///         Ok(u32::from_le_bytes(<[u8; 4]>::deserialize(de)?))
+///     }
+/// }
+///
+/// #[derive(JaguarSerialize, JaguarDeserialize)]
+/// struct Header {
+///     #[jaguar(with = "FixedU32")]
+///     magic: u32,
+/// }
+/// ```
+pub trait JaguarCodec<T> {
+    fn encode(value: &T, ser: &mut JaguarSerializer) -> Result<(), SerError>;
+    fn decode(de: &mut JaguarDeserializer<'_>) -> Result<T, SerError>;
+}
+
+/// Encodes `Vec`s of floats or of integers from 16 to 64 bits as raw
+/// native-endian bytes instead of per-element markers or varints, e.g.
+/// [`JaguarSerializer::write_u32_slice_raw`]. Selected in derives with
+/// `#[jaguar(raw)]`.
+pub struct Raw;
+
+macro_rules! impl_raw_codec {
+    ($($t:ty => $write:ident, $read:ident;)*) => {
+        $(
+            impl JaguarCodec<Vec<$t>> for Raw {
+                #[inline]
+                fn encode(value: &Vec<$t>, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+                    ser.$write(value)
+                }
+
+                #[inline]
+                fn decode(de: &mut JaguarDeserializer<'_>) -> Result<Vec<$t>, SerError> {
+                    de.$read()
+                }
+            }
+        )*
+    };
+}
+
+impl_raw_codec! {
+    u16 => write_u16_slice_raw, read_u16_vec_raw;
+    u32 => write_u32_slice_raw, read_u32_vec_raw;
+    u64 => write_u64_slice_raw, read_u64_vec_raw;
+    i16 => write_i16_slice_raw, read_i16_vec_raw;
+    i32 => write_i32_slice_raw, read_i32_vec_raw;
+    i64 => write_i64_slice_raw, read_i64_vec_raw;
+    f32 => write_f32_slice_raw, read_f32_vec_raw;
+    f64 => write_f64_slice_raw, read_f64_vec_raw;
+}
+
+/// Encodes a `Vec<u8>` with a single copy each way, instead of going through
+/// the generic `Vec<T>` impl one byte at a time. The encoding is the same, a
+/// varint length followed by the bytes. Selected in derives with
+/// `#[jaguar(bytes)]`.
+pub struct ByteVec;
+
+impl JaguarCodec<Vec<u8>> for ByteVec {
+    #[inline]
+    fn encode(value: &Vec<u8>, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_u8_slice(value)
+    }
+
+    #[inline]
+    fn decode(de: &mut JaguarDeserializer<'_>) -> Result<Vec<u8>, SerError> {
+        de.read_u8_vec()
+    }
+}
+
+/// Encodes integers and floats as fixed-width little-endian values instead of
+/// varints, so they cost the same to decode whatever their value and sit at
+/// predictable offsets. Selected in derives with `#[jaguar(fixed)]`, on a
+/// field or on a whole struct.
+/// 
+/// `usize` and `isize` have no fixed width and stay varints.
+/// 
+/// --------
+/// 
+/// ### Usage
+/// 
+/// ```rust
+/// use jaguar::{JaguarDeserialize, JaguarSerialize};
+///
+/// #[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+/// struct Order {
+///     #[jaguar(fixed)]
+///     price: u64,
+///     size: u32,
+/// }
+///
+/// let order = Order { price: 300, size: 300 };
+/// let data = jaguar::serialize(&order).unwrap();
+/// assert_eq!(data, [0x2C, 0x01, 0, 0, 0, 0, 0, 0, 0xAC, 0x02]);
+/// assert_eq!(jaguar::deserialize::<Order>(&data).unwrap(), order);
+/// ```
+pub struct Fixed;
+
+impl<T: FixedWidth> JaguarCodec<T> for Fixed {
+    #[inline]
+    fn encode(value: &T, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.ensure_space(T::WIDTH)?;
+        let end = ser.pos + T::WIDTH;
+        value.write_le(&mut ser.buffer[ser.pos..end]);
+        ser.pos = end;
+        Ok(())
+    }
+
+    #[inline]
+    fn decode(de: &mut JaguarDeserializer<'_>) -> Result<T, SerError> {
+        Ok(T::from_le_slice(de.take_bytes(T::WIDTH)?))
+    }
+}
+
+/// A type whose encoding always takes exactly `SIZE` bytes, so it can be
+/// written to the stack without a serializer.
+/// 
+/// The bytes written match [`JaguarSerialize`]. Only `u8`, `bool`, `[u8; N]`
+/// and structs deriving `JaguarConstSize` from them and from `#[jaguar(fixed)]`
+/// numbers qualify, since every other type is varint-encoded.
+/// 
+/// --------
+/// 
+/// ### Usage
+/// 
+/// ```rust
+/// use jaguar::{serialize_const, JaguarConstSize, JaguarSerialize};
+///
+/// #[derive(JaguarSerialize, JaguarConstSize)]
+/// struct Seeds {
+///     owner: [u8; 32],
+///     bump: u8,
+/// }
+///
+/// let seeds = Seeds { owner: [7u8; 32], bump: 254 };
+/// let bytes: [u8; Seeds::SIZE] = serialize_const(&seeds);
+/// assert_eq!(bytes[32], 254);
+/// ```
+pub trait JaguarConstSize {
+    const SIZE: usize;
+
+    /// Writes the encoding into `out[..Self::SIZE]`.
+    /// 
+    /// Panics if `out` is shorter than `Self::SIZE`.
+    fn write_const(&self, out: &mut [u8]);
+}
+
+/// Encodes a [`JaguarConstSize`] value into a stack array.
+/// 
+/// `N` must equal `T::SIZE`, which is checked at compile time.
+#[inline]
+pub fn serialize_const<T: JaguarConstSize, const N: usize>(value: &T) -> [u8; N] {
+    const { assert!(N == T::SIZE, "array length must equal `JaguarConstSize::SIZE`") };
+    let mut out = [0u8; N];
+    value.write_const(&mut out);
+    out
+}
+
+impl JaguarConstSize for u8 {
+    const SIZE: usize = 1;
+
+    #[inline]
+    fn write_const(&self, out: &mut [u8]) {
+        out[0] = *self;
+    }
+}
+
+impl JaguarConstSize for bool {
+    const SIZE: usize = 1;
+
+    #[inline]
+    fn write_const(&self, out: &mut [u8]) {
+        out[0] = *self as u8;
+    }
+}
+
+impl<const N: usize> JaguarConstSize for [u8; N] {
+    const SIZE: usize = N;
+
+    #[inline]
+    fn write_const(&self, out: &mut [u8]) {
+        out[..N].copy_from_slice(self);
+    }
+}
+
+impl JaguarSerialize for u8 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_u8(*self)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for u8 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        de.read_u8()
+    }
+}
+
+impl JaguarSerialize for u32 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_varint(*self as u64)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for u32 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(de.read_varint_bits(32)? as u32)
+    }
+}
+
+impl JaguarSerialize for i32 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_signed_varint(*self as i64)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for i32 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(de.read_signed_varint_bits(32)? as i32)
+    }
+}
+
+impl JaguarSerialize for String {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_str(self)
+    }
+}
+
+impl JaguarSerialize for str {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_str(self)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for String {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(alloc::string::String::from(de.read_str()?))
+    }
+
+    #[inline]
+    fn validate(de: &mut JaguarDeserializer<'a>) -> Result<(), SerError> {
+        de.read_str().map(drop)
+    }
+}
+
+pub fn serialize<T: JaguarSerialize + ?Sized>(value: &T) -> Result<Vec<u8>, SerError> {
+    let mut ser = JaguarSerializer::new();
+    value.serialize(&mut ser)?;
+    Ok(ser.finish())
+}
+
+pub fn deserialize<'a, T: JaguarDeserialize<'a>>(data: &'a [u8]) -> Result<T, SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    de.decode_traced::<T>()
+}
+
+/// Encodes `values` back to back, without a count prefix, the way a batch of
+/// messages is usually stored or sent. Each record decodes on its own with
+/// [`deserialize_with_len`], or all of them at once with [`deserialize_many`].
+/// 
+/// The buffer is sized after the first value, assuming the rest encode to
+/// about as many bytes.
+/// 
+/// --------
+/// 
+/// ### Usage
+/// 
+/// ```rust
+/// let batch = [String::from("buy"), String::from("sell")];
+/// let data = jaguar::serialize_many(&batch).unwrap();
+/// assert_eq!(data, b"\x03buy\x04sell");
+/// assert_eq!(jaguar::deserialize_many::<String>(&data).unwrap(), batch);
+/// ```
+pub fn serialize_many<T: JaguarSerialize>(values: &[T]) -> Result<Vec<u8>, SerError> {
+    let Some((first, rest)) = values.split_first() else {
+        return Ok(Vec::new());
+    };
+    let mut ser = JaguarSerializer::new();
+    first.serialize(&mut ser)?;
+    ser.buffer.reserve(ser.pos.saturating_mul(values.len()).saturating_sub(ser.buffer.len()));
+    for value in rest {
+        value.serialize(&mut ser)?;
+    }
+    Ok(ser.finish())
+}
+
+/// Decodes back-to-back values until `data` runs out, the inverse of
+/// [`serialize_many`].
+/// 
+/// Fails if the last value is cut short, or with `InvalidData` if a value
+/// takes no bytes while input is left, since the loop could never end.
+pub fn deserialize_many<'a, T: JaguarDeserialize<'a>>(data: &'a [u8]) -> Result<Vec<T>, SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    if !de.has_data() {
+        return Ok(Vec::new());
+    }
+    let first = de.decode_traced::<T>()?;
+    if de.position() == 0 {
+        return Err(SerError::InvalidData);
+    }
+    let mut values = Vec::with_capacity(1 + de.remaining() / de.position());
+    values.push(first);
+    while de.has_data() {
+        let start = de.position();
+        values.push(de.decode_traced::<T>()?);
+        if de.position() == start {
+            return Err(SerError::InvalidData);
+        }
+    }
+    Ok(values)
+}
+
+/// Deserializes a value from the front of `data`, also returning the number
+/// of bytes it took, so a reader of back-to-back records knows where the next
+/// one starts.
+/// 
+/// --------
+/// 
+/// ### Usage
+/// 
+/// ```rust
+/// let mut data = jaguar::serialize(&300u32).unwrap();
+/// data.extend(jaguar::serialize(&String::from("next")).unwrap());
+///
+/// let (value, len) = jaguar::deserialize_with_len::<u32>(&data).unwrap();
+/// assert_eq!((value, len), (300, 2));
+/// let (next, _) = jaguar::deserialize_with_len::<String>(&data[len..]).unwrap();
+/// assert_eq!(next, "next");
+/// ```
+pub fn deserialize_with_len<'a, T: JaguarDeserialize<'a>>(data: &'a [u8]) -> Result<(T, usize), SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    let value = de.decode_traced::<T>()?;
+    Ok((value, de.position()))
+}
+
+/// Deserializes a header from the front of `data` and returns it with the
+/// bytes after it, undecoded.
+/// 
+/// Since struct fields are encoded in order, a struct declaring just the
+/// leading fields of a larger one decodes as its header. Programs can check
+/// an account's owner or authority before paying to decode the whole body,
+/// then decode the rest with a struct declaring the remaining fields. This
+/// doesn't hold for `#[jaguar(optimize_layout)]` structs, which reorder
+/// their fields.
+/// 
+/// --------
+/// 
+/// ### Usage
+/// 
+/// ```rust
+/// use jaguar::{JaguarDeserialize, JaguarSerialize};
+///
+/// #[derive(JaguarSerialize)]
+/// struct Vault {
+///     authority: [u8; 32],
+///     balance: u64,
+///     history: Vec<u64>,
+/// }
+///
+/// #[derive(JaguarDeserialize)]
+/// struct VaultHeader {
+///     authority: [u8; 32],
+/// }
+///
+/// #[derive(JaguarDeserialize)]
+/// struct VaultBody {
+///     balance: u64,
+///     history: Vec<u64>,
+/// }
+///
+/// let data = jaguar::serialize(&Vault { authority: [7; 32], balance: 500, history: vec![100, 400] }).unwrap();
+///
+/// let (header, body) = jaguar::deserialize_prefix::<VaultHeader>(&data).unwrap();
+/// assert_eq!(header.authority, [7; 32]);
+/// let body: VaultBody = jaguar::deserialize(body).unwrap();
+/// assert_eq!((body.balance, body.history), (500, vec![100, 400]));
+/// ```
+pub fn deserialize_prefix<'a, H: JaguarDeserialize<'a>>(data: &'a [u8]) -> Result<(H, &'a [u8]), SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    let header = de.decode_traced::<H>()?;
+    Ok((header, &data[de.position()..]))
+}
+
+/// Creates a lazy view of the `T` encoded at the start of `data`, see
+/// [`view`](mod@view).
+#[inline]
+pub fn view<'a, T: JaguarView<'a>>(data: &'a [u8]) -> T::View {
+    T::view(data)
+}
+
+/// Checks that `data` starts with a well-formed encoding of `T`, without
+/// allocating.
+/// 
+/// --------
+/// 
+/// ### Usage
+/// 
+/// ```rust
+/// use jaguar::{validate, JaguarDeserialize, JaguarSerialize};
+///
+/// #[derive(JaguarSerialize, JaguarDeserialize)]
+/// struct Profile {
+///     name: String,
+///     scores: Vec<u32>,
+/// }
+///
+/// let data = jaguar::serialize(&Profile { name: "jag".into(), scores: vec![1, 2] }).unwrap();
+/// assert!(validate::<Profile>(&data).is_ok());
+/// assert!(validate::<Profile>(&data[..3]).is_err());
+/// ```
+pub fn validate<'a, T: JaguarDeserialize<'a>>(data: &'a [u8]) -> Result<(), SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    T::validate(&mut de)
+}
+
+/// Deserializes a value that must span all of `data`, failing with
+/// `TrailingBytes` if any bytes follow it.
+///
+/// [`deserialize`] ignores trailing bytes, so a reader that lags behind a
+/// writer which appended fields silently drops them. This catches that
+/// drift where the layout is known to match exactly.
+///
+/// ```rust
+/// use jaguar::SerError;
+///
+/// let data = jaguar::serialize(&(300u32, 7u8)).unwrap();
+/// assert_eq!(jaguar::deserialize::<u32>(&data), Ok(300));
+/// assert_eq!(jaguar::deserialize_exact::<u32>(&data), Err(SerError::TrailingBytes));
+/// assert_eq!(jaguar::deserialize_exact::<(u32, u8)>(&data), Ok((300, 7)));
+/// ```
+pub fn deserialize_exact<'a, T: JaguarDeserialize<'a>>(data: &'a [u8]) -> Result<T, SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    let value = de.decode_traced::<T>()?;
+    de.finish()?;
+    Ok(value)
+}
+
+/// Deserializes a value in canonical mode (see
+/// [`JaguarDeserializer::enable_canonical`]), also rejecting trailing bytes
+/// with `TrailingBytes`.
+pub fn deserialize_canonical<'a, T: JaguarDeserialize<'a>>(data: &'a [u8]) -> Result<T, SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    de.enable_canonical();
+    let value = de.decode_traced::<T>()?;
+    de.finish()?;
+    Ok(value)
+}
+
+/// Returns `true` if `data` is exactly the encoding of some `T`, i.e. the
+/// bytes `JaguarSerializer` produces for it and nothing else.
+///
+/// ```rust
+/// use jaguar::is_canonical;
+///
+/// assert!(is_canonical::<u32>(&[0x7F]));
+/// assert!(!is_canonical::<u32>(&[0xFF, 0x00]));
+/// assert!(!is_canonical::<u32>(&[0x7F, 0x00]));
+/// ```
+pub fn is_canonical<'a, T: JaguarDeserialize<'a>>(data: &'a [u8]) -> bool {
+    let mut de = JaguarDeserializer::new(data);
+    de.enable_canonical();
+    T::validate(&mut de).is_ok() && !de.has_data()
+}
+
+/// Deserializes a fully-owned value, without tying it to the input's lifetime.
+pub fn deserialize_owned<T: JaguarDeserializeOwned>(data: &[u8]) -> Result<T, SerError> {
+    let mut de = JaguarDeserializer::new(data);
+    de.decode_traced::<T>()
+}
+
+impl JaguarSerialize for u128 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        let high = (*self >> 64) as u64;
+        let low = *self as u64;
+        ser.write_varint(high)?;
+        ser.write_varint(low)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for u128 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        let high = de.read_varint()?;
+        let low = de.read_varint()?;
+        Ok(((high as u128) << 64) | (low as u128))
+    }
+}
+
+/// Zigzag encoded into a `u128`, so small magnitudes of either sign stay
+/// small.
+impl JaguarSerialize for i128 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        (((*self << 1) ^ (*self >> 127)) as u128).serialize(ser)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for i128 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        let encoded = u128::deserialize(de)?;
+        Ok((encoded >> 1) as i128 ^ -((encoded & 1) as i128))
+    }
+}
+
+impl JaguarSerialize for u16 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_varint(*self as u64)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for u16 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(de.read_varint_bits(16)? as u16)
+    }
+}
+
+impl JaguarSerialize for u64 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_varint(*self)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for u64 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        de.read_varint()
+    }
+}
+
+impl JaguarSerialize for i8 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_signed_varint(*self as i64)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for i8 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(de.read_signed_varint_bits(8)? as i8)
+    }
+}
+
+impl JaguarSerialize for i16 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_signed_varint(*self as i64)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for i16 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(de.read_signed_varint_bits(16)? as i16)
+    }
+}
+
+impl JaguarSerialize for i64 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_signed_varint(*self)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for i64 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        de.read_signed_varint()
+    }
+}
+
+/// Always a 64-bit varint, so the encoding does not depend on the target's
+/// pointer width. Decoding fails with `InvalidData` if the value does not fit
+/// in a 32-bit `usize`.
+impl JaguarSerialize for usize {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_varint(*self as u64)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for usize {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        usize::try_from(de.read_varint()?).map_err(|_| SerError::InvalidData)
+    }
+}
+
+/// A 64-bit zigzag varint, see the `usize` impl.
+impl JaguarSerialize for isize {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_signed_varint(*self as i64)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for isize {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        isize::try_from(de.read_signed_varint()?).map_err(|_| SerError::InvalidData)
+    }
+}
+
+impl JaguarSerialize for f32 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_f32(*self)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for f32 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        de.read_f32()
+    }
+}
+
+impl JaguarSerialize for f64 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_f64(*self)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for f64 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        de.read_f64()
+    }
+}
+
+impl JaguarSerialize for bool {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_bool(*self)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for bool {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        de.read_bool()
+    }
+}
+
+impl<T: JaguarSerialize> JaguarSerialize for Vec<T> {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        self.as_slice().serialize(ser)
+    }
+}
+
+/// Encoded like `Vec<T>`.
+impl<T: JaguarSerialize> JaguarSerialize for [T] {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_varint(self.len() as u64)?;
+        for item in self {
+            item.serialize(ser)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: JaguarDeserialize<'a>> JaguarDeserialize<'a> for Vec<T> {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        let len = de.read_seq_len::<T>()?;
+        let mut vec = Vec::with_capacity(de.capacity_hint(len));
+        de.nested(|de| {
+            for _ in 0..len {
+                vec.push(T::deserialize(de)?);
+            }
+            Ok(vec)
+        })
+    }
+
+    #[inline]
+    fn validate(de: &mut JaguarDeserializer<'a>) -> Result<(), SerError> {
+        let len = de.read_seq_len::<T>()?;
+        de.nested(|de| {
+            for _ in 0..len {
+                T::validate(de)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<K: JaguarSerialize + Ord, V: JaguarSerialize> JaguarSerialize for BTreeMap<K, V> {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_map_sorted(self)
+    }
+}
+
+impl<'a, K: JaguarDeserialize<'a> + Ord, V: JaguarDeserialize<'a>> JaguarDeserialize<'a> for BTreeMap<K, V> {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(de.read_map_sorted()?.into_iter().collect())
+    }
+}
+
+macro_rules! impl_pointer {
+    ($($(#[$attr:meta])* $ptr:ident),*) => {
+        $(
+            $(#[$attr])*
+            impl<T: JaguarSerialize + ?Sized> JaguarSerialize for $ptr<T> {
+                #[inline]
+                fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+                    (**self).serialize(ser)
+                }
+            }
+
+            $(#[$attr])*
+            impl<'a, T: JaguarDeserialize<'a>> JaguarDeserialize<'a> for $ptr<T> {
+                #[inline]
+                fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+                    T::deserialize(de).map($ptr::new)
+                }
+
+                #[inline]
+                fn validate(de: &mut JaguarDeserializer<'a>) -> Result<(), SerError> {
+                    T::validate(de)
+                }
+            }
+        )*
+    };
+}
+
+// Pointers are transparent on the wire. Sharing is not preserved: two `Rc`s to
+// one value are written twice and decode as two separate allocations.
+impl_pointer!(Box, Rc, #[cfg(target_has_atomic = "ptr")] Arc);
+
+impl<T: JaguarSerialize + ?Sized> JaguarSerialize for &T {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        (**self).serialize(ser)
+    }
+}
+
+impl<T: JaguarSerialize + ?Sized> JaguarSerialize for &mut T {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        (**self).serialize(ser)
+    }
+}
+
+macro_rules! impl_nonzero {
+    ($($nz:ident($t:ty)),*) => {
+        $(
+            impl JaguarSerialize for $nz {
+                #[inline]
+                fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+                    self.get().serialize(ser)
+                }
+            }
+
+            impl<'a> JaguarDeserialize<'a> for $nz {
+                #[inline]
+                fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+                    $nz::new(<$t>::deserialize(de)?).ok_or(SerError::InvalidData)
+                }
+            }
+        )*
+    };
+}
+
+// Encoded like the underlying integer. Decoding zero fails with `InvalidData`.
+impl_nonzero!(
+    NonZeroU8(u8), NonZeroU16(u16), NonZeroU32(u32), NonZeroU64(u64), NonZeroU128(u128), NonZeroUsize(usize),
+    NonZeroI8(i8), NonZeroI16(i16), NonZeroI32(i32), NonZeroI64(i64), NonZeroI128(i128), NonZeroIsize(isize)
+);
+
+/// Generates impls for zero-sized types, which encode to nothing.
+macro_rules! impl_zero_sized {
+    ($([$($param:ident),*] $t:ty => $value:expr;)*) => {
+        $(
+            impl<$($param: ?Sized),*> JaguarSerialize for $t {
+                #[inline]
+                fn serialize(&self, _ser: &mut JaguarSerializer) -> Result<(), SerError> {
+                    Ok(())
+                }
+            }
+
+            impl<'a, $($param: ?Sized),*> JaguarDeserialize<'a> for $t {
+                #[inline]
+                fn deserialize(_de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+                    Ok($value)
+                }
+            }
+
+            impl<$($param: ?Sized),*> JaguarConstSize for $t {
+                const SIZE: usize = 0;
+
+                #[inline]
+                fn write_const(&self, _out: &mut [u8]) {}
+            }
+        )*
+    };
+}
+
+impl_zero_sized! {
+    [] () => ();
+    [T] PhantomData<T> => PhantomData;
+    [] PhantomPinned => PhantomPinned;
+}
+
+/// Whole seconds as a varint, then nanoseconds as a varint. Nanoseconds of a
+/// second or more fail with `InvalidData`.
+impl JaguarSerialize for Duration {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_varint(self.as_secs())?;
+        ser.write_varint(self.subsec_nanos() as u64)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for Duration {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        let secs = de.read_varint()?;
+        let nanos = u32::deserialize(de)?;
+        if nanos >= 1_000_000_000 {
+            return Err(SerError::InvalidData);
+        }
+        Ok(Duration::new(secs, nanos))
+    }
+}
+
+/// The 4 octets, raw.
+impl JaguarSerialize for Ipv4Addr {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        self.octets().serialize(ser)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for Ipv4Addr {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(Ipv4Addr::from(*de.read_array_ref::<4>()?))
+    }
+}
+
+impl JaguarConstSize for Ipv4Addr {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn write_const(&self, out: &mut [u8]) {
+        self.octets().write_const(out);
+    }
+}
+
+/// The 16 octets, raw.
+impl JaguarSerialize for Ipv6Addr {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        self.octets().serialize(ser)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for Ipv6Addr {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(Ipv6Addr::from(*de.read_array_ref::<16>()?))
+    }
+}
+
+impl JaguarConstSize for Ipv6Addr {
+    const SIZE: usize = 16;
+
+    #[inline]
+    fn write_const(&self, out: &mut [u8]) {
+        self.octets().write_const(out);
+    }
+}
+
+/// A tag byte, `0` for IPv4 or `1` for IPv6, then the address. Other tags
+/// fail with `InvalidData`.
+impl JaguarSerialize for IpAddr {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        match self {
+            IpAddr::V4(ip) => {
+                ser.write_u8(0)?;
+                ip.serialize(ser)
+            }
+            IpAddr::V6(ip) => {
+                ser.write_u8(1)?;
+                ip.serialize(ser)
+            }
+        }
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for IpAddr {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        match de.read_u8()? {
+            0 => Ipv4Addr::deserialize(de).map(IpAddr::V4),
+            1 => Ipv6Addr::deserialize(de).map(IpAddr::V6),
+            _ => Err(SerError::InvalidData),
+        }
+    }
+}
+
+/// The address, then the port as a varint.
+impl JaguarSerialize for SocketAddrV4 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        self.ip().serialize(ser)?;
+        self.port().serialize(ser)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for SocketAddrV4 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        Ok(SocketAddrV4::new(Ipv4Addr::deserialize(de)?, u16::deserialize(de)?))
+    }
+}
+
+/// The address, then the port, flow info and scope ID as varints.
+impl JaguarSerialize for SocketAddrV6 {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        self.ip().serialize(ser)?;
+        self.port().serialize(ser)?;
+        self.flowinfo().serialize(ser)?;
+        self.scope_id().serialize(ser)
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for SocketAddrV6 {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        let ip = Ipv6Addr::deserialize(de)?;
+        let port = u16::deserialize(de)?;
+        let flowinfo = u32::deserialize(de)?;
+        let scope_id = u32::deserialize(de)?;
+        Ok(SocketAddrV6::new(ip, port, flowinfo, scope_id))
+    }
+}
+
+/// A tag byte, as for [`IpAddr`], then the socket address.
+impl JaguarSerialize for SocketAddr {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        match self {
+            SocketAddr::V4(addr) => {
+                ser.write_u8(0)?;
+                addr.serialize(ser)
+            }
+            SocketAddr::V6(addr) => {
+                ser.write_u8(1)?;
+                addr.serialize(ser)
+            }
+        }
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for SocketAddr {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        match de.read_u8()? {
+            0 => SocketAddrV4::deserialize(de).map(SocketAddr::V4),
+            1 => SocketAddrV6::deserialize(de).map(SocketAddr::V6),
+            _ => Err(SerError::InvalidData),
+        }
+    }
+}
+
+impl JaguarSerialize for Cow<'_, str> {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_str(self)
+    }
+}
+
+/// Borrows from the input, so decoding a `Cow<str>` never allocates.
+impl<'de: 'a, 'a> JaguarDeserialize<'de> for Cow<'a, str> {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'de>) -> Result<Self, SerError> {
+        de.read_str().map(Cow::Borrowed)
+    }
+}
+
+impl JaguarSerialize for Cow<'_, [u8]> {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_bytes(self)
+    }
+}
+
+/// Borrows from the input, so decoding a `Cow<[u8]>` never allocates.
+impl<'de: 'a, 'a> JaguarDeserialize<'de> for Cow<'a, [u8]> {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'de>) -> Result<Self, SerError> {
+        de.read_bytes().map(Cow::Borrowed)
+    }
+}
+
+impl<T: JaguarSerialize + Clone> JaguarSerialize for Cow<'_, T> {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        (**self).serialize(ser)
+    }
+}
+
+/// Sized values can't be borrowed from the input, so they always decode as
+/// `Cow::Owned`.
+impl<'de, T: JaguarDeserialize<'de> + Clone> JaguarDeserialize<'de> for Cow<'_, T> {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'de>) -> Result<Self, SerError> {
+        T::deserialize(de).map(Cow::Owned)
+    }
+
+    #[inline]
+    fn validate(de: &mut JaguarDeserializer<'de>) -> Result<(), SerError> {
+        T::validate(de)
+    }
+}
+
+impl<A: JaguarSerialize, B: JaguarSerialize> JaguarSerialize for (A, B) {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        self.0.serialize(ser)?;
+        self.1.serialize(ser)
+    }
+}
+
+impl<'a, A: JaguarDeserialize<'a>, B: JaguarDeserialize<'a>> JaguarDeserialize<'a> for (A, B) {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        let a = A::deserialize(de)?;
+        let b = B::deserialize(de)?;
+        Ok((a, b))
+    }
+
+    #[inline]
+    fn validate(de: &mut JaguarDeserializer<'a>) -> Result<(), SerError> {
+        A::validate(de)?;
+        B::validate(de)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrRef<'a>(pub &'a str);
+
+impl<'a> JaguarSerialize for StrRef<'a> {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_str(self.0)
+    }
+}
+
+impl<'de: 'a, 'a> JaguarDeserialize<'de> for StrRef<'a> {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'de>) -> Result<Self, SerError> {
+        Ok(StrRef(de.read_str()?))
+    }
+}
+
+/// A `[u8; N]` borrowed from the input instead of copied, e.g. a 32-byte
+/// pubkey in an account parser. Encoded exactly like `[u8; N]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArrayRef<'a, const N: usize>(pub &'a [u8; N]);
+
+impl<const N: usize> JaguarSerialize for ArrayRef<'_, N> {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        self.0.serialize(ser)
+    }
+}
+
+impl<'de: 'a, 'a, const N: usize> JaguarDeserialize<'de> for ArrayRef<'a, N> {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'de>) -> Result<Self, SerError> {
+        Ok(ArrayRef(de.read_array_ref::<N>()?))
+    }
+}
+
+impl<const N: usize> JaguarConstSize for ArrayRef<'_, N> {
+    const SIZE: usize = N;
+
+    #[inline]
+    fn write_const(&self, out: &mut [u8]) {
+        self.0.write_const(out)
+    }
+}
+
+/// Iterator over the elements of a sequence, decoded on demand, returned by
+/// [`JaguarDeserializer::read_seq`].
+/// 
+/// Iteration stops after the first error. The sequence counts as one level of
+/// nesting until the iterator is dropped.
+pub struct Seq<'d, 'a, T> {
+    de: &'d mut JaguarDeserializer<'a>,
+    remaining: u64,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: JaguarDeserialize<'a>> Seq<'_, 'a, T> {
+    /// Returns the number of elements not yet decoded.
+    #[inline]
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Validates the remaining elements without decoding them, leaving the
+    /// deserializer just past the sequence.
+    pub fn finish(mut self) -> Result<(), SerError> {
+        while self.remaining > 0 {
+            self.remaining -= 1;
+            if let Err(err) = T::validate(self.de) {
+                self.remaining = 0;
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: JaguarDeserialize<'a>> Iterator for Seq<'_, 'a, T> {
+    type Item = Result<T, SerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let item = T::deserialize(self.de);
+        if item.is_err() {
+            self.remaining = 0;
+        }
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // an error can end the sequence at any point
+        (0, usize::try_from(self.remaining).ok())
+    }
+}
+
+impl<'a, T: JaguarDeserialize<'a>> core::iter::FusedIterator for Seq<'_, 'a, T> {}
+
+impl<T> Drop for Seq<'_, '_, T> {
+    fn drop(&mut self) {
+        self.de.depth -= 1;
+    }
+}
+
+/// Iterator over incrementally validated chunks of a serialized string,
+/// returned by [`JaguarDeserializer::read_str_chunks`].
+///
+/// Chunks never split a UTF-8 character. Iteration stops after the first
+/// invalid chunk.
+#[derive(Debug, Clone)]
+pub struct StrChunks<'a> {
+    data: &'a [u8],
+    chunk_size: usize,
+}
+
+impl<'a> Iterator for StrChunks<'a> {
+    type Item = Result<&'a str, SerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let end = self.chunk_size.min(self.data.len());
+        let len = match core::str::from_utf8(&self.data[..end]) {
+            Ok(_) => end,
+            Err(e) if e.error_len().is_none() && e.valid_up_to() > 0 => e.valid_up_to(),
+            Err(e) if e.error_len().is_none() => {
+                // a single character wider than `chunk_size`, extend to cover it
+                let width = match self.data[0] {
+                    0xC0..=0xDF => 2,
+                    0xE0..=0xEF => 3,
+                    _ => 4,
+                };
+                let width = width.min(self.data.len());
+                if core::str::from_utf8(&self.data[..width]).is_err() {
+                    self.data = &[];
+                    return Some(Err(SerError::InvalidData));
+                }
+                width
+            }
+            Err(_) => {
+                self.data = &[];
+                return Some(Err(SerError::InvalidData));
+            }
+        };
+
+        let (chunk, rest) = self.data.split_at(len);
+        self.data = rest;
+        #[cfg(feature = "safe")]
+        let chunk = core::str::from_utf8(chunk).map_err(|_| SerError::InvalidData);
+        // SAFETY: `chunk` was validated as UTF-8 above
+        #[cfg(not(feature = "safe"))]
+        let chunk = Ok(unsafe { core::str::from_utf8_unchecked(chunk) });
+        Some(chunk)
+    }
+}
+
+// Add implementations for fixed-length arrays
+impl<const N: usize> JaguarSerialize for [u8; N] {
+    #[inline]
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.ensure_space(N)?;
+        ser.put_bytes(self);
+        Ok(())
+    }
+}
+
+impl<'a, const N: usize> JaguarDeserialize<'a> for [u8; N] {
+    #[inline]
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        de.read_array_ref::<N>().copied()
+    }
+}
+
+macro_rules! impl_fixed_array {
+    ($($t:ty),*) => {
+        $(
+            impl<const N: usize> JaguarSerialize for [$t; N] {
+                #[inline]
+                fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+                    ser.write_varint(N as u64)?;
+                    for item in self {
+                        item.serialize(ser)?;
+                    }
+                    Ok(())
+                }
+            }
+
+            impl<'a, const N: usize> JaguarDeserialize<'a> for [$t; N] {
+                #[inline]
+                fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+                    let len = de.read_varint()? as usize;
+                    if len != N {
+                        return Err(SerError::InvalidLength);
+                    }
+                    let mut result = Vec::with_capacity(N);
+                    for _ in 0..N {
+                        result.push(<$t>::deserialize(de)?);
+                    }
+                    Ok(result.try_into().unwrap())
+                }
+
+                #[inline]
+                fn validate(de: &mut JaguarDeserializer<'a>) -> Result<(), SerError> {
+                    if de.read_varint()? as usize != N {
+                        return Err(SerError::InvalidLength);
+                    }
+                    for _ in 0..N {
+                        <$t>::validate(de)?;
+                    }
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_array!(u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64, bool);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint_encode() {
+        let mut ser = JaguarSerializer::new();
+
+        ser.write_varint(0).unwrap();
+        ser.write_varint(127).unwrap();
+        ser.write_varint(128).unwrap();
+        ser.write_varint(16383).unwrap();
+    
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        
+        assert_eq!(de.read_varint().unwrap(), 0);
+        assert_eq!(de.read_varint().unwrap(), 127);
+        assert_eq!(de.read_varint().unwrap(), 128);
+        assert_eq!(de.read_varint().unwrap(), 16383);
+    }
+
+    #[test]
+    fn test_finish_ref_and_clear() {
+        let mut ser = JaguarSerializer::with_capacity(4);
+        ser.write_u8(1).unwrap();
+        ser.write_varint(300).unwrap();
+        let len = ser.buffer.len();
+        assert_eq!(ser.finish_ref(), &[1, 0xAC, 0x02]);
+        assert_eq!(ser.finish_ref(), &[1, 0xAC, 0x02]);
+        // the buffer keeps its grown length for the next write
+        assert_eq!(ser.buffer.len(), len);
+
+        ser.write_u8(9).unwrap();
+        assert_eq!(ser.finish_ref(), &[1, 0xAC, 0x02, 9]);
+
+        ser.clear();
+        assert!(ser.finish_ref().is_empty());
+        ser.write_u8(5).unwrap();
+        assert_eq!(ser.finish(), [5]);
+    }
+
+    #[test]
+    fn test_string_roundtrip() {
+        let original = "Hello, world! 🚀";
+
+        let mut ser = JaguarSerializer::new();
+        ser.write_str(original).unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        let decoded = de.read_str().unwrap();
+        
+        assert_eq!(original, decoded);
+    }
+
+    #[test]
+    fn test_str_chunks() {
+        let original = "abc🚀def🚀ghij";
+        let mut ser = JaguarSerializer::new();
+        ser.write_str(original).unwrap();
+        ser.write_u8(7).unwrap();
+        let data = ser.finish();
+
+        for chunk_size in 1..=8 {
+            let mut de = JaguarDeserializer::new(&data);
+            let chunks: Vec<&str> = de
+                .read_str_chunks(chunk_size)
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert!(chunks.iter().all(|c| c.len() <= chunk_size.max(4)));
+            assert_eq!(chunks.concat(), original);
+            assert_eq!(de.read_u8().unwrap(), 7);
+        }
+
+        let mut ser = JaguarSerializer::new();
+        ser.write_bytes(&[b'o', b'k', 0xFF, b'x']).unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        let mut chunks = de.read_str_chunks(2).unwrap();
+        assert_eq!(chunks.next(), Some(Ok("ok")));
+        assert_eq!(chunks.next(), Some(Err(SerError::InvalidData)));
+        assert_eq!(chunks.next(), None);
+    }
+
+    #[test]
+    fn test_string_table() {
+        let keys = ["mint", "owner", "mint", "amount", "owner", "mint"];
+
+        let mut ser = JaguarSerializer::new();
+        ser.enable_string_table();
+        for key in keys {
+            ser.write_str(key).unwrap();
+        }
+        let data = ser.finish();
+
+        let mut plain = JaguarSerializer::new();
+        for key in keys {
+            plain.write_str(key).unwrap();
+        }
+        assert!(data.len() < plain.finish().len());
+
+        let mut de = JaguarDeserializer::new(&data);
+        de.enable_string_table();
+        for key in keys {
+            assert_eq!(de.read_str().unwrap(), key);
+        }
+        assert!(!de.has_data());
+
+        // reference to an index that was never defined
+        let mut de = JaguarDeserializer::new(&[5]);
+        de.enable_string_table();
+        assert_eq!(de.read_str(), Err(SerError::InvalidData));
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn test_float_compression() {
+        let mut ser = JaguarSerializer::new();
+
+        ser.write_f32(0.0).unwrap();
+        ser.write_f32(1.0).unwrap();
+        ser.write_f32(-1.0).unwrap();
+        ser.write_f32(3.14159).unwrap();
+        
+        let data = ser.data();
+
+        assert_eq!(data[0], 0);
+        assert_eq!(data[1], 1);
+        assert_eq!(data[2], 2); 
+        assert_eq!(data[3], 255); // needs full encoding
+    }
+
+    #[test]
+    fn test_float_dictionary() {
+        let values = [
+            (0.5, 1),
+            (2.0, 1),
+            (10.0, 1),
+            (f64::INFINITY, 1),
+            (f64::NEG_INFINITY, 1),
+            (3.25, 3),
+            (-65504.0, 3),
+            (6.103515625e-5, 3), // smallest normal half
+            (5.960464477539063e-8, 3), // smallest subnormal half
+            (1.0 / 3.0, 9),
+            (65520.0, 9),
+            (1e-9, 9),
+        ];
+        for (value, len) in values {
+            let mut ser = JaguarSerializer::new();
+            ser.enable_float_dictionary();
+            ser.write_f64(value).unwrap();
+            let data = ser.finish();
+            assert_eq!(data.len(), len, "{}", value);
+
+            let mut de = JaguarDeserializer::new(&data);
+            de.enable_float_dictionary();
+            de.enable_canonical();
+            assert_eq!(de.read_f64().unwrap(), value);
+        }
+
+        let mut ser = JaguarSerializer::new();
+        ser.enable_float_dictionary();
+        ser.write_f32(f32::NAN).unwrap();
+        ser.write_f32(0.75).unwrap();
+        ser.write_f32(0.1).unwrap();
+        let data = ser.finish();
+        assert_eq!(data[..4], [6, 254, 0x00, 0x3A]);
+        let mut de = JaguarDeserializer::new(&data);
+        de.enable_float_dictionary();
+        assert!(de.read_f32().unwrap().is_nan());
+        assert_eq!(de.read_f32().unwrap(), 0.75);
+        assert_eq!(de.read_f32().unwrap(), 0.1);
+
+        // readers must opt in to the new markers
+        assert_eq!(JaguarDeserializer::new(&data).read_f32(), Err(SerError::InvalidData));
+
+        // full encodings of values with a shorter one are only canonical
+        // without the dictionary
+        let mut full = alloc::vec![255];
+        full.extend(0.5f32.to_ne_bytes());
+        let mut de = JaguarDeserializer::new(&full);
+        de.enable_canonical();
+        assert_eq!(de.read_f32().unwrap(), 0.5);
+        let mut de = JaguarDeserializer::new(&full);
+        de.enable_canonical();
+        de.enable_float_dictionary();
+        assert_eq!(de.read_f32(), Err(SerError::InvalidData));
+
+        // full encodings keep f32 NaN payloads, even signaling ones
+        let nan = f32::from_bits(0x7FA0_0001);
+        let data = serialize(&nan).unwrap();
+        assert_eq!(deserialize::<f32>(&data).unwrap().to_bits(), nan.to_bits());
+
+        // sections are read with the same markers
+        let mut ser = JaguarSerializer::new();
+        ser.enable_float_dictionary();
+        let section = ser.begin_section().unwrap();
+        ser.write_f64(0.5).unwrap();
+        ser.end_section(section).unwrap();
+        let data = ser.finish();
+        let mut de = JaguarDeserializer::new(&data);
+        de.enable_float_dictionary();
+        assert_eq!(de.read_section().unwrap().read_f64().unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_bool_slice_roundtrip() {
+        let bools: Vec<bool> = (0..10000).map(|i| i % 3 == 0).collect();
+        let mut ser = JaguarSerializer::new();
+        ser.write_bool_slice(&bools).unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        let decoded = de.read_bool_vec().unwrap();
+        assert_eq!(bools, decoded);
+
+        // check for size reduction
+        assert!(data.len() < bools.len() / 2, "size should be reduced by at least 2x");
+    }
+
+    #[test]
+    fn test_varint_micro_benchmark() {
+        let values: Vec<u64> = (0..10000).map(|i| if i % 2 == 0 { i as u64 } else { (i as u64) * 1000 }).collect();
+        let mut ser = JaguarSerializer::new();
+
+        for v in &values {
+            ser.write_varint(*v).unwrap();
+        }
+
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        for orig in &values {
+            let decoded = de.read_varint().unwrap();
+            assert_eq!(*orig, decoded);
+        }
+    }
+
+    #[test]
+    fn test_u32_svb_roundtrip() {
+        let values: Vec<u32> = (0..1001).map(|i| if i % 7 == 0 { u32::MAX - i } else { i * 37 }).collect();
+        let mut ser = JaguarSerializer::new();
+        ser.write_u32_slice_svb(&values).unwrap();
+        ser.write_u32_slice_svb(&[]).unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_u32_vec_svb().unwrap(), values);
+        assert!(de.read_u32_vec_svb().unwrap().is_empty());
+        assert_eq!(de.remaining(), 0);
+
+        // length, one control byte, then 1 + 2 + 4 bytes of data
+        let mut ser = JaguarSerializer::new();
+        ser.write_u32_slice_svb(&[5, 300, u32::MAX]).unwrap();
+        assert_eq!(ser.finish(), [3, 0b11_01_00, 5, 44, 1, 0xFF, 0xFF, 0xFF, 0xFF]);
+
+        // canonical readers reject padded values and stray control bits
+        for bad in [&[1u8, 0b01, 5, 0][..], &[1, 0b0100, 5]] {
+            assert!(JaguarDeserializer::new(bad).read_u32_vec_svb().is_ok());
+            let mut de = JaguarDeserializer::new(bad);
+            de.enable_canonical();
+            assert_eq!(de.read_u32_vec_svb(), Err(SerError::InvalidData));
+        }
+        let mut de = JaguarDeserializer::new(&[2, 0b11, 1]);
+        assert_eq!(de.read_u32_vec_svb(), Err(SerError::BufferTooSmall));
+    }
+
+    #[test]
+    fn test_take_window() {
+        let mut ser = JaguarSerializer::new();
+        ser.write_u8(4).unwrap();
+        ser.write_str("abc").unwrap();
+        ser.write_u8(9).unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        de.enable_canonical();
+        let len = de.read_u8().unwrap() as usize;
+        let mut window = de.take(len).unwrap();
+        assert_eq!(window.remaining_bytes(), b"\x03abc");
+        assert_eq!(window.read_str().unwrap(), "abc");
+        assert!(window.is_empty());
+        assert_eq!(window.read_u8(), Err(SerError::BufferTooSmall));
+        assert_eq!(de.remaining_bytes(), [9]);
+        assert_eq!(de.read_u8().unwrap(), 9);
+        assert!(de.is_empty());
+        assert_eq!(de.take(1).err(), Some(SerError::BufferTooSmall));
+
+        // the window inherits canonical mode and the depth limit
+        let mut de = JaguarDeserializer::new(&[2, 0x80, 0x00]);
+        de.enable_canonical();
+        de.set_max_depth(0);
+        let mut window = de.take(3).unwrap();
+        assert_eq!(window.nested(|_| Ok(())), Err(SerError::DepthLimitExceeded));
+        window.skip(1).unwrap();
+        assert_eq!(window.read_varint(), Err(SerError::InvalidData));
+
+        // raw bytes carry no prefix, so the reader takes whatever is left
+        let mut ser = JaguarSerializer::new();
+        ser.write_u8(2).unwrap();
+        ser.write_raw(&[9, 8, 7]).unwrap();
+        let data = ser.finish();
+        assert_eq!(data, [2, 9, 8, 7]);
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_u8().unwrap(), 2);
+        assert_eq!(de.take_rest(), &[9, 8, 7]);
+        assert!(!de.has_data());
+        assert!(de.take_rest().is_empty());
+    }
+
+    #[test]
+    fn test_nested_sections() {
+        let mut ser = JaguarSerializer::new();
+        let outer = ser.begin_section().unwrap();
+        ser.write_u8(1).unwrap();
+        let inner = ser.begin_section().unwrap();
+        ser.write_padding(200).unwrap();
+        ser.end_section(inner).unwrap();
+        ser.write_u8(2).unwrap();
+        ser.end_section(outer).unwrap();
+        ser.write_u8(3).unwrap();
+
+        let data = ser.finish();
+        // 200 needs a two-byte prefix, so the outer body is 1 + 2 + 200 + 1
+        assert_eq!(&data[..2], &[0xCC, 0x01]);
+
+        let mut de = JaguarDeserializer::new(&data);
+        let mut section = de.read_section().unwrap();
+        assert_eq!(section.read_u8().unwrap(), 1);
+        assert_eq!(section.read_section().unwrap().remaining(), 200);
+        assert_eq!(section.read_u8().unwrap(), 2);
+        assert!(!section.has_data());
+        assert_eq!(de.read_u8().unwrap(), 3);
+
+        let mut de = JaguarDeserializer::new(&data);
+        de.skip_section().unwrap();
+        assert_eq!(de.read_u8().unwrap(), 3);
+
+        // canonical mode applies inside sections too
+        let mut ser = JaguarSerializer::new();
+        let section = ser.begin_section().unwrap();
+        ser.write_raw(&[0x80, 0x00]).unwrap();
+        ser.end_section(section).unwrap();
+        let data = ser.finish();
+        assert_eq!(JaguarDeserializer::new(&data).read_section().unwrap().read_varint().unwrap(), 0);
+        let mut de = JaguarDeserializer::new(&data);
+        de.enable_canonical();
+        assert_eq!(de.read_section().unwrap().read_varint(), Err(SerError::InvalidData));
+
+        // strings inside a section stay out of the string table
+        let mut ser = JaguarSerializer::new();
+        ser.enable_string_table();
+        ser.write_str("alpha").unwrap();
+        let section = ser.begin_section().unwrap();
+        ser.write_str("alpha").unwrap();
+        ser.write_str("beta").unwrap();
+        ser.end_section(section).unwrap();
+        ser.write_str("beta").unwrap();
+        ser.write_str("alpha").unwrap();
+        let data = ser.finish();
+        for read_body in [true, false] {
+            let mut de = JaguarDeserializer::new(&data);
+            de.enable_string_table();
+            assert_eq!(de.read_str().unwrap(), "alpha");
+            if read_body {
+                let mut section = de.read_section().unwrap();
+                assert_eq!(section.read_str().unwrap(), "alpha");
+                assert_eq!(section.read_str().unwrap(), "beta");
+                assert!(!section.has_data());
+            } else {
+                de.skip_section().unwrap();
+            }
+            assert_eq!(de.read_str().unwrap(), "beta");
+            assert_eq!(de.read_str().unwrap(), "alpha");
+            assert!(!de.has_data());
+        }
+    }
+
+    #[test]
+    fn test_seek_and_rewind() {
+        let mut ser = JaguarSerializer::new();
+        ser.enable_string_table();
+        ser.write_str("usdc").unwrap();
+        ser.write_str("sol").unwrap();
+        ser.write_str("sol").unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        de.enable_string_table();
+        assert_eq!(de.read_str().unwrap(), "usdc");
+        let mark = de.mark();
+        assert_eq!(de.read_str().unwrap(), "sol");
+        assert_eq!(de.read_str().unwrap(), "sol");
+        assert!(de.is_empty());
+
+        // the second string is registered again after rewinding
+        de.rewind(mark);
+        assert_eq!(de.position(), 6);
+        assert_eq!(de.read_str().unwrap(), "sol");
+        assert_eq!(de.read_str().unwrap(), "sol");
+
+        de.seek(1).unwrap();
+        assert_eq!(de.read_u8().unwrap(), 4);
+        de.seek(data.len()).unwrap();
+        assert!(de.is_empty());
+        assert_eq!(de.seek(data.len() + 1), Err(SerError::BufferTooSmall));
+        assert_eq!(de.position(), data.len());
+
+        // peeking leaves the position alone
+        let data = [0xAC, 0x02, 7];
+        let mut de = JaguarDeserializer::new(&data);
+        for (position, byte, varint) in [(0, 0xAC, 300), (2, 7, 7)] {
+            assert_eq!(de.peek_u8().unwrap(), byte);
+            assert_eq!(de.peek_varint().unwrap(), varint);
+            assert_eq!(de.peek_bytes(data.len() - position).unwrap(), &data[position..]);
+            assert_eq!(de.peek_bytes(data.len() - position + 1), Err(SerError::BufferTooSmall));
+            assert_eq!(de.position(), position);
+            assert_eq!(de.read_varint().unwrap(), varint);
+        }
+        assert_eq!(de.peek_u8(), Err(SerError::BufferTooSmall));
+        assert_eq!(de.peek_varint(), Err(SerError::BufferTooSmall));
+    }
+
+    #[test]
+    fn test_u64_delta_roundtrip() {
+        let slots: Vec<u64> = (0..1000).map(|i| 250_000_000 + i * 2).collect();
+        let mut ser = JaguarSerializer::new();
+        ser.write_u64_slice_delta(&slots).unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_u64_vec_delta().unwrap(), slots);
+
+        let mut plain = JaguarSerializer::new();
+        plain.write_u64_slice(&slots).unwrap();
+        assert!(data.len() * 3 < plain.finish().len(), "deltas should be much smaller");
+
+        // non-monotonic input still roundtrips
+        let mixed = [10u64, 3, u64::MAX, 0, 42];
+        let mut ser = JaguarSerializer::new();
+        ser.write_u64_slice_delta(&mixed).unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_u64_vec_delta().unwrap(), mixed);
+    }
+
+    #[test]
+    fn test_rle_slices() {
+        let mut flags = alloc::vec![false; 1000];
+        flags[10] = true;
+        flags[500] = true;
+        let mut ser = JaguarSerializer::new();
+        ser.write_bool_slice_rle(&flags).unwrap();
+        let data = ser.finish();
+        // length, mode and first value, then runs of 10, 1, 489, 1 and 499
+        assert_eq!(data.len(), 2 + 1 + 1 + 7);
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_bool_vec_rle().unwrap(), flags);
+        assert!(!de.has_data());
+
+        // mixed input falls back to the bitmap
+        let mixed = [true, false, true, true, false, false, true, false, true];
+        let mut ser = JaguarSerializer::new();
+        ser.write_bool_slice_rle(&mixed).unwrap();
+        let data = ser.finish();
+        assert_eq!(data, [9, 0, 0b0100_1101, 1]);
+        assert_eq!(JaguarDeserializer::new(&data).read_bool_vec_rle().unwrap(), mixed);
+
+        let bytes = [[7u8; 300].as_slice(), &[0; 200], &[1, 2, 3]].concat();
+        let mut ser = JaguarSerializer::new();
+        ser.write_u8_slice_rle(&bytes).unwrap();
+        let data = ser.finish();
+        assert_eq!(data, [0xF7, 0x03, 1, 7, 0xAC, 0x02, 0, 0xC8, 0x01, 1, 1, 2, 1, 3, 1]);
+        assert_eq!(JaguarDeserializer::new(&data).read_u8_vec_rle().unwrap(), bytes);
+
+        let mut ser = JaguarSerializer::new();
+        ser.write_u8_slice_rle(&[1, 2, 3]).unwrap();
+        ser.write_u8_slice_rle(&[]).unwrap();
+        let data = ser.finish();
+        assert_eq!(data, [3, 0, 1, 2, 3, 0, 0]);
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_u8_vec_rle().unwrap(), [1, 2, 3]);
+        assert_eq!(de.read_u8_vec_rle().unwrap(), []);
+
+        // runs must be non-empty, add up to the length and fit the ratio
+        let read = |data: &[u8]| JaguarDeserializer::new(data).read_u8_vec_rle();
+        assert_eq!(read(&[3, 1, 5, 0, 5, 3]), Err(SerError::InvalidData));
+        assert_eq!(read(&[3, 1, 5, 4]), Err(SerError::InvalidData));
+        assert_eq!(read(&[3, 2, 5, 3]), Err(SerError::InvalidData));
+        assert_eq!(read(&[0x80, 0x80, 0x01, 1, 5, 0x80, 0x80, 0x01]), Err(SerError::InvalidLength));
+        assert_eq!(JaguarDeserializer::new(&[3, 1, 2, 3]).read_bool_vec_rle(), Err(SerError::InvalidData));
+
+        // canonical readers reject the mode the writer wouldn't pick
+        assert_eq!(read(&[4, 1, 5, 1, 5, 3]).unwrap(), [5; 4]);
+        let canonical = |data: &[u8]| {
+            let mut de = JaguarDeserializer::new(data);
+            de.enable_canonical();
+            de.read_u8_vec_rle()
+        };
+        assert_eq!(canonical(&[4, 1, 5, 1, 5, 3]), Err(SerError::InvalidData));
+        assert_eq!(canonical(&[4, 1, 5, 1, 6, 3]), Err(SerError::InvalidData));
+        assert_eq!(canonical(&[4, 0, 5, 5, 5, 5]), Err(SerError::InvalidData));
+        assert_eq!(canonical(&[4, 0, 5, 5, 6, 6]).unwrap(), [5, 5, 6, 6]);
+    }
+
+    #[test]
+    fn test_reference_impls() {
+        fn encode<T: JaguarSerialize>(value: T) -> Vec<u8> {
+            serialize(&value).unwrap()
+        }
+
+        let owned = alloc::vec![String::from("buy"), String::from("sell")];
+        let data = serialize(&owned).unwrap();
+        assert_eq!(encode(&owned), data);
+        let nested: &&Vec<String> = &&owned;
+        assert_eq!(encode(nested), data);
+        assert_eq!(encode(owned.as_slice()), data);
+        let mut copy = owned.clone();
+        let unique: &mut Vec<String> = &mut copy;
+        assert_eq!(encode(unique), data);
+        assert_eq!(encode(["buy", "sell"].as_slice()), data);
+        assert_eq!(encode(alloc::vec!["buy", "sell"].into_boxed_slice()), data);
+
+        assert_eq!(serialize("buy").unwrap(), serialize(&String::from("buy")).unwrap());
+        assert_eq!(encode(Box::<str>::from("buy")), b"\x03buy");
+        assert_eq!(serialize(&[1u8, 2][..]).unwrap(), serialize(&alloc::vec![1u8, 2]).unwrap());
+    }
+
+    #[test]
+    fn test_sequence_length_limits() {
+        // zero-sized elements take no input, so only the cap bounds them
+        let huge = [0xFF, 0xFF, 0xFF, 0x0F];
+        assert_eq!(deserialize::<Vec<()>>(&huge), Err(SerError::InvalidLength));
+        assert_eq!(validate::<Vec<PhantomData<u8>>>(&huge), Err(SerError::InvalidLength));
+        let max = JaguarDeserializer::MAX_ZERO_SIZED_LEN as usize;
+        let data = serialize(&alloc::vec![(); max]).unwrap();
+        assert_eq!(deserialize::<Vec<()>>(&data).unwrap().len(), max);
+        let data = serialize(&alloc::vec![(); max + 1]).unwrap();
+        assert_eq!(deserialize::<Vec<()>>(&data), Err(SerError::InvalidLength));
+
+        // other elements take at least a byte each
+        assert_eq!(deserialize::<Vec<u8>>(&[3, 1, 2]), Err(SerError::BufferTooSmall));
+        assert_eq!(validate::<Vec<String>>(&[3, 0, 0]), Err(SerError::BufferTooSmall));
+        let mut de = JaguarDeserializer::new(&huge);
+        assert_eq!(de.read_seq::<u64>().err(), Some(SerError::BufferTooSmall));
+        assert_eq!(deserialize::<Vec<u8>>(&[2, 1, 2]).unwrap(), [1, 2]);
+
+        // a corrupt length fails cleanly instead of overflowing or allocating
+        let mut ser = JaguarSerializer::new();
+        ser.write_varint(u64::MAX).unwrap();
+        let corrupt = ser.finish();
+        type Reader = fn(&[u8]) -> Result<(), SerError>;
+        for (read, expected) in [
+            ((|data| JaguarDeserializer::new(data).read_u32_vec().map(drop)) as Reader, SerError::InvalidLength),
+            (|data| JaguarDeserializer::new(data).read_bytes().map(drop), SerError::BufferTooSmall),
+            (|data| JaguarDeserializer::new(data).read_u64_vec().map(drop), SerError::BufferTooSmall),
+            (|data| deserialize::<Vec<String>>(data).map(drop), SerError::BufferTooSmall),
+        ] {
+            assert_eq!(read(&corrupt), Err(expected));
+        }
+    }
+
+    #[test]
+    fn test_u128_roundtrip() {
+        let value = u128::MAX;
+        let mut ser = JaguarSerializer::new();
+        value.serialize(&mut ser).unwrap();
+        let data = ser.finish();
+        
+        let mut de = JaguarDeserializer::new(&data);
+        let decoded = u128::deserialize(&mut de).unwrap();
+
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn test_fixed_array_roundtrip() {
+        // [u8; 32]
+        let pubkey = [1u8; 32];
+
+        let mut ser = JaguarSerializer::new();
+        pubkey.serialize(&mut ser).unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        let decoded = <[u8; 32]>::deserialize(&mut de).unwrap();
+
+        assert_eq!(pubkey, decoded);
+
+        // [u32; 4]
+        let ints: [u32; 4] = [1, 2, 3, 4];
+
+        let mut ser = JaguarSerializer::new();
+        ints.serialize(&mut ser).unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        let decoded = <[u32; 4]>::deserialize(&mut de).unwrap();
+
+        assert_eq!(ints, decoded);
+
+        // [bool; 8]
+        let bools: [bool; 8] = [true, false, true, false, true, false, true, false];
+
+        let mut ser = JaguarSerializer::new();
+        bools.serialize(&mut ser).unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        let decoded = <[bool; 8]>::deserialize(&mut de).unwrap();
+
+        assert_eq!(bools, decoded);
+    }
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let signature: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let blob = [9u8; 128];
+
+        let mut ser = JaguarSerializer::new();
+        ser.write_signature(&signature).unwrap();
+        blob.serialize(&mut ser).unwrap();
+        let data = ser.finish();
+        assert_eq!(data.len(), 64 + 128);
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_signature_ref().unwrap(), &signature);
+        assert_eq!(<[u8; 128]>::deserialize(&mut de).unwrap(), blob);
+        assert_eq!(de.read_signature_ref(), Err(SerError::BufferTooSmall));
+    }
+
+    #[test]
+    fn test_deserialize_owned() {
+        type Decoder = fn(&[u8]) -> Result<u64, SerError>;
+
+        fn widen<T: JaguarDeserializeOwned + Into<u64>>(data: &[u8]) -> Result<u64, SerError> {
+            deserialize_owned::<T>(data).map(Into::into)
+        }
+
+        let decoders: BTreeMap<&str, Decoder> = [
+            ("u8", widen::<u8> as Decoder),
+            ("u32", widen::<u32> as Decoder),
+        ]
+        .into_iter()
+        .collect();
+
+        let data = serialize(&70_000u32).unwrap();
+        assert_eq!(decoders["u32"](&data), Ok(70_000));
+        assert_eq!(decoders["u8"](&[9]), Ok(9));
+
+        let owned: String = {
+            let data = serialize(&String::from("owned")).unwrap();
+            deserialize_owned(&data).unwrap()
+        };
+        assert_eq!(owned, "owned");
+    }
+
+    #[test]
+    fn test_fixed_array_invalid_length() {
+        let data = [1u32, 2, 3];
+        let mut ser = JaguarSerializer::new();
+        ser.write_varint(3).unwrap();
+
+        for &x in &data {
+            x.serialize(&mut ser).unwrap();
+        }
+        let serialized = ser.finish();
+        
+        let mut de = JaguarDeserializer::new(&serialized);
+
+        assert!(matches!(<[u32; 4]>::deserialize(&mut de), Err(SerError::InvalidLength)));
+    }
+
+    #[test]
+    fn test_bit_packing() {
+        let mut ser = JaguarSerializer::new();
+        let mut bits = ser.bit_writer();
+        bits.write_bits(5, 3).unwrap();
+        bits.write_bool(true).unwrap();
+        bits.write_bits(17, 5).unwrap();
+        assert_eq!(bits.write_bits(8, 3), Err(SerError::InvalidData));
+        bits.finish().unwrap();
+        ser.write_u8(0xAB).unwrap();
+
+        let data = ser.finish();
+        assert_eq!(data, [0b0001_1101, 0b0000_0001, 0xAB]);
+
+        let mut de = JaguarDeserializer::new(&data);
+        let mut bits = de.bit_reader();
+        assert_eq!(bits.read_bits(3).unwrap(), 5);
+        assert!(bits.read_bool().unwrap());
+        assert_eq!(bits.read_bits(5).unwrap(), 17);
+        bits.finish().unwrap();
+        assert_eq!(de.read_u8().unwrap(), 0xAB);
+
+        let mut de = JaguarDeserializer::new(&[0b1000_0101]);
+        let mut bits = de.bit_reader();
+        assert_eq!(bits.read_bits(3).unwrap(), 5);
+        assert_eq!(bits.finish(), Err(SerError::InvalidData));
+    }
+
+    #[test]
+    fn test_max_size() {
+        let mut ser = JaguarSerializer::new().with_max_size(4);
+        ser.write_varint(300).unwrap();
+        ser.write_u8(1).unwrap();
+        assert_eq!(ser.write_varint(128), Err(SerError::BufferTooSmall));
+        assert_eq!(ser.write_raw(&[1, 2]), Err(SerError::BufferTooSmall));
+        ser.write_bool(true).unwrap();
+        assert_eq!(ser.write_u8(0), Err(SerError::BufferTooSmall));
+        assert_eq!(ser.finish(), [0xAC, 0x02, 1, 1]);
+    }
+
+    #[test]
+    fn test_varint_len() {
+        for value in [0, 1, 127, 128, 16_383, 16_384, u64::MAX] {
+            let mut buf = [0u8; 10];
+            assert_eq!(varint_len(value), encode_varint(value, &mut buf));
+        }
+    }
+
+    #[test]
+    fn test_serializer_builder() {
+        for growth in [Growth::Exact, Growth::Double] {
+            let mut ser = JaguarSerializer::builder().capacity(4).max_size(100).growth(growth).build();
+            for _ in 0..9 {
+                ser.write_raw(&[1; 10]).unwrap();
+                assert!(ser.buffer.capacity() <= 100);
+            }
+            assert_eq!(ser.write_raw(&[1; 11]), Err(SerError::BufferTooSmall));
+            ser.write_raw(&[1; 10]).unwrap();
+            assert_eq!(ser.write_u8(0), Err(SerError::BufferTooSmall));
+            assert!(ser.buffer.capacity() <= 100);
+            assert_eq!(ser.finish(), [1; 100]);
+        }
+
+        let mut ser = JaguarSerializer::builder().capacity(4).growth(Growth::Exact).build();
+        ser.write_raw(&[1; 5]).unwrap();
+        assert_eq!(ser.buffer.len(), 5);
+
+        let ser = JaguarSerializer::builder().capacity(4096).max_size(16).build();
+        assert!(ser.buffer.capacity() < 4096);
+    }
+
+    #[test]
+    fn test_serialize_many() {
+        let values = alloc::vec![1u64, 300, u64::MAX];
+        let data = serialize_many(&values).unwrap();
+        let mut expected = Vec::new();
+        for value in &values {
+            expected.extend(serialize(value).unwrap());
+        }
+        assert_eq!(data, expected);
+        assert_eq!(deserialize_many::<u64>(&data).unwrap(), values);
+
+        assert!(serialize_many::<u64>(&[]).unwrap().is_empty());
+        assert!(deserialize_many::<u64>(&[]).unwrap().is_empty());
+        assert_eq!(deserialize_many::<u64>(&data[..data.len() - 1]), Err(SerError::BufferTooSmall));
+        assert_eq!(deserialize_many::<()>(&[0]), Err(SerError::InvalidData));
+    }
+
+    #[test]
+    fn test_chained_deserializer() {
+        use crate::chained::ChainedDeserializer;
+
+        let values = alloc::vec![(1u64, String::from("a")), (u64::MAX, String::from("straddles")), (300, String::new())];
+        let data = serialize_many(&values).unwrap();
+        for split in [1, 3, data.len()] {
+            let slices: Vec<&[u8]> = data.chunks(split).chain([&[][..]]).collect();
+            let mut de = ChainedDeserializer::new(&slices);
+            for value in &values {
+                assert_eq!(&de.deserialize::<(u64, String)>().unwrap(), value);
+            }
+            assert!(!de.has_data());
+            assert_eq!(de.position(), data.len());
+            assert_eq!(de.deserialize::<u8>(), Err(SerError::BufferTooSmall));
+        }
+
+        let slices = [&data[..4], &data[4..12], &[][..], &data[12..20]];
+        let mut de = ChainedDeserializer::new(&slices);
+        de.skip(3).unwrap();
+        assert_eq!(de.remaining(), 17);
+        assert_eq!(de.deserialize::<(u64, String)>(), Err(SerError::BufferTooSmall));
+        assert_eq!(de.position(), 3);
+        assert_eq!(de.skip(18), Err(SerError::BufferTooSmall));
+        assert_eq!(de.deserialize::<u64>().unwrap(), u64::MAX);
+
+        // a value spanning many slices, retried as the joined bytes double
+        let long = "x".repeat(1000);
+        let data = serialize(&(long.clone(), 7u8)).unwrap();
+        let slices: Vec<&[u8]> = data.chunks(1).collect();
+        let mut de = ChainedDeserializer::new(&slices);
+        assert_eq!(de.deserialize::<String>().unwrap(), long);
+        assert_eq!(de.position(), data.len() - 1);
+        assert_eq!(de.deserialize::<u8>().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_multi_stream() {
+        use crate::streams::MultiSerializer;
+
+        let mut ser = MultiSerializer::new(3);
+        assert!(ser.is_empty());
+        let names = ["a", "bcd", ""];
+        ser.stream(0).write_u8(names.len() as u8).unwrap();
+        for name in names {
+            let offset = ser.stream(2).position() as u16;
+            ser.stream(1).write_raw(&offset.to_le_bytes()).unwrap();
+            ser.stream(2).write_str(name).unwrap();
+        }
+        assert_eq!(ser.offset(2), 1 + 2 * names.len());
+        assert_eq!(ser.len(), 7 + 2 + 4 + 1);
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        let count = de.read_u8().unwrap() as usize;
+        let body = 1 + 2 * count;
+        for (index, name) in names.iter().enumerate() {
+            let offset = u16::from_le_bytes(*de.read_array_ref::<2>().unwrap()) as usize;
+            let mut entry = JaguarDeserializer::new(&data[body + offset..]);
+            assert_eq!(entry.read_str().unwrap(), *name, "entry {}", index);
+        }
+        assert_eq!(MultiSerializer::new(0).finish(), [0u8; 0]);
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        let errors = [
+            SerError::BufferTooSmall,
+            SerError::InvalidData,
+            SerError::InvalidLength,
+            SerError::UnsupportedType,
+            SerError::DepthLimitExceeded,
+            SerError::ChecksumMismatch,
+            SerError::DiscriminatorMismatch,
+            SerError::VersionMismatch,
+            SerError::TrailingBytes,
+        ];
+        let codes = errors.each_ref().map(SerError::code);
+        assert_eq!(codes, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(SerError::InvalidLength.kind(), ErrorKind::InvalidData);
+
+        let mut de = JaguarDeserializer::new(&[0x80]);
+        let err = de.read_varint().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BufferTooSmall);
+        assert_eq!(alloc::format!("{}", err), "buffer too small");
+    }
+
+    #[cfg(feature = "solana")]
+    #[test]
+    fn test_account_space() {
+        use crate::solana::{AccountSpace, MAX_ACCOUNT_SIZE};
+        use pinocchio::sysvars::rent::{Rent, DEFAULT_LAMPORTS_PER_BYTE_YEAR};
+
+        let rent = Rent {
+            lamports_per_byte_year: DEFAULT_LAMPORTS_PER_BYTE_YEAR,
+            exemption_threshold: 2.0,
+            burn_percent: 50,
+        };
+        // the well-known rent-exempt minimum for a 165-byte token account
+        let space = AccountSpace::of::<[u8; 165]>(&rent);
+        assert_eq!(space, AccountSpace { space: 165, lamports: 2_039_280 });
+
+        let value = String::from("vault");
+        let space = AccountSpace::for_value(&value, 32, &rent).unwrap();
+        assert_eq!(space.space, 6 + 32);
+        assert_eq!(space.lamports, rent.minimum_balance(38));
+        assert!(AccountSpace::for_value(&value, MAX_ACCOUNT_SIZE, &rent).is_err());
+    }
+
+    #[test]
+    fn test_canonical() {
+        let mut ser = JaguarSerializer::new();
+        ser.write_varint(300).unwrap();
+        ser.write_f64(2.5).unwrap();
+        ser.write_bool_slice(&[true, false, true]).unwrap();
+        let data = ser.finish();
+        let mut de = JaguarDeserializer::new(&data);
+        de.enable_canonical();
+        assert_eq!(de.read_varint().unwrap(), 300);
+        assert_eq!(de.read_f64().unwrap(), 2.5);
+        assert_eq!(de.read_bool_vec().unwrap(), [true, false, true]);
+
+        for (data, valid) in [
+            (&[0xAC, 0x02][..], true),
+            (&[0xAC, 0x82, 0x00], false),
+            (&[0x80, 0x00], false),
+            (&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01], true),
+            (&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F], false),
+        ] {
+            assert!(JaguarDeserializer::new(data).read_varint().is_ok());
+            assert_eq!(is_canonical::<u64>(data), valid, "{:?}", data);
+        }
+
+        assert!(is_canonical::<u16>(&[0xFF, 0xFF, 0x03]));
+        assert!(!is_canonical::<u16>(&[0x80, 0x80, 0x04]));
+        assert!(!is_canonical::<i8>(&[0x80, 0x02]));
+        assert!(!is_canonical::<bool>(&[2]));
+        let mut full_one = [255u8; 5];
+        full_one[1..].copy_from_slice(&1.0f32.to_ne_bytes());
+        assert!(deserialize::<f32>(&full_one).is_ok());
+        assert!(!is_canonical::<f32>(&full_one));
+        let mut de = JaguarDeserializer::new(&[3, 0b1000_0101]);
+        de.enable_canonical();
+        assert_eq!(de.read_bool_vec(), Err(SerError::InvalidData));
+        assert_eq!(deserialize_canonical::<u8>(&[1, 2]), Err(SerError::TrailingBytes));
+        assert_eq!(deserialize_canonical::<u8>(&[1]), Ok(1));
+    }
+
+    #[test]
+    fn test_finish_into_and_take() {
+        let mut ser = JaguarSerializer::with_capacity(16).with_max_size(8);
+        let mut out = Vec::new();
+        ser.write_str("abc").unwrap();
+        ser.finish_into(&mut out);
+        assert_eq!(ser.position(), 0);
+        ser.write_varint(300).unwrap();
+        ser.finish_into(&mut out);
+        assert_eq!(out, [3, b'a', b'b', b'c', 0xAC, 0x02]);
+
+        ser.write_u8(7).unwrap();
+        assert_eq!(ser.take(), [7]);
+        assert_eq!(ser.take(), []);
+        // the size cap survives both
+        assert_eq!(ser.write_raw(&[0; 9]), Err(SerError::BufferTooSmall));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_serializer_pool() {
+        let pool = pool::SerializerPool::new(64).with_max_idle(1);
+        {
+            let mut a = pool.get();
+            let mut b = pool.get();
+            a.write_u8(1).unwrap();
+            b.write_u8(2).unwrap();
+        }
+        assert_eq!(pool.idle(), 1);
+
+        let ser = pool.get();
+        assert_eq!(ser.position(), 0);
+        assert!(ser.detach().finish().is_empty());
+        assert_eq!(pool.idle(), 0);
+    }
+
+    #[test]
+    fn test_record_splitter() {
+        let mut ser = JaguarSerializer::new();
+        for name in ["a", "bc", ""] {
+            ser.write_str(name).unwrap();
+        }
+        ser.write_raw(&[5, b'x']).unwrap();
+        let data = ser.finish();
+
+        let mut records = records::RecordSplitter::<String>::new(&data);
+        assert_eq!(records.next(), Some(Ok(String::from("a"))));
+        assert_eq!(records.next(), Some(Ok(String::from("bc"))));
+        assert_eq!(records.next(), Some(Ok(String::new())));
+        assert_eq!(records.offset(), 6);
+        let err = records.next().unwrap().unwrap_err();
+        assert_eq!((err.offset, err.error), (6, SerError::BufferTooSmall));
+        assert_eq!(records.next(), None);
+
+        let mut units = records::RecordSplitter::<[u8; 0]>::new(&[0]);
+        assert_eq!(units.next().unwrap().unwrap_err().error, SerError::InvalidData);
+        assert_eq!(units.next(), None);
+    }
+
+    #[test]
+    fn test_sorted_maps() {
+        let keys = [30u32, 1, 200];
+        let values = [String::from("c"), String::from("a"), String::from("b")];
+        let mut ser = JaguarSerializer::new();
+        ser.write_map_sorted(keys.iter().zip(values.iter())).unwrap();
+        let data = ser.finish();
+
+        let map: BTreeMap<u32, String> = keys.into_iter().zip(values).collect();
+        assert_eq!(serialize(&map).unwrap(), data);
+        assert_eq!(data[..5], [3, 1, 1, b'a', 30]);
+        assert_eq!(deserialize::<BTreeMap<u32, String>>(&data).unwrap(), map);
+
+        let mut ser = JaguarSerializer::new();
+        assert_eq!(ser.write_map_sorted([(&1u8, &0u8), (&1, &1)]), Err(SerError::InvalidData));
+        for unsorted in [&[2, 5, 0, 4, 0][..], &[2, 4, 0, 4, 0]] {
+            assert_eq!(deserialize::<BTreeMap<u8, u8>>(unsorted), Err(SerError::InvalidData));
+        }
+    }
+
+    #[test]
+    fn test_i128_roundtrip() {
+        let values = [0i128, -1, 1, i64::MIN as i128 - 1, i128::MIN, i128::MAX];
+        let mut ser = JaguarSerializer::new();
+        for value in values {
+            value.serialize(&mut ser).unwrap();
+        }
+        ser.write_i128_slice(&values).unwrap();
+        let data = ser.finish();
+        // high word, then low word of the zigzag value
+        assert_eq!(data[..6], [0, 0, 0, 1, 0, 2]);
+
+        let mut de = JaguarDeserializer::new(&data);
+        for value in values {
+            assert_eq!(i128::deserialize(&mut de).unwrap(), value);
+        }
+        assert_eq!(de.read_i128_vec().unwrap(), values);
+        assert!(!de.has_data());
+    }
+
+    #[test]
+    fn test_deserialize_prefix() {
+        let data = serialize(&((7u8, 300u32), alloc::vec![String::from("body")])).unwrap();
+
+        let (header, body) = deserialize_prefix::<(u8, u32)>(&data).unwrap();
+        assert_eq!(header, (7, 300));
+        assert_eq!(body, &data[3..]);
+        assert_eq!(deserialize::<Vec<String>>(body).unwrap(), ["body"]);
+
+        let truncated = deserialize_prefix::<(u8, u32)>(&data[..2]);
+        assert_eq!(truncated.map(|(header, _)| header), Err(SerError::BufferTooSmall));
+        let (_, rest) = deserialize_prefix::<(u8, u32)>(&data[..3]).unwrap();
+        assert!(rest.is_empty());
+
+        // back-to-back records, each stepped over by its decoded length
+        let records = [0u64, 127, 128, u64::MAX];
+        let data = serialize_many(&records).unwrap();
+        let mut cursor = 0;
+        for record in records {
+            let (value, len) = deserialize_with_len::<u64>(&data[cursor..]).unwrap();
+            assert_eq!(value, record);
+            cursor += len;
+        }
+        assert_eq!(cursor, data.len());
+        assert!(deserialize_with_len::<u64>(&data[cursor..]).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_exact() {
+        let data = serialize(&(String::from("jag"), 300u32)).unwrap();
+        assert_eq!(deserialize_exact::<(String, u32)>(&data).unwrap(), (String::from("jag"), 300));
+        assert_eq!(deserialize_exact::<String>(&data), Err(SerError::TrailingBytes));
+        assert_eq!(deserialize::<String>(&data).unwrap(), "jag");
+        assert_eq!(SerError::TrailingBytes.kind(), ErrorKind::InvalidData);
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_str().unwrap(), "jag");
+        assert_eq!(de.finish(), Err(SerError::TrailingBytes));
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_str().unwrap(), "jag");
+        assert_eq!(u32::deserialize(&mut de).unwrap(), 300);
+        assert_eq!(de.finish(), Ok(()));
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Lamports(u64);
+
+    impl FixedWidth for Lamports {
+        const WIDTH: usize = 8;
+
+        fn from_ne_slice(bytes: &[u8]) -> Self {
+            Self(u64::from_ne_slice(bytes))
+        }
+
+        fn from_le_slice(bytes: &[u8]) -> Self {
+            Self(u64::from_le_slice(bytes))
+        }
+
+        fn write_le(self, out: &mut [u8]) {
+            self.0.write_le(out)
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Span(u128, u128);
+
+    impl FixedWidth for Span {
+        const WIDTH: usize = 32;
+
+        fn from_ne_slice(bytes: &[u8]) -> Self {
+            Self(u128::from_ne_slice(bytes), u128::from_ne_slice(&bytes[16..]))
+        }
+
+        fn from_le_slice(bytes: &[u8]) -> Self {
+            Self(u128::from_le_slice(bytes), u128::from_le_slice(&bytes[16..]))
+        }
+
+        fn write_le(self, out: &mut [u8]) {
+            self.0.write_le(out);
+            self.1.write_le(&mut out[16..]);
+        }
+    }
+
+    #[test]
+    fn test_fixed_width_newtype() {
+        let mut ser = JaguarSerializer::new();
+        ser.write_raw(&5u64.to_ne_bytes()).unwrap();
+        ser.write_raw(&300u64.to_ne_bytes()).unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_fixed_array::<Lamports, 2>().unwrap(), [Lamports(5), Lamports(300)]);
+        assert_eq!(de.read_fixed_array::<Lamports, 1>(), Err(SerError::BufferTooSmall));
+
+        let mut ser = JaguarSerializer::new();
+        <Fixed as JaguarCodec<Lamports>>::encode(&Lamports(7), &mut ser).unwrap();
+        let data = ser.finish();
+        assert_eq!(data, 7u64.to_le_bytes());
+        let decoded: Lamports = <Fixed as JaguarCodec<Lamports>>::decode(&mut JaguarDeserializer::new(&data)).unwrap();
+        assert_eq!(decoded, Lamports(7));
+
+        // types wider than any primitive are written in place
+        let span = Span(1, u128::MAX);
+        let mut ser = JaguarSerializer::new();
+        <Fixed as JaguarCodec<Span>>::encode(&span, &mut ser).unwrap();
+        let data = ser.finish();
+        assert_eq!(data.len(), 32);
+        assert_eq!(data[..16], 1u128.to_le_bytes());
+        assert_eq!(<Fixed as JaguarCodec<Span>>::decode(&mut JaguarDeserializer::new(&data)).unwrap(), span);
+    }
+
+    #[test]
+    fn test_u24_u48() {
+        let mut ser = JaguarSerializer::new();
+        ser.write_u24(0x12_3456).unwrap();
+        ser.write_u48(0xFFFF_FFFF_FFFF).unwrap();
+        ser.write_uint_le::<5>(300).unwrap();
+        ser.write_u24_slice(&[1, 0xFF_FFFF]).unwrap();
+        ser.write_u48_slice(&[1 << 40]).unwrap();
+        let data = ser.finish_ref().to_vec();
+        assert_eq!(ser.write_u24(1 << 24), Err(SerError::InvalidData));
+        assert_eq!(ser.write_u48(1 << 48), Err(SerError::InvalidData));
+        assert_eq!(ser.write_uint_le::<9>(0), Err(SerError::InvalidLength));
+        assert_eq!(ser.write_u24_slice(&[1, 1 << 24]), Err(SerError::InvalidData));
+        assert_eq!(ser.write_u48_slice(&[1, 1 << 48]), Err(SerError::InvalidData));
+        assert_eq!(ser.position(), data.len());
+        assert_eq!(data[..9], [0x56, 0x34, 0x12, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_u24().unwrap(), 0x12_3456);
+        assert_eq!(de.read_u48().unwrap(), 0xFFFF_FFFF_FFFF);
+        assert_eq!(de.read_uint_le::<5>().unwrap(), 300);
+        assert_eq!(de.read_u24_vec().unwrap(), [1, 0xFF_FFFF]);
+        assert_eq!(de.read_u48_vec().unwrap(), [1 << 40]);
+        assert!(!de.has_data());
+        assert_eq!(de.read_u24(), Err(SerError::BufferTooSmall));
+    }
+
+    #[test]
+    fn test_i32_slice() {
+        let values = alloc::vec![0i32, -1, 1, i32::MIN, i32::MAX];
+        let mut ser = JaguarSerializer::new();
+        ser.write_i32_slice(&values).unwrap();
+        let data = ser.finish();
+        assert_eq!(data, serialize(&values).unwrap());
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_i32_vec().unwrap(), values);
+        assert!(!de.has_data());
+    }
+
+    #[test]
+    fn test_usize_isize() {
+        let data = serialize(&(usize::MAX, isize::MIN)).unwrap();
+        let expected = serialize(&(usize::MAX as u64, isize::MIN as i64)).unwrap();
+        assert_eq!(data, expected);
+        assert_eq!(deserialize::<(usize, isize)>(&data).unwrap(), (usize::MAX, isize::MIN));
+        assert_eq!(deserialize::<[usize; 2]>(&serialize(&[3usize, 300]).unwrap()).unwrap(), [3, 300]);
+
+        let wide = serialize(&(u32::MAX as u64 + 1)).unwrap();
+        let negative = serialize(&(i32::MIN as i64 - 1)).unwrap();
+        #[cfg(target_pointer_width = "64")]
+        {
+            assert_eq!(deserialize::<usize>(&wide).unwrap(), 1 << 32);
+            assert_eq!(deserialize::<isize>(&negative).unwrap(), i32::MIN as isize - 1);
+        }
+        #[cfg(target_pointer_width = "32")]
+        {
+            assert_eq!(deserialize::<usize>(&wide), Err(SerError::InvalidData));
+            assert_eq!(deserialize::<isize>(&negative), Err(SerError::InvalidData));
+        }
+    }
+
+    #[test]
+    fn test_raw_float_slices() {
+        let f32s = [0.0f32, 1.5, -2.25, f32::MAX];
+        let f64s = [1.0f64, f64::MIN_POSITIVE, -0.5];
+        let mut ser = JaguarSerializer::new();
+        ser.write_f32_slice_raw(&f32s).unwrap();
+        ser.write_f64_slice_raw(&f64s).unwrap();
+        ser.write_f32_slice_raw(&[]).unwrap();
+        let data = ser.finish();
+        assert_eq!(data.len(), 1 + 16 + 1 + 24 + 1);
+        assert_eq!(data[5..9], 1.5f32.to_ne_bytes());
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_f32_vec_raw().unwrap(), f32s);
+        assert_eq!(de.read_f64_vec_raw().unwrap(), f64s);
+        assert!(de.read_f32_vec_raw().unwrap().is_empty());
+        assert!(!de.has_data());
+
+        // the length must be backed by input
+        let mut de = JaguarDeserializer::new(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0]);
+        assert_eq!(de.read_f64_vec_raw(), Err(SerError::BufferTooSmall));
+        let huge = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+        assert_eq!(JaguarDeserializer::new(&huge).read_f32_vec_raw(), Err(SerError::InvalidLength));
+        assert_eq!(JaguarDeserializer::new(&huge).read_f64_vec_raw(), Err(SerError::InvalidLength));
+    }
+
+    #[test]
+    fn test_len_prefixes() {
+        let mut ser = JaguarSerializer::new();
+        ser.write_bytes_with::<prefix::U8>(&[1, 2]).unwrap();
+        ser.write_str_with::<prefix::U32>("ab").unwrap();
+        ser.write_seq_with::<prefix::U16, u16>(&[300]).unwrap();
+        ser.write_seq_with::<prefix::Implicit, u8>(&[7, 8]).unwrap();
+        let data = ser.finish();
+        assert_eq!(data, [2, 1, 2, 2, 0, 0, 0, b'a', b'b', 1, 0, 0xAC, 0x02, 7, 8]);
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_bytes_with::<prefix::U8>().unwrap(), [1, 2]);
+        assert_eq!(de.read_str_with::<prefix::U32>().unwrap(), "ab");
+        assert_eq!(de.read_vec_with::<prefix::U16, u16>().unwrap(), [300]);
+        assert_eq!(de.read_vec_with::<prefix::Implicit, u8>().unwrap(), [7, 8]);
+
+        let mut ser = JaguarSerializer::new();
+        assert_eq!(ser.write_bytes_with::<prefix::U8>(&[0; 256]), Err(SerError::InvalidLength));
+        let mut de = JaguarDeserializer::new(&[1]);
+        assert_eq!(de.read_vec_with::<prefix::Implicit, [u8; 0]>(), Err(SerError::InvalidData));
+
+        // prefixed lengths are capped like the default `Vec` encoding
+        let huge = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+        let mut de = JaguarDeserializer::new(&huge);
+        assert_eq!(de.read_vec_with::<prefix::Varint, ()>(), Err(SerError::InvalidLength));
+        let mut de = JaguarDeserializer::new(&[3, 0, 0, 0, 1]);
+        assert_eq!(de.read_vec_with::<prefix::U32, u8>(), Err(SerError::BufferTooSmall));
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        #[derive(Debug, PartialEq)]
+        struct Nest(Vec<Nest>);
+
+        impl<'a> JaguarDeserialize<'a> for Nest {
+            fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+                Vec::deserialize(de).map(Nest)
+            }
+        }
+
+        // 40 nested vectors, each holding just the next one
+        let mut data = [1u8; 41];
+        data[40] = 0;
+        assert_eq!(deserialize::<Nest>(&data), Err(SerError::DepthLimitExceeded));
+        let mut de = JaguarDeserializer::new(&data);
+        de.set_max_depth(41);
+        assert!(Nest::deserialize(&mut de).is_ok());
+
+        let data = [1, 1, 1, 0];
+        let mut de = JaguarDeserializer::new(&data);
+        de.set_max_depth(2);
+        assert_eq!(Vec::<Vec<Vec<u8>>>::deserialize(&mut de), Err(SerError::DepthLimitExceeded));
+        let mut de = JaguarDeserializer::new(&data);
+        de.set_max_depth(2);
+        assert_eq!(Vec::<Vec<Vec<u8>>>::validate(&mut de), Err(SerError::DepthLimitExceeded));
+
+        let mut de = JaguarDeserializer::new(&data);
+        de.set_max_depth(3);
+        assert_eq!(Vec::<Vec<Vec<u8>>>::deserialize(&mut de).unwrap(), [[[0u8]]]);
+
+        // the depth is released after each element
+        let data = [3, 0, 0, 0];
+        let mut de = JaguarDeserializer::new(&data);
+        de.set_max_depth(2);
+        assert_eq!(Vec::<Vec<u8>>::deserialize(&mut de).unwrap().len(), 3);
+        assert_eq!(de.nested(|de| de.nested(|_| Ok(()))), Ok(()));
+    }
+
+    #[test]
+    fn test_pointers_and_cow() {
+        let mut ser = JaguarSerializer::new();
+        Box::new(300u16).serialize(&mut ser).unwrap();
+        Rc::new(String::from("rc")).serialize(&mut ser).unwrap();
+        Arc::new([1u8; 2]).serialize(&mut ser).unwrap();
+        Cow::<str>::Owned(String::from("cow")).serialize(&mut ser).unwrap();
+        Cow::<[u8]>::Borrowed(&[7, 8]).serialize(&mut ser).unwrap();
+        Cow::Borrowed(&5u32).serialize(&mut ser).unwrap();
+        let data = ser.finish();
+
+        let mut plain = JaguarSerializer::new();
+        300u16.serialize(&mut plain).unwrap();
+        plain.write_str("rc").unwrap();
+        plain.write_raw(&[1, 1]).unwrap();
+        plain.write_str("cow").unwrap();
+        plain.write_bytes(&[7, 8]).unwrap();
+        5u32.serialize(&mut plain).unwrap();
+        assert_eq!(data, plain.finish());
+
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(*Box::<u16>::deserialize(&mut de).unwrap(), 300);
+        assert_eq!(*Rc::<String>::deserialize(&mut de).unwrap(), "rc");
+        assert_eq!(*Arc::<[u8; 2]>::deserialize(&mut de).unwrap(), [1, 1]);
+        assert!(matches!(Cow::<str>::deserialize(&mut de).unwrap(), Cow::Borrowed("cow")));
+        assert!(matches!(Cow::<[u8]>::deserialize(&mut de).unwrap(), Cow::Borrowed([7, 8])));
+        assert!(matches!(Cow::<u32>::deserialize(&mut de).unwrap(), Cow::Owned(5)));
+        assert!(!de.has_data());
+    }
+
+    #[test]
+    fn test_frame_decoder() {
+        use crate::framing::{write_frame, FrameDecoder};
+
+        let mut ser = JaguarSerializer::new();
+        write_frame(&mut ser, &[7u8; 200]).unwrap();
+        write_frame(&mut ser, &1u8).unwrap();
+        let wire = ser.finish();
+        assert_eq!(&wire[..2], &[200, 1]);
+
+        // one byte at a time, including the middle of the length varint
+        let mut decoder = FrameDecoder::new();
+        let mut frames = 0;
+        for byte in wire.iter() {
+            decoder.push(core::slice::from_ref(byte));
+            if let Some(frame) = decoder.next_frame().unwrap() {
+                assert_eq!(frame.len(), [200, 1][frames]);
+                frames += 1;
+            }
+        }
+        assert_eq!(frames, 2);
+        assert_eq!(decoder.buffered(), 0);
+
+        let mut decoder = FrameDecoder::new().with_max_len(199);
+        decoder.push(&wire[..2]);
+        assert_eq!(decoder.next_frame(), Err(SerError::InvalidLength));
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&[0xFF; 11]);
+        assert_eq!(decoder.next_frame(), Err(SerError::InvalidData));
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for i64 {
-    #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        de.read_signed_varint()
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_framed_io() {
+        use crate::framing::{FramedReader, FramedWriter};
+        use std::io::{ErrorKind, Read};
+
+        /// Returns at most three bytes per read.
+        struct Trickle<'a>(&'a [u8]);
+
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let len = buf.len().min(self.0.len()).min(3);
+                buf[..len].copy_from_slice(&self.0[..len]);
+                self.0 = &self.0[len..];
+                Ok(len)
+            }
+        }
+
+        let mut writer = FramedWriter::new(Vec::new());
+        writer.write(&String::from("hello")).unwrap();
+        writer.write(&70_000u64).unwrap();
+        let wire = writer.into_inner();
+
+        let mut reader = FramedReader::new(Trickle(&wire));
+        assert_eq!(reader.read::<String>().unwrap().unwrap(), "hello");
+        assert_eq!(reader.read::<u64>().unwrap(), Some(70_000));
+        assert_eq!(reader.read::<u64>().unwrap(), None);
+
+        let mut reader = FramedReader::new(Trickle(&wire[..4]));
+        assert_eq!(reader.read::<String>().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        // a complete frame holding half a varint
+        let mut reader = FramedReader::new(Trickle(&[1, 0x80]));
+        assert_eq!(reader.read::<u64>().unwrap_err().kind(), ErrorKind::InvalidData);
     }
-}
 
-impl JaguarSerialize for f32 {
-    #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_f32(*self)
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_framed_async() {
+        use crate::framing::{AsyncFramedReader, AsyncFramedWriter};
+        use std::io::ErrorKind;
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(async {
+            // a pipe buffering three bytes, so frames arrive split across reads
+            let (client, server) = tokio::io::duplex(3);
+            let write = async {
+                let mut writer = AsyncFramedWriter::new(client);
+                writer.write(&String::from("hello")).await.unwrap();
+                writer.write(&70_000u64).await.unwrap();
+            };
+            let read = async {
+                let mut reader = AsyncFramedReader::new(server);
+                assert_eq!(reader.read::<String>().await.unwrap().unwrap(), "hello");
+                assert_eq!(reader.read::<u64>().await.unwrap(), Some(70_000));
+                assert_eq!(reader.read::<u64>().await.unwrap(), None);
+            };
+            tokio::join!(write, read);
+
+            let mut writer = AsyncFramedWriter::new(Vec::new());
+            writer.write(&String::from("hello")).await.unwrap();
+            let wire = writer.into_inner();
+            let mut reader = AsyncFramedReader::new(&wire[..4]);
+            assert_eq!(reader.read::<String>().await.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+            let mut reader = AsyncFramedReader::new(&[1u8, 0x80][..]);
+            assert_eq!(reader.read::<u64>().await.unwrap_err().kind(), ErrorKind::InvalidData);
+        });
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for f32 {
-    #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        de.read_f32()
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_debug_dump() {
+        use crate::debug::{dump, dump_json};
+        use crate::schema::{FieldSchema, Schema, StructSchema};
+
+        let field = |name: &str, schema, bits| FieldSchema { name: name.into(), schema, bits };
+        let schema = Schema::Struct(StructSchema {
+            name: "Order".into(),
+            fields: alloc::vec![
+                field("frozen", Schema::Bool, Some(1)),
+                field("kind", Schema::U8, Some(3)),
+                field("price", Schema::F64, None),
+                field(
+                    "fills",
+                    Schema::Vec(Box::new(Schema::Tuple(alloc::vec![Schema::Varint { bits: 64 }, Schema::String]))),
+                    None,
+                ),
+                field("raw", Schema::Vec(Box::new(Schema::U8)), None),
+            ],
+            reserve: 0,
+        });
+
+        let mut ser = JaguarSerializer::new();
+        let mut bits = ser.bit_writer();
+        bits.write_bits(1, 1).unwrap();
+        bits.write_bits(5, 3).unwrap();
+        bits.finish().unwrap();
+        f64::NAN.serialize(&mut ser).unwrap();
+        alloc::vec![(7u64, String::from("a\"b"))].serialize(&mut ser).unwrap();
+        ser.write_bytes(&[1, 2]).unwrap();
+        ser.write_u8(9).unwrap();
+        let data = ser.finish();
+
+        assert_eq!(
+            dump(&data, &schema),
+            "Order(\n    frozen: true,\n    kind: 5,\n    price: NaN,\n    fills: [\n        (7, \"a\\\"b\"),\n    ],\n    \
+             raw: [1, 2],\n)\n// 1 trailing bytes"
+        );
+        assert_eq!(
+            dump_json(&data, &schema),
+            "{\n  \"frozen\": true,\n  \"kind\": 5,\n  \"price\": \"NaN\",\n  \"fills\": [\n    [7, \"a\\\"b\"]\n  ],\n  \
+             \"raw\": [1, 2]\n}"
+        );
+
+        // decoding stops at the first error
+        let cut = data.len() - 5;
+        assert_eq!(
+            dump(&data[..cut], &schema),
+            "Order(\n    frozen: true,\n    kind: 5,\n    price: NaN,\n    fills: [\n        \
+             (7, <error: buffer too small at byte 12>),\n    ],\n)"
+        );
+
+        // lengths are bounded by the input, or the cap for zero-sized elements
+        let units = Schema::Vec(Box::new(Schema::Tuple(Vec::new())));
+        assert_eq!(dump(&[2], &units), "[\n    (),\n    (),\n]");
+        assert_eq!(dump(&[0xFF, 0xFF, 0xFF, 0x0F], &units), "<error: invalid length at byte 0>");
+        let strings = Schema::Vec(Box::new(Schema::String));
+        assert_eq!(dump(&[0xFF, 0xFF, 0xFF, 0x0F], &strings), "<error: buffer too small at byte 0>");
     }
-}
 
-impl JaguarSerialize for f64 {
-    #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_f64(*self)
+    impl JaguarAccount for u64 {
+        const DISCRIMINATOR: [u8; 8] = discriminator_of("u64");
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for f64 {
-    #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        de.read_f64()
+    impl JaguarAccount for u32 {
+        const DISCRIMINATOR: [u8; 8] = discriminator_of("u32");
     }
-}
 
-impl JaguarSerialize for bool {
-    #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_bool(*self)
+    impl JaguarEvent for u64 {
+        const DISCRIMINATOR: [u8; 8] = event_discriminator_of("u64");
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for bool {
-    #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        de.read_bool()
+    impl JaguarEvent for u32 {
+        const DISCRIMINATOR: [u8; 8] = event_discriminator_of("u32");
     }
-}
 
-impl<T: JaguarSerialize> JaguarSerialize for Vec<T> {
-    #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_varint(self.len() as u64)?;
-        for item in self {
-            item.serialize(ser)?;
-        }
-        Ok(())
+    #[test]
+    fn test_discriminator() {
+        // matches Anchor's `account:<Name>` discriminators
+        assert_eq!(discriminator_of("Escrow"), [31, 213, 123, 187, 186, 22, 218, 155]);
+        assert_eq!(discriminator_of("Vault"), [211, 8, 232, 43, 2, 152, 117, 119]);
+        // spans two SHA-256 blocks
+        assert_eq!(discriminator_of(&"A".repeat(60)), [52, 54, 133, 255, 66, 202, 100, 86]);
+
+        let data = serialize_with_discriminator(&300u64).unwrap();
+        assert_eq!(data[..8], discriminator_of("u64"));
+        assert_eq!(deserialize_checked::<u64>(&data).unwrap(), 300);
+        assert_eq!(deserialize_checked::<u32>(&data).err(), Some(SerError::DiscriminatorMismatch));
+        assert_eq!(deserialize_checked::<u64>(&data[..7]).err(), Some(SerError::BufferTooSmall));
+
+        // matches Anchor's `event:<Name>` discriminators
+        assert_eq!(event_discriminator_of("Deposited"), [111, 141, 26, 45, 161, 35, 100, 57]);
+        assert_eq!(event_discriminator_of("u64"), [174, 123, 177, 49, 34, 13, 200, 97]);
+
+        let data = serialize_event(&300u64).unwrap();
+        assert_eq!(data[..8], event_discriminator_of("u64"));
+        assert_eq!(deserialize_event::<u64>(&data).unwrap(), 300);
+        assert_eq!(deserialize_event::<u32>(&data).err(), Some(SerError::DiscriminatorMismatch));
+        assert_eq!(deserialize_checked::<u64>(&data).err(), Some(SerError::DiscriminatorMismatch));
     }
-}
 
-impl<'a, T: JaguarDeserialize<'a>> JaguarDeserialize<'a> for Vec<T> {
-    #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        let len = de.read_varint()? as usize;
-        let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            vec.push(T::deserialize(de)?);
-        }
-        Ok(vec)
+    impl JaguarMessage for u64 {
+        const TYPE_TAG: [u8; 8] = message_tag_of("u64");
     }
-}
 
-impl<A: JaguarSerialize, B: JaguarSerialize> JaguarSerialize for (A, B) {
-    #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        self.0.serialize(ser)?;
-        self.1.serialize(ser)
+    impl JaguarMessage for u32 {
+        const TYPE_TAG: [u8; 8] = message_tag_of("u32");
     }
-}
 
-impl<'a, A: JaguarDeserialize<'a>, B: JaguarDeserialize<'a>> JaguarDeserialize<'a> for (A, B) {
-    #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        let a = A::deserialize(de)?;
-        let b = B::deserialize(de)?;
-        Ok((a, b))
+    #[test]
+    fn test_envelope() {
+        // tags live apart from account discriminators
+        assert_eq!(message_tag_of("u64"), [97, 210, 253, 7, 211, 32, 111, 195]);
+        assert_ne!(message_tag_of("u64"), discriminator_of("u64"));
+
+        let data = Envelope::wrap(&300u64).unwrap();
+        assert_eq!(data[0], envelope::ENVELOPE_VERSION);
+        assert_eq!(data[1..9], message_tag_of("u64"));
+
+        let env: Envelope = deserialize(&data).unwrap();
+        assert!(env.is::<u64>());
+        assert!(!env.is::<u32>());
+        assert_eq!(env.unwrap_checked::<u64>().unwrap(), 300);
+        assert_eq!(env.unwrap_checked::<u32>().err(), Some(SerError::DiscriminatorMismatch));
+
+        let future = Envelope { version: envelope::ENVELOPE_VERSION + 1, ..env };
+        assert_eq!(future.unwrap_checked::<u64>().err(), Some(SerError::VersionMismatch));
+        let reread = serialize(&future).unwrap();
+        assert_eq!(deserialize::<Envelope>(&reread).unwrap(), future);
+
+        assert_eq!(deserialize::<Envelope>(&data[..8]).err(), Some(SerError::BufferTooSmall));
     }
-}
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct StrRef<'a>(pub &'a str);
+    #[test]
+    fn test_checksum_trailer() {
+        // the standard CRC-32 check value
+        assert_eq!(checksum::crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(checksum::crc32(&[]), 0);
 
-impl<'a> JaguarSerialize for StrRef<'a> {
-    #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.write_str(self.0)
+        let mut ser = JaguarSerializer::new();
+        ser.write_str("radio").unwrap();
+        let data = ser.finish_with_checksum().unwrap();
+        assert_eq!(data[6..], checksum::crc32(&data[..6]).to_le_bytes());
+
+        let mut de = JaguarDeserializer::new_verified(&data).unwrap();
+        assert_eq!(de.read_str().unwrap(), "radio");
+        assert!(!de.has_data());
+
+        for bit in 0..data.len() * 8 {
+            let mut corrupt = data.clone();
+            corrupt[bit / 8] ^= 1 << (bit % 8);
+            assert_eq!(JaguarDeserializer::new_verified(&corrupt).err(), Some(SerError::ChecksumMismatch));
+        }
+        assert_eq!(JaguarDeserializer::new_verified(&data[..3]).err(), Some(SerError::BufferTooSmall));
+
+        let mut ser = JaguarSerializer::new().with_max_size(4);
+        ser.write_raw(&[0; 3]).unwrap();
+        assert_eq!(ser.finish_with_checksum(), Err(SerError::BufferTooSmall));
     }
-}
 
-impl<'a> JaguarDeserialize<'a> for StrRef<'a> {
-    #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        Ok(StrRef(de.read_str()?))
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn test_compressed() {
+        let snapshot: Vec<(u64, String)> = (0..50).map(|_| (1_000, String::from("SOL-PERP"))).collect();
+        let data = serialize_compressed(&snapshot).unwrap();
+        assert!(data.len() < serialize(&snapshot).unwrap().len() / 2);
+        assert_eq!(deserialize_compressed::<Vec<(u64, String)>>(&data).unwrap(), snapshot);
+
+        let mut corrupt = data.clone();
+        corrupt.truncate(data.len() - 3);
+        assert_eq!(deserialize_compressed::<Vec<(u64, String)>>(&corrupt), Err(SerError::InvalidData));
+        // a length no LZ4 block this short could expand to
+        assert_eq!(deserialize_compressed::<u8>(&[0xFF, 0xFF, 0x03, 0]), Err(SerError::InvalidLength));
     }
-}
 
-// Add implementations for fixed-length arrays
-impl<const N: usize> JaguarSerialize for [u8; N] {
-    #[inline]
-    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-        ser.ensure_space(N);
-        unsafe {
-            ser.write_bytes_unchecked(self);
-        }
-        Ok(())
+    #[cfg(feature = "registry")]
+    impl crate::registry::JaguarTagged for u64 {
+        const TAG: u64 = 1;
     }
-}
 
-impl<'a, const N: usize> JaguarDeserialize<'a> for [u8; N] {
-    #[inline]
-    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-        if de.pos + N > de.data.len() {
-            return Err(SerError::BufferTooSmall);
-        }
-        let mut result = [0u8; N];
-        unsafe {
-            ptr::copy_nonoverlapping(
-                de.data.as_ptr().add(de.pos),
-                result.as_mut_ptr(),
-                N
-            );
-        }
-        de.pos += N;
-        Ok(result)
+    #[cfg(feature = "registry")]
+    impl crate::registry::JaguarTagged for String {
+        const TAG: u64 = 300;
     }
-}
 
-macro_rules! impl_fixed_array {
-    ($($t:ty),*) => {
-        $(
-            impl<const N: usize> JaguarSerialize for [$t; N] {
-                #[inline]
-                fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
-                    ser.write_varint(N as u64)?;
-                    for item in self {
-                        item.serialize(ser)?;
-                    }
-                    Ok(())
-                }
-            }
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_bytes() {
+        use ::bytes::{Bytes, BytesMut};
 
-            impl<'a, const N: usize> JaguarDeserialize<'a> for [$t; N] {
-                #[inline]
-                fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
-                    let len = de.read_varint()? as usize;
-                    if len != N {
-                        return Err(SerError::InvalidLength);
-                    }
-                    let mut result = Vec::with_capacity(N);
-                    for _ in 0..N {
-                        result.push(<$t>::deserialize(de)?);
-                    }
-                    Ok(result.try_into().unwrap())
-                }
-            }
-        )*
-    };
-}
+        let mut buf = BytesMut::with_capacity(64);
+        buf.extend_from_slice(b"stale");
+        let mut ser = JaguarSerializer::from_bytes_mut(buf);
+        (7u32, Bytes::from_static(b"payload")).serialize(&mut ser).unwrap();
+        let data = ser.finish_bytes();
+        assert_eq!(&data[..], &serialize(&(7u32, Vec::from(&b"payload"[..]))).unwrap()[..]);
 
-impl_fixed_array!(u16, u32, u64, i8, i16, i32, i64, f32, f64, bool);
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(u32::deserialize(&mut de).unwrap(), 7);
+        let body = de.read_bytes().unwrap();
+        assert_eq!(data.slice_ref(body), Bytes::from_static(b"payload"));
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let (_, owned): (u32, BytesMut) = deserialize(&data).unwrap();
+        assert_eq!(owned, &b"payload"[..]);
 
-    #[test]
-    fn test_varint_encode() {
         let mut ser = JaguarSerializer::new();
-
-        ser.write_varint(0).unwrap();
-        ser.write_varint(127).unwrap();
-        ser.write_varint(128).unwrap();
-        ser.write_varint(16383).unwrap();
-    
-        let data = ser.finish();
-
-        let mut de = JaguarDeserializer::new(&data);
-        
-        assert_eq!(de.read_varint().unwrap(), 0);
-        assert_eq!(de.read_varint().unwrap(), 127);
-        assert_eq!(de.read_varint().unwrap(), 128);
-        assert_eq!(de.read_varint().unwrap(), 16383);
+        owned.serialize(&mut ser).unwrap();
+        assert_eq!(ser.finish_bytes_mut(), &serialize(&Bytes::from_static(b"payload")).unwrap()[..]);
     }
 
+    #[cfg(feature = "registry")]
     #[test]
-    fn test_string_roundtrip() {
-        let original = "Hello, world! 🚀";
+    fn test_registry() {
+        use crate::registry::{serialize_any, write_any, JaguarAny, Registry};
+
+        let mut registry = Registry::new();
+        registry.register::<u64>().register::<String>();
+        assert!(registry.contains(300) && !registry.contains(2));
 
         let mut ser = JaguarSerializer::new();
-        ser.write_str(original).unwrap();
+        write_any(&mut ser, &5u64).unwrap();
+        write_any(&mut ser, &String::from("hi")).unwrap();
         let data = ser.finish();
+        assert_eq!(data, [1, 5, 0xAC, 0x02, 2, b'h', b'i']);
 
         let mut de = JaguarDeserializer::new(&data);
-        let decoded = de.read_str().unwrap();
-        
-        assert_eq!(original, decoded);
+        let first = registry.read(&mut de).unwrap();
+        let second = registry.read(&mut de).unwrap();
+        assert!(!de.has_data());
+        assert_eq!(first.downcast_ref::<u64>(), Some(&5));
+        assert!(second.is::<String>());
+        let second = second.downcast::<u64>().unwrap_err();
+        assert_eq!(*second.downcast::<String>().unwrap(), "hi");
+
+        let boxed: Box<dyn JaguarAny> = Box::new(7u64);
+        assert_eq!(serialize_any(boxed.as_ref()).unwrap(), [1, 7]);
+        assert_eq!(registry.deserialize(&[2, 7]).err(), Some(SerError::InvalidData));
+        assert_eq!(registry.deserialize(&[1]).err(), Some(SerError::BufferTooSmall));
     }
 
+    #[cfg(feature = "bumpalo")]
     #[test]
-    fn test_float_compression() {
-        let mut ser = JaguarSerializer::new();
-
-        ser.write_f32(0.0).unwrap();
-        ser.write_f32(1.0).unwrap();
-        ser.write_f32(-1.0).unwrap();
-        ser.write_f32(3.14159).unwrap();
-        
-        let data = ser.data();
+    fn test_deserialize_in() {
+        use bumpalo::collections::{String as BumpString, Vec as BumpVec};
+        use bumpalo::Bump;
 
-        assert_eq!(data[0], 0);
-        assert_eq!(data[1], 1);
-        assert_eq!(data[2], 2); 
-        assert_eq!(data[3], 255); // needs full encoding
+        let data = serialize(&(alloc::vec![String::from("a"), String::from("bc")], alloc::vec![(1u8, -1i64)])).unwrap();
+        let bump = Bump::new();
+        let mut de = JaguarDeserializer::new(&data);
+        let names = BumpVec::<BumpString>::deserialize_in(&mut de, &bump).unwrap();
+        let items = BumpVec::<(u8, i64)>::deserialize_in(&mut de, &bump).unwrap();
+        assert_eq!(names, ["a", "bc"]);
+        assert_eq!(items, [(1, -1)]);
+        assert!(!de.has_data());
+        assert!(bump.allocated_bytes() > 0);
+        assert_eq!(deserialize_in::<BumpVec<BumpString>>(&data[..3], &bump).err(), Some(SerError::BufferTooSmall));
+
+        let nested = serialize(&alloc::vec![alloc::vec![alloc::vec![0u8]]]).unwrap();
+        let mut de = JaguarDeserializer::new(&nested);
+        de.set_max_depth(2);
+        type Nested<'b> = BumpVec<'b, BumpVec<'b, Vec<u8>>>;
+        assert_eq!(Nested::deserialize_in(&mut de, &bump).err(), Some(SerError::DepthLimitExceeded));
     }
 
     #[test]
-    fn test_bool_slice_roundtrip() {
-        let bools: Vec<bool> = (0..10000).map(|i| i % 3 == 0).collect();
+    fn test_int_slice_modes() {
+        let u32s = [1u32, 300, u32::MAX];
+        let i64s = [-1i64, 0, i64::MIN];
         let mut ser = JaguarSerializer::new();
-        ser.write_bool_slice(&bools).unwrap();
+        ser.write_u32_slice_packed(&u32s).unwrap();
+        ser.write_u32_slice_raw(&u32s).unwrap();
+        ser.write_i64_slice_packed(&i64s).unwrap();
+        ser.write_i64_slice_raw(&i64s).unwrap();
+        ser.write_u16_slice_raw(&[7]).unwrap();
         let data = ser.finish();
 
-        let mut de = JaguarDeserializer::new(&data);
-        let decoded = de.read_bool_vec().unwrap();
-        assert_eq!(bools, decoded);
-
-        // check for size reduction
-        assert!(data.len() < bools.len() / 2, "size should be reduced by at least 2x");
-    }
+        let mut packed = JaguarSerializer::new();
+        packed.write_varint(3).unwrap();
+        for value in u32s {
+            value.serialize(&mut packed).unwrap();
+        }
+        assert_eq!(data[..9], *packed.data());
+        let mut raw = JaguarSerializer::new();
+        raw.write_u32_slice(&u32s).unwrap();
+        assert_eq!(data[9..22], *raw.data());
 
-    #[test]
-    fn test_varint_micro_benchmark() {
-        let values: Vec<u64> = (0..10000).map(|i| if i % 2 == 0 { i as u64 } else { (i as u64) * 1000 }).collect();
+        let mut de = JaguarDeserializer::new(&data);
+        assert_eq!(de.read_u32_vec_packed().unwrap(), u32s);
+        assert_eq!(de.read_u32_vec_raw().unwrap(), u32s);
+        let mut buf = Vec::with_capacity(8);
+        de.read_i64_into_packed(&mut buf).unwrap();
+        assert_eq!(buf, i64s);
+        de.read_i64_into_raw(&mut buf).unwrap();
+        assert_eq!(buf, i64s);
+        assert_eq!(de.read_u16_vec_raw().unwrap(), [7]);
+        assert!(!de.has_data());
+
+        // packed slices share the element encoding, so canonical checks apply
+        let mut de = JaguarDeserializer::new(&[1, 0x80, 0x80, 0x04]);
+        de.enable_canonical();
+        assert_eq!(de.read_u16_vec_packed(), Err(SerError::InvalidData));
+        let mut de = JaguarDeserializer::new(&[2, 0, 0, 0]);
+        assert_eq!(de.read_i16_vec_raw(), Err(SerError::BufferTooSmall));
+
+        // the `_into` readers reuse the buffer's allocation
+        let u64s: [&[u64]; 2] = [&[1, 2, 3], &[4]];
         let mut ser = JaguarSerializer::new();
-
-        for v in &values {
-            ser.write_varint(*v).unwrap();
+        for slice in u64s {
+            ser.write_u64_slice(slice).unwrap();
         }
-
+        ser.write_u32_slice(&[7, 8]).unwrap();
+        ser.write_u8(9).unwrap();
         let data = ser.finish();
 
         let mut de = JaguarDeserializer::new(&data);
-        for orig in &values {
-            let decoded = de.read_varint().unwrap();
-            assert_eq!(*orig, decoded);
+        let mut buf = Vec::with_capacity(3);
+        let capacity = buf.capacity();
+        for slice in u64s {
+            de.read_u64_into(&mut buf).unwrap();
+            assert_eq!(buf, slice);
+            assert_eq!(buf.capacity(), capacity);
         }
+        let mut words = Vec::new();
+        de.read_u32_into(&mut words).unwrap();
+        assert_eq!(words, [7, 8]);
+        let mut byte = [0u8; 1];
+        de.read_exact_into(&mut byte).unwrap();
+        assert_eq!(byte, [9]);
+        assert_eq!(de.read_exact_into(&mut byte), Err(SerError::BufferTooSmall));
     }
 
     #[test]
-    fn test_u128_roundtrip() {
-        let value = u128::MAX;
-        let mut ser = JaguarSerializer::new();
-        value.serialize(&mut ser).unwrap();
-        let data = ser.finish();
-        
+    fn test_read_seq() {
+        let data = serialize(&(alloc::vec![String::from("a"), String::from("bb"), String::from("ccc")], 7u8)).unwrap();
         let mut de = JaguarDeserializer::new(&data);
-        let decoded = u128::deserialize(&mut de).unwrap();
+        let mut seq = de.read_seq::<StrRef>().unwrap();
+        assert_eq!(seq.remaining(), 3);
+        assert_eq!(seq.next(), Some(Ok(StrRef("a"))));
+        seq.finish().unwrap();
+        assert_eq!(de.read_u8().unwrap(), 7);
 
-        assert_eq!(value, decoded);
+        let mut de = JaguarDeserializer::new(&data);
+        let lens: Result<Vec<usize>, _> = de.read_seq::<StrRef>().unwrap().map(|s| s.map(|s| s.0.len())).collect();
+        assert_eq!(lens.unwrap(), [1, 2, 3]);
+
+        // a truncated sequence yields one error, then ends
+        let mut de = JaguarDeserializer::new(&data[..5]);
+        let mut seq = de.read_seq::<StrRef>().unwrap();
+        assert!(seq.next().unwrap().is_ok());
+        assert_eq!(seq.next(), Some(Err(SerError::BufferTooSmall)));
+        assert_eq!(seq.next(), None);
+        drop(seq);
+
+        // the sequence holds a level of depth until dropped
+        let mut de = JaguarDeserializer::new(&[2, 1, 0, 0]);
+        de.set_max_depth(1);
+        let mut seq = de.read_seq::<Vec<u8>>().unwrap();
+        assert_eq!(seq.next(), Some(Err(SerError::DepthLimitExceeded)));
+        drop(seq);
+        assert_eq!(de.nested(|_| Ok(())), Ok(()));
     }
 
     #[test]
-    fn test_fixed_array_roundtrip() {
-        // [u8; 32]
-        let pubkey = [1u8; 32];
-
+    fn test_write_seq() {
         let mut ser = JaguarSerializer::new();
-        pubkey.serialize(&mut ser).unwrap();
+        ser.write_seq(3, (1..=3u64).map(|i| i * 100)).unwrap();
+        ser.write_seq_unsized([String::from("x")]).unwrap();
+        ser.write_seq_unsized(core::iter::empty::<u8>()).unwrap();
         let data = ser.finish();
 
         let mut de = JaguarDeserializer::new(&data);
-        let decoded = <[u8; 32]>::deserialize(&mut de).unwrap();
+        assert_eq!(Vec::<u64>::deserialize(&mut de).unwrap(), [100, 200, 300]);
+        assert_eq!(Vec::<String>::deserialize(&mut de).unwrap(), [String::from("x")]);
+        assert_eq!(Vec::<u8>::deserialize(&mut de).unwrap(), []);
+        assert!(!de.has_data());
 
-        assert_eq!(pubkey, decoded);
+        let mut ser = JaguarSerializer::new();
+        assert_eq!(ser.write_seq(2, [1u8]), Err(SerError::InvalidLength));
+        let mut items = 0..5u8;
+        assert_eq!(ser.write_seq(2, &mut items), Err(SerError::InvalidLength));
+        assert_eq!(items.next(), Some(3));
+    }
 
-        // [u32; 4]
-        let ints: [u32; 4] = [1, 2, 3, 4];
+    #[cfg(not(feature = "safe"))]
+    #[test]
+    fn test_pod() {
+        use crate::pod::JaguarPod;
+
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        #[repr(C)]
+        struct Fill {
+            price: u32,
+            size: i32,
+        }
 
+        // SAFETY: two 4-byte integers, no padding, any bits are valid
+        unsafe impl JaguarPod for Fill {}
+
+        let fills = [Fill { price: 5, size: -1 }, Fill { price: 300, size: 2 }];
         let mut ser = JaguarSerializer::new();
-        ints.serialize(&mut ser).unwrap();
+        ser.write_pod_array(&fills).unwrap();
+        ser.write_pod_slice(&fills[1..]).unwrap();
+        ser.write_pod_slice::<[u16; 2]>(&[[1, 2]]).unwrap();
         let data = ser.finish();
+        assert_eq!(data.len(), 16 + 1 + 8 + 1 + 4);
+        assert_eq!(data[..4], 5u32.to_ne_bytes());
 
         let mut de = JaguarDeserializer::new(&data);
-        let decoded = <[u32; 4]>::deserialize(&mut de).unwrap();
+        assert_eq!(de.read_pod_array::<Fill, 2>().unwrap(), fills);
+        assert_eq!(de.read_pod_vec::<Fill>().unwrap(), fills[1..]);
+        assert_eq!(de.read_pod_vec::<[u16; 2]>().unwrap(), [[1, 2]]);
+        assert!(!de.has_data());
+
+        assert_eq!(JaguarDeserializer::new(&data[..15]).read_pod_array::<Fill, 2>(), Err(SerError::BufferTooSmall));
+        assert_eq!(JaguarDeserializer::new(&[2, 0, 0, 0, 0]).read_pod_vec::<u32>(), Err(SerError::BufferTooSmall));
+        let huge = [0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+        assert_eq!(JaguarDeserializer::new(&huge).read_pod_vec::<[u8; 0]>(), Err(SerError::InvalidLength));
+    }
 
-        assert_eq!(ints, decoded);
+    #[cfg(not(feature = "safe"))]
+    #[test]
+    fn test_new_unchecked() {
+        let mut ser = JaguarSerializer::new();
+        ser.write_varint(300).unwrap();
+        ser.write_str("trusted").unwrap();
+        ser.write_u8(7).unwrap();
+        let section = ser.begin_section().unwrap();
+        ser.write_bytes(&[1, 2]).unwrap();
+        ser.end_section(section).unwrap();
+        let data = ser.finish();
 
-        // [bool; 8]
-        let bools: [bool; 8] = [true, false, true, false, true, false, true, false];
+        // SAFETY: `data` is exactly the encoding read back below
+        let mut de = unsafe { JaguarDeserializer::new_unchecked(&data) };
+        assert_eq!(de.read_varint().unwrap(), 300);
+        assert_eq!(de.read_str().unwrap(), "trusted");
+        assert_eq!(de.read_u8().unwrap(), 7);
+        let mut section = de.read_section().unwrap();
+        assert_eq!(section.read_bytes().unwrap(), [1, 2]);
+        assert!(!de.has_data());
+
+        // checks other than bounds still apply
+        let data = [2, 0xFF, 0xFE];
+        // SAFETY: the string is in bounds, only its contents are invalid
+        let mut de = unsafe { JaguarDeserializer::new_unchecked(&data) };
+        assert_eq!(de.read_str(), Err(SerError::InvalidData));
+    }
 
+    #[test]
+    fn test_nonzero() {
         let mut ser = JaguarSerializer::new();
-        bools.serialize(&mut ser).unwrap();
+        NonZeroU8::new(7).unwrap().serialize(&mut ser).unwrap();
+        NonZeroU32::new(300).unwrap().serialize(&mut ser).unwrap();
+        NonZeroI64::new(-1).unwrap().serialize(&mut ser).unwrap();
+        NonZeroU128::new(u128::MAX).unwrap().serialize(&mut ser).unwrap();
         let data = ser.finish();
+        assert_eq!(data[..4], [7, 0xAC, 0x02, 1]);
 
         let mut de = JaguarDeserializer::new(&data);
-        let decoded = <[bool; 8]>::deserialize(&mut de).unwrap();
-
-        assert_eq!(bools, decoded);
+        assert_eq!(NonZeroU8::deserialize(&mut de).unwrap().get(), 7);
+        assert_eq!(NonZeroU32::deserialize(&mut de).unwrap().get(), 300);
+        assert_eq!(NonZeroI64::deserialize(&mut de).unwrap().get(), -1);
+        assert_eq!(NonZeroU128::deserialize(&mut de).unwrap().get(), u128::MAX);
+        assert!(!de.has_data());
+
+        assert_eq!(crate::deserialize::<NonZeroU16>(&[0]), Err(SerError::InvalidData));
+        assert_eq!(crate::deserialize::<NonZeroI32>(&[0]), Err(SerError::InvalidData));
+        assert_eq!(crate::deserialize::<NonZeroUsize>(&[0]), Err(SerError::InvalidData));
+        assert_eq!(crate::deserialize::<Vec<NonZeroU8>>(&[2, 1, 0]), Err(SerError::InvalidData));
     }
 
     #[test]
-    fn test_fixed_array_invalid_length() {
-        let data = [1u32, 2, 3];
-        let mut ser = JaguarSerializer::new();
-        ser.write_varint(3).unwrap();
-
-        for &x in &data {
-            x.serialize(&mut ser).unwrap();
-        }
-        let serialized = ser.finish();
-        
-        let mut de = JaguarDeserializer::new(&serialized);
+    fn test_network_addresses() {
+        use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+
+        let v4: SocketAddr = "10.0.0.7:8899".parse().unwrap();
+        let data = crate::serialize(&v4).unwrap();
+        assert_eq!(data, [0, 10, 0, 0, 7, 0xC3, 0x45]);
+        assert_eq!(crate::deserialize::<SocketAddr>(&data).unwrap(), v4);
+
+        let v6 = SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8001, 3, 9));
+        let data = crate::serialize(&v6).unwrap();
+        assert_eq!(data.len(), 1 + 16 + 2 + 1 + 1);
+        assert_eq!(crate::deserialize::<SocketAddr>(&data).unwrap(), v6);
+
+        let ips = alloc::vec![IpAddr::V4(Ipv4Addr::BROADCAST), IpAddr::V6(Ipv6Addr::UNSPECIFIED)];
+        let data = crate::serialize(&ips).unwrap();
+        assert_eq!(crate::deserialize::<Vec<IpAddr>>(&data).unwrap(), ips);
+
+        assert_eq!(serialize_const::<_, 4>(&Ipv4Addr::new(127, 0, 0, 1)), [127, 0, 0, 1]);
+        assert_eq!(crate::deserialize::<IpAddr>(&[2, 1, 2, 3, 4]), Err(SerError::InvalidData));
+        assert_eq!(crate::deserialize::<SocketAddr>(&[1, 0, 0]), Err(SerError::BufferTooSmall));
+    }
 
-        assert!(matches!(<[u32; 4]>::deserialize(&mut de), Err(SerError::InvalidLength)));
+    #[test]
+    fn test_duration_and_timestamp() {
+        use crate::clock::UnixTimestamp;
+
+        let data = crate::serialize(&Duration::new(90, 500)).unwrap();
+        assert_eq!(data, [90, 0xF4, 0x03]);
+        assert_eq!(crate::deserialize::<Duration>(&data).unwrap(), Duration::new(90, 500));
+        let max = crate::serialize(&Duration::MAX).unwrap();
+        assert_eq!(crate::deserialize::<Duration>(&max).unwrap(), Duration::MAX);
+        assert_eq!(crate::deserialize::<Duration>(&[0, 0x80, 0x94, 0xEB, 0xDC, 0x03]), Err(SerError::InvalidData));
+
+        let now = UnixTimestamp(1_700_000_000);
+        let data = crate::serialize(&now).unwrap();
+        assert_eq!(data.len(), 5);
+        assert_eq!(crate::deserialize::<UnixTimestamp>(&data).unwrap(), now);
+        assert_eq!(crate::deserialize::<UnixTimestamp>(&[1]).unwrap(), UnixTimestamp(-1));
     }
 }
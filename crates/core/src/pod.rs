@@ -0,0 +1,150 @@
+//! Plain-old-data types, copied to and from the wire as raw bytes.
+//!
+//! A [`JaguarPod`] type is valid for any bit pattern and has no padding, so
+//! arrays and slices of it can be written and read back with a single
+//! `memcpy` instead of element by element. Values are native-endian, like the
+//! `_raw` slice encodings such as [`JaguarSerializer::write_u32_slice_raw`].
+//!
+//! Only available without the `safe` feature, since implementing the trait
+//! is `unsafe`. Reads build values in [`MaybeUninit`] storage and never from
+//! zeroed memory.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::pod::{JaguarPod, Pod};
+//! use jaguar::{JaguarDeserialize, JaguarSerialize};
+//!
+//! #[derive(Clone, Copy, Debug, PartialEq)]
+//! #[repr(C)]
+//! struct Tick {
+//!     price: u64,
+//!     size: u64,
+//! }
+//!
+//! // SAFETY: two `u64`s, no padding, and any bits are a valid `Tick`
+//! unsafe impl JaguarPod for Tick {}
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+//! struct Book {
+//!     #[jaguar(with = "Pod")]
+//!     ticks: Vec<Tick>,
+//! }
+//!
+//! let book = Book { ticks: vec![Tick { price: 100, size: 5 }] };
+//! let data = jaguar::serialize(&book).unwrap();
+//! assert_eq!(data.len(), 1 + 16);
+//! assert_eq!(jaguar::deserialize::<Book>(&data).unwrap(), book);
+//! ```
+
+use crate::{JaguarCodec, JaguarDeserializer, JaguarSerializer, SerError};
+use alloc::vec::Vec;
+use core::mem::{self, MaybeUninit};
+use core::ptr;
+
+/// A type whose values are plain bytes.
+///
+/// # Safety
+///
+/// Implementors must have no padding bytes and must be valid for every bit
+/// pattern of `size_of::<Self>()` bytes, like the primitive integers and
+/// floats, arrays of them, and `#[repr(C)]` or `#[repr(transparent)]`
+/// structs of such fields laid out without padding.
+pub unsafe trait JaguarPod: Copy {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(
+            unsafe impl JaguarPod for $t {}
+        )*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: JaguarPod, const N: usize> JaguarPod for [T; N] {}
+
+/// The bytes of `slice`.
+#[inline]
+fn as_bytes<T: JaguarPod>(slice: &[T]) -> &[u8] {
+    // SAFETY: `T` has no padding, so every byte of the slice is initialized
+    unsafe { core::slice::from_raw_parts(slice.as_ptr().cast(), mem::size_of_val(slice)) }
+}
+
+impl JaguarSerializer {
+    /// Writes a fixed-size array of plain-old-data values as raw bytes,
+    /// without a length prefix.
+    #[inline]
+    pub fn write_pod_array<T: JaguarPod, const N: usize>(&mut self, array: &[T; N]) -> Result<(), SerError> {
+        self.write_raw(as_bytes(array))
+    }
+
+    /// Writes a slice of plain-old-data values as a varint length followed
+    /// by their raw bytes.
+    #[inline]
+    pub fn write_pod_slice<T: JaguarPod>(&mut self, slice: &[T]) -> Result<(), SerError> {
+        self.write_varint(slice.len() as u64)?;
+        self.write_raw(as_bytes(slice))
+    }
+}
+
+impl<'a> JaguarDeserializer<'a> {
+    /// Reads a fixed-size array written by
+    /// [`JaguarSerializer::write_pod_array`].
+    #[inline]
+    pub fn read_pod_array<T: JaguarPod, const N: usize>(&mut self) -> Result<[T; N], SerError> {
+        let bytes = self.take_bytes(mem::size_of::<[T; N]>())?;
+        let mut array = MaybeUninit::<[T; N]>::uninit();
+        // SAFETY: `bytes` fills the whole array, and any bytes are a valid `T`
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), array.as_mut_ptr().cast::<u8>(), bytes.len());
+            Ok(array.assume_init())
+        }
+    }
+
+    /// Reads a vector written by [`JaguarSerializer::write_pod_slice`].
+    #[inline]
+    pub fn read_pod_vec<T: JaguarPod>(&mut self) -> Result<Vec<T>, SerError> {
+        let len = self.read_seq_len::<T>()?;
+        let bytes = self.take_bytes(len.checked_mul(mem::size_of::<T>()).ok_or(SerError::InvalidLength)?)?;
+        let mut vec = Vec::<T>::with_capacity(len);
+        // SAFETY: `bytes` fills `len` elements of the allocation, and any bytes
+        // are a valid `T`
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), vec.as_mut_ptr().cast::<u8>(), bytes.len());
+            vec.set_len(len);
+        }
+        Ok(vec)
+    }
+}
+
+/// Encodes `Vec`s and arrays of [`JaguarPod`] values as raw bytes, see
+/// [`JaguarSerializer::write_pod_slice`]. Selected in derives with
+/// `#[jaguar(with = "jaguar::pod::Pod")]`.
+pub struct Pod;
+
+impl<T: JaguarPod> JaguarCodec<Vec<T>> for Pod {
+    #[inline]
+    fn encode(value: &Vec<T>, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_pod_slice(value)
+    }
+
+    #[inline]
+    fn decode(de: &mut JaguarDeserializer<'_>) -> Result<Vec<T>, SerError> {
+        de.read_pod_vec()
+    }
+}
+
+impl<T: JaguarPod, const N: usize> JaguarCodec<[T; N]> for Pod {
+    #[inline]
+    fn encode(value: &[T; N], ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_pod_array(value)
+    }
+
+    #[inline]
+    fn decode(de: &mut JaguarDeserializer<'_>) -> Result<[T; N], SerError> {
+        de.read_pod_array()
+    }
+}
@@ -0,0 +1,124 @@
+//! Reusable serializers for high-throughput encoding.
+//!
+//! Every [`JaguarSerializer`] owns a buffer, so creating one per message
+//! means an allocation per message. A [`SerializerPool`] hands out
+//! serializers that go back to the pool, buffer and all, when dropped. It is
+//! `Sync`, so one pool can be shared by every worker thread.
+//!
+//! Requires the `std` feature.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::pool::SerializerPool;
+//! use jaguar::JaguarSerialize;
+//!
+//! let pool = SerializerPool::new(256);
+//! let mut batch = Vec::new();
+//! for amount in [1u64, 2, 3] {
+//!     let mut ser = pool.get();
+//!     amount.serialize(&mut ser).unwrap();
+//!     ser.finish_into(&mut batch);
+//! }
+//! assert_eq!(batch, [1, 2, 3]);
+//! assert_eq!(pool.idle(), 1);
+//! ```
+
+use crate::JaguarSerializer;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+
+/// A pool of serializers that keep their buffers between messages.
+pub struct SerializerPool {
+    free: Mutex<Vec<JaguarSerializer>>,
+    capacity: usize,
+    max_idle: usize,
+}
+
+impl SerializerPool {
+    /// Creates an empty pool whose new serializers start with `capacity`
+    /// bytes.
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        Self { free: Mutex::new(Vec::new()), capacity, max_idle: usize::MAX }
+    }
+
+    /// Caps how many idle serializers the pool keeps. Serializers returned
+    /// beyond that are dropped, freeing their buffers.
+    #[inline]
+    pub fn with_max_idle(mut self, max_idle: usize) -> Self {
+        self.max_idle = max_idle;
+        self
+    }
+
+    /// Takes an idle serializer, or creates one if there is none.
+    ///
+    /// It is reset before being handed out, but keeps any settings from
+    /// earlier use, such as a maximum size or the string table mode.
+    pub fn get(&self) -> PooledSerializer<'_> {
+        let ser = self.lock().pop();
+        let ser = ser.unwrap_or_else(|| JaguarSerializer::with_capacity(self.capacity));
+        PooledSerializer { ser: Some(ser), pool: self }
+    }
+
+    /// Returns the number of idle serializers.
+    #[inline]
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn put(&self, mut ser: JaguarSerializer) {
+        ser.reset();
+        let mut free = self.lock();
+        if free.len() < self.max_idle {
+            free.push(ser);
+        }
+    }
+
+    /// Locks the free list. A panic while holding the lock cannot leave the
+    /// list inconsistent, so poisoning is ignored.
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<JaguarSerializer>> {
+        self.free.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// A serializer borrowed from a [`SerializerPool`], returned to it on drop.
+pub struct PooledSerializer<'a> {
+    ser: Option<JaguarSerializer>,
+    pool: &'a SerializerPool,
+}
+
+impl PooledSerializer<'_> {
+    /// Removes the serializer from the pool for good.
+    #[inline]
+    pub fn detach(mut self) -> JaguarSerializer {
+        self.ser.take().unwrap()
+    }
+}
+
+impl Deref for PooledSerializer<'_> {
+    type Target = JaguarSerializer;
+
+    #[inline]
+    fn deref(&self) -> &JaguarSerializer {
+        self.ser.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledSerializer<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut JaguarSerializer {
+        self.ser.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledSerializer<'_> {
+    fn drop(&mut self) {
+        if let Some(ser) = self.ser.take() {
+            self.pool.put(ser);
+        }
+    }
+}
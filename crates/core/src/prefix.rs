@@ -0,0 +1,151 @@
+//! Length-prefix strategies for byte strings, strings and sequences.
+//!
+//! Jaguar prefixes lengths with a varint, but other layouts fix the width
+//! (Solana's `u8` or `u16` vector lengths) or leave the length implicit
+//! because the value runs to the end of the buffer. A [`LenPrefix`] names one
+//! of these, and is accepted by the `*_with` helpers on
+//! [`JaguarSerializer`]/[`JaguarDeserializer`] and by the
+//! `#[jaguar(len = "...")]` field attribute, which takes `"varint"`, `"u8"`,
+//! `"u16"`, `"u32"`, `"none"` or the path of a custom strategy.
+//!
+//! `"none"` is only accepted on the last field of a struct, and not with
+//! `reserve`, `optimize_layout` or `default_on_eof`, which would put bytes
+//! after it or expect it to end early. The struct itself then has to be the
+//! last thing in the input, such as the top-level value.
+//!
+//! Fixed-width prefixes are little-endian. Writing a length that does not
+//! fit fails with `InvalidLength`.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::prefix::{Implicit, U16};
+//! use jaguar::{JaguarDeserialize, JaguarSerialize};
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+//! struct Packet {
+//!     #[jaguar(len = "u8")]
+//!     keys: Vec<[u8; 4]>,
+//!     #[jaguar(len = "none")]
+//!     payload: Vec<u8>,
+//! }
+//!
+//! let packet = Packet { keys: vec![[1; 4]], payload: vec![9, 9] };
+//! let data = jaguar::serialize(&packet).unwrap();
+//! assert_eq!(data, [1, 1, 1, 1, 1, 9, 9]);
+//! assert_eq!(jaguar::deserialize::<Packet>(&data).unwrap(), packet);
+//!
+//! let mut ser = jaguar::JaguarSerializer::new();
+//! ser.write_bytes_with::<U16>(b"hi").unwrap();
+//! ser.write_bytes_with::<Implicit>(b"rest").unwrap();
+//! assert_eq!(ser.finish(), b"\x02\x00hirest");
+//! ```
+
+use crate::{JaguarCodec, JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// How a length is written before a byte string, string or sequence.
+pub trait LenPrefix {
+    /// Writes `len`.
+    fn write_len(ser: &mut JaguarSerializer, len: usize) -> Result<(), SerError>;
+
+    /// Reads a length, or `None` if the value runs to the end of the input.
+    fn read_len(de: &mut JaguarDeserializer<'_>) -> Result<Option<usize>, SerError>;
+}
+
+/// A varint, the default for every Jaguar length.
+pub struct Varint;
+
+/// A single byte, for at most 255 elements.
+pub struct U8;
+
+/// A little-endian `u16`.
+pub struct U16;
+
+/// A little-endian `u32`, as in borsh.
+pub struct U32;
+
+/// No prefix: the value takes the rest of the input, so it has to be the
+/// last thing written.
+pub struct Implicit;
+
+impl LenPrefix for Varint {
+    #[inline]
+    fn write_len(ser: &mut JaguarSerializer, len: usize) -> Result<(), SerError> {
+        ser.write_varint(len as u64)
+    }
+
+    #[inline]
+    fn read_len(de: &mut JaguarDeserializer<'_>) -> Result<Option<usize>, SerError> {
+        Ok(Some(de.read_varint()? as usize))
+    }
+}
+
+macro_rules! fixed_prefix {
+    ($($name:ident => $t:ty),*) => {
+        $(
+            impl LenPrefix for $name {
+                #[inline]
+                fn write_len(ser: &mut JaguarSerializer, len: usize) -> Result<(), SerError> {
+                    let len = <$t>::try_from(len).map_err(|_| SerError::InvalidLength)?;
+                    ser.write_raw(&len.to_le_bytes())
+                }
+
+                #[inline]
+                fn read_len(de: &mut JaguarDeserializer<'_>) -> Result<Option<usize>, SerError> {
+                    let bytes = de.read_array_ref::<{ core::mem::size_of::<$t>() }>()?;
+                    Ok(Some(<$t>::from_le_bytes(*bytes) as usize))
+                }
+            }
+        )*
+    };
+}
+
+fixed_prefix!(U8 => u8, U16 => u16, U32 => u32);
+
+impl LenPrefix for Implicit {
+    #[inline]
+    fn write_len(_: &mut JaguarSerializer, _: usize) -> Result<(), SerError> {
+        Ok(())
+    }
+
+    #[inline]
+    fn read_len(_: &mut JaguarDeserializer<'_>) -> Result<Option<usize>, SerError> {
+        Ok(None)
+    }
+}
+
+/// Encodes `String` and `Vec<T>` fields with the length prefix `P`. Selected
+/// in derives with `#[jaguar(len = "...")]`.
+pub struct Prefixed<P>(PhantomData<P>);
+
+impl<P: LenPrefix> JaguarCodec<String> for Prefixed<P> {
+    #[inline]
+    fn encode(value: &String, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_str_with::<P>(value)
+    }
+
+    #[inline]
+    fn decode(de: &mut JaguarDeserializer<'_>) -> Result<String, SerError> {
+        de.read_str_with::<P>().map(String::from)
+    }
+}
+
+impl<P: LenPrefix, T> JaguarCodec<Vec<T>> for Prefixed<P>
+where
+    T: JaguarSerialize + for<'a> JaguarDeserialize<'a>,
+{
+    #[inline]
+    fn encode(value: &Vec<T>, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_seq_with::<P, T>(value)
+    }
+
+    #[inline]
+    fn decode(de: &mut JaguarDeserializer<'_>) -> Result<Vec<T>, SerError> {
+        de.read_vec_with::<P, T>()
+    }
+}
@@ -0,0 +1,101 @@
+//! Decoding buffers of back-to-back records.
+//!
+//! Logs, account snapshots and message batches are often plain
+//! concatenations of encoded values. [`RecordSplitter`] iterates over them,
+//! reporting where a malformed record starts so it can be skipped or logged.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::records::RecordSplitter;
+//!
+//! let mut data = Vec::new();
+//! for amount in [5u64, 300, 70_000] {
+//!     data.extend(jaguar::serialize(&amount).unwrap());
+//! }
+//!
+//! let amounts: Result<Vec<u64>, _> = RecordSplitter::new(&data).collect();
+//! assert_eq!(amounts.unwrap(), [5, 300, 70_000]);
+//!
+//! let err = RecordSplitter::<u64>::new(&data[..4]).last().unwrap().unwrap_err();
+//! assert_eq!(err.offset, 3);
+//! ```
+
+use crate::{JaguarDeserialize, JaguarDeserializer, SerError};
+use core::fmt;
+use core::marker::PhantomData;
+
+/// A record that failed to decode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeError {
+    /// Offset of the start of the failing record.
+    pub offset: usize,
+    pub error: SerError,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in record at offset {}", self.error, self.offset)
+    }
+}
+
+impl core::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Deserializes `T` repeatedly until the input runs out.
+///
+/// After an error the iterator ends, since the start of the next record is
+/// unknown. A record that decodes from zero bytes is an `InvalidData` error,
+/// as it would otherwise repeat forever.
+pub struct RecordSplitter<'a, T> {
+    de: JaguarDeserializer<'a>,
+    failed: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: JaguarDeserialize<'a>> RecordSplitter<'a, T> {
+    /// Iterates over the records in `data`.
+    #[inline]
+    pub fn new(data: &'a [u8]) -> Self {
+        Self::from_deserializer(JaguarDeserializer::new(data))
+    }
+
+    /// Iterates over the records left in `de`, keeping its settings such as
+    /// canonical mode or the string table.
+    #[inline]
+    pub fn from_deserializer(de: JaguarDeserializer<'a>) -> Self {
+        Self { de, failed: false, _marker: PhantomData }
+    }
+
+    /// Returns the offset of the next record.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.de.position()
+    }
+}
+
+impl<'a, T: JaguarDeserialize<'a>> Iterator for RecordSplitter<'a, T> {
+    type Item = Result<T, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || !self.de.has_data() {
+            return None;
+        }
+        let offset = self.de.position();
+        match T::deserialize(&mut self.de) {
+            Ok(value) if self.de.position() > offset => Some(Ok(value)),
+            result => {
+                let error = result.err().unwrap_or(SerError::InvalidData);
+                self.failed = true;
+                Some(Err(DecodeError { offset, error }))
+            }
+        }
+    }
+}
+
+impl<'a, T: JaguarDeserialize<'a>> core::iter::FusedIterator for RecordSplitter<'a, T> {}
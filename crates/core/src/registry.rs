@@ -0,0 +1,182 @@
+//! Trait objects tagged with their concrete type.
+//!
+//! A message bus carrying plugin-defined messages can't name every message
+//! type in one enum. Instead, each type picks a [`JaguarTagged::TAG`], and
+//! `Box<dyn JaguarAny>` values are written as that tag, as a varint, followed
+//! by the value's own encoding. Decoding looks the tag up in a [`Registry`]
+//! the application fills at startup, and fails with `InvalidData` on tags it
+//! doesn't know, like an unknown enum variant.
+//!
+//! Decoded values come back as `Box<dyn JaguarAny>`, which can be downcast
+//! to the concrete type.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::registry::{serialize_any, JaguarAny, JaguarTagged, Registry};
+//! use jaguar::{JaguarDeserialize, JaguarSerialize};
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+//! struct Ping {
+//!     seq: u64,
+//! }
+//!
+//! impl JaguarTagged for Ping {
+//!     const TAG: u64 = 1;
+//! }
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+//! struct Log {
+//!     line: String,
+//! }
+//!
+//! impl JaguarTagged for Log {
+//!     const TAG: u64 = 2;
+//! }
+//!
+//! let mut registry = Registry::new();
+//! registry.register::<Ping>().register::<Log>();
+//!
+//! let message: Box<dyn JaguarAny> = Box::new(Log { line: "started".into() });
+//! let data = serialize_any(message.as_ref()).unwrap();
+//! assert_eq!(data[0], 2);
+//!
+//! let decoded = registry.deserialize(&data).unwrap();
+//! assert_eq!(decoded.downcast_ref::<Log>(), Some(&Log { line: "started".into() }));
+//! assert!(decoded.downcast_ref::<Ping>().is_none());
+//! ```
+
+use crate::{JaguarDeserializeOwned, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::fmt;
+
+/// A type with a registry tag, unique among the types of one [`Registry`].
+pub trait JaguarTagged {
+    const TAG: u64;
+}
+
+/// An object-safe value that encodes with its [`JaguarTagged::TAG`].
+/// Implemented for every tagged type that can be serialized.
+pub trait JaguarAny: JaguarSerialize + Any {
+    /// The tag of the concrete type.
+    fn tag(&self) -> u64;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: JaguarTagged + JaguarSerialize + Any> JaguarAny for T {
+    #[inline]
+    fn tag(&self) -> u64 {
+        T::TAG
+    }
+
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl dyn JaguarAny {
+    /// Returns `true` if the value is a `T`.
+    #[inline]
+    pub fn is<T: Any>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+
+    /// Returns the value as a `T`, or `None` if it is another type.
+    #[inline]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    /// Takes the value out as a `T`, or gives it back if it is another type.
+    pub fn downcast<T: Any>(self: Box<Self>) -> Result<Box<T>, Box<Self>> {
+        if self.is::<T>() {
+            Ok(self.into_any().downcast().unwrap_or_else(|_| unreachable!()))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Debug for dyn JaguarAny {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JaguarAny").field("tag", &self.tag()).finish_non_exhaustive()
+    }
+}
+
+/// Writes `value` as its tag followed by its encoding.
+pub fn write_any(ser: &mut JaguarSerializer, value: &dyn JaguarAny) -> Result<(), SerError> {
+    ser.write_varint(value.tag())?;
+    value.serialize(ser)
+}
+
+/// Serializes `value` as its tag followed by its encoding.
+pub fn serialize_any(value: &dyn JaguarAny) -> Result<Vec<u8>, SerError> {
+    let mut ser = JaguarSerializer::new();
+    write_any(&mut ser, value)?;
+    Ok(ser.finish())
+}
+
+type DecodeFn = fn(&mut JaguarDeserializer<'_>) -> Result<Box<dyn JaguarAny>, SerError>;
+
+fn decode<T>(de: &mut JaguarDeserializer<'_>) -> Result<Box<dyn JaguarAny>, SerError>
+where
+    T: JaguarAny + JaguarDeserializeOwned,
+{
+    Ok(Box::new(T::deserialize(de)?))
+}
+
+/// The types a tagged value may decode to, by tag.
+#[derive(Default)]
+pub struct Registry {
+    decoders: BTreeMap<u64, DecodeFn>,
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `T` under its tag.
+    ///
+    /// Panics if another type is already registered with the same tag, since
+    /// its values could no longer be told apart.
+    pub fn register<T: JaguarTagged + JaguarAny + JaguarDeserializeOwned>(&mut self) -> &mut Self {
+        if self.decoders.insert(T::TAG, decode::<T>).is_some() {
+            panic!("registry tag {} is registered twice", T::TAG);
+        }
+        self
+    }
+
+    /// Returns `true` if a type is registered under `tag`.
+    pub fn contains(&self, tag: u64) -> bool {
+        self.decoders.contains_key(&tag)
+    }
+
+    /// Reads a value written by [`write_any`], failing with `InvalidData` if
+    /// its tag is not registered.
+    pub fn read(&self, de: &mut JaguarDeserializer<'_>) -> Result<Box<dyn JaguarAny>, SerError> {
+        let tag = de.read_varint()?;
+        let decode = self.decoders.get(&tag).ok_or(SerError::InvalidData)?;
+        de.nested(|de| decode(de))
+    }
+
+    /// Deserializes a value written by [`serialize_any`].
+    pub fn deserialize(&self, data: &[u8]) -> Result<Box<dyn JaguarAny>, SerError> {
+        self.read(&mut JaguarDeserializer::new(data))
+    }
+}
@@ -0,0 +1,300 @@
+//! Runtime descriptions of the wire format of Jaguar types.
+//!
+//! A [`Schema`] mirrors exactly what `JaguarSerialize` writes for a type, so
+//! tools like `jaguar-codegen` can emit byte-identical encoders and decoders
+//! for other languages. Derive it with `#[derive(JaguarSchema)]`.
+
+use crate::clock::{Epoch, Slot, UnixTimestamp};
+use crate::{ArrayRef, StrRef};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32,
+    NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+use core::marker::{PhantomData, PhantomPinned};
+use core::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+use core::time::Duration;
+
+/// The wire layout of a type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    /// A single raw byte.
+    U8,
+    /// A single byte, `0` or `1`.
+    Bool,
+    /// An unsigned varint holding an integer of `bits` bits.
+    Varint { bits: u32 },
+    /// A zigzag varint holding a signed integer of `bits` bits.
+    SignedVarint { bits: u32 },
+    /// Two varints, the high then the low 64 bits.
+    U128,
+    /// A zigzag-encoded `i128`, written like [`Schema::U128`].
+    I128,
+    /// A float with a marker byte, see the spec.
+    F32,
+    /// A float with a marker byte, see the spec.
+    F64,
+    /// A length-prefixed UTF-8 string.
+    String,
+    /// `[u8; N]`, written raw.
+    ByteArray(usize),
+    /// `[T; N]` for non-byte `T`, prefixed with `N` as a varint.
+    Array(usize, Box<Schema>),
+    /// A length-prefixed sequence.
+    Vec(Box<Schema>),
+    /// A `Vec<u64>` marked `#[jaguar(delta)]`.
+    DeltaVec,
+    /// A `NonZero*` integer, written as the inner integer but never zero.
+    NonZero(Box<Schema>),
+    /// Each element in order.
+    Tuple(Vec<Schema>),
+    /// A derived struct.
+    Struct(StructSchema),
+}
+
+impl Schema {
+    /// Returns whether values of this layout encode to no bytes at all, like
+    /// `()` or a struct of only such fields.
+    pub fn is_zero_sized(&self) -> bool {
+        match self {
+            Schema::ByteArray(len) => *len == 0,
+            Schema::Tuple(schemas) => schemas.iter().all(Schema::is_zero_sized),
+            Schema::Struct(def) => {
+                def.reserve == 0 && def.fields.iter().all(|field| field.bits.is_none() && field.schema.is_zero_sized())
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The layout of a struct deriving `JaguarSchema`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructSchema {
+    pub name: String,
+    pub fields: Vec<FieldSchema>,
+    /// Zero bytes following the last field, from `#[jaguar(reserve = N)]`.
+    pub reserve: usize,
+}
+
+/// A single struct field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSchema {
+    /// The field name, or `_0`, `_1`, ... for tuple struct fields.
+    pub name: String,
+    pub schema: Schema,
+    /// Width from `#[jaguar(bits = N)]`. Adjacent packed fields share bytes.
+    pub bits: Option<u32>,
+}
+
+/// A type whose wire layout can be described at runtime.
+///
+/// Schemas are trees without references, so recursive types have none. The
+/// derive rejects a struct naming itself in a field, but types recursing
+/// through each other overflow the stack in `schema()`.
+pub trait JaguarSchema {
+    fn schema() -> Schema;
+}
+
+macro_rules! impl_schema {
+    ($($t:ty => $schema:expr),* $(,)?) => {
+        $(
+            impl JaguarSchema for $t {
+                #[inline]
+                fn schema() -> Schema {
+                    $schema
+                }
+            }
+        )*
+    };
+}
+
+impl_schema!(
+    u8 => Schema::U8,
+    bool => Schema::Bool,
+    u16 => Schema::Varint { bits: 16 },
+    u32 => Schema::Varint { bits: 32 },
+    u64 => Schema::Varint { bits: 64 },
+    usize => Schema::Varint { bits: 64 },
+    i8 => Schema::SignedVarint { bits: 8 },
+    i16 => Schema::SignedVarint { bits: 16 },
+    i32 => Schema::SignedVarint { bits: 32 },
+    i64 => Schema::SignedVarint { bits: 64 },
+    isize => Schema::SignedVarint { bits: 64 },
+    u128 => Schema::U128,
+    i128 => Schema::I128,
+    f32 => Schema::F32,
+    f64 => Schema::F64,
+    String => Schema::String,
+    StrRef<'_> => Schema::String,
+    Slot => Schema::Varint { bits: 64 },
+    Epoch => Schema::Varint { bits: 64 },
+    UnixTimestamp => Schema::SignedVarint { bits: 64 },
+    NonZeroU8 => Schema::NonZero(Box::new(Schema::U8)),
+    NonZeroU16 => Schema::NonZero(Box::new(Schema::Varint { bits: 16 })),
+    NonZeroU32 => Schema::NonZero(Box::new(Schema::Varint { bits: 32 })),
+    NonZeroU64 => Schema::NonZero(Box::new(Schema::Varint { bits: 64 })),
+    NonZeroUsize => Schema::NonZero(Box::new(Schema::Varint { bits: 64 })),
+    NonZeroI8 => Schema::NonZero(Box::new(Schema::SignedVarint { bits: 8 })),
+    NonZeroI16 => Schema::NonZero(Box::new(Schema::SignedVarint { bits: 16 })),
+    NonZeroI32 => Schema::NonZero(Box::new(Schema::SignedVarint { bits: 32 })),
+    NonZeroI64 => Schema::NonZero(Box::new(Schema::SignedVarint { bits: 64 })),
+    NonZeroIsize => Schema::NonZero(Box::new(Schema::SignedVarint { bits: 64 })),
+    NonZeroU128 => Schema::NonZero(Box::new(Schema::U128)),
+    NonZeroI128 => Schema::NonZero(Box::new(Schema::I128)),
+);
+
+impl<const N: usize> JaguarSchema for [u8; N] {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::ByteArray(N)
+    }
+}
+
+impl<const N: usize> JaguarSchema for ArrayRef<'_, N> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::ByteArray(N)
+    }
+}
+
+macro_rules! impl_array_schema {
+    ($($t:ty),*) => {
+        $(
+            impl<const N: usize> JaguarSchema for [$t; N] {
+                #[inline]
+                fn schema() -> Schema {
+                    Schema::Array(N, Box::new(<$t>::schema()))
+                }
+            }
+        )*
+    };
+}
+
+impl_array_schema!(u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64, bool);
+
+impl<T: JaguarSchema> JaguarSchema for Vec<T> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Vec(Box::new(T::schema()))
+    }
+}
+
+impl<T: JaguarSchema> JaguarSchema for Box<T> {
+    #[inline]
+    fn schema() -> Schema {
+        T::schema()
+    }
+}
+
+impl<T: JaguarSchema> JaguarSchema for Rc<T> {
+    #[inline]
+    fn schema() -> Schema {
+        T::schema()
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T: JaguarSchema> JaguarSchema for Arc<T> {
+    #[inline]
+    fn schema() -> Schema {
+        T::schema()
+    }
+}
+
+/// Zero-sized types encode to nothing, an empty tuple.
+macro_rules! impl_zero_sized_schema {
+    ($([$($param:ident),*] $t:ty;)*) => {
+        $(
+            impl<$($param: ?Sized),*> JaguarSchema for $t {
+                #[inline]
+                fn schema() -> Schema {
+                    Schema::Tuple(Vec::new())
+                }
+            }
+        )*
+    };
+}
+
+impl_zero_sized_schema! {
+    [] ();
+    [T] PhantomData<T>;
+    [] PhantomPinned;
+}
+
+/// Seconds, then nanoseconds.
+impl JaguarSchema for Duration {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Tuple(vec![Schema::Varint { bits: 64 }, Schema::Varint { bits: 32 }])
+    }
+}
+
+impl JaguarSchema for Ipv4Addr {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::ByteArray(4)
+    }
+}
+
+impl JaguarSchema for Ipv6Addr {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::ByteArray(16)
+    }
+}
+
+/// Address, then port.
+impl JaguarSchema for SocketAddrV4 {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Tuple(vec![Schema::ByteArray(4), Schema::Varint { bits: 16 }])
+    }
+}
+
+/// Address, port, flow info, then scope ID.
+impl JaguarSchema for SocketAddrV6 {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Tuple(vec![
+            Schema::ByteArray(16),
+            Schema::Varint { bits: 16 },
+            Schema::Varint { bits: 32 },
+            Schema::Varint { bits: 32 },
+        ])
+    }
+}
+
+impl JaguarSchema for Cow<'_, str> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::String
+    }
+}
+
+impl JaguarSchema for Cow<'_, [u8]> {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Vec(Box::new(Schema::U8))
+    }
+}
+
+impl<T: JaguarSchema + Clone> JaguarSchema for Cow<'_, T> {
+    #[inline]
+    fn schema() -> Schema {
+        T::schema()
+    }
+}
+
+impl<A: JaguarSchema, B: JaguarSchema> JaguarSchema for (A, B) {
+    #[inline]
+    fn schema() -> Schema {
+        Schema::Tuple(vec![A::schema(), B::schema()])
+    }
+}
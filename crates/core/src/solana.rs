@@ -0,0 +1,144 @@
+//! Helpers for storing Jaguar-encoded state in Solana accounts.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust,ignore
+//! use jaguar::solana::{load, store};
+//!
+//! let mut vault: Vault = load(vault_account)?;
+//! vault.amount += deposit;
+//! store(vault_account, &vault)?;
+//! ```
+//!
+//! [`AccountSpace`] sizes new accounts for the system program's
+//! `CreateAccount`:
+//!
+//! ```rust,ignore
+//! use jaguar::solana::AccountSpace;
+//! use pinocchio::sysvars::{rent::Rent, Sysvar};
+//!
+//! let AccountSpace { space, lamports } = AccountSpace::of::<Vault>(&Rent::get()?);
+//! ```
+//!
+//! [`emit`], or the [`emit_event!`](crate::emit_event) macro, logs an event
+//! for indexers, which read it back from the `Program data:` log line with
+//! [`deserialize_event`](crate::discriminator::deserialize_event):
+//!
+//! ```rust,ignore
+//! jaguar::emit_event!(Deposited { vault: *vault_account.key(), amount: deposit })?;
+//! ```
+
+use crate::{
+    ErrorKind, JaguarConstSize, JaguarDeserializeOwned, JaguarDeserializer, JaguarEvent, JaguarSerialize,
+    JaguarSerializer, SerError,
+};
+use pinocchio::{account_info::AccountInfo, log::sol_log_data, program_error::ProgramError, sysvars::rent::Rent};
+
+/// The largest data size of a Solana account.
+pub const MAX_ACCOUNT_SIZE: usize = 10 * 1024 * 1024;
+
+impl From<SerError> for ProgramError {
+    #[inline]
+    fn from(err: SerError) -> Self {
+        match err.kind() {
+            ErrorKind::BufferTooSmall => ProgramError::AccountDataTooSmall,
+            ErrorKind::InvalidData | ErrorKind::Unsupported => ProgramError::InvalidAccountData,
+        }
+    }
+}
+
+/// Deserializes a `T` from the start of the account's data.
+///
+/// Bytes past the end of the encoded value are ignored, so accounts may be
+/// allocated larger than their current contents. Data that ends before the
+/// value does fails with `AccountDataTooSmall`.
+#[inline]
+pub fn load<T>(account: &AccountInfo) -> Result<T, ProgramError>
+where
+    T: JaguarDeserializeOwned,
+{
+    let data = account.try_borrow_data()?;
+    let mut de = JaguarDeserializer::new(&data);
+    Ok(de.decode_traced::<T>()?)
+}
+
+/// Serializes `value` into the start of the account's data.
+///
+/// Fails with `AccountDataTooSmall` without touching the account if the
+/// encoded value does not fit. The buffer grows with the value rather than
+/// starting at the account's size, so large accounts don't exhaust the heap.
+#[inline]
+pub fn store<T>(account: &AccountInfo, value: &T) -> Result<(), ProgramError>
+where
+    T: JaguarSerialize,
+{
+    let mut ser = JaguarSerializer::builder().max_size(account.data_len()).build();
+    value.serialize(&mut ser)?;
+
+    let bytes = ser.data();
+    account.try_borrow_mut_data()?[..bytes.len()].copy_from_slice(bytes);
+    Ok(())
+}
+
+/// Logs `event` behind its event discriminator with `sol_log_data`, which
+/// the runtime prints base64-encoded as a `Program data:` line.
+#[inline]
+pub fn emit<T>(event: &T) -> Result<(), ProgramError>
+where
+    T: JaguarSerialize + JaguarEvent,
+{
+    let data = crate::discriminator::serialize_event(event)?;
+    sol_log_data(&[&data]);
+    Ok(())
+}
+
+/// Logs an event with [`solana::emit`](crate::solana::emit).
+#[macro_export]
+macro_rules! emit_event {
+    ($event:expr) => {
+        $crate::solana::emit(&$event)
+    };
+}
+
+/// Data size and rent-exempt balance for a new account, as passed to the
+/// system program's `CreateAccount`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountSpace {
+    pub space: u64,
+    pub lamports: u64,
+}
+
+impl AccountSpace {
+    /// Space for `len` bytes of data.
+    #[inline]
+    pub fn with_len(len: usize, rent: &Rent) -> Self {
+        Self { space: len as u64, lamports: rent.minimum_balance(len) }
+    }
+
+    /// Space for an account holding a `T`, whose encoding always has the
+    /// same size.
+    #[inline]
+    pub fn of<T: JaguarConstSize>(rent: &Rent) -> Self {
+        Self::with_len(T::SIZE, rent)
+    }
+
+    /// Space for an account holding `value`, plus `margin` bytes it can grow
+    /// into without a realloc.
+    ///
+    /// Fails with `InvalidArgument` if that exceeds [`MAX_ACCOUNT_SIZE`].
+    pub fn for_value<T>(value: &T, margin: usize, rent: &Rent) -> Result<Self, ProgramError>
+    where
+        T: JaguarSerialize,
+    {
+        let mut ser = JaguarSerializer::new().with_max_size(MAX_ACCOUNT_SIZE);
+        value.serialize(&mut ser).map_err(|_| ProgramError::InvalidArgument)?;
+        let len = ser
+            .position()
+            .checked_add(margin)
+            .filter(|&len| len <= MAX_ACCOUNT_SIZE)
+            .ok_or(ProgramError::InvalidArgument)?;
+        Ok(Self::with_len(len, rent))
+    }
+}
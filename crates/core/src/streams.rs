@@ -0,0 +1,94 @@
+//! Single-pass serialization of layouts with an up-front index.
+//!
+//! Formats like "fixed-size header of offsets, then variable-size payloads"
+//! normally need two passes: one to measure the payloads and one to write
+//! them. A [`MultiSerializer`] instead writes into several independent
+//! streams, each its own [`JaguarSerializer`], and concatenates them in order
+//! on [`finish`](MultiSerializer::finish).
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::streams::MultiSerializer;
+//!
+//! const HEADER: usize = 0;
+//! const BODY: usize = 1;
+//!
+//! let mut ser = MultiSerializer::new(2);
+//! for name in ["alpha", "beta"] {
+//!     let offset = ser.stream(BODY).position() as u32;
+//!     ser.stream(HEADER).write_raw(&offset.to_le_bytes()).unwrap();
+//!     ser.stream(BODY).write_str(name).unwrap();
+//! }
+//! let data = ser.finish();
+//! assert_eq!(&data[..8], &[0, 0, 0, 0, 6, 0, 0, 0]);
+//! ```
+
+use crate::JaguarSerializer;
+use alloc::vec::Vec;
+
+/// Writes several streams independently and concatenates them on finish.
+pub struct MultiSerializer {
+    streams: Vec<JaguarSerializer>,
+}
+
+impl MultiSerializer {
+    /// Creates a serializer with `count` empty streams.
+    #[inline]
+    pub fn new(count: usize) -> Self {
+        Self::from_streams((0..count).map(|_| JaguarSerializer::new()).collect())
+    }
+
+    /// Uses the given serializers as streams, e.g. to preallocate a known
+    /// header size or cap a stream with [`JaguarSerializer::with_max_size`].
+    #[inline]
+    pub fn from_streams(streams: Vec<JaguarSerializer>) -> Self {
+        Self { streams }
+    }
+
+    /// Returns stream `index` for writing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not below the number of streams.
+    #[inline]
+    pub fn stream(&mut self, index: usize) -> &mut JaguarSerializer {
+        &mut self.streams[index]
+    }
+
+    /// Returns the offset stream `index` starts at in the finished output,
+    /// given what has been written to the streams before it so far.
+    #[inline]
+    pub fn offset(&self, index: usize) -> usize {
+        self.streams[..index].iter().map(JaguarSerializer::position).sum()
+    }
+
+    /// Returns the total number of bytes written across all streams.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.offset(self.streams.len())
+    }
+
+    /// Returns `true` if nothing has been written to any stream.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Concatenates the streams in order.
+    pub fn finish(self) -> Vec<u8> {
+        let len = self.len();
+        let mut streams = self.streams.into_iter();
+        let mut out = match streams.next() {
+            Some(first) => first.finish(),
+            None => return Vec::new(),
+        };
+        out.reserve_exact(len - out.len());
+        for stream in streams {
+            out.extend_from_slice(stream.data());
+        }
+        out
+    }
+}
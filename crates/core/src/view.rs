@@ -0,0 +1,214 @@
+//! Lazy, zero-copy access to nested fields of an encoded struct.
+//!
+//! Decoding a whole account to read one field wastes compute on every byte
+//! after it. A view instead walks only as far as it needs to: `#[derive(JaguarView)]`
+//! generates a `FooView<'a>` with one accessor per field, and each accessor
+//! skips the fields before it (with [`JaguarDeserialize::validate`], which
+//! allocates nothing) and returns a view of that field. Chaining accessors
+//! projects down a path, touching only the bytes up to its end.
+//!
+//! Views of non-struct types are [`Value`]s, decoded with [`Value::get`].
+//! Derived views also have `get` and `data` methods, from [`StructView`]. A
+//! field named `get` or `data` takes over the name for its accessor, leaving
+//! the view's own method reachable as `StructView::get(&view)`.
+//! Fields with `with`, `compat`, `raw`, `len`, `delta` or `module`
+//! are skipped correctly but have no accessor. `bits` fields and generic
+//! structs are not supported.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::{JaguarDeserialize, JaguarSerialize, JaguarView};
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, JaguarView)]
+//! struct Position {
+//!     market: String,
+//!     amount: u64,
+//! }
+//!
+//! #[derive(JaguarSerialize, JaguarDeserialize, JaguarView)]
+//! struct Account {
+//!     owner: [u8; 32],
+//!     position: Position,
+//!     history: Vec<u64>,
+//! }
+//!
+//! let account = Account {
+//!     owner: [1; 32],
+//!     position: Position { market: "SOL-PERP".into(), amount: 250 },
+//!     history: vec![1, 2, 3],
+//! };
+//! let data = jaguar::serialize(&account).unwrap();
+//!
+//! let view = jaguar::view::<Account>(&data);
+//! assert_eq!(view.position()?.amount()?.get()?, 250);
+//! assert_eq!(view.owner()?.get()?, [1; 32]);
+//! # Ok::<(), jaguar::SerError>(())
+//! ```
+
+use crate::clock::{Epoch, Slot, UnixTimestamp};
+use crate::compat::{BorshLayout, COption};
+use crate::{ArrayRef, JaguarDeserialize, JaguarDeserializer, SerError, StrRef};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32,
+    NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+use core::time::Duration;
+
+/// A type with a lazy view over its encoding. Derive it with
+/// `#[derive(JaguarView)]`.
+pub trait JaguarView<'a>: JaguarDeserialize<'a> {
+    /// The view type, `Value<'a, Self>` for anything but derived structs.
+    type View;
+
+    /// Creates a view of the value encoded at the start of `data`. Nothing is
+    /// read until the view is used.
+    fn view(data: &'a [u8]) -> Self::View;
+}
+
+/// The methods every derived view has besides its field accessors.
+pub trait StructView<'a>: Copy {
+    /// The type the view is of.
+    type Target;
+
+    /// Decodes the whole value.
+    fn get(&self) -> Result<Self::Target, SerError>;
+
+    /// Returns the input from the start of the value, including anything
+    /// encoded after it.
+    fn data(&self) -> &'a [u8];
+}
+
+/// A view of a value that is only decoded as a whole.
+pub struct Value<'a, T> {
+    data: &'a [u8],
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: JaguarDeserialize<'a>> Value<'a, T> {
+    /// Creates a view of the value encoded at the start of `data`.
+    #[inline]
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, _marker: PhantomData }
+    }
+
+    /// Decodes the value.
+    #[inline]
+    pub fn get(&self) -> Result<T, SerError> {
+        JaguarDeserializer::new(self.data).decode_traced::<T>()
+    }
+
+    /// Returns the input from the start of the value, including anything
+    /// encoded after it.
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<T> Clone for Value<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Value<'_, T> {}
+
+macro_rules! impl_value_view {
+    ($($t:ty),*) => {
+        $(
+            impl<'a> JaguarView<'a> for $t {
+                type View = Value<'a, Self>;
+
+                #[inline]
+                fn view(data: &'a [u8]) -> Self::View {
+                    Value::new(data)
+                }
+            }
+        )*
+    };
+}
+
+impl_value_view!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, String, Slot, Epoch, UnixTimestamp, Duration,
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize, NonZeroI8, NonZeroI16, NonZeroI32,
+    NonZeroI64, NonZeroI128, NonZeroIsize
+);
+
+macro_rules! impl_array_view {
+    ($($t:ty),*) => {
+        $(
+            impl<'a, const N: usize> JaguarView<'a> for [$t; N] {
+                type View = Value<'a, Self>;
+
+                #[inline]
+                fn view(data: &'a [u8]) -> Self::View {
+                    Value::new(data)
+                }
+            }
+        )*
+    };
+}
+
+impl_array_view!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64, bool);
+
+impl<'a> JaguarView<'a> for StrRef<'a> {
+    type View = Value<'a, Self>;
+
+    #[inline]
+    fn view(data: &'a [u8]) -> Self::View {
+        Value::new(data)
+    }
+}
+
+impl<'a, const N: usize> JaguarView<'a> for ArrayRef<'a, N> {
+    type View = Value<'a, Self>;
+
+    #[inline]
+    fn view(data: &'a [u8]) -> Self::View {
+        Value::new(data)
+    }
+}
+
+impl<'a, T: JaguarDeserialize<'a>> JaguarView<'a> for Vec<T> {
+    type View = Value<'a, Self>;
+
+    #[inline]
+    fn view(data: &'a [u8]) -> Self::View {
+        Value::new(data)
+    }
+}
+
+impl<'a, A: JaguarDeserialize<'a>, B: JaguarDeserialize<'a>> JaguarView<'a> for (A, B) {
+    type View = Value<'a, Self>;
+
+    #[inline]
+    fn view(data: &'a [u8]) -> Self::View {
+        Value::new(data)
+    }
+}
+
+impl<'a, K: JaguarDeserialize<'a> + Ord, V: JaguarDeserialize<'a>> JaguarView<'a> for BTreeMap<K, V> {
+    type View = Value<'a, Self>;
+
+    #[inline]
+    fn view(data: &'a [u8]) -> Self::View {
+        Value::new(data)
+    }
+}
+
+impl<'a, T: BorshLayout + Default> JaguarView<'a> for COption<T> {
+    type View = Value<'a, Self>;
+
+    #[inline]
+    fn view(data: &'a [u8]) -> Self::View {
+        Value::new(data)
+    }
+}
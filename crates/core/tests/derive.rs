@@ -0,0 +1,1383 @@
+use jaguar::compat::COption;
+use std::borrow::Cow;
+use std::sync::Arc;
+use jaguar::{
+    JaguarDeserialize, JaguarDeserializeOwned, JaguarDeserializer, JaguarSerialize, JaguarSerializer, JaguarView,
+};
+
+fn roundtrip<T>(value: &T) -> (T, Vec<u8>)
+where
+    T: JaguarSerialize + JaguarDeserializeOwned,
+{
+    let mut ser = JaguarSerializer::new();
+    value.serialize(&mut ser).unwrap();
+    let data = ser.finish();
+
+    let mut de = JaguarDeserializer::new(&data);
+    (T::deserialize(&mut de).unwrap(), data)
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct SlotHistory {
+    authority: [u8; 32],
+    #[jaguar(delta)]
+    slots: Vec<u64>,
+}
+
+#[test]
+fn test_delta_attribute() {
+    let history = SlotHistory {
+        authority: [7u8; 32],
+        slots: (300_000_000..300_000_100).collect(),
+    };
+
+    let (decoded, data) = roundtrip(&history);
+    assert_eq!(decoded, history);
+    // 32 byte authority + 1 byte length + 5 byte first slot + 99 single byte deltas
+    assert_eq!(data.len(), 32 + 1 + 5 + 99);
+}
+
+/// Encodes a `u128` as two little-endian `u64` halves.
+struct SplitU128;
+
+impl jaguar::JaguarCodec<u128> for SplitU128 {
+    fn encode(value: &u128, ser: &mut JaguarSerializer) -> Result<(), jaguar::SerError> {
+        (*value as u64).to_le_bytes().serialize(ser)?;
+        ((*value >> 64) as u64).to_le_bytes().serialize(ser)
+    }
+
+    fn decode(de: &mut JaguarDeserializer<'_>) -> Result<u128, jaguar::SerError> {
+        let low = u64::from_le_bytes(<[u8; 8]>::deserialize(de)?);
+        let high = u64::from_le_bytes(<[u8; 8]>::deserialize(de)?);
+        Ok(((high as u128) << 64) | low as u128)
+    }
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Ledger {
+    #[jaguar(with = "SplitU128")]
+    total: u128,
+    count: u32,
+}
+
+#[test]
+fn test_with_codec_attribute() {
+    let ledger = Ledger {
+        total: u128::MAX - 7,
+        count: 3,
+    };
+
+    let (decoded, data) = roundtrip(&ledger);
+    assert_eq!(decoded, ledger);
+    assert_eq!(data.len(), 16 + 1);
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, jaguar::JaguarConstSize, Debug, Clone, Copy, PartialEq)]
+#[jaguar(transparent)]
+struct Authority([u8; 32]);
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, Copy, PartialEq)]
+#[jaguar(transparent)]
+struct Lamports(u64);
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Stake {
+    authority: Authority,
+    lamports: Lamports,
+}
+
+#[test]
+fn test_transparent_newtypes() {
+    let (decoded, data) = roundtrip(&Lamports(1_000_000));
+    assert_eq!(decoded, Lamports(1_000_000));
+    assert_eq!(data, jaguar::serialize(&1_000_000u64).unwrap());
+
+    let stake = Stake {
+        authority: Authority([5u8; 32]),
+        lamports: Lamports(u64::MAX),
+    };
+    let (decoded, data) = roundtrip(&stake);
+    assert_eq!(decoded, stake);
+    assert_eq!(data.len(), 32 + 10);
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Tagged<T> {
+    tag: u8,
+    items: Vec<T>,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Borrowed<'a, T> {
+    name: jaguar::StrRef<'a>,
+    value: T,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Pair<'a, 'b> {
+    left: jaguar::StrRef<'a>,
+    right: jaguar::StrRef<'b>,
+}
+
+#[test]
+fn test_generic_structs() {
+    let tagged = Tagged {
+        tag: 3,
+        items: vec![(1u64, 2i64), (3, -4)],
+    };
+    assert_eq!(roundtrip(&tagged).0, tagged);
+
+    let borrowed = Borrowed {
+        name: jaguar::StrRef("mint"),
+        value: Tagged { tag: 1, items: vec![7u16] },
+    };
+    let data = jaguar::serialize(&borrowed).unwrap();
+    let decoded: Borrowed<Tagged<u16>> = jaguar::deserialize(&data).unwrap();
+    assert_eq!(decoded, borrowed);
+
+    let pair = Pair {
+        left: jaguar::StrRef("left"),
+        right: jaguar::StrRef("right"),
+    };
+    let data = jaguar::serialize(&pair).unwrap();
+    let decoded: Pair = jaguar::deserialize(&data).unwrap();
+    assert_eq!(decoded, pair);
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Migrating {
+    #[jaguar(compat = "borsh")]
+    legacy_amount: u64,
+    #[jaguar(compat = "borsh")]
+    legacy_owner: [u8; 32],
+    #[jaguar(compat = "borsh")]
+    legacy_memo: String,
+    amount: u64,
+}
+
+#[derive(borsh::BorshSerialize)]
+struct LegacyPrefix {
+    amount: u64,
+    owner: [u8; 32],
+    memo: String,
+}
+
+#[test]
+fn test_borsh_compat_fields() {
+    let value = Migrating {
+        legacy_amount: 300,
+        legacy_owner: [8u8; 32],
+        legacy_memo: "legacy".to_string(),
+        amount: 300,
+    };
+
+    let (decoded, data) = roundtrip(&value);
+    assert_eq!(decoded, value);
+
+    let legacy = borsh::to_vec(&LegacyPrefix {
+        amount: 300,
+        owner: [8u8; 32],
+        memo: "legacy".to_string(),
+    })
+    .unwrap();
+    assert_eq!(&data[..legacy.len()], legacy.as_slice());
+    assert_eq!(&data[legacy.len()..], &[0xAC, 0x02]);
+}
+
+/// The SPL token program's mint layout.
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Mint {
+    mint_authority: COption<[u8; 32]>,
+    #[jaguar(compat = "borsh")]
+    supply: u64,
+    decimals: u8,
+    #[jaguar(compat = "borsh")]
+    is_initialized: bool,
+    freeze_authority: COption<[u8; 32]>,
+}
+
+#[test]
+fn test_spl_coption() {
+    let mint = Mint {
+        mint_authority: COption::Some([4u8; 32]),
+        supply: 1_000_000,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: None.into(),
+    };
+
+    let (decoded, data) = roundtrip(&mint);
+    assert_eq!(decoded, mint);
+    // the fixed 82-byte `spl_token::state::Mint::LEN`
+    assert_eq!(data.len(), 82);
+    assert_eq!(&data[..4], &[1, 0, 0, 0]);
+    assert_eq!(&data[46..50], &[0, 0, 0, 0]);
+    assert!(data[50..].iter().all(|&b| b == 0));
+
+    let mut corrupt = data.clone();
+    corrupt[0] = 2;
+    assert!(jaguar::deserialize::<Mint>(&corrupt).is_err());
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+#[jaguar(reserve = 64)]
+struct Config {
+    admin: [u8; 32],
+    fee_bps: u16,
+}
+
+#[test]
+fn test_reserved_tail() {
+    let config = Config {
+        admin: [2u8; 32],
+        fee_bps: 30,
+    };
+
+    let (decoded, data) = roundtrip(&config);
+    assert_eq!(decoded, config);
+    assert_eq!(data.len(), 32 + 1 + 64);
+    assert!(data[33..].iter().all(|&b| b == 0));
+
+    let mut de = JaguarDeserializer::new(&data[..40]);
+    assert_eq!(Config::deserialize(&mut de), Err(jaguar::SerError::BufferTooSmall));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Listing {
+    seller: [u8; 32],
+    title: String,
+    #[jaguar(delta)]
+    slots: Vec<u64>,
+    bids: Vec<(u64, String)>,
+}
+
+#[test]
+fn test_validate_matches_deserialize() {
+    let listing = Listing {
+        seller: [5u8; 32],
+        title: "used jaguar".to_string(),
+        slots: vec![100, 150, 151],
+        bids: vec![(10, "a".to_string()), (12, "bb".to_string())],
+    };
+    let (_, data) = roundtrip(&listing);
+
+    for end in 0..=data.len() {
+        let input = &data[..end];
+        assert_eq!(
+            jaguar::validate::<Listing>(input),
+            jaguar::deserialize_owned::<Listing>(input).map(drop),
+            "prefix of {} bytes",
+            end
+        );
+    }
+
+    let mut corrupt = data.clone();
+    corrupt[33] = 0xFF;
+    assert_eq!(jaguar::validate::<Listing>(&corrupt), Err(jaguar::SerError::InvalidData));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Flags {
+    #[jaguar(bits = 3)]
+    kind: u8,
+    #[jaguar(bits = 1)]
+    frozen: bool,
+    #[jaguar(bits = 5)]
+    count: u8,
+    fee: u16,
+    #[jaguar(bits = 10)]
+    level: u8,
+}
+
+#[test]
+fn test_bit_packed_fields() {
+    let flags = Flags {
+        kind: 5,
+        frozen: true,
+        count: 17,
+        fee: 300,
+        level: 255,
+    };
+
+    let (decoded, data) = roundtrip(&flags);
+    assert_eq!(decoded, flags);
+    assert_eq!(data, [0b0001_1101, 0b0000_0001, 0xAC, 0x02, 0xFF, 0x00]);
+    assert!(jaguar::validate::<Flags>(&data).is_ok());
+
+    // 10 bits can hold more than a `u8`
+    let mut corrupt = data.clone();
+    corrupt[5] = 0x01;
+    assert_eq!(jaguar::deserialize_owned::<Flags>(&corrupt), Err(jaguar::SerError::InvalidData));
+    assert_eq!(jaguar::validate::<Flags>(&corrupt), Err(jaguar::SerError::InvalidData));
+
+    let mut ser = JaguarSerializer::new();
+    let too_wide = Flags { kind: 8, ..flags };
+    assert_eq!(too_wide.serialize(&mut ser), Err(jaguar::SerError::InvalidData));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct VoteHistory {
+    epoch: jaguar::clock::Epoch,
+    #[jaguar(with = "jaguar::clock::History")]
+    slots: Vec<jaguar::clock::Slot>,
+}
+
+#[test]
+fn test_slot_history() {
+    use jaguar::clock::{Epoch, Slot};
+
+    let history = VoteHistory {
+        epoch: Epoch(712),
+        slots: (307_000_000..307_000_064).map(Slot).collect(),
+    };
+
+    let (decoded, data) = roundtrip(&history);
+    assert_eq!(decoded, history);
+    // epoch (2) + length (1) + first slot (5) + one byte per delta
+    assert_eq!(data.len(), 2 + 1 + 5 + 63);
+}
+
+/// Stand-in for a third-party timestamp type without Jaguar impls.
+#[derive(Debug, Clone, PartialEq)]
+struct Timestamp {
+    secs: i64,
+    nanos: u32,
+}
+
+mod unix_millis {
+    use super::Timestamp;
+    use jaguar::{JaguarDeserializer, JaguarSerializer, SerError};
+
+    pub fn serialize(value: &Timestamp, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        ser.write_signed_varint(value.secs * 1000 + (value.nanos / 1_000_000) as i64)
+    }
+
+    pub fn deserialize(de: &mut JaguarDeserializer<'_>) -> Result<Timestamp, SerError> {
+        let millis = de.read_signed_varint()?;
+        Ok(Timestamp {
+            secs: millis.div_euclid(1000),
+            nanos: millis.rem_euclid(1000) as u32 * 1_000_000,
+        })
+    }
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Event {
+    #[jaguar(module = "unix_millis")]
+    at: Timestamp,
+    kind: u8,
+}
+
+#[test]
+fn test_module_functions() {
+    let event = Event {
+        at: Timestamp { secs: 1_700_000_000, nanos: 250_000_000 },
+        kind: 3,
+    };
+
+    let (decoded, data) = roundtrip(&event);
+    assert_eq!(decoded, event);
+    assert_eq!(jaguar::deserialize_owned::<i64>(&data).unwrap(), 1_700_000_000_250);
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, jaguar::JaguarConstSize, Debug, Clone, PartialEq)]
+#[jaguar(reserve = 4)]
+struct SwapEvent {
+    pool: [u8; 32],
+    authority: Authority,
+    a_to_b: bool,
+    bump: u8,
+}
+
+#[test]
+fn test_const_size_matches_serialize() {
+    use jaguar::JaguarConstSize;
+
+    let event = SwapEvent {
+        pool: [3u8; 32],
+        authority: Authority([9u8; 32]),
+        a_to_b: true,
+        bump: 251,
+    };
+
+    assert_eq!(SwapEvent::SIZE, 32 + 32 + 1 + 1 + 4);
+    let bytes: [u8; SwapEvent::SIZE] = jaguar::serialize_const(&event);
+    let (decoded, data) = roundtrip(&event);
+    assert_eq!(decoded, event);
+    assert_eq!(bytes.as_slice(), data.as_slice());
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+#[jaguar(convert)]
+struct Transfer<'a> {
+    to: [u8; 32],
+    memo: jaguar::StrRef<'a>,
+    lamports: u64,
+}
+
+#[test]
+fn test_convert_attribute() {
+    let transfer = Transfer {
+        to: [1u8; 32],
+        memo: jaguar::StrRef("rent"),
+        lamports: 5_000,
+    };
+
+    let bytes = transfer.to_bytes().unwrap();
+    assert_eq!(Transfer::try_from(bytes.as_slice()).unwrap(), transfer);
+    assert_eq!(Transfer::try_from(&bytes[..10]), Err(jaguar::SerError::BufferTooSmall));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, JaguarView, Debug, PartialEq)]
+struct Leg(u8, i64);
+
+#[derive(JaguarSerialize, JaguarDeserialize, JaguarView, Debug, PartialEq)]
+#[jaguar(reserve = 8)]
+struct Book {
+    #[jaguar(delta)]
+    slots: Vec<u64>,
+    #[jaguar(compat = "borsh")]
+    r#type: u32,
+    name: String,
+    legs: Vec<Leg>,
+    best: Leg,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, JaguarView, Debug, PartialEq)]
+struct Instruction {
+    get: u8,
+    data: Vec<u8>,
+}
+
+#[test]
+fn test_view_projection() {
+    let book = Book {
+        slots: vec![100, 101, 250],
+        r#type: 7,
+        name: "perp".into(),
+        legs: vec![Leg(1, -1)],
+        best: Leg(2, -300),
+    };
+    let data = jaguar::serialize(&book).unwrap();
+
+    let view = jaguar::view::<Book>(&data);
+    assert_eq!(view.name().unwrap().get().unwrap(), "perp");
+    assert_eq!(view.best().unwrap()._1().unwrap().get().unwrap(), -300);
+    assert_eq!(view.best().unwrap().get().unwrap(), Leg(2, -300));
+    assert_eq!(view.legs().unwrap().get().unwrap(), [Leg(1, -1)]);
+    assert_eq!(view.get().unwrap(), book);
+
+    // fields before the target must be intact, fields after it are never read
+    let cut = data.len() - 8 - 2;
+    assert_eq!(jaguar::view::<Book>(&data[..cut]).best().unwrap()._0().unwrap().get().unwrap(), 2);
+    assert!(jaguar::view::<Book>(&data[..4]).name().is_err());
+
+    // fields can take the names of the view's own methods
+    use jaguar::view::StructView;
+    let instruction = Instruction { get: 1, data: vec![2, 3] };
+    let data = jaguar::serialize(&instruction).unwrap();
+    let view = jaguar::view::<Instruction>(&data);
+    assert_eq!(view.get().unwrap().get().unwrap(), 1);
+    assert_eq!(view.data().unwrap().get().unwrap(), [2, 3]);
+    assert_eq!(StructView::get(&view).unwrap(), instruction);
+    assert_eq!(StructView::data(&view), data);
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Quote {
+    side: u8,
+    post_only: bool,
+    tick: i8,
+    lots: u16,
+    level: i16,
+    id: u32,
+    offset: i32,
+    price: u64,
+    pnl: i64,
+    size: f32,
+    fee: f64,
+    trader: [u8; 4],
+    venue: String,
+    seq: u64,
+    slot: u64,
+}
+
+#[test]
+fn test_coalesced_writes() {
+    let quote = Quote {
+        side: 1,
+        post_only: true,
+        tick: i8::MIN,
+        lots: u16::MAX,
+        level: -2,
+        id: 300,
+        offset: i32::MIN,
+        price: u64::MAX,
+        pnl: -1,
+        size: 1.0,
+        fee: 0.25,
+        trader: [7; 4],
+        venue: "dex".into(),
+        seq: 5,
+        slot: 6,
+    };
+    let (decoded, data) = roundtrip(&quote);
+    assert_eq!(decoded, quote);
+
+    let mut ser = JaguarSerializer::new();
+    ser.write_u8(1).unwrap();
+    ser.write_bool(true).unwrap();
+    ser.write_signed_varint(i8::MIN as i64).unwrap();
+    ser.write_varint(u16::MAX as u64).unwrap();
+    ser.write_signed_varint(-2).unwrap();
+    ser.write_varint(300).unwrap();
+    ser.write_signed_varint(i32::MIN as i64).unwrap();
+    ser.write_varint(u64::MAX).unwrap();
+    ser.write_signed_varint(-1).unwrap();
+    ser.write_f32(1.0).unwrap();
+    ser.write_f64(0.25).unwrap();
+    ser.write_raw(&[7; 4]).unwrap();
+    ser.write_str("dex").unwrap();
+    ser.write_varint(5).unwrap();
+    ser.write_varint(6).unwrap();
+    assert_eq!(ser.finish(), data);
+
+    // the worst case of the leading run is larger than the value, so a
+    // tight size cap must fall back to the checked writes
+    let mut ser = JaguarSerializer::new().with_max_size(data.len());
+    quote.serialize(&mut ser).unwrap();
+    assert_eq!(ser.finish(), data);
+    let mut ser = JaguarSerializer::new().with_max_size(data.len() - 1);
+    assert_eq!(quote.serialize(&mut ser), Err(jaguar::SerError::BufferTooSmall));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Cursor {
+    index: usize,
+    offset: isize,
+    page: u8,
+}
+
+#[test]
+fn test_usize_fields() {
+    let cursor = Cursor { index: usize::MAX, offset: isize::MIN, page: 3 };
+    let (decoded, data) = roundtrip(&cursor);
+    assert_eq!(decoded, cursor);
+
+    // written as 64-bit varints whatever the pointer width
+    let mut ser = JaguarSerializer::new();
+    ser.write_varint(usize::MAX as u64).unwrap();
+    ser.write_signed_varint(isize::MIN as i64).unwrap();
+    ser.write_u8(3).unwrap();
+    assert_eq!(ser.finish(), data);
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Frame {
+    #[jaguar(raw)]
+    samples: Vec<f32>,
+    #[jaguar(raw)]
+    weights: Vec<f64>,
+    gain: f32,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Ticks {
+    packed: Vec<u32>,
+    #[jaguar(raw)]
+    raw: Vec<u32>,
+    #[jaguar(raw)]
+    offsets: Vec<i16>,
+}
+
+#[test]
+fn test_raw_ints() {
+    let ticks = Ticks { packed: vec![1, 2], raw: vec![1, 2], offsets: vec![-1] };
+    let (decoded, data) = roundtrip(&ticks);
+    assert_eq!(decoded, ticks);
+    // varints by default, native-endian bytes with `raw`
+    assert_eq!(data[..3], [2, 1, 2]);
+    assert_eq!(data[4..8], 1u32.to_ne_bytes());
+    assert_eq!(data.len(), 3 + 9 + 3);
+}
+
+#[test]
+fn test_raw_floats() {
+    let frame = Frame { samples: vec![0.5, 1.0, -3.75], weights: vec![0.0, 2.5], gain: 1.0 };
+    let (decoded, data) = roundtrip(&frame);
+    assert_eq!(decoded, frame);
+    // no markers: the length, then 4 or 8 bytes per element
+    assert_eq!(data.len(), 1 + 12 + 1 + 16 + 1);
+    assert_eq!(data[1..5], 0.5f32.to_ne_bytes());
+}
+
+/// A length prefix that counts in pairs of elements.
+struct Pairs;
+
+impl jaguar::LenPrefix for Pairs {
+    fn write_len(ser: &mut JaguarSerializer, len: usize) -> Result<(), jaguar::SerError> {
+        if !len.is_multiple_of(2) {
+            return Err(jaguar::SerError::InvalidLength);
+        }
+        ser.write_u8((len / 2) as u8)
+    }
+
+    fn read_len(de: &mut JaguarDeserializer<'_>) -> Result<Option<usize>, jaguar::SerError> {
+        Ok(Some(de.read_u8()? as usize * 2))
+    }
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Message {
+    #[jaguar(len = "u16")]
+    topic: String,
+    #[jaguar(len = "varint")]
+    ids: Vec<u32>,
+    #[jaguar(len = "Pairs")]
+    points: Vec<u8>,
+    #[jaguar(len = "none")]
+    body: Vec<u8>,
+}
+
+#[test]
+fn test_len_attribute() {
+    let message = Message { topic: "px".into(), ids: vec![1, 300], points: vec![1, 2, 3, 4], body: vec![0xAA; 3] };
+    let (decoded, data) = roundtrip(&message);
+    assert_eq!(decoded, message);
+    assert_eq!(data, [2, 0, b'p', b'x', 2, 1, 0xAC, 0x02, 2, 1, 2, 3, 4, 0xAA, 0xAA, 0xAA]);
+
+    let odd = Message { points: vec![1], ..message };
+    assert_eq!(jaguar::serialize(&odd), Err(jaguar::SerError::InvalidLength));
+}
+
+#[derive(jaguar::JaguarBitfield, Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Bid,
+    Ask,
+}
+
+#[derive(jaguar::JaguarBitfield, Debug, Clone, Copy, PartialEq)]
+enum Tier {
+    Retail = 1,
+    Maker = 4,
+}
+
+#[derive(jaguar::JaguarBitfield, Debug, Clone, Copy, PartialEq)]
+struct Permissions {
+    #[jaguar(bits = 1)]
+    trade: bool,
+    #[jaguar(bits = 1)]
+    withdraw: bool,
+    #[jaguar(bits = 2)]
+    level: u8,
+}
+
+#[derive(jaguar::JaguarBitfield, Debug, Clone, Copy, PartialEq)]
+struct Sequence {
+    #[jaguar(bits = 62)]
+    number: u64,
+    #[jaguar(bits = 1)]
+    sealed: bool,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Ticket {
+    #[jaguar(bits = 63)]
+    sequence: Sequence,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct Trader {
+    #[jaguar(bits = 1)]
+    side: Side,
+    #[jaguar(bits = 3)]
+    tier: Tier,
+    #[jaguar(bits = 4)]
+    permissions: Permissions,
+    #[jaguar(bits = 1)]
+    frozen: bool,
+    #[jaguar(bits = 1)]
+    delegated: bool,
+    balance: u64,
+}
+
+#[test]
+fn test_bitfield_derive() {
+    let trader = Trader {
+        side: Side::Ask,
+        tier: Tier::Maker,
+        permissions: Permissions { trade: true, withdraw: false, level: 3 },
+        frozen: false,
+        delegated: true,
+        balance: 5,
+    };
+    let (decoded, data) = roundtrip(&trader);
+    assert_eq!(decoded, trader);
+    // ask = 1, maker = 4 << 1, trade = 1 << 4, level = 3 << 6, delegated = 1 << 9
+    assert_eq!(data, [0b1101_1001, 0b10, 5]);
+
+    // 3 is not a `Tier`
+    assert!(jaguar::deserialize::<Trader>(&[0b0000_0110, 0, 0]).is_err());
+
+    let invalid = Trader { permissions: Permissions { level: 4, ..trader.permissions }, ..trader };
+    assert_eq!(jaguar::serialize(&invalid), Err(jaguar::SerError::InvalidData));
+
+    // the widest struct still rejects out-of-range fields
+    let ticket = Ticket { sequence: Sequence { number: (1 << 62) - 1, sealed: true } };
+    assert_eq!(roundtrip(&ticket).0, ticket);
+    let invalid = Ticket { sequence: Sequence { number: 1 << 62, sealed: false } };
+    assert_eq!(jaguar::serialize(&invalid), Err(jaguar::SerError::InvalidData));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+struct Inner {
+    values: Vec<u8>,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+struct Outer {
+    inner: Inner,
+}
+
+#[test]
+fn test_depth_limit() {
+    let outer = Outer { inner: Inner { values: vec![1, 2] } };
+    let (decoded, data) = roundtrip(&outer);
+    assert_eq!(decoded, outer);
+
+    // `Outer`, `Inner` and the vector each take a level
+    let mut de = JaguarDeserializer::new(&data);
+    de.set_max_depth(2);
+    assert_eq!(Outer::deserialize(&mut de), Err(jaguar::SerError::DepthLimitExceeded));
+    let mut de = JaguarDeserializer::new(&data);
+    de.set_max_depth(2);
+    assert_eq!(Outer::validate(&mut de), Err(jaguar::SerError::DepthLimitExceeded));
+    let mut de = JaguarDeserializer::new(&data);
+    de.set_max_depth(3);
+    assert_eq!(Outer::deserialize(&mut de).unwrap(), outer);
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+struct Expr {
+    op: u8,
+    children: Vec<Expr>,
+    shared: Arc<[u8; 4]>,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+struct Label<'a> {
+    name: Cow<'a, str>,
+    root: Box<Expr>,
+}
+
+#[test]
+fn test_smart_pointers() {
+    let shared = Arc::new([9; 4]);
+    let leaf = |op| Expr { op, children: Vec::new(), shared: shared.clone() };
+    let root = Expr { op: 1, children: vec![leaf(2), leaf(3)], shared: shared.clone() };
+    let label = Label { name: Cow::Borrowed("sum"), root: Box::new(root) };
+
+    let data = jaguar::serialize(&label).unwrap();
+    let decoded = jaguar::deserialize::<Label>(&data).unwrap();
+    assert_eq!(decoded, label);
+    assert!(matches!(decoded.name, Cow::Borrowed("sum")));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+struct VaultV1 {
+    owner: [u8; 4],
+    balance: u64,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+struct VaultV2 {
+    owner: [u8; 4],
+    balance: u64,
+    #[jaguar(default_on_eof)]
+    fee_bps: u16,
+    #[jaguar(default_on_eof)]
+    delegates: Vec<[u8; 4]>,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Default, PartialEq)]
+#[jaguar(default_on_eof)]
+struct Settings {
+    limit: u32,
+    label: String,
+}
+
+#[test]
+fn test_default_on_eof() {
+    let v1 = jaguar::serialize(&VaultV1 { owner: [1; 4], balance: 50 }).unwrap();
+    let upgraded: VaultV2 = jaguar::deserialize(&v1).unwrap();
+    assert_eq!(upgraded, VaultV2 { owner: [1; 4], balance: 50, fee_bps: 0, delegates: Vec::new() });
+    assert!(jaguar::validate::<VaultV2>(&v1).is_ok());
+
+    let v2 = VaultV2 { fee_bps: 30, delegates: vec![[2; 4]], ..upgraded };
+    let (decoded, data) = roundtrip(&v2);
+    assert_eq!(decoded, v2);
+
+    // only a clean cut between fields is tolerated
+    assert_eq!(jaguar::deserialize::<VaultV2>(&data[..v1.len() + 1]), Ok(VaultV2 { delegates: Vec::new(), ..v2 }));
+    assert!(jaguar::deserialize::<VaultV2>(&data[..data.len() - 1]).is_err());
+    assert!(jaguar::deserialize::<VaultV2>(&v1[..3]).is_err());
+
+    assert_eq!(jaguar::deserialize::<Settings>(&[]).unwrap(), Settings::default());
+    assert_eq!(jaguar::deserialize::<Settings>(&[9]).unwrap(), Settings { limit: 9, label: String::new() });
+}
+
+#[cfg(feature = "bumpalo")]
+#[derive(jaguar::JaguarDeserializeIn)]
+struct Batch<'bump, T> {
+    id: u32,
+    names: bumpalo::collections::Vec<'bump, bumpalo::collections::String<'bump>>,
+    items: bumpalo::collections::Vec<'bump, T>,
+    #[jaguar(delta)]
+    slots: Vec<u64>,
+    #[jaguar(default_on_eof)]
+    fee_bps: u16,
+}
+
+#[cfg(feature = "bumpalo")]
+#[test]
+fn test_deserialize_in_derive() {
+    let mut ser = JaguarSerializer::new();
+    7u32.serialize(&mut ser).unwrap();
+    vec![String::from("a"), String::from("bc")].serialize(&mut ser).unwrap();
+    vec![(1u8, -1i64)].serialize(&mut ser).unwrap();
+    ser.write_u64_slice_delta(&[100, 101]).unwrap();
+    let data = ser.finish();
+
+    let bump = bumpalo::Bump::new();
+    let batch: Batch<(u8, i64)> = jaguar::deserialize_in(&data, &bump).unwrap();
+    assert_eq!(batch.id, 7);
+    assert_eq!(batch.names, ["a", "bc"]);
+    assert_eq!(batch.items, [(1, -1)]);
+    assert_eq!(batch.slots, [100, 101]);
+    assert_eq!(batch.fee_bps, 0);
+
+    assert!(jaguar::deserialize_in::<Batch<(u8, i64)>>(&data[..data.len() - 1], &bump).is_err());
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+enum LedgerEvent {
+    #[jaguar(tag = 3)]
+    Deposit { amount: u64 },
+    Withdraw(u64, #[jaguar(delta)] Vec<u64>),
+    #[jaguar(tag = 200)]
+    Pause,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+#[jaguar(tag_width = "u16", convert)]
+enum Command<'a> {
+    #[jaguar(tag = 0x0102)]
+    Say(Cow<'a, str>),
+    Stop,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+#[jaguar(tag_width = "u8")]
+enum Flag {
+    Off,
+    On,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, Copy, PartialEq)]
+enum OrderSide {
+    Bid = 1,
+    #[jaguar(tag = 2)]
+    Ask = 2,
+    Cross,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize)]
+enum Never {}
+
+#[test]
+fn test_enum_tags() {
+    let (decoded, data) = roundtrip(&LedgerEvent::Deposit { amount: 300 });
+    assert_eq!(decoded, LedgerEvent::Deposit { amount: 300 });
+    assert_eq!(data, [3, 0xAC, 0x02]);
+
+    // untagged variants follow the previous tag
+    let withdraw = LedgerEvent::Withdraw(5, vec![10, 11]);
+    let (decoded, data) = roundtrip(&withdraw);
+    assert_eq!(decoded, withdraw);
+    assert_eq!(data, [4, 5, 2, 10, 1]);
+
+    // tags past 127 take a second varint byte
+    assert_eq!(roundtrip(&LedgerEvent::Pause).1, [0xC8, 0x01]);
+    assert_eq!(jaguar::deserialize::<LedgerEvent>(&[5]), Err(jaguar::SerError::InvalidData));
+    assert!(jaguar::validate::<LedgerEvent>(&[4, 5, 0]).is_ok());
+    assert!(jaguar::validate::<LedgerEvent>(&[0]).is_err());
+
+    let say = Command::Say(Cow::Borrowed("hi"));
+    let data = say.to_bytes().unwrap();
+    assert_eq!(data, [0x02, 0x01, 2, b'h', b'i']);
+    assert_eq!(Command::try_from(&data[..]), Ok(say));
+    assert_eq!(Command::Stop.to_bytes().unwrap(), [0x03, 0x01]);
+
+    assert_eq!(roundtrip(&Flag::On), (Flag::On, vec![1]));
+    assert_eq!(jaguar::deserialize::<Flag>(&[2]), Err(jaguar::SerError::InvalidData));
+
+    // explicit discriminants are the tags
+    for side in [OrderSide::Bid, OrderSide::Ask, OrderSide::Cross] {
+        assert_eq!(roundtrip(&side), (side, vec![side as u8]));
+    }
+    assert_eq!(jaguar::deserialize::<OrderSide>(&[0]), Err(jaguar::SerError::InvalidData));
+    assert!(jaguar::deserialize::<Never>(&[0]).is_err());
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, jaguar::JaguarSchema, Debug, PartialEq)]
+struct Memo<'a> {
+    #[jaguar(borrow)]
+    text: &'a str,
+    amount: u64,
+    #[jaguar(borrow)]
+    payload: &'a [u8],
+}
+
+#[test]
+fn test_borrow_attribute() {
+    let memo = Memo { text: "gm", amount: 300, payload: &[7, 8] };
+    let data = jaguar::serialize(&memo).unwrap();
+    assert_eq!(data, [2, b'g', b'm', 0xAC, 0x02, 2, 7, 8]);
+
+    let decoded: Memo = jaguar::deserialize(&data).unwrap();
+    assert_eq!(decoded, memo);
+    assert!(std::ptr::eq(decoded.text.as_ptr(), data[1..].as_ptr()), "text should borrow from the input");
+    assert!(std::ptr::eq(decoded.payload.as_ptr(), data[6..].as_ptr()), "payload should borrow from the input");
+    assert!(jaguar::validate::<Memo>(&data).is_ok());
+
+    let mut invalid = data.clone();
+    invalid[1] = 0xFF;
+    assert!(jaguar::deserialize::<Memo>(&invalid).is_err());
+    assert!(jaguar::validate::<Memo>(&invalid).is_err());
+
+    use jaguar::schema::Schema;
+    let schema = match <Memo as jaguar::JaguarSchema>::schema() {
+        Schema::Struct(schema) => schema,
+        other => panic!("unexpected schema {:?}", other),
+    };
+    let fields: Vec<_> = schema.fields.into_iter().map(|field| field.schema).collect();
+    assert_eq!(fields, [Schema::String, Schema::Varint { bits: 64 }, Schema::Vec(Box::new(Schema::U8))]);
+}
+
+/// A marker type implementing none of the Jaguar traits.
+#[derive(Debug, PartialEq)]
+struct Mainnet;
+
+#[derive(JaguarSerialize, JaguarDeserialize, jaguar::JaguarConstSize, jaguar::JaguarSchema, Debug, PartialEq)]
+struct NetworkAmount<N, T> {
+    amount: [u8; 2],
+    value: T,
+    unit: (),
+    _network: std::marker::PhantomData<fn() -> N>,
+}
+
+#[test]
+fn test_zero_sized_fields() {
+    let tagged = NetworkAmount::<Mainnet, u8> { amount: [1, 2], value: 7, unit: (), _network: std::marker::PhantomData };
+    let (decoded, data) = roundtrip(&tagged);
+    assert_eq!(decoded, tagged);
+    assert_eq!(data, [1, 2, 7]);
+    assert_eq!(jaguar::serialize(&((), std::marker::PhantomPinned)).unwrap(), []);
+
+    use jaguar::JaguarConstSize;
+    assert_eq!(<NetworkAmount<Mainnet, [u8; 2]> as JaguarConstSize>::SIZE, 4);
+
+    use jaguar::schema::Schema;
+    match <NetworkAmount<Mainnet, u8> as jaguar::JaguarSchema>::schema() {
+        Schema::Struct(schema) => assert_eq!(schema.fields[3].schema, Schema::Tuple(Vec::new())),
+        other => panic!("unexpected schema {:?}", other),
+    }
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+enum Update<T> {
+    Set(T),
+    Clear,
+}
+
+/// A program whose instruction arguments are an associated type.
+trait Program {
+    type Args;
+}
+
+impl Program for Mainnet {
+    type Args = Update<String>;
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+enum Invoke<P: Program> {
+    Call { args: P::Args, batch: Vec<P::Args> },
+    #[jaguar(tag = 9)]
+    Raw(#[jaguar(len = "u16")] Vec<u8>, std::marker::PhantomData<P>),
+}
+
+#[test]
+fn test_generic_enum() {
+    let (decoded, data) = roundtrip(&Update::Set(vec![1u32, 300]));
+    assert_eq!(decoded, Update::Set(vec![1, 300]));
+    assert_eq!(data, [0, 2, 1, 0xAC, 0x02]);
+    assert_eq!(roundtrip(&Update::<String>::Clear).1, [1]);
+
+    // `Mainnet` itself isn't encodable, only its `Args`
+    let invoke = Invoke::<Mainnet>::Call { args: Update::Set("mint".into()), batch: vec![Update::Clear] };
+    let (decoded, _) = roundtrip(&invoke);
+    assert_eq!(decoded, invoke);
+    let (decoded, data) = roundtrip(&Invoke::<Mainnet>::Raw(vec![7], std::marker::PhantomData));
+    assert_eq!(data, [9, 1, 0, 7]);
+    assert_eq!(decoded, Invoke::Raw(vec![7], std::marker::PhantomData));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+#[jaguar(optimize_layout)]
+struct Treasury {
+    memo: String,
+    balance: u64,
+    #[jaguar(bits = 3)]
+    kind: u8,
+    owner: [u8; 4],
+    #[jaguar(bits = 1)]
+    frozen: bool,
+    bump: u8,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, JaguarView, jaguar::JaguarSchema, Debug, PartialEq)]
+#[jaguar(optimize_layout)]
+struct Reserve {
+    name: String,
+    amount: u64,
+    bump: u8,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+#[jaguar(optimize_layout)]
+struct Labeled(String, u8);
+
+#[test]
+fn test_optimize_layout() {
+    use jaguar::layout::{manifest, FieldClass, JaguarLayout};
+    use jaguar::schema::Schema;
+    use jaguar::JaguarSchema;
+
+    let treasury = Treasury { memo: "rent".into(), balance: 300, kind: 5, owner: [1, 2, 3, 4], frozen: true, bump: 254 };
+    let (decoded, data) = roundtrip(&treasury);
+    assert_eq!(decoded, treasury);
+    // the two bit fields share a byte, then the fixed fields sit at constant offsets
+    assert_eq!(data[1..6], [1, 2, 3, 4, 254]);
+    assert_eq!(data[6..8], [0xAC, 0x02]);
+    assert_eq!(data.len(), 13);
+    assert_eq!(
+        manifest::<Treasury>(),
+        "bits kind\nbits frozen\nfixed owner\nfixed bump\nscalar balance\nvariable memo\n"
+    );
+    assert_eq!(Treasury::LAYOUT[4].class, FieldClass::Scalar);
+
+    let (_, data) = roundtrip(&Reserve { name: "main".into(), amount: 300, bump: 254 });
+    assert_eq!(data[..3], [254, 0xAC, 0x02]);
+    let view = jaguar::view::<Reserve>(&data);
+    assert_eq!(view.amount().unwrap().get().unwrap(), 300);
+    assert_eq!(view.bump().unwrap().get().unwrap(), 254);
+    let names: Vec<String> = match Reserve::schema() {
+        Schema::Struct(schema) => schema.fields.into_iter().map(|field| field.name).collect(),
+        schema => panic!("unexpected schema {:?}", schema),
+    };
+    assert_eq!(names, ["bump", "amount", "name"]);
+
+    let (decoded, data) = roundtrip(&Labeled("hi".into(), 7));
+    assert_eq!(data, [7, 2, b'h', b'i']);
+    assert_eq!(decoded, Labeled("hi".into(), 7));
+    assert_eq!(manifest::<Labeled>(), "fixed _1\nvariable _0\n");
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+struct TokenAccount<'a> {
+    mint: jaguar::ArrayRef<'a, 32>,
+    owner: jaguar::ArrayRef<'a, 32>,
+    amount: u64,
+}
+
+#[test]
+fn test_array_ref() {
+    use jaguar::ArrayRef;
+
+    let account = TokenAccount { mint: ArrayRef(&[1; 32]), owner: ArrayRef(&[2; 32]), amount: 300 };
+    let data = jaguar::serialize(&account).unwrap();
+    assert_eq!(data, [&[1u8; 32][..], &[2; 32], &[0xAC, 0x02]].concat());
+
+    // the keys point into `data` rather than being copied out of it
+    let decoded: TokenAccount = jaguar::deserialize(&data).unwrap();
+    assert_eq!(decoded, account);
+    assert!(std::ptr::eq(decoded.owner.0.as_ptr(), data[32..].as_ptr()));
+    assert_eq!(jaguar::view::<ArrayRef<32>>(&data).get().unwrap(), ArrayRef(&[1; 32]));
+    assert_eq!(jaguar::deserialize::<ArrayRef<64>>(&data[..40]), Err(jaguar::SerError::BufferTooSmall));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, jaguar::JaguarConstSize, Debug, PartialEq)]
+#[jaguar(fixed)]
+struct Ticker {
+    price: f64,
+    size: u32,
+    delta: i16,
+    side: u8,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, JaguarView, Debug, PartialEq)]
+#[jaguar(optimize_layout)]
+struct Fill {
+    note: String,
+    #[jaguar(fixed)]
+    slot: u64,
+    qty: u32,
+}
+
+#[test]
+fn test_fixed() {
+    use jaguar::layout::manifest;
+    use jaguar::{serialize_const, JaguarConstSize};
+
+    let quote = Ticker { price: 1.5, size: 300, delta: -2, side: 1 };
+    let (decoded, data) = roundtrip(&quote);
+    assert_eq!(decoded, quote);
+    assert_eq!(data, [&1.5f64.to_le_bytes()[..], &[0x2C, 0x01, 0, 0], &[0xFE, 0xFF], &[1]].concat());
+    assert_eq!(Ticker::SIZE, 15);
+    let bytes: [u8; Ticker::SIZE] = serialize_const(&quote);
+    assert_eq!(bytes[..], data[..]);
+
+    let fill = Fill { note: "x".into(), slot: 300, qty: 300 };
+    let (decoded, data) = roundtrip(&fill);
+    assert_eq!(decoded, fill);
+    assert_eq!(data, [0x2C, 0x01, 0, 0, 0, 0, 0, 0, 0xAC, 0x02, 1, b'x']);
+    assert_eq!(manifest::<Fill>(), "fixed slot\nscalar qty\nvariable note\n");
+    assert_eq!(jaguar::view::<Fill>(&data).qty().unwrap().get().unwrap(), 300);
+    assert_eq!(jaguar::deserialize::<Fill>(&data[..7]), Err(jaguar::SerError::BufferTooSmall));
+}
+
+mod framework {
+    pub use jaguar as codec;
+}
+
+mod renamed {
+    use crate::framework::codec::{JaguarDeserialize, JaguarSerialize, JaguarView};
+
+    // shadows the `jaguar` crate, so only the `crate` path can resolve
+    #[allow(dead_code)]
+    mod jaguar {}
+
+    #[derive(JaguarSerialize, JaguarDeserialize, JaguarView, Debug, PartialEq)]
+    #[jaguar(crate = "crate::framework::codec", convert)]
+    pub struct Relayed {
+        pub seq: u32,
+        #[jaguar(fixed)]
+        pub stamp: u64,
+    }
+
+    #[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+    #[jaguar(crate = "crate::framework::codec")]
+    pub enum Envelope {
+        Empty,
+        Relayed(Relayed),
+    }
+}
+
+#[test]
+fn test_crate_path() {
+    use renamed::{Envelope, Relayed};
+
+    let relayed = Relayed { seq: 300, stamp: 7 };
+    let data = relayed.to_bytes().unwrap();
+    assert_eq!(data, [0xAC, 0x02, 7, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(Relayed::try_from(&data[..]).unwrap(), relayed);
+    assert_eq!(jaguar::view::<Relayed>(&data).seq().unwrap().get().unwrap(), 300);
+
+    let (decoded, _) = roundtrip(&Envelope::Relayed(relayed));
+    assert_eq!(decoded, Envelope::Relayed(Relayed { seq: 300, stamp: 7 }));
+}
+
+/// Legacy state from a borsh-based program, embedded as-is.
+#[cfg(feature = "borsh")]
+#[derive(borsh::BorshSerialize, borsh::BorshDeserialize, Debug, Clone, PartialEq)]
+struct LegacyStake {
+    staker: [u8; 32],
+    lockup: Option<i64>,
+    delegations: Vec<(u8, u64)>,
+}
+
+#[cfg(feature = "borsh")]
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+struct HybridStake {
+    version: u32,
+    #[jaguar(repr = "borsh")]
+    legacy: LegacyStake,
+    rewards: u64,
+}
+
+#[cfg(feature = "borsh")]
+#[test]
+fn test_borsh_repr() {
+    let legacy = LegacyStake { staker: [3; 32], lockup: Some(-5), delegations: vec![(1, 300)] };
+    let value = HybridStake { version: 2, legacy: legacy.clone(), rewards: 300 };
+    let (decoded, data) = roundtrip(&value);
+    assert_eq!(decoded, value);
+
+    let blob = borsh::to_vec(&legacy).unwrap();
+    assert_eq!(data, [&[2][..], &blob, &[0xAC, 0x02]].concat());
+
+    assert_eq!(jaguar::deserialize::<HybridStake>(&data[..20]), Err(jaguar::SerError::InvalidData));
+    let mut ser = JaguarSerializer::builder().max_size(10).build();
+    assert_eq!(value.serialize(&mut ser), Err(jaguar::SerError::BufferTooSmall));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, jaguar::JaguarSchema, Debug, PartialEq)]
+struct Blob {
+    #[jaguar(bytes)]
+    payload: Vec<u8>,
+    crc: u32,
+}
+
+#[derive(JaguarSerialize)]
+struct PlainBlob {
+    payload: Vec<u8>,
+    crc: u32,
+}
+
+#[test]
+fn test_bytes_field() {
+    use jaguar::JaguarSchema;
+
+    let blob = Blob { payload: (0..=255).collect(), crc: 300 };
+    let (decoded, data) = roundtrip(&blob);
+    assert_eq!(decoded, blob);
+    assert_eq!(data, jaguar::serialize(&PlainBlob { payload: blob.payload.clone(), crc: 300 }).unwrap());
+
+    let jaguar::schema::Schema::Struct(schema) = Blob::schema() else { panic!("expected a struct schema") };
+    assert_eq!(schema.fields[0].schema, <Vec<u8> as JaguarSchema>::schema());
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+#[jaguar(validate = "Pda::check")]
+struct Pda {
+    seed: u64,
+    bump: u16,
+}
+
+impl Pda {
+    fn check(&self) -> bool {
+        self.bump < 256
+    }
+}
+
+fn in_range(range: &Range) -> bool {
+    match range {
+        Range::Empty => true,
+        Range::Span { start, end } => start <= end,
+    }
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+#[jaguar(validate = "in_range")]
+enum Range {
+    Empty,
+    Span { start: u32, end: u32 },
+}
+
+#[test]
+fn test_validate_hook() {
+    let pda = Pda { seed: 7, bump: 255 };
+    assert_eq!(roundtrip(&pda).0, pda);
+
+    let data = jaguar::serialize(&Pda { seed: 7, bump: 256 }).unwrap();
+    assert_eq!(jaguar::deserialize::<Pda>(&data), Err(jaguar::SerError::InvalidData));
+    assert_eq!(jaguar::validate::<Pda>(&data), Err(jaguar::SerError::InvalidData));
+    assert_eq!(roundtrip(&vec![pda]).0.len(), 1);
+
+    assert_eq!(roundtrip(&Range::Span { start: 1, end: 2 }).0, Range::Span { start: 1, end: 2 });
+    assert_eq!(roundtrip(&Range::Empty).0, Range::Empty);
+    let data = jaguar::serialize(&Range::Span { start: 2, end: 1 }).unwrap();
+    assert_eq!(jaguar::deserialize::<Range>(&data), Err(jaguar::SerError::InvalidData));
+    assert_eq!(jaguar::validate::<Range>(&data), Err(jaguar::SerError::InvalidData));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, jaguar::JaguarMessage, jaguar::JaguarAccount, Debug, PartialEq)]
+struct Deposit {
+    amount: u64,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, jaguar::JaguarMessage, Debug, PartialEq)]
+#[jaguar(name = "Deposit")]
+struct DepositV2 {
+    amount: u64,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+struct Queued<T> {
+    items: Vec<T>,
+}
+
+impl jaguar::JaguarMessage for Queued<u8> {
+    const TYPE_TAG: [u8; 8] = jaguar::message_tag_of("Queued<u8>");
+}
+
+impl jaguar::JaguarMessage for Queued<u64> {
+    const TYPE_TAG: [u8; 8] = jaguar::message_tag_of("Queued<u64>");
+}
+
+#[test]
+fn test_message_tags() {
+    use jaguar::{message_tag_of, Envelope, JaguarMessage};
+
+    assert_eq!(Deposit::TYPE_TAG, message_tag_of("Deposit"));
+    assert_eq!(DepositV2::TYPE_TAG, Deposit::TYPE_TAG);
+
+    let data = Envelope::wrap(&Queued { items: vec![1u8, 2] }).unwrap();
+    let envelope: Envelope = jaguar::deserialize(&data).unwrap();
+    assert!(envelope.is::<Queued<u8>>());
+    assert_eq!(envelope.unwrap_checked::<Queued<u8>>().unwrap().items, [1, 2]);
+    assert_eq!(envelope.unwrap_checked::<Queued<u64>>().err(), Some(jaguar::SerError::DiscriminatorMismatch));
+    assert_eq!(envelope.unwrap_checked::<Deposit>().err(), Some(jaguar::SerError::DiscriminatorMismatch));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, jaguar::JaguarAccount, Debug, PartialEq)]
+struct StakePool {
+    staked: u64,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, jaguar::JaguarAccount, Debug, PartialEq)]
+#[jaguar(name = "StakePool")]
+struct StakePoolV2 {
+    staked: u64,
+}
+
+#[test]
+fn test_account_discriminators() {
+    use jaguar::{deserialize_checked, discriminator_of, serialize_with_discriminator, JaguarAccount, SerError};
+
+    assert_eq!(StakePool::DISCRIMINATOR, discriminator_of("StakePool"));
+    assert_eq!(StakePoolV2::DISCRIMINATOR, StakePool::DISCRIMINATOR);
+
+    let data = serialize_with_discriminator(&StakePool { staked: 5 }).unwrap();
+    assert_eq!(deserialize_checked::<StakePoolV2>(&data).unwrap(), StakePoolV2 { staked: 5 });
+    assert_eq!(deserialize_checked::<Deposit>(&data).err(), Some(SerError::DiscriminatorMismatch));
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, jaguar::JaguarEvent, Debug, PartialEq)]
+#[jaguar(name = "Deposit")]
+struct Deposited {
+    amount: u64,
+}
+
+#[test]
+fn test_event_discriminators() {
+    use jaguar::{deserialize_event, event_discriminator_of, serialize_event, JaguarEvent, SerError};
+
+    assert_eq!(Deposited::DISCRIMINATOR, event_discriminator_of("Deposit"));
+    let data = serialize_event(&Deposited { amount: 5 }).unwrap();
+    assert_eq!(data[..8], Deposited::DISCRIMINATOR);
+    assert_eq!(deserialize_event::<Deposited>(&data).unwrap(), Deposited { amount: 5 });
+    // account and event discriminators of the same name differ
+    let data = jaguar::serialize_with_discriminator(&Deposit { amount: 5 }).unwrap();
+    assert_eq!(deserialize_event::<Deposited>(&data).err(), Some(SerError::DiscriminatorMismatch));
+}
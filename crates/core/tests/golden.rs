@@ -0,0 +1,126 @@
+//! Checks every encoding against the fixtures in `tests/golden`, so the wire
+//! format can't change without a `FORMAT_VERSION` bump.
+//!
+//! A case without a fixture fails. Run with `JAGUAR_BLESS=1` to record the
+//! missing ones, and commit them. Existing fixtures are never overwritten.
+
+use jaguar::clock::{Slot, UnixTimestamp};
+use jaguar::golden::{encode_golden, verify_golden};
+use jaguar::{JaguarDeserialize, JaguarDeserializeOwned, JaguarSerialize};
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::{env, fs};
+
+fn check<T: JaguarSerialize + JaguarDeserializeOwned + PartialEq>(name: &str, value: T) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(format!("{}.golden", name));
+    match fs::read_to_string(&path) {
+        Ok(fixture) => {
+            if let Err(err) = verify_golden(&fixture, &value) {
+                panic!("{}: {}", path.display(), err);
+            }
+        }
+        Err(_) if env::var_os("JAGUAR_BLESS").is_some() => {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, encode_golden(&value).unwrap()).unwrap();
+        }
+        Err(err) => panic!("{}: {} (run with JAGUAR_BLESS=1 to record it)", path.display(), err),
+    }
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+struct Account {
+    owner: [u8; 32],
+    #[jaguar(compat = "borsh")]
+    legacy_amount: u64,
+    #[jaguar(delta)]
+    slots: Vec<u64>,
+    #[jaguar(bits = 3)]
+    kind: u8,
+    #[jaguar(bits = 1)]
+    frozen: bool,
+    #[jaguar(raw)]
+    weights: Vec<f32>,
+    #[jaguar(len = "u16")]
+    memo: String,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+enum Instruction {
+    #[jaguar(tag = 3)]
+    Deposit { amount: u64 },
+    Withdraw(u64),
+    #[jaguar(tag = 200)]
+    Pause,
+}
+
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, PartialEq)]
+#[jaguar(tag_width = "u16")]
+enum Wide {
+    #[jaguar(tag = 0x0102)]
+    Say(String),
+}
+
+#[test]
+fn test_integers() {
+    check("u8", 255u8);
+    check("u16", 65_535u16);
+    check("u32", 300u32);
+    check("u64", u64::MAX);
+    check("u128", u128::MAX - 1);
+    check("i8", -128i8);
+    check("i16", -300i16);
+    check("i32", i32::MIN);
+    check("i64", -1_000_000i64);
+    check("i128", i128::MIN);
+    check("usize", 70_000usize);
+    check("nonzero", NonZeroU32::new(42).unwrap());
+}
+
+#[test]
+fn test_scalars() {
+    check("bool", (true, false));
+    check("float_markers", ((0.0f32, 1.0f32), (-1.0f64, 0.0f64)));
+    check("float_full", (3.5f32, -0.1f64));
+}
+
+#[test]
+fn test_collections() {
+    check("string", "ñandú 🐆".to_string());
+    check("bytes", [1u8, 2, 3, 4]);
+    check("vec_u32", vec![0u32, 127, 128, 16_384, u32::MAX]);
+    check("vec_i64", vec![-1i64, 1, i64::MIN]);
+    check("vec_bool", vec![true, false, true, true, false, false, true, false, true]);
+    check("vec_string", vec!["a".to_string(), String::new()]);
+    check("array_u16", [1u16, 300, u16::MAX]);
+    check("map", BTreeMap::from([(1u8, "one".to_string()), (2, "two".to_string())]));
+    check("boxed", Box::new(7u64));
+}
+
+#[test]
+fn test_time() {
+    check("duration", Duration::new(5, 250));
+    check("unix_timestamp", UnixTimestamp(-5));
+    check("slot", Slot(310_000_000));
+}
+
+#[test]
+fn test_derived() {
+    check(
+        "account",
+        Account {
+            owner: [9; 32],
+            legacy_amount: 1_000,
+            slots: vec![100, 101, 105],
+            kind: 5,
+            frozen: true,
+            weights: vec![0.5, -2.0],
+            memo: "hi".to_string(),
+        },
+    );
+    check("enum_deposit", Instruction::Deposit { amount: 10 });
+    check("enum_withdraw", Instruction::Withdraw(20));
+    check("enum_pause", Instruction::Pause);
+    check("enum_u16_tag", Wide::Say("go".to_string()));
+}
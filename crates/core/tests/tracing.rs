@@ -0,0 +1,71 @@
+#![cfg(feature = "tracing")]
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use jaguar::SerError;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Records the fields of every event as `name=value` strings.
+#[derive(Clone, Default)]
+struct Recorder {
+    events: Arc<Mutex<Vec<Vec<String>>>>,
+}
+
+struct Fields<'a>(&'a mut Vec<String>);
+
+impl Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push(format!("{}={:?}", field.name(), value));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Vec::new();
+        event.record(&mut Fields(&mut fields));
+        self.events.lock().unwrap().push(fields);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn test_decode_failure_event() {
+    let recorder = Recorder::default();
+    let data = jaguar::serialize(&(7u32, String::from("truncated"))).unwrap();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        assert_eq!(jaguar::deserialize::<(u32, String)>(&data).unwrap().0, 7);
+        assert_eq!(jaguar::deserialize::<(u32, String)>(&data[..5]), Err(SerError::BufferTooSmall));
+    });
+
+    let events = recorder.events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0],
+        [
+            "message=jaguar: failed to decode",
+            "type_name=\"(u32, alloc::string::String)\"",
+            "offset=2",
+            "len=5",
+            "error=BufferTooSmall",
+        ]
+    );
+}
@@ -1,78 +1,1760 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Fields};
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DataEnum, DeriveInput, Expr, ExprLit, Field, Fields,
+    GenericParam, Generics, Ident, Index, Lifetime, LifetimeDef, Lit, Member, Meta, NestedMeta, Path, Type, Variant,
+};
 
-#[proc_macro_derive(JaguarSerialize)]
-pub fn derive_serialize(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+/// Container-level options parsed from `#[jaguar(...)]` attributes.
+#[derive(Default)]
+struct ContainerAttrs {
+    /// Encode a single-field struct exactly as its inner value.
+    transparent: bool,
+    /// Zero bytes appended after the last field, reserved for future fields.
+    reserve: Option<usize>,
+    /// Also emit `TryFrom<&[u8]>` and an inherent `to_bytes` method.
+    convert: bool,
+    /// Apply `default_on_eof` to every field.
+    default_on_eof: bool,
+    /// Encoding of enum variant tags, varints unless set.
+    tag_width: Option<TagWidth>,
+    /// Encode fields grouped by `FieldClass` rather than in declaration
+    /// order.
+    optimize_layout: bool,
+    /// Apply `fixed` to every integer and float field.
+    fixed: bool,
+    /// `fn(&Self) -> bool` run on every decoded value, rejecting it with
+    /// `InvalidData` when it returns `false`.
+    validate: Option<Path>,
+    /// Name hashed into the tags of `JaguarAccount`, `JaguarEvent` and
+    /// `JaguarMessage`, the type's own name unless set.
+    name: Option<String>,
+}
+
+/// How an enum's variant tag is written, chosen with `#[jaguar(tag_width = "...")]`.
+#[derive(Clone, Copy)]
+enum TagWidth {
+    U8,
+    U16,
+    Varint,
+}
+
+impl TagWidth {
+    /// The largest tag this width can hold.
+    fn max(self) -> u64 {
+        match self {
+            Self::U8 => u8::MAX as u64,
+            Self::U16 => u16::MAX as u64,
+            Self::Varint => u64::MAX,
+        }
+    }
+}
+
+/// Flattens every `#[jaguar(...)]` attribute into its list of options.
+fn jaguar_meta(attrs: &[Attribute]) -> syn::Result<Vec<NestedMeta>> {
+    let mut nested = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("jaguar")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => nested.extend(list.nested),
+            meta => return Err(syn::Error::new_spanned(meta, "expected `#[jaguar(...)]`")),
+        }
+    }
+    Ok(nested)
+}
+
+fn parse_container_attrs(input: &DeriveInput) -> syn::Result<ContainerAttrs> {
+    let mut attrs = ContainerAttrs::default();
+
+    for nested in jaguar_meta(&input.attrs)? {
+        match nested {
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("transparent") => attrs.transparent = true,
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("convert") => attrs.convert = true,
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("default_on_eof") => attrs.default_on_eof = true,
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("optimize_layout") => attrs.optimize_layout = true,
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("fixed") => attrs.fixed = true,
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("reserve") => match &nv.lit {
+                Lit::Int(lit) => attrs.reserve = Some(lit.base10_parse()?),
+                lit => return Err(syn::Error::new_spanned(lit, "expected an integer literal")),
+            },
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("tag_width") => match &nv.lit {
+                Lit::Str(lit) => {
+                    attrs.tag_width = Some(match lit.value().as_str() {
+                        "u8" => TagWidth::U8,
+                        "u16" => TagWidth::U16,
+                        "varint" => TagWidth::Varint,
+                        _ => return Err(syn::Error::new_spanned(lit, "expected `\"u8\"`, `\"u16\"` or `\"varint\"`")),
+                    })
+                }
+                lit => return Err(syn::Error::new_spanned(lit, "expected a string literal")),
+            },
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("validate") => {
+                attrs.validate = Some(parse_str_path(&nv.lit)?)
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("name") => match &nv.lit {
+                Lit::Str(lit) => attrs.name = Some(lit.value()),
+                lit => return Err(syn::Error::new_spanned(lit, "expected a string literal")),
+            },
+            // read separately by `crate_path`, for every derive
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("crate") => {}
+            other => return Err(syn::Error::new_spanned(other, "unknown jaguar attribute")),
+        }
+    }
+
+    if let Data::Enum(_) = input.data {
+        if attrs.transparent || attrs.reserve.is_some() || attrs.default_on_eof || attrs.optimize_layout || attrs.fixed {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`transparent`, `reserve`, `default_on_eof`, `optimize_layout` and `fixed` are not supported on enums",
+            ));
+        }
+    } else if attrs.tag_width.is_some() {
+        return Err(syn::Error::new_spanned(&input.ident, "`tag_width` is only supported on enums"));
+    }
+    if attrs.transparent && attrs.reserve.is_some() {
+        return Err(syn::Error::new_spanned(&input.ident, "`transparent` cannot be combined with `reserve`"));
+    }
+    if attrs.default_on_eof && attrs.reserve.is_some() {
+        return Err(syn::Error::new_spanned(&input.ident, "`default_on_eof` cannot be combined with `reserve`"));
+    }
+    if attrs.default_on_eof && attrs.optimize_layout {
+        return Err(syn::Error::new_spanned(&input.ident, "`default_on_eof` cannot be combined with `optimize_layout`"));
+    }
+
+    Ok(attrs)
+}
+
+/// The path to the `jaguar` crate set with `#[jaguar(crate = "...")]`, for
+/// types deriving through a crate that re-exports it.
+fn crate_path(input: &DeriveInput) -> syn::Result<Option<Path>> {
+    let mut path = None;
+    for nested in jaguar_meta(&input.attrs)? {
+        if let NestedMeta::Meta(Meta::NameValue(nv)) = nested {
+            if nv.path.is_ident("crate") {
+                path = Some(parse_str_path(&nv.lit)?);
+            }
+        }
+    }
+    Ok(path)
+}
+
+/// Makes the `jaguar::` paths in `tokens` resolve to the crate path set with
+/// `#[jaguar(crate = "...")]`, by importing it as `jaguar` in an anonymous
+/// scope. Only impls can go in there, since other items would be unnameable.
+fn with_crate_path(input: &DeriveInput, tokens: TokenStream2) -> syn::Result<TokenStream2> {
+    Ok(match crate_path(input)? {
+        Some(path) => quote! {
+            const _: () = {
+                #[allow(unused_imports)]
+                use #path as jaguar;
+                #tokens
+            };
+        },
+        None => tokens,
+    })
+}
+
+/// Field-level options parsed from `#[jaguar(...)]` attributes.
+#[derive(Default)]
+struct FieldAttrs {
+    /// Encode a `Vec<u64>` field as a first value followed by varint deltas.
+    delta: bool,
+    /// Encode the field through a `JaguarCodec` implementation. Also set by
+    /// `compat = "borsh"`, `repr = "borsh"`, `raw`, `bytes`, `len`
+    /// and `fixed`.
+    with: Option<Path>,
+    /// Encode an integer or float field as a fixed-width little-endian value,
+    /// through `jaguar::Fixed`.
+    fixed: bool,
+    /// Encode a `Vec<u8>` field with a single copy, through `jaguar::ByteVec`.
+    bytes: bool,
+    /// Pack the field into this many bits, sharing bytes with adjacent packed
+    /// fields.
+    bits: Option<u32>,
+    /// Module encoding the field, called as `m::serialize(&value, ser)` and
+    /// `m::deserialize(de)`.
+    module: Option<Path>,
+    /// Decode as `Default::default()` if the input ends before the field, so
+    /// fields can be appended to a layout that older data lacks. Only valid
+    /// on a trailing run of fields, checked by `struct_fields`.
+    default_on_eof: bool,
+    /// Decode a `&str` or `&[u8]` field by borrowing from the input.
+    borrow: Option<Borrow>,
+    /// Set by `len = "none"`: the field runs to the end of the input, so
+    /// nothing may follow it.
+    runs_to_end: bool,
+}
+
+/// The borrowed field types supported by `#[jaguar(borrow)]`.
+#[derive(Clone, Copy)]
+enum Borrow {
+    Str,
+    Bytes,
+}
+
+impl Borrow {
+    /// Matches `&str` and `&[u8]`, with any lifetime.
+    fn of(ty: &Type) -> Option<Self> {
+        let reference = match ty {
+            Type::Reference(reference) if reference.mutability.is_none() => reference,
+            _ => return None,
+        };
+        match &*reference.elem {
+            Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => Some(Self::Str),
+            Type::Slice(slice) => match &*slice.elem {
+                Type::Path(elem) if elem.qself.is_none() && elem.path.is_ident("u8") => Some(Self::Bytes),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl FieldAttrs {
+    /// Whether the field uses anything other than its own `JaguarSerialize`
+    /// and `JaguarDeserialize` implementations.
+    fn is_custom(&self) -> bool {
+        self.delta
+            || self.with.is_some()
+            || self.module.is_some()
+            || self.borrow.is_some()
+    }
+}
+
+/// Whether `ty` names an integer or float type with a fixed width, the types
+/// a container-level `#[jaguar(fixed)]` applies to. `u8` is left out since it
+/// is already a single byte.
+fn is_fixed_width(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => match path.path.segments.last() {
+            Some(segment) => matches!(
+                segment.ident.to_string().as_str(),
+                "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128" | "f32" | "f64"
+            ),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn parse_str_path(lit: &Lit) -> syn::Result<Path> {
+    match lit {
+        Lit::Str(lit) => lit.parse(),
+        lit => Err(syn::Error::new_spanned(lit, "expected a string literal")),
+    }
+}
+
+fn parse_field_attrs(field: &Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs::default();
+
+    for nested in jaguar_meta(&field.attrs)? {
+        match nested {
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("delta") => attrs.delta = true,
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("default_on_eof") => attrs.default_on_eof = true,
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("borrow") => match Borrow::of(&field.ty) {
+                Some(borrow) => attrs.borrow = Some(borrow),
+                None => return Err(syn::Error::new_spanned(&field.ty, "`borrow` requires a `&str` or `&[u8]` field")),
+            },
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("fixed") && attrs.with.is_none() => {
+                attrs.with = Some(parse_quote!(jaguar::Fixed));
+                attrs.fixed = true;
+            }
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("fixed") => {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "`fixed` cannot be combined with `with`, `compat`, `repr`, `raw`, `bytes` or `len`",
+                ))
+            }
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("bytes") && attrs.with.is_none() => {
+                attrs.with = Some(parse_quote!(jaguar::ByteVec));
+                attrs.bytes = true;
+            }
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("bytes") => {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "`bytes` cannot be combined with `with`, `compat`, `repr`, `raw`, `len` or `fixed`",
+                ))
+            }
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("raw") && attrs.with.is_none() => {
+                attrs.with = Some(parse_quote!(jaguar::Raw))
+            }
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("raw") => {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "`raw` cannot be combined with `with`, `compat`, `repr`, `bytes`, `len` or `fixed`",
+                ))
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("with") => match &nv.lit {
+                Lit::Str(lit) if attrs.with.is_none() => attrs.with = Some(lit.parse()?),
+                Lit::Str(_) => {
+                    return Err(syn::Error::new_spanned(
+                        nv,
+                        "`with` cannot be combined with `compat`, `repr`, `raw`, `bytes`, `len` or `fixed`",
+                    ))
+                }
+                lit => return Err(syn::Error::new_spanned(lit, "expected a string literal")),
+            },
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("compat") => match &nv.lit {
+                Lit::Str(lit) if lit.value() == "borsh" && attrs.with.is_none() => {
+                    attrs.with = Some(parse_quote!(jaguar::compat::Borsh))
+                }
+                Lit::Str(lit) if lit.value() == "borsh" => {
+                    return Err(syn::Error::new_spanned(
+                        nv,
+                        "`compat` cannot be combined with `with`, `repr`, `raw`, `bytes`, `len` or `fixed`",
+                    ))
+                }
+                lit => return Err(syn::Error::new_spanned(lit, "expected `compat = \"borsh\"`")),
+            },
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("repr") => match &nv.lit {
+                Lit::Str(lit) if lit.value() == "borsh" && attrs.with.is_none() => {
+                    attrs.with = Some(parse_quote!(jaguar::compat::BorshRepr))
+                }
+                Lit::Str(lit) if lit.value() == "borsh" => {
+                    return Err(syn::Error::new_spanned(
+                        nv,
+                        "`repr` cannot be combined with `with`, `compat`, `raw`, `bytes`, `len` or `fixed`",
+                    ))
+                }
+                lit => return Err(syn::Error::new_spanned(lit, "expected `repr = \"borsh\"`")),
+            },
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("len") => match &nv.lit {
+                Lit::Str(lit) if attrs.with.is_none() => {
+                    let prefix: Path = match lit.value().as_str() {
+                        "varint" => parse_quote!(jaguar::prefix::Varint),
+                        "u8" => parse_quote!(jaguar::prefix::U8),
+                        "u16" => parse_quote!(jaguar::prefix::U16),
+                        "u32" => parse_quote!(jaguar::prefix::U32),
+                        "none" => {
+                            attrs.runs_to_end = true;
+                            parse_quote!(jaguar::prefix::Implicit)
+                        }
+                        _ => lit.parse()?,
+                    };
+                    attrs.with = Some(parse_quote!(jaguar::prefix::Prefixed<#prefix>))
+                }
+                Lit::Str(_) => {
+                    return Err(syn::Error::new_spanned(
+                        nv,
+                        "`len` cannot be combined with `with`, `compat`, `repr`, `raw`, `bytes` or `fixed`",
+                    ))
+                }
+                lit => return Err(syn::Error::new_spanned(lit, "expected a string literal")),
+            },
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("bits") => match &nv.lit {
+                Lit::Int(lit) => match lit.base10_parse()? {
+                    bits @ 1..=64 => attrs.bits = Some(bits),
+                    _ => return Err(syn::Error::new_spanned(lit, "`bits` must be between 1 and 64")),
+                },
+                lit => return Err(syn::Error::new_spanned(lit, "expected an integer literal")),
+            },
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("module") => {
+                attrs.module = Some(parse_str_path(&nv.lit)?)
+            }
+            other => return Err(syn::Error::new_spanned(other, "unknown jaguar attribute")),
+        }
+    }
+
+    if attrs.module.is_some() && (attrs.delta || attrs.with.is_some()) {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`module` cannot be combined with `delta`, `with`, `compat`, `repr`, `raw`, `bytes`, `len` or `fixed`",
+        ));
+    }
+    if attrs.delta && attrs.with.is_some() {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`delta` cannot be combined with `with`, `compat`, `repr`, `raw`, `bytes`, `len` or `fixed`",
+        ));
+    }
+    if attrs.borrow.is_some() && (attrs.delta || attrs.with.is_some() || attrs.module.is_some()) {
+        return Err(syn::Error::new_spanned(field, "`borrow` cannot be combined with other field encodings"));
+    }
+    if attrs.bits.is_some() && attrs.is_custom() {
+        return Err(syn::Error::new_spanned(field, "`bits` cannot be combined with other field encodings"));
+    }
+    Ok(attrs)
+}
+
+/// Parses the attributes of a struct field, applying a container-level
+/// `fixed` to integer and float fields with no other encoding.
+fn struct_field_attrs(container: &ContainerAttrs, field: &Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = parse_field_attrs(field)?;
+    if container.fixed && !attrs.is_custom() && attrs.bits.is_none() && is_fixed_width(&field.ty) {
+        attrs.with = Some(parse_quote!(jaguar::Fixed));
+        attrs.fixed = true;
+    }
+    Ok(attrs)
+}
+
+/// Statement serializing `value` (an expression of type `&ty`) according to the
+/// field's attributes.
+fn serialize_field(attrs: &FieldAttrs, value: TokenStream2, ty: &Type) -> TokenStream2 {
+    if attrs.delta {
+        quote! { ser.write_u64_slice_delta(#value)?; }
+    } else if let Some(codec) = &attrs.with {
+        quote! { <#codec as jaguar::JaguarCodec<#ty>>::encode(#value, ser)?; }
+    } else if let Some(module) = &attrs.module {
+        quote! { #module::serialize(#value, ser)?; }
+    } else if let Some(borrow) = attrs.borrow {
+        match borrow {
+            Borrow::Str => quote! { ser.write_str(#value)?; },
+            Borrow::Bytes => quote! { ser.write_bytes(#value)?; },
+        }
+    } else {
+        quote! { jaguar::JaguarSerialize::serialize(#value, ser)?; }
+    }
+}
+
+/// Expression deserializing a value of type `ty` according to the field's
+/// attributes.
+fn deserialize_field(attrs: &FieldAttrs, ty: &Type) -> TokenStream2 {
+    if attrs.delta {
+        quote! { de.read_u64_vec_delta()? }
+    } else if let Some(codec) = &attrs.with {
+        quote! { <#codec as jaguar::JaguarCodec<#ty>>::decode(de)? }
+    } else if let Some(module) = &attrs.module {
+        quote! { #module::deserialize(de)? }
+    } else if let Some(borrow) = attrs.borrow {
+        match borrow {
+            Borrow::Str => quote! { de.read_str()? },
+            Borrow::Bytes => quote! { de.read_bytes()? },
+        }
+    } else {
+        quote! { <#ty as jaguar::JaguarDeserialize>::deserialize(de)? }
+    }
+}
+
+/// Statement checking a value of type `ty` without constructing it, according
+/// to the field's attributes.
+fn validate_field(attrs: &FieldAttrs, ty: &Type) -> TokenStream2 {
+    if attrs.delta || attrs.with.is_some() || attrs.module.is_some() || attrs.borrow.is_some() {
+        let value = deserialize_field(attrs, ty);
+        quote! { let _ = #value; }
+    } else {
+        quote! { <#ty as jaguar::JaguarDeserialize>::validate(de)?; }
+    }
+}
 
-    let fields = match input.data {
-        Data::Struct(data) => match data.fields {
-            Fields::Named(fields) => fields.named,
-            Fields::Unnamed(fields) => fields.unnamed,
-            Fields::Unit => return quote! {}.into(),
+/// A field whose encoding has a small fixed upper bound, as its worst-case
+/// size and a write through `jaguar::Coalesced`. Only recognizes primitive
+/// types by name, so aliases fall back to the checked path.
+fn coalesced_write(ty: &Type, value: &TokenStream2) -> Option<(TokenStream2, TokenStream2)> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let ident = path.path.get_ident()?;
+            let max: usize = match ident.to_string().as_str() {
+                "u8" | "bool" => 1,
+                "i8" => 2,
+                "u16" | "i16" => 3,
+                "u32" | "i32" | "f32" => 5,
+                "f64" => 9,
+                "u64" | "i64" | "usize" | "isize" => 10,
+                _ => return None,
+            };
+            Some((quote! { #max }, quote! { __w.#ident(#value); }))
+        }
+        Type::Array(array) => match &*array.elem {
+            Type::Path(elem) if elem.qself.is_none() && elem.path.is_ident("u8") => {
+                let len = &array.len;
+                Some((quote! { (#len) }, quote! { __w.bytes(&#value); }))
+            }
+            _ => None,
         },
-        _ => return quote! {}.into(),
+        _ => None,
+    }
+}
+
+/// Emits a run of fixed-bound fields with a single space check, falling back
+/// to the checked writes if the serializer's size cap is too close.
+fn flush_coalesced_run(statements: &mut Vec<TokenStream2>, run: &mut Vec<(TokenStream2, TokenStream2, TokenStream2)>) {
+    if run.len() < 2 {
+        statements.extend(run.drain(..).map(|(_, _, checked)| checked));
+        return;
+    }
+    let max = run.iter().map(|(max, _, _)| max);
+    let writes = run.iter().map(|(_, write, _)| write);
+    let checked = run.iter().map(|(_, _, checked)| checked);
+    statements.push(quote! {
+        match ser.__coalesce(#(#max)+*) {
+            ::core::option::Option::Some(mut __w) => {
+                #(#writes)*
+            }
+            ::core::option::Option::None => {
+                #(#checked)*
+            }
+        }
+    });
+    run.clear();
+}
+
+/// Wraps a run of adjacent `bits` fields in a shared bit writer or reader.
+fn flush_bit_group(statements: &mut Vec<TokenStream2>, group: &mut Vec<TokenStream2>, begin: TokenStream2) {
+    if group.is_empty() {
+        return;
+    }
+    statements.push(quote! {
+        let mut __bits = #begin;
+        #(#group)*
+        __bits.finish()?;
+    });
+    group.clear();
+}
+
+/// Returns the container attributes and fields of a struct, validating the
+/// attributes against the fields.
+fn struct_fields(input: &DeriveInput) -> syn::Result<Option<(ContainerAttrs, &Fields)>> {
+    let attrs = parse_container_attrs(input)?;
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Ok(None),
     };
 
-    let field_serialize = fields.iter().map(|field| {
-        let field_name = field.ident.as_ref().unwrap();
-        quote! {
-            self.#field_name.serialize(ser)?;
+    if attrs.transparent && fields.len() != 1 {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`transparent` requires a struct with exactly one field",
+        ));
+    }
+    let mut defaulted = false;
+    for (index, field) in fields.iter().enumerate() {
+        let field_attrs = parse_field_attrs(field)?;
+        // a missing field means the input ended, so every field after it is
+        // missing too
+        let default_on_eof = attrs.default_on_eof || field_attrs.default_on_eof;
+        if default_on_eof && field_attrs.bits.is_some() {
+            return Err(syn::Error::new_spanned(field, "`bits` cannot be combined with `default_on_eof`"));
+        }
+        if defaulted && !default_on_eof {
+            return Err(syn::Error::new_spanned(field, "fields after a `default_on_eof` field must be `default_on_eof`"));
+        }
+        defaulted = default_on_eof;
+        if !field_attrs.runs_to_end {
+            continue;
+        }
+        if index + 1 != fields.len() {
+            return Err(syn::Error::new_spanned(field, "a `len = \"none\"` field must be the last field"));
+        }
+        if attrs.reserve.is_some() || attrs.optimize_layout || attrs.default_on_eof || field_attrs.default_on_eof {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`len = \"none\"` cannot be combined with `reserve`, `optimize_layout` or `default_on_eof`",
+            ));
+        }
+    }
+
+    Ok(Some((attrs, fields)))
+}
+
+/// The groups `#[jaguar(optimize_layout)]` orders fields by, mirroring
+/// `jaguar::layout::FieldClass`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum FieldClass {
+    Bits,
+    Fixed,
+    Scalar,
+    Variable,
+}
+
+impl FieldClass {
+    /// Classifies a field. Like `coalesced_write`, only recognizes types by
+    /// name, so anything unknown is variable.
+    fn of(attrs: &FieldAttrs, ty: &Type) -> Self {
+        if attrs.bits.is_some() {
+            return Self::Bits;
+        }
+        if attrs.fixed {
+            return Self::Fixed;
+        }
+        if attrs.is_custom() {
+            return Self::Variable;
+        }
+        match ty {
+            Type::Path(path) if path.qself.is_none() => {
+                let ident = match path.path.segments.last() {
+                    Some(segment) => segment.ident.to_string(),
+                    None => return Self::Variable,
+                };
+                match ident.as_str() {
+                    "u8" | "bool" | "Ipv4Addr" | "Ipv6Addr" | "PhantomData" | "PhantomPinned" => Self::Fixed,
+                    "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "u128" | "i128" | "usize" | "isize"
+                    | "f32" | "f64" => Self::Scalar,
+                    _ => Self::Variable,
+                }
+            }
+            Type::Array(array) => match &*array.elem {
+                Type::Path(elem) if elem.qself.is_none() && elem.path.is_ident("u8") => Self::Fixed,
+                _ => Self::Variable,
+            },
+            Type::Tuple(tuple) if tuple.elems.is_empty() => Self::Fixed,
+            _ => Self::Variable,
+        }
+    }
+
+    fn tokens(self) -> TokenStream2 {
+        match self {
+            Self::Bits => quote! { jaguar::layout::FieldClass::Bits },
+            Self::Fixed => quote! { jaguar::layout::FieldClass::Fixed },
+            Self::Scalar => quote! { jaguar::layout::FieldClass::Scalar },
+            Self::Variable => quote! { jaguar::layout::FieldClass::Variable },
+        }
+    }
+}
+
+/// Returns the fields, with their declaration index, in the order they are
+/// encoded: as declared, or grouped by `FieldClass` with `optimize_layout`.
+/// Every struct derive encodes fields in this order.
+fn encode_order<'a>(container: &ContainerAttrs, fields: &'a Fields) -> syn::Result<Vec<(usize, &'a Field)>> {
+    let mut ordered: Vec<(usize, &Field)> = fields.iter().enumerate().collect();
+    if !container.optimize_layout {
+        return Ok(ordered);
+    }
+    let mut classes = Vec::with_capacity(ordered.len());
+    for (_, field) in &ordered {
+        let attrs = struct_field_attrs(container, field)?;
+        if attrs.default_on_eof {
+            return Err(syn::Error::new_spanned(field, "`default_on_eof` cannot be combined with `optimize_layout`"));
+        }
+        classes.push(FieldClass::of(&attrs, &field.ty));
+    }
+    // a stable sort keeps declaration order within each class
+    ordered.sort_by_key(|(index, _)| classes[*index]);
+    Ok(ordered)
+}
+
+/// The `jaguar::layout::JaguarLayout` impl emitted for
+/// `#[jaguar(optimize_layout)]`.
+fn layout_impl(input: &DeriveInput, container: &ContainerAttrs, fields: &[(usize, &Field)]) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut entries = Vec::with_capacity(fields.len());
+    for (index, field) in fields {
+        let attrs = struct_field_attrs(container, field)?;
+        let class = FieldClass::of(&attrs, &field.ty).tokens();
+        let field_name = match &field.ident {
+            Some(ident) => ident.unraw().to_string(),
+            None => format!("_{}", index),
+        };
+        entries.push(quote! {
+            jaguar::layout::LayoutField { name: #field_name, class: #class }
+        });
+    }
+    Ok(quote! {
+        impl #impl_generics jaguar::layout::JaguarLayout for #name #ty_generics #where_clause {
+            const LAYOUT: &'static [jaguar::layout::LayoutField] = &[#(#entries),*];
+        }
+    })
+}
+
+/// Returns the container attributes of an enum and every variant with its
+/// tag. Variants without `#[jaguar(tag = N)]` take their explicit Rust
+/// discriminant, or else the previous tag plus one, starting from 0, like
+/// Rust discriminants.
+fn enum_variants<'a>(input: &DeriveInput, data: &'a DataEnum) -> syn::Result<(ContainerAttrs, Vec<(&'a Variant, u64)>)> {
+    let attrs = parse_container_attrs(input)?;
+    let width = attrs.tag_width.unwrap_or(TagWidth::Varint);
+
+    let mut variants: Vec<(&Variant, u64)> = Vec::with_capacity(data.variants.len());
+    let mut next = Some(0u64);
+    for variant in &data.variants {
+        let mut tag = None;
+        for nested in jaguar_meta(&variant.attrs)? {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("tag") => match &nv.lit {
+                    Lit::Int(lit) => tag = Some(lit.base10_parse::<u64>()?),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected an integer literal")),
+                },
+                other => return Err(syn::Error::new_spanned(other, "unknown jaguar attribute")),
+            }
+        }
+        if let Some((_, expr)) = &variant.discriminant {
+            let discriminant = match expr {
+                Expr::Lit(ExprLit { lit: Lit::Int(lit), .. }) => lit.base10_parse::<u64>()?,
+                expr => {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        "only integer literal discriminants can be used as tags; set the tag with `#[jaguar(tag = N)]`",
+                    ))
+                }
+            };
+            if tag.is_some_and(|tag| tag != discriminant) {
+                return Err(syn::Error::new_spanned(expr, "`#[jaguar(tag = N)]` differs from the variant's discriminant"));
+            }
+            tag = Some(discriminant);
+        }
+        let tag = match tag.or(next) {
+            Some(tag) if tag <= width.max() => tag,
+            _ => return Err(syn::Error::new_spanned(variant, "variant tag does not fit in `tag_width`")),
+        };
+        if let Some((other, _)) = variants.iter().find(|(_, other)| *other == tag) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!("variant tag {} is already used by `{}`", tag, other.ident),
+            ));
+        }
+        variants.push((variant, tag));
+        next = tag.checked_add(1);
+    }
+
+    Ok((attrs, variants))
+}
+
+/// Statement writing an enum variant tag.
+fn write_tag(width: TagWidth, tag: u64) -> TokenStream2 {
+    match width {
+        TagWidth::U8 => {
+            let tag = tag as u8;
+            quote! { ser.write_u8(#tag)?; }
+        }
+        TagWidth::U16 => {
+            let tag = tag as u16;
+            quote! { ser.write_raw(&#tag.to_le_bytes())?; }
+        }
+        TagWidth::Varint => quote! { ser.write_varint(#tag)?; },
+    }
+}
+
+/// Expression reading an enum variant tag.
+fn read_tag(width: TagWidth) -> TokenStream2 {
+    match width {
+        TagWidth::U8 => quote! { de.read_u8()? },
+        TagWidth::U16 => quote! { u16::from_le_bytes(*de.read_array_ref::<2>()?) },
+        TagWidth::Varint => quote! { de.read_varint()? },
+    }
+}
+
+/// Parses the field attributes of an enum variant, rejecting the ones that
+/// only make sense on structs.
+fn variant_field_attrs(field: &Field) -> syn::Result<FieldAttrs> {
+    let attrs = parse_field_attrs(field)?;
+    if attrs.bits.is_some() || attrs.default_on_eof {
+        return Err(syn::Error::new_spanned(field, "`bits` and `default_on_eof` are not supported in enum variants"));
+    }
+    if attrs.runs_to_end {
+        return Err(syn::Error::new_spanned(field, "`len = \"none\"` is not supported in enum variants"));
+    }
+    Ok(attrs)
+}
+
+/// The pattern binding every field of `variant`, also usable to construct it.
+fn variant_pattern(variant: &Variant) -> TokenStream2 {
+    let ident = &variant.ident;
+    let fields = variant.fields.iter().enumerate().map(|(index, field)| {
+        match field_idents(index, field) {
+            (Member::Named(ident), _) => quote! { #ident },
+            (member, binding) => quote! { #member: #binding },
         }
     });
+    quote! { Self::#ident { #(#fields,)* } }
+}
+
+/// How a field is accessed on `self`, and the local it is bound to while
+/// deserializing.
+fn field_idents(index: usize, field: &Field) -> (Member, Ident) {
+    match &field.ident {
+        Some(ident) => (Member::Named(ident.clone()), ident.clone()),
+        None => (Member::Unnamed(Index::from(index)), format_ident!("__field{}", index)),
+    }
+}
+
+/// Adds `bound` to every type parameter of `generics` that a field of `input`
+/// uses, in structs and in every variant of enums. Parameters only used inside
+/// `PhantomData` are left unbounded, so marker types don't need to implement
+/// the trait.
+///
+/// Associated types such as `P::Args` are bounded themselves rather than `P`,
+/// and fields encoded `with` a codec require the codec to handle their type
+/// instead, so neither forces a bound on a parameter that is never encoded.
+fn with_bound(mut generics: Generics, input: &DeriveInput, bound: TokenStream2) -> Generics {
+    let params: Vec<Ident> = generics.type_params().map(|param| param.ident.clone()).collect();
+    let mut used = Vec::new();
+    let mut predicates: Vec<TokenStream2> = Vec::new();
+    let fields: Vec<&Field> = match &input.data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|variant| &variant.fields).collect(),
+        Data::Union(data) => data.fields.named.iter().collect(),
+    };
+    for field in fields {
+        let ty = &field.ty;
+        // invalid attributes are reported by the expansion itself
+        let attrs = parse_field_attrs(field).unwrap_or_default();
+        if let Some(codec) = &attrs.with {
+            let (mut field_params, mut field_projections) = (Vec::new(), Vec::new());
+            used_params(quote! { #ty }, &params, &mut field_params, &mut field_projections);
+            if !field_params.is_empty() || !field_projections.is_empty() {
+                predicates.push(quote! { #codec: jaguar::JaguarCodec<#ty> });
+            }
+            continue;
+        }
+
+        let mut projections = Vec::new();
+        used_params(quote! { #ty }, &params, &mut used, &mut projections);
+        predicates.extend(projections.into_iter().map(|projection| quote! { #projection: #bound }));
+    }
+
+    for param in generics.type_params_mut().filter(|param| used.contains(&param.ident)) {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    if !predicates.is_empty() {
+        let where_clause = generics.make_where_clause();
+        where_clause.predicates.extend(predicates.into_iter().map(|predicate| -> syn::WherePredicate {
+            parse_quote!(#predicate)
+        }));
+    }
+    generics
+}
+
+/// Collects the type parameters among `params` that appear in `tokens`,
+/// skipping the arguments of `PhantomData<...>`. Associated types of a
+/// parameter, like `P::Args`, go to `projections` instead.
+fn used_params(tokens: TokenStream2, params: &[Ident], used: &mut Vec<Ident>, projections: &mut Vec<TokenStream2>) {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident == "PhantomData" => {
+                if !matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '<') {
+                    continue;
+                }
+                // angle brackets aren't token groups, so count them, minding
+                // the `>` of a `->` in fn pointer types
+                let mut depth = 0;
+                let mut arrow = false;
+                for token in tokens.by_ref() {
+                    if let TokenTree::Punct(punct) = &token {
+                        match punct.as_char() {
+                            '<' => depth += 1,
+                            '>' if !arrow => depth -= 1,
+                            _ => {}
+                        }
+                        arrow = punct.as_char() == '-';
+                    } else {
+                        arrow = false;
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            TokenTree::Ident(ident) if params.contains(&ident) => {
+                let mut path = vec![ident];
+                let mut lookahead = tokens.clone();
+                while let (Some(TokenTree::Punct(first)), Some(TokenTree::Punct(second)), Some(TokenTree::Ident(assoc))) =
+                    (lookahead.next(), lookahead.next(), lookahead.next())
+                {
+                    if first.as_char() != ':' || second.as_char() != ':' {
+                        break;
+                    }
+                    path.push(assoc);
+                    tokens = lookahead.clone();
+                }
+                if path.len() == 1 {
+                    if !used.contains(&path[0]) {
+                        used.push(path.remove(0));
+                    }
+                } else {
+                    let projection = quote! { #(#path)::* };
+                    if !projections.iter().any(|existing| existing.to_string() == projection.to_string()) {
+                        projections.push(projection);
+                    }
+                }
+            }
+            TokenTree::Group(group) => used_params(group.stream(), params, used, projections),
+            _ => {}
+        }
+    }
+}
+
+/// Picks the arena lifetime for a `JaguarDeserializeIn` impl: the struct's
+/// own lifetime if it has exactly one, otherwise a fresh `'bump`.
+fn arena_lifetime(generics: &mut Generics) -> Lifetime {
+    let lifetimes: Vec<Lifetime> = generics.lifetimes().map(|def| def.lifetime.clone()).collect();
+    if lifetimes.len() == 1 {
+        return lifetimes[0].clone();
+    }
+
+    let name = if lifetimes.iter().any(|lifetime| lifetime.ident == "bump") { "'__bump" } else { "'bump" };
+    let bump = Lifetime::new(name, proc_macro2::Span::call_site());
+    generics.params.insert(0, GenericParam::Lifetime(LifetimeDef::new(bump.clone())));
+    bump
+}
+
+/// Picks the lifetime of the deserializer input for the generated impl.
+///
+/// A struct with a single lifetime borrows straight from the input, so that
+/// lifetime is reused. Otherwise a fresh `'de` is introduced that outlives
+/// every lifetime on the struct.
+fn deserializer_lifetime(generics: &mut Generics) -> Lifetime {
+    let lifetimes: Vec<Lifetime> = generics.lifetimes().map(|def| def.lifetime.clone()).collect();
+    if lifetimes.len() == 1 {
+        return lifetimes[0].clone();
+    }
+
+    let name = if lifetimes.iter().any(|lifetime| lifetime.ident == "de") { "'__de" } else { "'de" };
+    let de = Lifetime::new(name, proc_macro2::Span::call_site());
+    let mut def = LifetimeDef::new(de.clone());
+    def.bounds.extend(lifetimes);
+    generics.params.insert(0, GenericParam::Lifetime(def));
+    de
+}
+
+#[proc_macro_derive(JaguarSerialize, attributes(jaguar))]
+pub fn derive_serialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_serialize(&input)
+        .and_then(|tokens| with_crate_path(&input, tokens))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn expand_serialize(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = with_bound(input.generics.clone(), input, quote! { jaguar::JaguarSerialize });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    if let Data::Enum(data) = &input.data {
+        let (container, variants) = enum_variants(input, data)?;
+        let width = container.tag_width.unwrap_or(TagWidth::Varint);
+        let mut arms = Vec::with_capacity(variants.len());
+        for (variant, tag) in variants {
+            let tag = write_tag(width, tag);
+            let mut field_serialize = Vec::with_capacity(variant.fields.len());
+            for (index, field) in variant.fields.iter().enumerate() {
+                let attrs = variant_field_attrs(field)?;
+                let (_, binding) = field_idents(index, field);
+                field_serialize.push(serialize_field(&attrs, quote! { #binding }, &field.ty));
+            }
+            let pattern = variant_pattern(variant);
+            arms.push(quote! {
+                #pattern => {
+                    #tag
+                    #(#field_serialize)*
+                }
+            });
+        }
+        // an empty enum is matched by value, since a reference to it is
+        // considered inhabited
+        let body = if data.variants.is_empty() {
+            quote! { match *self {} }
+        } else {
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+                ::core::result::Result::Ok(())
+            }
+        };
+        let convert = container.convert.then(|| to_bytes_impl(input, &generics));
+        return Ok(quote! {
+            impl #impl_generics jaguar::JaguarSerialize for #name #ty_generics #where_clause {
+                fn serialize(&self, ser: &mut jaguar::JaguarSerializer) -> ::core::result::Result<(), jaguar::SerError> {
+                    #body
+                }
+            }
+
+            #convert
+        });
+    }
+
+    let (container, fields) = match struct_fields(input)? {
+        Some(parsed) => parsed,
+        None => return Ok(quote! {}),
+    };
+
+    let fields = encode_order(&container, fields)?;
+    let mut field_serialize = Vec::with_capacity(fields.len() + 1);
+    let mut bit_group = Vec::new();
+    let mut run = Vec::new();
+    for &(index, field) in &fields {
+        let attrs = struct_field_attrs(&container, field)?;
+        let (member, _) = field_idents(index, field);
+        if let Some(bits) = attrs.bits {
+            flush_coalesced_run(&mut field_serialize, &mut run);
+            bit_group.push(quote! {
+                __bits.write_bits(jaguar::bits::BitField::to_bits(&self.#member), #bits)?;
+            });
+            continue;
+        }
+        flush_bit_group(&mut field_serialize, &mut bit_group, quote! { ser.bit_writer() });
+        let checked = serialize_field(&attrs, quote! { &self.#member }, &field.ty);
+        match coalesced_write(&field.ty, &quote! { self.#member }).filter(|_| !attrs.is_custom()) {
+            Some((max, write)) => run.push((max, write, checked)),
+            None => {
+                flush_coalesced_run(&mut field_serialize, &mut run);
+                field_serialize.push(checked);
+            }
+        }
+    }
+    flush_coalesced_run(&mut field_serialize, &mut run);
+    flush_bit_group(&mut field_serialize, &mut bit_group, quote! { ser.bit_writer() });
+    if let Some(reserve) = container.reserve {
+        field_serialize.push(quote! { ser.write_padding(#reserve)?; });
+    }
+
+    let convert = container.convert.then(|| to_bytes_impl(input, &generics));
+    let layout = match container.optimize_layout {
+        true => Some(layout_impl(input, &container, &fields)?),
+        false => None,
+    };
 
-    let expanded = quote! {
+    Ok(quote! {
         impl #impl_generics jaguar::JaguarSerialize for #name #ty_generics #where_clause {
-            fn serialize(&self, ser: &mut jaguar::JaguarSerializer) -> Result<(), jaguar::SerError> {
+            fn serialize(&self, ser: &mut jaguar::JaguarSerializer) -> ::core::result::Result<(), jaguar::SerError> {
                 #(#field_serialize)*
-                Ok(())
+                ::core::result::Result::Ok(())
             }
         }
-    };
 
-    expanded.into()
+        #convert
+        #layout
+    })
+}
+
+/// The inherent `to_bytes` method emitted for `#[jaguar(convert)]`.
+fn to_bytes_impl(input: &DeriveInput, generics: &Generics) -> TokenStream2 {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Serializes `self` into a new buffer.
+            pub fn to_bytes(&self) -> ::core::result::Result<jaguar::__private::Vec<u8>, jaguar::SerError> {
+                jaguar::serialize(self)
+            }
+        }
+    }
 }
 
-#[proc_macro_derive(JaguarDeserialize)]
+#[proc_macro_derive(JaguarDeserialize, attributes(jaguar))]
 pub fn derive_deserialize(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    expand_deserialize(&input)
+        .and_then(|tokens| with_crate_path(&input, tokens))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
 
-    let fields = match input.data {
-        Data::Struct(data) => match data.fields {
-            Fields::Named(fields) => fields.named,
-            Fields::Unnamed(fields) => fields.unnamed,
-            Fields::Unit => return quote! {}.into(),
-        },
-        _ => return quote! {}.into(),
+fn expand_deserialize(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let de = deserializer_lifetime(&mut generics);
+    let generics = with_bound(generics, input, quote! { jaguar::JaguarDeserialize<#de> });
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    if let Data::Enum(data) = &input.data {
+        let (container, variants) = enum_variants(input, data)?;
+        let width = container.tag_width.unwrap_or(TagWidth::Varint);
+        let mut deserialize_arms = Vec::with_capacity(variants.len());
+        let mut validate_arms = Vec::with_capacity(variants.len());
+        for (variant, tag) in variants {
+            let tag = proc_macro2::Literal::u64_unsuffixed(tag);
+            let mut field_deserialize = Vec::with_capacity(variant.fields.len());
+            let mut field_validate = Vec::with_capacity(variant.fields.len());
+            for (index, field) in variant.fields.iter().enumerate() {
+                let attrs = variant_field_attrs(field)?;
+                let (_, binding) = field_idents(index, field);
+                let value = deserialize_field(&attrs, &field.ty);
+                field_deserialize.push(quote! { let #binding = #value; });
+                field_validate.push(validate_field(&attrs, &field.ty));
+            }
+            let construct = variant_pattern(variant);
+            deserialize_arms.push(quote! {
+                #tag => {
+                    #(#field_deserialize)*
+                    ::core::result::Result::Ok(#construct)
+                }
+            });
+            validate_arms.push(quote! {
+                #tag => {
+                    #(#field_validate)*
+                    ::core::result::Result::Ok(())
+                }
+            });
+        }
+        let read_tag = read_tag(width);
+        let convert = container.convert.then(|| try_from_impl(input, &generics, &de));
+        let deserialize = with_validate_hook(
+            &container,
+            quote! {
+                de.nested(|de| match #read_tag {
+                    #(#deserialize_arms)*
+                    _ => ::core::result::Result::Err(jaguar::SerError::InvalidData),
+                })
+            },
+        );
+        let validate = validate_body(
+            &container,
+            &de,
+            quote! {
+                de.nested(|de| match #read_tag {
+                    #(#validate_arms)*
+                    _ => ::core::result::Result::Err(jaguar::SerError::InvalidData),
+                })
+            },
+        );
+        return Ok(quote! {
+            #convert
+
+            impl #impl_generics jaguar::JaguarDeserialize<#de> for #name #ty_generics #where_clause {
+                fn deserialize(de: &mut jaguar::JaguarDeserializer<#de>) -> ::core::result::Result<Self, jaguar::SerError> {
+                    #deserialize
+                }
+
+                fn validate(de: &mut jaguar::JaguarDeserializer<#de>) -> ::core::result::Result<(), jaguar::SerError> {
+                    #validate
+                }
+            }
+        });
+    }
+
+    let (container, fields) = match struct_fields(input)? {
+        Some(parsed) => parsed,
+        None => return Ok(quote! {}),
+    };
+
+    let mut field_deserialize = Vec::with_capacity(fields.len() + 1);
+    let mut field_validate = Vec::with_capacity(fields.len() + 1);
+    let mut bit_group = Vec::new();
+    let mut bit_validate_group = Vec::new();
+    for (index, field) in encode_order(&container, fields)? {
+        let attrs = struct_field_attrs(&container, field)?;
+        let (_, binding) = field_idents(index, field);
+        let ty = &field.ty;
+        if let Some(bits) = attrs.bits {
+            let value = quote! { <#ty as jaguar::bits::BitField>::from_bits(__bits.read_bits(#bits)?)? };
+            bit_group.push(quote! { let #binding = #value; });
+            bit_validate_group.push(quote! { let _ = #value; });
+            continue;
+        }
+        flush_bit_group(&mut field_deserialize, &mut bit_group, quote! { de.bit_reader() });
+        flush_bit_group(&mut field_validate, &mut bit_validate_group, quote! { de.bit_reader() });
+
+        let mut value = deserialize_field(&attrs, ty);
+        let mut validate = validate_field(&attrs, ty);
+        if attrs.default_on_eof || container.default_on_eof {
+            value = quote! {
+                if de.has_data() { #value } else { <#ty as ::core::default::Default>::default() }
+            };
+            validate = quote! {
+                if de.has_data() { #validate }
+            };
+        }
+        field_deserialize.push(quote! {
+            let #binding = #value;
+        });
+        field_validate.push(validate);
+    }
+    flush_bit_group(&mut field_deserialize, &mut bit_group, quote! { de.bit_reader() });
+    flush_bit_group(&mut field_validate, &mut bit_validate_group, quote! { de.bit_reader() });
+    if let Some(reserve) = container.reserve {
+        field_deserialize.push(quote! { de.skip(#reserve)?; });
+        field_validate.push(quote! { de.skip(#reserve)?; });
+    }
+
+    // constructed in declaration order, whatever order the fields decode in
+    let bindings = fields.iter().enumerate().map(|(index, field)| field_idents(index, field).1);
+    let construct = match fields {
+        Fields::Named(_) => quote! { Self { #(#bindings,)* } },
+        Fields::Unnamed(_) => quote! { Self(#(#bindings,)*) },
+        Fields::Unit => quote! { Self },
     };
 
-    let field_deserialize = fields.iter().map(|field| {
-        let field_name = field.ident.as_ref().unwrap();
-        let field_type = &field.ty;
+    let convert = container.convert.then(|| try_from_impl(input, &generics, &de));
+    let deserialize = with_validate_hook(
+        &container,
+        quote! {
+            de.nested(|de| {
+                #(#field_deserialize)*
+                ::core::result::Result::Ok(#construct)
+            })
+        },
+    );
+    let validate = validate_body(
+        &container,
+        &de,
         quote! {
-            let #field_name = <#field_type as jaguar::JaguarDeserialize>::deserialize(de)?;
+            de.nested(|de| {
+                #(#field_validate)*
+                ::core::result::Result::Ok(())
+            })
+        },
+    );
+
+    Ok(quote! {
+        #convert
+
+        impl #impl_generics jaguar::JaguarDeserialize<#de> for #name #ty_generics #where_clause {
+            fn deserialize(de: &mut jaguar::JaguarDeserializer<#de>) -> ::core::result::Result<Self, jaguar::SerError> {
+                #deserialize
+            }
+
+            fn validate(de: &mut jaguar::JaguarDeserializer<#de>) -> ::core::result::Result<(), jaguar::SerError> {
+                #validate
+            }
         }
-    });
+    })
+}
 
-    let field_names = fields.iter().map(|field| {
-        field.ident.as_ref().unwrap()
-    });
+/// Runs the `#[jaguar(validate = "...")]` hook on the value `decode`
+/// evaluates to, if the container sets one.
+fn with_validate_hook(container: &ContainerAttrs, decode: TokenStream2) -> TokenStream2 {
+    match &container.validate {
+        Some(hook) => quote! {
+            let value: Self = #decode?;
+            if !#hook(&value) {
+                return ::core::result::Result::Err(jaguar::SerError::InvalidData);
+            }
+            ::core::result::Result::Ok(value)
+        },
+        None => decode,
+    }
+}
+
+/// The body of `JaguarDeserialize::validate`. With a validate hook, the
+/// value has to exist to be checked, so it is decoded in full.
+fn validate_body(container: &ContainerAttrs, de: &Lifetime, validate: TokenStream2) -> TokenStream2 {
+    match &container.validate {
+        Some(_) => quote! { <Self as jaguar::JaguarDeserialize<#de>>::deserialize(de).map(|_| ()) },
+        None => validate,
+    }
+}
 
-    let expanded = quote! {
-        impl<'a> #impl_generics jaguar::JaguarDeserialize<'a> for #name #ty_generics #where_clause {
-            fn deserialize(de: &mut jaguar::JaguarDeserializer<'a>) -> Result<Self, jaguar::SerError> {
+#[proc_macro_derive(JaguarDeserializeIn, attributes(jaguar))]
+pub fn derive_deserialize_in(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_deserialize_in(&input)
+        .and_then(|tokens| with_crate_path(&input, tokens))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Implements `jaguar::arena::JaguarDeserializeIn`. The struct's lifetime, if
+/// it has exactly one, is taken to be the arena's.
+fn expand_deserialize_in(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let bump = arena_lifetime(&mut generics);
+    let generics = with_bound(generics, input, quote! { jaguar::arena::JaguarDeserializeIn<#bump> });
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let (container, fields) = match struct_fields(input)? {
+        Some(parsed) => parsed,
+        None => return Err(syn::Error::new_spanned(name, "`JaguarDeserializeIn` can only be derived for structs")),
+    };
+
+    let mut field_deserialize = Vec::with_capacity(fields.len() + 1);
+    for (index, field) in encode_order(&container, fields)? {
+        let attrs = struct_field_attrs(&container, field)?;
+        if attrs.bits.is_some() {
+            return Err(syn::Error::new_spanned(field, "`bits` is not supported by `JaguarDeserializeIn`"));
+        }
+        let (_, binding) = field_idents(index, field);
+        let ty = &field.ty;
+
+        let mut value = if attrs.is_custom() {
+            deserialize_field(&attrs, ty)
+        } else {
+            quote! { <#ty as jaguar::arena::JaguarDeserializeIn<#bump>>::deserialize_in(de, bump)? }
+        };
+        if attrs.default_on_eof || container.default_on_eof {
+            value = quote! {
+                if de.has_data() { #value } else { <#ty as ::core::default::Default>::default() }
+            };
+        }
+        field_deserialize.push(quote! {
+            let #binding = #value;
+        });
+    }
+    if let Some(reserve) = container.reserve {
+        field_deserialize.push(quote! { de.skip(#reserve)?; });
+    }
+
+    // constructed in declaration order, whatever order the fields decode in
+    let bindings = fields.iter().enumerate().map(|(index, field)| field_idents(index, field).1);
+    let construct = match fields {
+        Fields::Named(_) => quote! { Self { #(#bindings,)* } },
+        Fields::Unnamed(_) => quote! { Self(#(#bindings,)*) },
+        Fields::Unit => quote! { Self },
+    };
+
+    let deserialize = with_validate_hook(
+        &container,
+        quote! {
+            de.nested(|de| {
                 #(#field_deserialize)*
-                Ok(Self {
-                    #(#field_names,)*
+                ::core::result::Result::Ok(#construct)
+            })
+        },
+    );
+
+    Ok(quote! {
+        impl #impl_generics jaguar::arena::JaguarDeserializeIn<#bump> for #name #ty_generics #where_clause {
+            fn deserialize_in(
+                de: &mut jaguar::JaguarDeserializer<'_>,
+                bump: &#bump jaguar::__private::Bump,
+            ) -> ::core::result::Result<Self, jaguar::SerError> {
+                #deserialize
+            }
+        }
+    })
+}
+
+/// The `TryFrom<&[u8]>` impl emitted for `#[jaguar(convert)]`.
+fn try_from_impl(input: &DeriveInput, generics: &Generics, de: &Lifetime) -> TokenStream2 {
+    let name = &input.ident;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::core::convert::TryFrom<&#de [u8]> for #name #ty_generics #where_clause {
+            type Error = jaguar::SerError;
+
+            fn try_from(data: &#de [u8]) -> ::core::result::Result<Self, jaguar::SerError> {
+                jaguar::deserialize(data)
+            }
+        }
+    }
+}
+
+#[proc_macro_derive(JaguarConstSize, attributes(jaguar))]
+pub fn derive_const_size(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_const_size(&input)
+        .and_then(|tokens| with_crate_path(&input, tokens))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn expand_const_size(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = with_bound(input.generics.clone(), input, quote! { jaguar::JaguarConstSize });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (container, fields) = match struct_fields(input)? {
+        Some(parsed) => parsed,
+        None => return Err(syn::Error::new_spanned(name, "`JaguarConstSize` can only be derived for structs")),
+    };
+
+    // each field starts where the sizes of the fields before it add up to
+    let mut offset = quote! { 0usize };
+    let mut field_writes = Vec::with_capacity(fields.len() + 1);
+    for (index, field) in encode_order(&container, fields)? {
+        let attrs = struct_field_attrs(&container, field)?;
+        let (member, _) = field_idents(index, field);
+        let ty = &field.ty;
+        if attrs.fixed {
+            field_writes.push(quote! {
+                jaguar::FixedWidth::write_le(self.#member, &mut out[#offset..]);
+            });
+            offset = quote! { #offset + <#ty as jaguar::FixedWidth>::WIDTH };
+            continue;
+        }
+        if attrs.is_custom() || attrs.bits.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`JaguarConstSize` does not support field encoding attributes",
+            ));
+        }
+
+        field_writes.push(quote! {
+            jaguar::JaguarConstSize::write_const(&self.#member, &mut out[#offset..]);
+        });
+        offset = quote! { #offset + <#ty as jaguar::JaguarConstSize>::SIZE };
+    }
+
+    let size = match container.reserve {
+        Some(reserve) => {
+            field_writes.push(quote! { out[#offset..#offset + #reserve].fill(0); });
+            quote! { #offset + #reserve }
+        }
+        None => offset,
+    };
+
+    Ok(quote! {
+        impl #impl_generics jaguar::JaguarConstSize for #name #ty_generics #where_clause {
+            const SIZE: usize = #size;
+
+            fn write_const(&self, out: &mut [u8]) {
+                #(#field_writes)*
+            }
+        }
+    })
+}
+
+/// Returns whether `tokens` mention the type `name`, by name or as `Self`.
+fn names_type(tokens: TokenStream2, name: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == *name || ident == "Self",
+        TokenTree::Group(group) => names_type(group.stream(), name),
+        _ => false,
+    })
+}
+
+#[proc_macro_derive(JaguarSchema, attributes(jaguar))]
+pub fn derive_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_schema(&input)
+        .and_then(|tokens| with_crate_path(&input, tokens))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn expand_schema(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = with_bound(input.generics.clone(), input, quote! { jaguar::JaguarSchema });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (container, fields) = match struct_fields(input)? {
+        Some(parsed) => parsed,
+        None => return Err(syn::Error::new_spanned(name, "`JaguarSchema` can only be derived for structs")),
+    };
+
+    let mut field_schemas = Vec::with_capacity(fields.len());
+    for (index, field) in encode_order(&container, fields)? {
+        let attrs = struct_field_attrs(&container, field)?;
+        let with = attrs.with.is_some() && !attrs.bytes;
+        if with || attrs.module.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`JaguarSchema` does not support `with`, `compat`, `repr`, `raw`, `len`, `fixed` or `module`",
+            ));
+        }
+
+        let ty = &field.ty;
+        let schema = if attrs.delta {
+            quote! { jaguar::schema::Schema::DeltaVec }
+        } else if attrs.bytes {
+            quote! { <jaguar::__private::Vec<u8> as jaguar::JaguarSchema>::schema() }
+        } else if let Some(borrow) = attrs.borrow {
+            match borrow {
+                Borrow::Str => quote! { jaguar::schema::Schema::String },
+                Borrow::Bytes => quote! { <jaguar::__private::Vec<u8> as jaguar::JaguarSchema>::schema() },
+            }
+        } else {
+            // schemas are trees, so a type containing itself would recurse
+            // forever building one
+            if names_type(quote! { #ty }, name) {
+                return Err(syn::Error::new_spanned(ty, "`JaguarSchema` cannot describe recursive types"));
+            }
+            quote! { <#ty as jaguar::JaguarSchema>::schema() }
+        };
+        if container.transparent {
+            return Ok(quote! {
+                impl #impl_generics jaguar::JaguarSchema for #name #ty_generics #where_clause {
+                    fn schema() -> jaguar::schema::Schema {
+                        #schema
+                    }
+                }
+            });
+        }
+
+        let field_name = match &field.ident {
+            Some(ident) => ident.unraw().to_string(),
+            None => format!("_{}", index),
+        };
+        let bits = match attrs.bits {
+            Some(bits) => quote! { ::core::option::Option::Some(#bits) },
+            None => quote! { ::core::option::Option::None },
+        };
+        field_schemas.push(quote! {
+            jaguar::schema::FieldSchema {
+                name: jaguar::__private::String::from(#field_name),
+                schema: #schema,
+                bits: #bits,
+            }
+        });
+    }
+    let reserve = container.reserve.unwrap_or(0);
+    let struct_name = name.to_string();
+
+    Ok(quote! {
+        impl #impl_generics jaguar::JaguarSchema for #name #ty_generics #where_clause {
+            fn schema() -> jaguar::schema::Schema {
+                jaguar::schema::Schema::Struct(jaguar::schema::StructSchema {
+                    name: jaguar::__private::String::from(#struct_name),
+                    fields: jaguar::__private::Vec::from([#(#field_schemas),*]),
+                    reserve: #reserve,
                 })
             }
         }
+    })
+}
+
+#[proc_macro_derive(JaguarView, attributes(jaguar))]
+pub fn derive_view(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_view(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn expand_view(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let view = format_ident!("{}View", name);
+
+    let (fields, container) = match struct_fields(input)? {
+        Some((container, fields)) => (encode_order(&container, fields)?, container),
+        None => return Err(syn::Error::new_spanned(name, "`JaguarView` can only be derived for structs")),
     };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&input.generics, "`JaguarView` does not support generic structs"));
+    }
+
+    // each accessor skips every field before its own
+    let mut skips = Vec::with_capacity(fields.len());
+    let mut accessors = Vec::with_capacity(fields.len());
+    let mut methods = Vec::with_capacity(fields.len());
+    for (index, field) in fields {
+        let attrs = struct_field_attrs(&container, field)?;
+        if attrs.bits.is_some() {
+            return Err(syn::Error::new_spanned(field, "`JaguarView` does not support `bits` fields"));
+        }
+
+        let ty = &field.ty;
+        if !attrs.is_custom() {
+            let method = match &field.ident {
+                Some(ident) => ident.clone(),
+                None => format_ident!("_{}", index),
+            };
+            let doc = format!("A view of `{}`.", method.unraw());
+            methods.push(method.unraw().to_string());
+            accessors.push(quote! {
+                #[doc = #doc]
+                pub fn #method(&self) -> ::core::result::Result<<#ty as jaguar::JaguarView<'a>>::View, jaguar::SerError> {
+                    let de = &mut jaguar::JaguarDeserializer::new(self.data);
+                    #(#skips)*
+                    ::core::result::Result::Ok(<#ty as jaguar::JaguarView<'a>>::view(&self.data[de.position()..]))
+                }
+            });
+        }
+        skips.push(validate_field(&attrs, ty));
+    }
+
+    // fields named like these take the name, and the methods stay reachable
+    // through `StructView`
+    let get = (!methods.iter().any(|method| method == "get")).then(|| {
+        quote! {
+            /// Decodes the whole value.
+            pub fn get(&self) -> ::core::result::Result<#name, jaguar::SerError> {
+                jaguar::view::StructView::get(self)
+            }
+        }
+    });
+    let data = (!methods.iter().any(|method| method == "data")).then(|| {
+        quote! {
+            /// Returns the input from the start of the value, including
+            /// anything encoded after it.
+            pub fn data(&self) -> &'a [u8] {
+                self.data
+            }
+        }
+    });
+
+    let doc = format!("A lazy view of an encoded [`{}`].", name);
+    let impls = with_crate_path(input, quote! {
+        impl<'a> #view<'a> {
+            #(#accessors)*
+            #get
+            #data
+        }
+
+        impl<'a> jaguar::view::StructView<'a> for #view<'a> {
+            type Target = #name;
 
-    expanded.into()
+            fn get(&self) -> ::core::result::Result<#name, jaguar::SerError> {
+                jaguar::deserialize(self.data)
+            }
+
+            fn data(&self) -> &'a [u8] {
+                self.data
+            }
+        }
+
+        impl<'a> jaguar::JaguarView<'a> for #name {
+            type View = #view<'a>;
+
+            fn view(data: &'a [u8]) -> #view<'a> {
+                #view { data }
+            }
+        }
+    })?;
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy)]
+        #vis struct #view<'a> {
+            data: &'a [u8],
+        }
+
+        #impls
+    })
+}
+
+#[proc_macro_derive(JaguarBitfield, attributes(jaguar))]
+pub fn derive_bitfield(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_bitfield(&input)
+        .and_then(|tokens| with_crate_path(&input, tokens))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn expand_bitfield(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = with_bound(input.generics.clone(), input, quote! { jaguar::bits::BitField });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (to_bits, from_bits) = match &input.data {
+        Data::Enum(data) => {
+            let mut checks = Vec::with_capacity(data.variants.len());
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(syn::Error::new_spanned(variant, "`JaguarBitfield` enums must be fieldless"));
+                }
+                let ident = &variant.ident;
+                checks.push(quote! {
+                    if bits == Self::#ident as u64 {
+                        return ::core::result::Result::Ok(Self::#ident);
+                    }
+                });
+            }
+            let arms = data.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                quote! { Self::#ident => Self::#ident as u64, }
+            });
+            (
+                quote! { match self { #(#arms)* } },
+                quote! {
+                    #(#checks)*
+                    ::core::result::Result::Err(jaguar::SerError::InvalidData)
+                },
+            )
+        }
+        Data::Struct(data) => {
+            // fields are packed least significant bit first, matching a run
+            // of `bits` fields written one by one
+            let mut shift = 0u32;
+            let mut packs = Vec::with_capacity(data.fields.len());
+            let mut unpacks = Vec::with_capacity(data.fields.len());
+            let mut bindings = Vec::with_capacity(data.fields.len());
+            for (index, field) in data.fields.iter().enumerate() {
+                let bits = match parse_field_attrs(field)?.bits {
+                    Some(bits) => bits,
+                    None => return Err(syn::Error::new_spanned(field, "`JaguarBitfield` fields need `#[jaguar(bits = N)]`")),
+                };
+                // the top bit stays clear, so the all-ones value flagging an
+                // out-of-range field never fits the struct's width
+                if shift + bits > 63 {
+                    return Err(syn::Error::new_spanned(field, "`JaguarBitfield` structs must fit in 63 bits"));
+                }
+                let (member, binding) = field_idents(index, field);
+                let ty = &field.ty;
+                let mask = (1u64 << bits) - 1;
+                packs.push(quote! {
+                    let value = jaguar::bits::BitField::to_bits(&self.#member);
+                    if value & !#mask != 0 {
+                        return u64::MAX;
+                    }
+                    out |= value << #shift;
+                });
+                unpacks.push(quote! {
+                    let #binding = <#ty as jaguar::bits::BitField>::from_bits((bits >> #shift) & #mask)?;
+                });
+                bindings.push(binding);
+                shift += bits;
+            }
+            let construct = match &data.fields {
+                Fields::Named(_) => quote! { Self { #(#bindings,)* } },
+                Fields::Unnamed(_) => quote! { Self(#(#bindings,)*) },
+                Fields::Unit => quote! { Self },
+            };
+            // a field out of range for its bits yields all ones, which the bit
+            // writer rejects as too wide for the struct
+            (
+                quote! {
+                    let mut out = 0u64;
+                    #(#packs)*
+                    out
+                },
+                quote! {
+                    if bits >> #shift != 0 {
+                        return ::core::result::Result::Err(jaguar::SerError::InvalidData);
+                    }
+                    #(#unpacks)*
+                    ::core::result::Result::Ok(#construct)
+                },
+            )
+        }
+        Data::Union(_) => return Err(syn::Error::new_spanned(name, "`JaguarBitfield` cannot be derived for unions")),
+    };
+
+    Ok(quote! {
+        impl #impl_generics jaguar::bits::BitField for #name #ty_generics #where_clause {
+            fn to_bits(&self) -> u64 {
+                #to_bits
+            }
+
+            fn from_bits(bits: u64) -> ::core::result::Result<Self, jaguar::SerError> {
+                #from_bits
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(JaguarAccount, attributes(jaguar))]
+pub fn derive_account(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_tag(&input, quote! { jaguar::JaguarAccount }, quote! { DISCRIMINATOR }, quote! { jaguar::discriminator_of })
+        .and_then(|tokens| with_crate_path(&input, tokens))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(JaguarEvent, attributes(jaguar))]
+pub fn derive_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_tag(&input, quote! { jaguar::JaguarEvent }, quote! { DISCRIMINATOR }, quote! {
+        jaguar::event_discriminator_of
+    })
+    .and_then(|tokens| with_crate_path(&input, tokens))
+    .unwrap_or_else(|err| err.to_compile_error())
+    .into()
+}
+
+#[proc_macro_derive(JaguarMessage, attributes(jaguar))]
+pub fn derive_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_tag(&input, quote! { jaguar::envelope::JaguarMessage }, quote! { TYPE_TAG }, quote! {
+        jaguar::envelope::message_tag_of
+    })
+    .and_then(|tokens| with_crate_path(&input, tokens))
+    .unwrap_or_else(|err| err.to_compile_error())
+    .into()
+}
+
+/// Implements `tag_trait`, whose only item is the 8-byte constant `tag`, as
+/// `tag_of` the type's name or `#[jaguar(name = "...")]`.
+fn expand_tag(
+    input: &DeriveInput,
+    tag_trait: TokenStream2,
+    tag: TokenStream2,
+    tag_of: TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    // every instantiation would share the tag, so `Vec<u8>` would pass for
+    // `Vec<u64>`
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "tags can't be derived for generic types; implement the trait for each instantiation instead",
+        ));
+    }
+    let container = parse_container_attrs(input)?;
+    let tag_name = container.name.unwrap_or_else(|| name.unraw().to_string());
+
+    Ok(quote! {
+        impl #tag_trait for #name {
+            const #tag: [u8; jaguar::discriminator::DISCRIMINATOR_LEN] = #tag_of(#tag_name);
+        }
+    })
 }
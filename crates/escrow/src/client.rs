@@ -0,0 +1,72 @@
+//! Off-chain helpers for building escrow instruction data and sizing
+//! accounts.
+
+use crate::{
+    instruction::EscrowInstruction,
+    state::{Escrow, HEADER_LEN},
+};
+
+/// Instruction data for [`EscrowInstruction::Make`].
+pub fn make(taker: [u8; 32], amount: u64, memo: &str) -> Vec<u8> {
+    encode(&EscrowInstruction::Make {
+        taker,
+        amount,
+        memo: memo.to_string(),
+    })
+}
+
+/// Instruction data for [`EscrowInstruction::Take`].
+pub fn take() -> Vec<u8> {
+    encode(&EscrowInstruction::Take)
+}
+
+/// Instruction data for [`EscrowInstruction::Cancel`].
+pub fn cancel() -> Vec<u8> {
+    encode(&EscrowInstruction::Cancel)
+}
+
+/// Number of bytes to allocate for an escrow account holding `memo`.
+///
+/// Varints make the encoded size depend on the values, so this sizes for the
+/// largest possible `amount`.
+pub fn escrow_space(memo: &str) -> usize {
+    let worst_case = Escrow {
+        maker: [0u8; 32],
+        taker: [0u8; 32],
+        amount: u64::MAX,
+        memo: memo.to_string(),
+    };
+    HEADER_LEN + encode_len(&worst_case)
+}
+
+fn encode<T: jaguar::JaguarSerialize>(value: &T) -> Vec<u8> {
+    jaguar::serialize(value).expect("encoding into a growable buffer cannot fail")
+}
+
+fn encode_len<T: jaguar::JaguarSerialize>(value: &T) -> usize {
+    encode(value).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jaguar::JaguarDeserialize;
+
+    #[test]
+    fn test_instruction_roundtrip() {
+        let data = make([3u8; 32], 1_000, "deposit");
+        let mut de = jaguar::JaguarDeserializer::new(&data);
+        assert_eq!(
+            EscrowInstruction::deserialize(&mut de).unwrap(),
+            EscrowInstruction::Make {
+                taker: [3u8; 32],
+                amount: 1_000,
+                memo: "deposit".to_string(),
+            }
+        );
+
+        assert_eq!(take(), [EscrowInstruction::TAKE]);
+        assert_eq!(cancel(), [EscrowInstruction::CANCEL]);
+        assert_eq!(escrow_space(""), HEADER_LEN + 32 + 32 + 10 + 1);
+    }
+}
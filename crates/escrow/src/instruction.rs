@@ -0,0 +1,59 @@
+use jaguar::{JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+
+/// Instructions understood by the escrow program, encoded as a one byte tag
+/// followed by the variant's fields.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EscrowInstruction {
+    /// Initializes a pre-funded escrow account.
+    ///
+    /// Accounts: `[signer] maker`, `[writable] escrow`
+    Make {
+        taker: [u8; 32],
+        amount: u64,
+        memo: String,
+    },
+    /// Releases the escrowed lamports to the taker and closes the account.
+    ///
+    /// Accounts: `[signer, writable] taker`, `[writable] escrow`
+    Take,
+    /// Returns the escrowed lamports to the maker and closes the account.
+    ///
+    /// Accounts: `[signer, writable] maker`, `[writable] escrow`
+    Cancel,
+}
+
+impl EscrowInstruction {
+    pub const MAKE: u8 = 0;
+    pub const TAKE: u8 = 1;
+    pub const CANCEL: u8 = 2;
+}
+
+impl JaguarSerialize for EscrowInstruction {
+    fn serialize(&self, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        match self {
+            Self::Make { taker, amount, memo } => {
+                ser.write_u8(Self::MAKE)?;
+                taker.serialize(ser)?;
+                amount.serialize(ser)?;
+                ser.write_str(memo)
+            }
+            Self::Take => ser.write_u8(Self::TAKE),
+            Self::Cancel => ser.write_u8(Self::CANCEL),
+        }
+    }
+}
+
+impl<'a> JaguarDeserialize<'a> for EscrowInstruction {
+    fn deserialize(de: &mut JaguarDeserializer<'a>) -> Result<Self, SerError> {
+        match de.read_u8()? {
+            Self::MAKE => Ok(Self::Make {
+                taker: <[u8; 32]>::deserialize(de)?,
+                amount: u64::deserialize(de)?,
+                memo: String::deserialize(de)?,
+            }),
+            Self::TAKE => Ok(Self::Take),
+            Self::CANCEL => Ok(Self::Cancel),
+            _ => Err(SerError::InvalidData),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! Lamport escrow program built on Jaguar serialization.
+//!
+//! This crate is meant to be copied from. It shows the pieces most programs
+//! need when adopting Jaguar:
+//!
+//! - [`state`]: discriminated, versioned account layouts with `load`/`store`
+//!   helpers and an upgrade path for older layouts
+//! - [`instruction`]: tagged instruction enums encoded with Jaguar
+//! - [`processor`]: the on-chain handlers
+//! - [`client`]: off-chain builders for instruction data and account sizing
+//!
+//! Build with `no-entrypoint` to use the client helpers from another crate.
+
+#![allow(unexpected_cfgs)]
+
+pub mod client;
+pub mod instruction;
+pub mod processor;
+pub mod state;
+
+#[cfg(not(feature = "no-entrypoint"))]
+mod entrypoint {
+    use crate::processor::process_instruction;
+    use pinocchio::{default_allocator, default_panic_handler, program_entrypoint};
+
+    program_entrypoint!(process_instruction);
+    default_allocator!();
+    default_panic_handler!();
+}
@@ -0,0 +1,75 @@
+use crate::{
+    instruction::EscrowInstruction,
+    state::{self, Escrow},
+};
+use jaguar::{JaguarDeserialize, JaguarDeserializer};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult};
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let mut de = JaguarDeserializer::new(data);
+    let instruction =
+        EscrowInstruction::deserialize(&mut de).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        EscrowInstruction::Make { taker, amount, memo } => {
+            process_make(program_id, accounts, Escrow { maker: [0u8; 32], taker, amount, memo })
+        }
+        EscrowInstruction::Take => process_release(program_id, accounts, |escrow| &escrow.taker),
+        EscrowInstruction::Cancel => process_release(program_id, accounts, |escrow| &escrow.maker),
+    }
+}
+
+fn process_make(program_id: &Pubkey, accounts: &[AccountInfo], mut escrow: Escrow) -> ProgramResult {
+    let [maker, escrow_account, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !maker.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if escrow_account.owner() != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if escrow_account.lamports() < escrow.amount {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    let mut data = escrow_account.try_borrow_mut_data()?;
+    if state::is_initialized(&data) {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    escrow.maker = *maker.key();
+    state::store(&mut data, &escrow)
+}
+
+/// Moves every lamport held by the escrow to `recipient` (which must match the
+/// key picked from the escrow state and have signed) and closes the account.
+fn process_release(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    expected: impl Fn(&Escrow) -> &[u8; 32],
+) -> ProgramResult {
+    let [recipient, escrow_account, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !recipient.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if escrow_account.owner() != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let escrow = Escrow::load_any(&escrow_account.try_borrow_data()?)?;
+    if expected(&escrow) != recipient.key() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let lamports = escrow_account.lamports();
+    *recipient.try_borrow_mut_lamports()? += lamports;
+    escrow_account.try_borrow_mut_data()?.fill(0);
+    escrow_account.close()
+}
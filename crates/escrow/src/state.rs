@@ -0,0 +1,151 @@
+use jaguar::{JaguarDeserialize, JaguarDeserializeOwned, JaguarDeserializer, JaguarSerialize, JaguarSerializer};
+use pinocchio::program_error::ProgramError;
+
+/// Length of the `[discriminator: 8][version: 1]` header preceding every
+/// account body.
+pub const HEADER_LEN: usize = 9;
+
+/// A program account laid out as `[discriminator][version][jaguar body]`.
+///
+/// The discriminator keeps one account type from being decoded as another,
+/// while the version lets a layout evolve without breaking existing accounts.
+pub trait AccountState: JaguarSerialize + JaguarDeserializeOwned {
+    const DISCRIMINATOR: [u8; 8];
+    const VERSION: u8;
+}
+
+/// Returns `true` if `data` carries a non-zero discriminator.
+#[inline]
+pub fn is_initialized(data: &[u8]) -> bool {
+    data.len() >= HEADER_LEN && data[..8] != [0u8; 8]
+}
+
+/// Checks the discriminator of `T` and returns the stored layout version.
+#[inline]
+pub fn version<T: AccountState>(data: &[u8]) -> Result<u8, ProgramError> {
+    if data.len() < HEADER_LEN {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    if data[..8] != T::DISCRIMINATOR {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(data[8])
+}
+
+/// Decodes a `T` stored at exactly `T::VERSION`.
+pub fn load<T: AccountState>(data: &[u8]) -> Result<T, ProgramError> {
+    if version::<T>(data)? != T::VERSION {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let mut de = JaguarDeserializer::new(&data[HEADER_LEN..]);
+    T::deserialize(&mut de).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+/// Writes the header and body of `value` into the start of `data`.
+pub fn store<T: AccountState>(data: &mut [u8], value: &T) -> Result<(), ProgramError> {
+    let mut ser = JaguarSerializer::with_capacity(data.len());
+    T::DISCRIMINATOR
+        .serialize(&mut ser)
+        .and_then(|_| ser.write_u8(T::VERSION))
+        .and_then(|_| value.serialize(&mut ser))
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    let bytes = ser.data();
+    if bytes.len() > data.len() {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    data[..bytes.len()].copy_from_slice(bytes);
+    Ok(())
+}
+
+/// First 8 bytes of `sha256("account:Escrow")`.
+pub const ESCROW_DISCRIMINATOR: [u8; 8] = jaguar::discriminator_of("Escrow");
+
+/// The original escrow layout, kept so accounts created before memos were
+/// introduced still load.
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+pub struct EscrowV1 {
+    pub maker: [u8; 32],
+    pub taker: [u8; 32],
+    pub amount: u64,
+}
+
+impl AccountState for EscrowV1 {
+    const DISCRIMINATOR: [u8; 8] = ESCROW_DISCRIMINATOR;
+    const VERSION: u8 = 1;
+}
+
+/// Lamports held for `taker` until they claim them or `maker` cancels.
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+pub struct Escrow {
+    pub maker: [u8; 32],
+    pub taker: [u8; 32],
+    pub amount: u64,
+    pub memo: String,
+}
+
+impl AccountState for Escrow {
+    const DISCRIMINATOR: [u8; 8] = ESCROW_DISCRIMINATOR;
+    const VERSION: u8 = 2;
+}
+
+impl From<EscrowV1> for Escrow {
+    fn from(v1: EscrowV1) -> Self {
+        Self {
+            maker: v1.maker,
+            taker: v1.taker,
+            amount: v1.amount,
+            memo: String::new(),
+        }
+    }
+}
+
+impl Escrow {
+    /// Decodes an escrow stored with any known layout version.
+    pub fn load_any(data: &[u8]) -> Result<Self, ProgramError> {
+        match version::<Self>(data)? {
+            EscrowV1::VERSION => load::<EscrowV1>(data).map(Into::into),
+            Self::VERSION => load::<Self>(data),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_load_roundtrip() {
+        let escrow = Escrow {
+            maker: [1u8; 32],
+            taker: [2u8; 32],
+            amount: 5_000_000,
+            memo: "rent for march".to_string(),
+        };
+
+        let mut data = vec![0u8; 128];
+        assert!(!is_initialized(&data));
+        store(&mut data, &escrow).unwrap();
+        assert!(is_initialized(&data));
+        assert_eq!(load::<Escrow>(&data).unwrap(), escrow);
+
+        let mut small = vec![0u8; 16];
+        assert_eq!(store(&mut small, &escrow), Err(ProgramError::AccountDataTooSmall));
+    }
+
+    #[test]
+    fn test_v1_upgrade() {
+        let v1 = EscrowV1 {
+            maker: [1u8; 32],
+            taker: [2u8; 32],
+            amount: 42,
+        };
+
+        let mut data = vec![0u8; 128];
+        store(&mut data, &v1).unwrap();
+
+        assert_eq!(load::<Escrow>(&data), Err(ProgramError::InvalidAccountData));
+        assert_eq!(Escrow::load_any(&data).unwrap(), Escrow::from(v1));
+    }
+}
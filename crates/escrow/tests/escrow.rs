@@ -0,0 +1,93 @@
+//! End-to-end tests against the SBF build of the program.
+//!
+//! Run `cargo build-sbf --manifest-path crates/escrow/Cargo.toml` first, then
+//! `cargo test -p jaguar-escrow -- --ignored`. Compute unit usage of every
+//! instruction is printed for benchmarking.
+
+use jaguar_escrow::{
+    client,
+    state::{self, Escrow},
+};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+    transaction::Transaction,
+};
+
+async fn start() -> (ProgramTestContext, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("jaguar_escrow", program_id, None);
+    (program_test.start_with_context().await, program_id)
+}
+
+async fn send(ctx: &mut ProgramTestContext, instructions: &[Instruction], signers: &[&Keypair]) -> u64 {
+    let blockhash = ctx.get_new_latest_blockhash().await.unwrap();
+    let transaction =
+        Transaction::new_signed_with_payer(instructions, Some(&ctx.payer.pubkey()), signers, blockhash);
+    let result = ctx
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    result.result.unwrap();
+    result.metadata.unwrap().compute_units_consumed
+}
+
+#[tokio::test]
+#[ignore = "requires the program to be built with cargo build-sbf"]
+async fn test_make_and_take() {
+    let (mut ctx, program_id) = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let escrow = Keypair::new();
+    let taker = Keypair::new();
+
+    let amount = 500_000_000;
+    let memo = "invoice #42";
+    let space = client::escrow_space(memo);
+    let lamports = Rent::default().minimum_balance(space) + amount;
+
+    let make = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(payer.pubkey(), true),
+            AccountMeta::new(escrow.pubkey(), false),
+        ],
+        data: client::make(taker.pubkey().to_bytes(), amount, memo),
+    };
+    let cu = send(
+        &mut ctx,
+        &[
+            system_instruction::create_account(&payer.pubkey(), &escrow.pubkey(), lamports, space as u64, &program_id),
+            make,
+        ],
+        &[&payer, &escrow],
+    )
+    .await;
+    println!("make cu_usage: {}", cu);
+
+    let account = ctx.banks_client.get_account(escrow.pubkey()).await.unwrap().unwrap();
+    let stored = state::load::<Escrow>(&account.data).unwrap();
+    assert_eq!(stored.maker, payer.pubkey().to_bytes());
+    assert_eq!(stored.memo, memo);
+
+    let take = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(taker.pubkey(), true),
+            AccountMeta::new(escrow.pubkey(), false),
+        ],
+        data: client::take(),
+    };
+    let cu = send(&mut ctx, &[take], &[&payer, &taker]).await;
+    println!("take cu_usage: {}", cu);
+
+    assert_eq!(ctx.banks_client.get_balance(taker.pubkey()).await.unwrap(), lamports);
+    assert!(ctx.banks_client.get_account(escrow.pubkey()).await.unwrap().is_none());
+}
@@ -0,0 +1,177 @@
+//! Fuzzing and property-testing harness for Jaguar.
+//!
+//! [`read_all`] and [`decode_types`] feed arbitrary bytes through every reader
+//! method and a set of derived types covering each field attribute. Malformed
+//! input must only ever produce a `SerError`, never a panic, so these back the
+//! cargo-fuzz targets in `fuzz/` as well as the proptest suite in `tests/`.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```bash
+//! cargo +nightly fuzz run readers --fuzz-dir crates/fuzz/fuzz
+//! cargo +nightly fuzz run types --fuzz-dir crates/fuzz/fuzz
+//! ```
+
+use jaguar::{
+    JaguarDeserialize, JaguarDeserializeOwned, JaguarDeserializer, JaguarSerialize, JaguarSerializer,
+    SerError, StrRef,
+};
+
+type Reader = fn(&mut JaguarDeserializer<'_>) -> Result<(), SerError>;
+
+/// Every reader method, each consuming at least one byte on success.
+const READERS: &[Reader] = &[
+    |de| de.read_u8().map(drop),
+    |de| de.read_varint().map(drop),
+    |de| de.read_signed_varint().map(drop),
+    |de| de.read_bool().map(drop),
+    |de| de.read_f32().map(drop),
+    |de| de.read_f64().map(drop),
+    |de| de.read_str().map(drop),
+    |de| de.read_bytes().map(drop),
+    |de| de.read_str_chunks(3)?.try_for_each(|chunk| chunk.map(drop)),
+    |de| de.read_u8_vec().map(drop),
+    |de| de.read_u16_vec().map(drop),
+    |de| de.read_u32_vec().map(drop),
+    |de| de.read_u64_vec().map(drop),
+    |de| de.read_u64_vec_delta().map(drop),
+    |de| de.read_i8_vec().map(drop),
+    |de| de.read_i16_vec().map(drop),
+    |de| de.read_i32_vec().map(drop),
+    |de| de.read_u24_vec().map(drop),
+    |de| de.read_u48_vec().map(drop),
+    |de| de.read_i64_vec().map(drop),
+    |de| de.read_i32_vec_packed().map(drop),
+    |de| de.read_u16_vec_raw().map(drop),
+    |de| de.read_i64_vec_raw().map(drop),
+    |de| de.read_u32_vec_svb().map(drop),
+    |de| de.read_f32_vec().map(drop),
+    |de| de.read_f64_vec().map(drop),
+    |de| de.read_f32_vec_raw().map(drop),
+    |de| de.read_f64_vec_raw().map(drop),
+    |de| de.read_bool_vec().map(drop),
+    |de| de.read_bool_vec_rle().map(drop),
+    |de| de.read_u8_vec_rle().map(drop),
+    |de| de.read_string_vec().map(drop),
+    |de| de.read_map_sorted::<u16, String>().map(drop),
+    |de| de.read_seq::<String>()?.try_for_each(|item| item.map(drop)),
+    |de| de.read_u8_into(&mut Vec::new()),
+    |de| de.read_u32_into(&mut Vec::new()),
+    |de| de.read_u32_svb_into(&mut Vec::new()),
+    |de| de.read_u64_delta_into(&mut Vec::new()),
+    |de| de.read_bool_into(&mut Vec::new()),
+    |de| de.read_fixed_array::<u64, 3>().map(drop),
+    |de| de.read_array_ref::<7>().map(drop),
+    |de| de.read_signature_ref().map(drop),
+    |de| de.read_uint_le::<5>().map(drop),
+    |de| {
+        let mut section = de.read_section()?;
+        while section.has_data() {
+            section.read_varint()?;
+        }
+        Ok(())
+    },
+    |de| {
+        let len = usize::from(de.read_u8()?);
+        de.take(len)?.read_str().map(drop)
+    },
+    |de| {
+        let width = u32::from(de.read_u8()? % 72);
+        let mut bits = de.bit_reader();
+        bits.read_bits(width)?;
+        bits.finish()
+    },
+];
+
+/// Runs every reader over `data` until it fails or the input is exhausted,
+/// with and without the string table and canonical mode enabled.
+pub fn read_all(data: &[u8]) {
+    for reader in READERS {
+        for (string_table, canonical) in [(false, false), (true, false), (false, true), (true, true)] {
+            let mut de = JaguarDeserializer::new(data);
+            if string_table {
+                de.enable_string_table();
+            }
+            if canonical {
+                de.enable_canonical();
+            }
+            while de.has_data() && reader(&mut de).is_ok() {}
+        }
+    }
+}
+
+/// Wraps a `u64` to exercise `#[jaguar(transparent)]`.
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+#[jaguar(transparent)]
+pub struct Lamports(pub u64);
+
+/// An account-like struct touching every primitive and field attribute.
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+#[jaguar(reserve = 8)]
+pub struct Account {
+    pub authority: [u8; 32],
+    pub lamports: Lamports,
+    pub tag: u8,
+    pub nonce: u16,
+    pub index: u32,
+    pub slot: u64,
+    pub supply: u128,
+    pub delta: (i8, i16),
+    pub offset: i32,
+    pub drift: i64,
+    pub ratio: f32,
+    pub price: f64,
+    pub frozen: bool,
+    pub name: String,
+    pub tags: Vec<String>,
+    pub weights: [u16; 4],
+    #[jaguar(delta)]
+    pub slots: Vec<u64>,
+    #[jaguar(compat = "borsh")]
+    pub legacy_memo: String,
+    #[jaguar(compat = "borsh")]
+    pub legacy_limits: Vec<u16>,
+    #[jaguar(compat = "borsh")]
+    pub legacy_expiry: Option<i64>,
+}
+
+/// A struct borrowing its string from the input.
+#[derive(JaguarSerialize, JaguarDeserialize, Debug, Clone, PartialEq)]
+pub struct Borrowed<'a> {
+    pub label: StrRef<'a>,
+    pub entries: Vec<(u32, StrRef<'a>)>,
+}
+
+/// Decodes `data` as each derived type, checking that [`jaguar::validate`]
+/// agrees with decoding and that anything accepted re-encodes stably.
+pub fn decode_types(data: &[u8]) {
+    check_owned::<Account>(data);
+    check_owned::<Vec<Lamports>>(data);
+
+    let decoded = jaguar::deserialize::<Borrowed>(data);
+    assert_eq!(jaguar::validate::<Borrowed>(data).is_ok(), decoded.is_ok());
+    if let Ok(value) = decoded {
+        let encoded = jaguar::serialize(&value).unwrap();
+        assert_eq!(jaguar::deserialize::<Borrowed>(&encoded).unwrap(), value);
+    }
+}
+
+fn check_owned<T: JaguarSerialize + JaguarDeserializeOwned>(data: &[u8]) {
+    let decoded = jaguar::deserialize_owned::<T>(data);
+    assert_eq!(jaguar::validate::<T>(data).is_ok(), decoded.is_ok());
+
+    // compare encodings rather than values, so NaN floats still round-trip
+    if let Ok(value) = decoded {
+        let encoded = encode(&value);
+        let redecoded = jaguar::deserialize_owned::<T>(&encoded).unwrap();
+        assert_eq!(encode(&redecoded), encoded);
+    }
+}
+
+fn encode<T: JaguarSerialize>(value: &T) -> Vec<u8> {
+    let mut ser = JaguarSerializer::new();
+    value.serialize(&mut ser).unwrap();
+    ser.finish()
+}
@@ -0,0 +1,197 @@
+use jaguar::{JaguarDeserializer, JaguarSerializer};
+use jaguar_fuzz::{Account, Lamports};
+use proptest::prelude::*;
+
+/// Writes with `write`, then checks `read` returns `expected` and consumes
+/// every byte.
+macro_rules! check_roundtrip {
+    ($write:ident($value:expr), $read:ident, $expected:expr) => {{
+        let mut ser = JaguarSerializer::new();
+        ser.$write($value).unwrap();
+        let data = ser.finish();
+        let mut de = JaguarDeserializer::new(&data);
+        prop_assert_eq!(&de.$read().unwrap(), &$expected);
+        prop_assert!(!de.has_data());
+    }};
+}
+
+fn account() -> impl Strategy<Value = Account> {
+    (
+        (any::<[u8; 32]>(), any::<u64>(), any::<u8>(), any::<u16>(), any::<u32>(), any::<u64>()),
+        (any::<u128>(), any::<(i8, i16)>(), any::<i32>(), any::<i64>(), any::<f32>(), any::<f64>()),
+        (any::<bool>(), ".*", prop::collection::vec(".*", 0..4), any::<[u16; 4]>()),
+        (
+            prop::collection::vec(any::<u64>(), 0..16),
+            ".*",
+            prop::collection::vec(any::<u16>(), 0..16),
+            any::<Option<i64>>(),
+        ),
+    )
+        .prop_filter("NaN never compares equal", |(_, (_, _, _, _, ratio, price), _, _)| {
+            !ratio.is_nan() && !price.is_nan()
+        })
+        .prop_map(
+            |(
+                (authority, lamports, tag, nonce, index, slot),
+                (supply, delta, offset, drift, ratio, price),
+                (frozen, name, tags, weights),
+                (slots, legacy_memo, legacy_limits, legacy_expiry),
+            )| Account {
+                authority,
+                lamports: Lamports(lamports),
+                tag,
+                nonce,
+                index,
+                slot,
+                supply,
+                delta,
+                offset,
+                drift,
+                ratio,
+                price,
+                frozen,
+                name,
+                tags,
+                weights,
+                slots,
+                legacy_memo,
+                legacy_limits,
+                legacy_expiry,
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_input_never_panics(data in prop::collection::vec(any::<u8>(), 0..512)) {
+        jaguar_fuzz::read_all(&data);
+        jaguar_fuzz::decode_types(&data);
+    }
+
+    #[test]
+    fn varints_roundtrip(unsigned in any::<u64>(), signed in any::<i64>()) {
+        check_roundtrip!(write_varint(unsigned), read_varint, unsigned);
+        check_roundtrip!(write_signed_varint(signed), read_signed_varint, signed);
+    }
+
+    #[test]
+    fn scalars_roundtrip(
+        byte in any::<u8>(),
+        flag in any::<bool>(),
+        single in any::<f32>().prop_filter("NaN", |v| !v.is_nan()),
+        double in any::<f64>().prop_filter("NaN", |v| !v.is_nan()),
+    ) {
+        check_roundtrip!(write_u8(byte), read_u8, byte);
+        check_roundtrip!(write_bool(flag), read_bool, flag);
+        check_roundtrip!(write_f32(single), read_f32, single);
+        check_roundtrip!(write_f64(double), read_f64, double);
+    }
+
+    #[test]
+    fn strings_roundtrip(s in ".*", bytes in prop::collection::vec(any::<u8>(), 0..64)) {
+        check_roundtrip!(write_str(&s), read_str, s.as_str());
+        check_roundtrip!(write_bytes(&bytes), read_bytes, bytes.as_slice());
+
+        let mut ser = JaguarSerializer::new();
+        ser.write_str(&s).unwrap();
+        let data = ser.finish();
+        let mut de = JaguarDeserializer::new(&data);
+        let chunks: Result<String, _> = de.read_str_chunks(3).unwrap().collect();
+        prop_assert_eq!(chunks.unwrap(), s);
+    }
+
+    #[test]
+    fn string_table_roundtrip(strings in prop::collection::vec("[a-c]{0,2}", 0..16)) {
+        let mut ser = JaguarSerializer::new();
+        ser.enable_string_table();
+        for s in &strings {
+            ser.write_str(s).unwrap();
+        }
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        de.enable_string_table();
+        for s in &strings {
+            prop_assert_eq!(de.read_str().unwrap(), s.as_str());
+        }
+        prop_assert!(!de.has_data());
+    }
+
+    #[test]
+    fn unsigned_slices_roundtrip(
+        u8s in prop::collection::vec(any::<u8>(), 0..32),
+        u16s in prop::collection::vec(any::<u16>(), 0..32),
+        u32s in prop::collection::vec(any::<u32>(), 0..32),
+        u64s in prop::collection::vec(any::<u64>(), 0..32),
+    ) {
+        check_roundtrip!(write_u8_slice(&u8s), read_u8_vec, u8s);
+        check_roundtrip!(write_u16_slice(&u16s), read_u16_vec, u16s);
+        check_roundtrip!(write_u32_slice(&u32s), read_u32_vec, u32s);
+        check_roundtrip!(write_u64_slice(&u64s), read_u64_vec, u64s);
+        check_roundtrip!(write_u64_slice_delta(&u64s), read_u64_vec_delta, u64s);
+    }
+
+    #[test]
+    fn signed_slices_roundtrip(
+        i8s in prop::collection::vec(any::<i8>(), 0..32),
+        i16s in prop::collection::vec(any::<i16>(), 0..32),
+        i32s in prop::collection::vec(any::<i32>(), 0..32),
+        i64s in prop::collection::vec(any::<i64>(), 0..32),
+    ) {
+        check_roundtrip!(write_i8_slice(&i8s), read_i8_vec, i8s);
+        check_roundtrip!(write_i16_slice(&i16s), read_i16_vec, i16s);
+        check_roundtrip!(write_i32_slice(&i32s), read_i32_vec, i32s);
+        check_roundtrip!(write_i64_slice(&i64s), read_i64_vec, i64s);
+    }
+
+    #[test]
+    fn float_and_bool_slices_roundtrip(
+        f32s in prop::collection::vec(any::<f32>().prop_filter("NaN", |v| !v.is_nan()), 0..32),
+        f64s in prop::collection::vec(any::<f64>().prop_filter("NaN", |v| !v.is_nan()), 0..32),
+        bools in prop::collection::vec(any::<bool>(), 0..64),
+    ) {
+        check_roundtrip!(write_f32_slice(&f32s), read_f32_vec, f32s);
+        check_roundtrip!(write_f64_slice(&f64s), read_f64_vec, f64s);
+        check_roundtrip!(write_bool_slice(&bools), read_bool_vec, bools);
+    }
+
+    #[test]
+    fn rle_slices_roundtrip(
+        bools in prop::collection::vec(prop::bool::weighted(0.02), 0..512),
+        u8s in prop::collection::vec(prop::sample::select(vec![0u8, 0, 0, 1, 255]), 0..512),
+    ) {
+        check_roundtrip!(write_bool_slice_rle(&bools), read_bool_vec_rle, bools);
+        check_roundtrip!(write_u8_slice_rle(&u8s), read_u8_vec_rle, u8s);
+    }
+
+    #[test]
+    fn bits_roundtrip(fields in prop::collection::vec((1u32..=64, any::<u64>()), 0..16)) {
+        let fields: Vec<(u32, u64)> = fields
+            .into_iter()
+            .map(|(width, value)| (width, if width == 64 { value } else { value & ((1 << width) - 1) }))
+            .collect();
+
+        let mut ser = JaguarSerializer::new();
+        let mut bits = ser.bit_writer();
+        for &(width, value) in &fields {
+            bits.write_bits(value, width).unwrap();
+        }
+        bits.finish().unwrap();
+        let data = ser.finish();
+
+        let mut de = JaguarDeserializer::new(&data);
+        let mut bits = de.bit_reader();
+        for &(width, value) in &fields {
+            prop_assert_eq!(bits.read_bits(width).unwrap(), value);
+        }
+        bits.finish().unwrap();
+        prop_assert!(!de.has_data());
+    }
+
+    #[test]
+    fn derived_struct_roundtrip(account in account()) {
+        let data = jaguar::serialize(&account).unwrap();
+        prop_assert!(jaguar::validate::<Account>(&data).is_ok());
+        prop_assert_eq!(jaguar::deserialize_owned::<Account>(&data).unwrap(), account);
+    }
+}
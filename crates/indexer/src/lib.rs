@@ -0,0 +1,106 @@
+//! Decodes account data, instruction data and events to JSON by their
+//! 8-byte discriminator, for indexers that shouldn't link the program's
+//! crate.
+//!
+//! An [`Indexer`] maps discriminators to schemas, typically loaded with
+//! `jaguar_json::schema::from_json` from files the program publishes.
+//! Accounts are registered under `jaguar::discriminator_of(name)` and events
+//! under `jaguar::event_discriminator_of(name)`, matching
+//! `serialize_with_discriminator` and `jaguar::solana::emit`. Other tags,
+//! such as instruction discriminators, go through [`Indexer::register`].
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::{JaguarAccount, JaguarSchema, JaguarSerialize};
+//! use jaguar_indexer::Indexer;
+//! use serde_json::json;
+//!
+//! #[derive(JaguarSerialize, JaguarSchema, JaguarAccount)]
+//! struct Vault {
+//!     amount: u64,
+//! }
+//!
+//! let mut indexer = Indexer::new();
+//! indexer.register_account("Vault", Vault::schema());
+//!
+//! let data = jaguar::serialize_with_discriminator(&Vault { amount: 5 }).unwrap();
+//! let (name, value) = indexer.decode(&data).unwrap();
+//! assert_eq!(name, "Vault");
+//! assert_eq!(value, json!({ "amount": 5 }));
+//! ```
+
+use jaguar::discriminator::DISCRIMINATOR_LEN;
+use jaguar::schema::Schema;
+use jaguar_json::{decode, format_data, parse_data, Encoding};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Prefix of the log lines `sol_log_data` writes.
+const DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// Schemas by discriminator, with the name each decodes as.
+#[derive(Debug, Default)]
+pub struct Indexer {
+    types: BTreeMap<[u8; DISCRIMINATOR_LEN], (String, Schema)>,
+}
+
+impl Indexer {
+    /// Creates an empty indexer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `schema` under `discriminator`, decoding as `name`.
+    ///
+    /// Panics if another type is already registered with the same
+    /// discriminator, since their data could no longer be told apart.
+    pub fn register(&mut self, discriminator: [u8; DISCRIMINATOR_LEN], name: &str, schema: Schema) -> &mut Self {
+        if self.types.insert(discriminator, (name.into(), schema)).is_some() {
+            panic!("discriminator {:?} is registered twice", discriminator);
+        }
+        self
+    }
+
+    /// Registers the account type `name` under its account discriminator.
+    pub fn register_account(&mut self, name: &str, schema: Schema) -> &mut Self {
+        self.register(jaguar::discriminator_of(name), name, schema)
+    }
+
+    /// Registers the event type `name` under its event discriminator.
+    pub fn register_event(&mut self, name: &str, schema: Schema) -> &mut Self {
+        self.register(jaguar::event_discriminator_of(name), name, schema)
+    }
+
+    /// Decodes `data` as the type registered under its leading
+    /// discriminator, returning the type's name and value.
+    ///
+    /// Bytes after the value are ignored, since accounts may be allocated
+    /// larger than their contents.
+    /// Error offsets count from the end of the discriminator.
+    pub fn decode(&self, data: &[u8]) -> Result<(&str, Value), String> {
+        if data.len() < DISCRIMINATOR_LEN {
+            return Err(format!("{} bytes is too short for a discriminator", data.len()));
+        }
+        let (discriminator, body) = data.split_at(DISCRIMINATOR_LEN);
+        let (name, schema) = self
+            .types
+            .get(discriminator)
+            .ok_or_else(|| format!("unknown discriminator {}", format_data(discriminator, Encoding::Hex)))?;
+        let (value, _) = decode(schema, body).map_err(|err| format!("{}: {}", name, err))?;
+        Ok((name, value))
+    }
+
+    /// Decodes base64 data, as returned by RPC `getAccountInfo` calls.
+    pub fn decode_base64(&self, data: &str) -> Result<(&str, Value), String> {
+        self.decode(&parse_data(data, Encoding::Base64)?)
+    }
+
+    /// Decodes the event in a `Program data:` transaction log line, or
+    /// returns `None` for any other line.
+    pub fn decode_log(&self, line: &str) -> Option<Result<(&str, Value), String>> {
+        line.strip_prefix(DATA_LOG_PREFIX).map(|data| self.decode_base64(data))
+    }
+}
@@ -0,0 +1,69 @@
+use jaguar::{JaguarAccount, JaguarEvent, JaguarSchema, JaguarSerialize};
+use jaguar_json::{format_data, Encoding};
+use jaguar_indexer::Indexer;
+use serde_json::json;
+
+#[derive(JaguarSerialize, JaguarSchema, JaguarAccount)]
+struct Vault {
+    owner: [u8; 4],
+    amount: u64,
+}
+
+#[derive(JaguarSerialize, JaguarSchema, JaguarAccount, JaguarEvent)]
+struct Deposited {
+    amount: u64,
+    memo: String,
+}
+
+fn indexer() -> Indexer {
+    let mut indexer = Indexer::new();
+    indexer
+        .register_account("Vault", Vault::schema())
+        .register_event("Deposited", Deposited::schema())
+        .register([1, 0, 0, 0, 0, 0, 0, 0], "withdraw", u64::schema());
+    indexer
+}
+
+#[test]
+fn test_decode_by_discriminator() {
+    let indexer = indexer();
+
+    let mut account = jaguar::serialize_with_discriminator(&Vault { owner: [1, 2, 3, 4], amount: 300 }).unwrap();
+    // space the account can grow into
+    account.extend([0; 16]);
+    let (name, value) = indexer.decode(&account).unwrap();
+    assert_eq!(name, "Vault");
+    assert_eq!(value, json!({ "owner": [1, 2, 3, 4], "amount": 300 }));
+
+    let event = jaguar::serialize_event(&Deposited { amount: 5, memo: "rent".into() }).unwrap();
+    let line = format!("Program data: {}", format_data(&event, Encoding::Base64));
+    let (name, value) = indexer.decode_log(&line).unwrap().unwrap();
+    assert_eq!(name, "Deposited");
+    assert_eq!(value, json!({ "amount": 5, "memo": "rent" }));
+    assert!(indexer.decode_log("Program log: Instruction: Deposit").is_none());
+
+    let instruction = [&[1, 0, 0, 0, 0, 0, 0, 0][..], &jaguar::serialize(&7u64).unwrap()].concat();
+    let (name, value) = indexer.decode_base64(&format_data(&instruction, Encoding::Base64)).unwrap();
+    assert_eq!((name, value), ("withdraw", json!(7)));
+}
+
+#[test]
+fn test_decode_errors() {
+    let indexer = indexer();
+
+    assert_eq!(indexer.decode(&[1, 2, 3]).unwrap_err(), "3 bytes is too short for a discriminator");
+    let unknown = jaguar::serialize_with_discriminator(&Deposited { amount: 5, memo: "rent".into() }).unwrap();
+    assert_eq!(
+        indexer.decode(&unknown).unwrap_err(),
+        format!("unknown discriminator {}", format_data(&unknown[..8], Encoding::Hex))
+    );
+    let account = jaguar::serialize_with_discriminator(&Vault { owner: [1, 2, 3, 4], amount: 300 }).unwrap();
+    // offsets count from the end of the discriminator
+    assert_eq!(indexer.decode(&account[..13]).unwrap_err(), "Vault: buffer too small at byte 5");
+}
+
+#[test]
+#[should_panic(expected = "registered twice")]
+fn test_duplicate_discriminator() {
+    indexer().register_account("Vault", u64::schema());
+}
@@ -0,0 +1,357 @@
+//! Decodes Jaguar-encoded data to JSON and encodes JSON back, driven by a
+//! schema, so transactions and accounts can be inspected without writing
+//! Rust.
+//!
+//! Schemas travel as the JSON form of a `JaguarSchema`, see [`schema`].
+//! Values map to JSON like this:
+//!
+//! - integers are numbers, except `u128`/`i128` values beyond 64 bits, which
+//!   are decimal strings;
+//! - floats are numbers, with `"NaN"`, `"inf"` and `"-inf"` as strings;
+//! - `[u8; N]`, arrays, vectors and tuples are arrays;
+//! - structs are objects keyed by field name.
+//!
+//! Encoding accepts exactly what decoding produces. The `jaguar` binary of
+//! `jaguar-cli` wraps [`decode`] and [`encode`], see `jaguar --help`, and
+//! indexers decoding many types by discriminator can use
+//! `jaguar_indexer::Indexer`.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```rust
+//! use jaguar::JaguarSchema;
+//! use serde_json::json;
+//!
+//! let data = jaguar::serialize(&(300u64, String::from("rent"))).unwrap();
+//! let schema = <(u64, String)>::schema();
+//! let (value, len) = jaguar_json::decode(&schema, &data).unwrap();
+//! assert_eq!(value, json!([300, "rent"]));
+//! assert_eq!(len, data.len());
+//! assert_eq!(jaguar_json::encode(&schema, &value).unwrap(), data);
+//!
+//! let json = jaguar_json::schema::to_json(&schema);
+//! assert_eq!(jaguar_json::schema::from_json(&json).unwrap(), schema);
+//! ```
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use jaguar::bits::BitField;
+use jaguar::schema::{Schema, StructSchema};
+use jaguar::{JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+use serde_json::{Map, Value};
+
+pub mod schema;
+
+/// How binary data is written as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Hex digits, optionally prefixed with `0x`.
+    Hex,
+    Base64,
+}
+
+/// Parses binary data written as `encoding`, ignoring whitespace.
+pub fn parse_data(text: &str, encoding: Encoding) -> Result<Vec<u8>, String> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    match encoding {
+        Encoding::Hex => {
+            let digits = text.strip_prefix("0x").unwrap_or(&text);
+            if !digits.len().is_multiple_of(2) {
+                return Err("odd number of hex digits".into());
+            }
+            digits
+                .as_bytes()
+                .chunks(2)
+                .map(|pair| {
+                    std::str::from_utf8(pair)
+                        .ok()
+                        .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                        .ok_or_else(|| format!("invalid hex `{}`", digits))
+                })
+                .collect()
+        }
+        Encoding::Base64 => STANDARD.decode(&text).map_err(|err| format!("invalid base64: {}", err)),
+    }
+}
+
+/// Writes binary data as `encoding`.
+pub fn format_data(data: &[u8], encoding: Encoding) -> String {
+    match encoding {
+        Encoding::Hex => data.iter().map(|byte| format!("{:02x}", byte)).collect(),
+        Encoding::Base64 => STANDARD.encode(data),
+    }
+}
+
+/// Decodes a value of layout `schema` from the start of `data`, returning it
+/// with the number of bytes it took.
+pub fn decode(schema: &Schema, data: &[u8]) -> Result<(Value, usize), String> {
+    let mut de = JaguarDeserializer::new(data);
+    match decode_value(schema, &mut de) {
+        Ok(value) => Ok((value, de.position())),
+        Err(err) => Err(format!("{} at byte {}", err, de.position())),
+    }
+}
+
+/// Encodes `value` in the layout `schema`.
+pub fn encode(schema: &Schema, value: &Value) -> Result<Vec<u8>, String> {
+    let mut ser = JaguarSerializer::new();
+    encode_value(schema, value, &mut ser, "$")?;
+    Ok(ser.finish())
+}
+
+fn decode_value(schema: &Schema, de: &mut JaguarDeserializer<'_>) -> Result<Value, SerError> {
+    Ok(match schema {
+        Schema::U8 => u8::deserialize(de)?.into(),
+        Schema::Bool => bool::deserialize(de)?.into(),
+        Schema::Varint { bits: 16 } => u16::deserialize(de)?.into(),
+        Schema::Varint { bits: 32 } => u32::deserialize(de)?.into(),
+        Schema::Varint { .. } => u64::deserialize(de)?.into(),
+        Schema::SignedVarint { bits: 8 } => i8::deserialize(de)?.into(),
+        Schema::SignedVarint { bits: 16 } => i16::deserialize(de)?.into(),
+        Schema::SignedVarint { bits: 32 } => i32::deserialize(de)?.into(),
+        Schema::SignedVarint { .. } => i64::deserialize(de)?.into(),
+        Schema::U128 => {
+            let int = u128::deserialize(de)?;
+            u64::try_from(int).map_or_else(|_| int.to_string().into(), Value::from)
+        }
+        Schema::I128 => {
+            let int = i128::deserialize(de)?;
+            i64::try_from(int).map_or_else(|_| int.to_string().into(), Value::from)
+        }
+        Schema::F32 => float_value(f32::deserialize(de)?.into()),
+        Schema::F64 => float_value(f64::deserialize(de)?),
+        Schema::String => String::deserialize(de)?.into(),
+        Schema::ByteArray(len) => {
+            let bytes: Vec<Value> = de.peek_bytes(*len)?.iter().map(|&byte| byte.into()).collect();
+            de.skip(*len)?;
+            bytes.into()
+        }
+        Schema::Array(len, inner) => {
+            if de.read_varint()? != *len as u64 {
+                return Err(SerError::InvalidLength);
+            }
+            decode_items(inner, *len as u64, de)?
+        }
+        Schema::Vec(inner) => {
+            let len = de.read_varint()?;
+            decode_items(inner, len, de)?
+        }
+        Schema::DeltaVec => de.read_u64_vec_delta()?.into(),
+        Schema::NonZero(inner) => match decode_value(inner, de)? {
+            value if is_zero(&value) => return Err(SerError::InvalidData),
+            value => value,
+        },
+        Schema::Tuple(schemas) => {
+            schemas.iter().map(|schema| decode_value(schema, de)).collect::<Result<Vec<_>, _>>()?.into()
+        }
+        Schema::Struct(def) => de.nested(|de| decode_struct(def, de))?,
+    })
+}
+
+fn float_value(float: f64) -> Value {
+    match float {
+        float if float.is_nan() => "NaN".into(),
+        f64::INFINITY => "inf".into(),
+        f64::NEG_INFINITY => "-inf".into(),
+        float => float.into(),
+    }
+}
+
+fn decode_items(schema: &Schema, len: u64, de: &mut JaguarDeserializer<'_>) -> Result<Value, SerError> {
+    let len = de.check_len(len, schema.is_zero_sized())?;
+    de.nested(|de| {
+        let mut items = Vec::new();
+        for _ in 0..len {
+            items.push(decode_value(schema, de)?);
+        }
+        Ok(Value::Array(items))
+    })
+}
+
+/// Converts the raw bits of a `#[jaguar(bits = N)]` field.
+fn bit_field(schema: &Schema, bits: u64) -> Result<Value, SerError> {
+    match schema {
+        Schema::Bool => Ok(bool::from_bits(bits)?.into()),
+        _ => Ok(bits.into()),
+    }
+}
+
+fn decode_struct(def: &StructSchema, de: &mut JaguarDeserializer<'_>) -> Result<Value, SerError> {
+    let mut object = Map::new();
+    let mut fields = def.fields.iter().peekable();
+    while let Some(first) = fields.next() {
+        let Some(bits) = first.bits else {
+            object.insert(first.name.clone(), decode_value(&first.schema, de)?);
+            continue;
+        };
+
+        let mut reader = de.bit_reader();
+        object.insert(first.name.clone(), bit_field(&first.schema, reader.read_bits(bits)?)?);
+        while let Some(next) = fields.next_if(|next| next.bits.is_some()) {
+            let bits = next.bits.unwrap_or_default();
+            object.insert(next.name.clone(), bit_field(&next.schema, reader.read_bits(bits)?)?);
+        }
+        reader.finish()?;
+    }
+    de.skip(def.reserve)?;
+    Ok(Value::Object(object))
+}
+
+/// Returns whether `value` is an integer zero, as a number or a string.
+fn is_zero(value: &Value) -> bool {
+    value.as_f64() == Some(0.0) || value.as_str().is_some_and(|text| text.parse() == Ok(0i128))
+}
+
+fn expected(what: &str, value: &Value, path: &str) -> String {
+    format!("{}: expected {}, got {}", path, what, value)
+}
+
+/// Converts a number, or a decimal string, to an integer of `bits` bits.
+fn to_uint(value: &Value, bits: u32, path: &str) -> Result<u128, String> {
+    let int = match value {
+        Value::Number(number) => number.as_u64().map(u128::from),
+        Value::String(text) => text.parse().ok(),
+        _ => None,
+    };
+    match int {
+        Some(int) if bits == 128 || int >> bits == 0 => Ok(int),
+        _ => Err(expected(&format!("a {}-bit unsigned integer", bits), value, path)),
+    }
+}
+
+/// Converts a number, or a decimal string, to a signed integer of `bits` bits.
+fn to_int(value: &Value, bits: u32, path: &str) -> Result<i128, String> {
+    let int = match value {
+        Value::Number(number) => number.as_i64().map(i128::from),
+        Value::String(text) => text.parse().ok(),
+        _ => None,
+    };
+    match int {
+        Some(int) if bits == 128 || (int >> (bits - 1) == 0 || int >> (bits - 1) == -1) => Ok(int),
+        _ => Err(expected(&format!("a {}-bit signed integer", bits), value, path)),
+    }
+}
+
+fn to_float(value: &Value, path: &str) -> Result<f64, String> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => match text.as_str() {
+            "NaN" => Some(f64::NAN),
+            "inf" => Some(f64::INFINITY),
+            "-inf" => Some(f64::NEG_INFINITY),
+            _ => None,
+        },
+        _ => None,
+    }
+    .ok_or_else(|| expected("a number", value, path))
+}
+
+fn to_array<'v>(value: &'v Value, len: Option<usize>, path: &str) -> Result<&'v [Value], String> {
+    match (value, len) {
+        (Value::Array(items), Some(len)) if items.len() != len => {
+            Err(expected(&format!("an array of {} elements", len), value, path))
+        }
+        (Value::Array(items), _) => Ok(items),
+        _ => Err(expected("an array", value, path)),
+    }
+}
+
+fn encode_value(schema: &Schema, value: &Value, ser: &mut JaguarSerializer, path: &str) -> Result<(), String> {
+    let ser_err = |err: SerError| format!("{}: {}", path, err);
+    match schema {
+        Schema::U8 => ser.write_u8(to_uint(value, 8, path)? as u8).map_err(ser_err),
+        Schema::Bool => {
+            let value = value.as_bool().ok_or_else(|| expected("a boolean", value, path))?;
+            ser.write_bool(value).map_err(ser_err)
+        }
+        Schema::Varint { bits } => ser.write_varint(to_uint(value, *bits, path)? as u64).map_err(ser_err),
+        Schema::SignedVarint { bits } => ser.write_signed_varint(to_int(value, *bits, path)? as i64).map_err(ser_err),
+        Schema::U128 => to_uint(value, 128, path)?.serialize(ser).map_err(ser_err),
+        Schema::I128 => to_int(value, 128, path)?.serialize(ser).map_err(ser_err),
+        Schema::F32 => ser.write_f32(to_float(value, path)? as f32).map_err(ser_err),
+        Schema::F64 => ser.write_f64(to_float(value, path)?).map_err(ser_err),
+        Schema::String => {
+            let value = value.as_str().ok_or_else(|| expected("a string", value, path))?;
+            ser.write_str(value).map_err(ser_err)
+        }
+        Schema::ByteArray(len) => {
+            let bytes = to_array(value, Some(*len), path)?
+                .iter()
+                .map(|byte| to_uint(byte, 8, path).map(|byte| byte as u8))
+                .collect::<Result<Vec<_>, _>>()?;
+            ser.write_raw(&bytes).map_err(ser_err)
+        }
+        Schema::Array(len, inner) => encode_items(inner, to_array(value, Some(*len), path)?, ser, path),
+        Schema::Vec(inner) => encode_items(inner, to_array(value, None, path)?, ser, path),
+        Schema::NonZero(_) if is_zero(value) => Err(expected("a non-zero integer", value, path)),
+        Schema::NonZero(inner) => encode_value(inner, value, ser, path),
+        Schema::DeltaVec => {
+            let items = to_array(value, None, path)?
+                .iter()
+                .map(|item| to_uint(item, 64, path).map(|int| int as u64))
+                .collect::<Result<Vec<_>, _>>()?;
+            ser.write_u64_slice_delta(&items).map_err(ser_err)
+        }
+        Schema::Tuple(schemas) => {
+            let items = to_array(value, Some(schemas.len()), path)?;
+            for (index, (schema, item)) in schemas.iter().zip(items).enumerate() {
+                encode_value(schema, item, ser, &format!("{}[{}]", path, index))?;
+            }
+            Ok(())
+        }
+        Schema::Struct(def) => encode_struct(def, value, ser, path),
+    }
+}
+
+fn encode_items(schema: &Schema, items: &[Value], ser: &mut JaguarSerializer, path: &str) -> Result<(), String> {
+    ser.write_varint(items.len() as u64).map_err(|err| format!("{}: {}", path, err))?;
+    for (index, item) in items.iter().enumerate() {
+        encode_value(schema, item, ser, &format!("{}[{}]", path, index))?;
+    }
+    Ok(())
+}
+
+/// The raw bits of a `#[jaguar(bits = N)]` field.
+fn bit_value(schema: &Schema, value: &Value, path: &str) -> Result<u64, String> {
+    match schema {
+        Schema::Bool => Ok(value.as_bool().ok_or_else(|| expected("a boolean", value, path))?.to_bits()),
+        Schema::U8 => Ok(to_uint(value, 8, path)? as u64),
+        Schema::Varint { bits } => Ok(to_uint(value, *bits, path)? as u64),
+        _ => Err(format!("{}: only booleans and unsigned integers can be bit-packed", path)),
+    }
+}
+
+fn encode_struct(def: &StructSchema, value: &Value, ser: &mut JaguarSerializer, path: &str) -> Result<(), String> {
+    let object = value.as_object().ok_or_else(|| expected("an object", value, path))?;
+    if let Some(name) = object.keys().find(|name| !def.fields.iter().any(|field| field.name == **name)) {
+        return Err(format!("{}: unknown field `{}` in `{}`", path, name, def.name));
+    }
+    let field = |name: &str| {
+        object.get(name).map(|value| (value, format!("{}.{}", path, name))).ok_or_else(|| {
+            format!("{}: missing field `{}` of `{}`", path, name, def.name)
+        })
+    };
+    let ser_err = |err: SerError| format!("{}: {}", path, err);
+
+    let mut fields = def.fields.iter().peekable();
+    while let Some(first) = fields.next() {
+        let Some(bits) = first.bits else {
+            let (value, path) = field(&first.name)?;
+            encode_value(&first.schema, value, ser, &path)?;
+            continue;
+        };
+
+        let mut writer = ser.bit_writer();
+        let (value, field_path) = field(&first.name)?;
+        writer.write_bits(bit_value(&first.schema, value, &field_path)?, bits).map_err(ser_err)?;
+        while let Some(next) = fields.next_if(|next| next.bits.is_some()) {
+            let (value, field_path) = field(&next.name)?;
+            let bits = next.bits.unwrap_or_default();
+            writer.write_bits(bit_value(&next.schema, value, &field_path)?, bits).map_err(ser_err)?;
+        }
+        writer.finish().map_err(ser_err)?;
+    }
+    ser.write_padding(def.reserve).map_err(ser_err)
+}
@@ -0,0 +1,123 @@
+//! JSON form of [`Schema`], for handing type layouts to tools and to
+//! JavaScript without linking the crate that defines them.
+//!
+//! Primitives are strings (`"u64"`, `"string"`, `"delta"`, ...). Compound
+//! types are single-key objects: `{"bytes": 32}`, `{"array": [4, "u16"]}`,
+//! `{"vec": "u32"}`, `{"nonzero": "u64"}` and `{"tuple": ["u8", "string"]}`.
+//! Structs are `{"struct": "Name", "fields": [{"name": "a", "schema": "u8",
+//! "bits": 3}], "reserve": 0}`, where `bits` is omitted for unpacked fields.
+
+use jaguar::schema::{FieldSchema, Schema, StructSchema};
+use serde_json::{json, Value};
+
+/// Converts a schema to its JSON form.
+pub fn to_json(schema: &Schema) -> String {
+    to_value(schema).to_string()
+}
+
+/// Parses the JSON form of a schema.
+pub fn from_json(json: &str) -> Result<Schema, String> {
+    let value: Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+    from_value(&value)
+}
+
+fn to_value(schema: &Schema) -> Value {
+    match schema {
+        Schema::U8 => "u8".into(),
+        Schema::Bool => "bool".into(),
+        Schema::Varint { bits } => format!("u{}", bits).into(),
+        Schema::SignedVarint { bits } => format!("i{}", bits).into(),
+        Schema::U128 => "u128".into(),
+        Schema::I128 => "i128".into(),
+        Schema::F32 => "f32".into(),
+        Schema::F64 => "f64".into(),
+        Schema::String => "string".into(),
+        Schema::DeltaVec => "delta".into(),
+        Schema::ByteArray(len) => json!({ "bytes": len }),
+        Schema::Array(len, inner) => json!({ "array": [len, to_value(inner)] }),
+        Schema::Vec(inner) => json!({ "vec": to_value(inner) }),
+        Schema::NonZero(inner) => json!({ "nonzero": to_value(inner) }),
+        Schema::Tuple(items) => json!({ "tuple": items.iter().map(to_value).collect::<Vec<_>>() }),
+        Schema::Struct(def) => {
+            let fields: Vec<Value> = def
+                .fields
+                .iter()
+                .map(|field| {
+                    let mut value = json!({ "name": field.name, "schema": to_value(&field.schema) });
+                    if let Some(bits) = field.bits {
+                        value["bits"] = bits.into();
+                    }
+                    value
+                })
+                .collect();
+            json!({ "struct": def.name, "fields": fields, "reserve": def.reserve })
+        }
+    }
+}
+
+fn from_value(value: &Value) -> Result<Schema, String> {
+    if let Some(name) = value.as_str() {
+        return Ok(match name {
+            "u8" => Schema::U8,
+            "bool" => Schema::Bool,
+            "u16" => Schema::Varint { bits: 16 },
+            "u32" => Schema::Varint { bits: 32 },
+            "u64" => Schema::Varint { bits: 64 },
+            "i8" => Schema::SignedVarint { bits: 8 },
+            "i16" => Schema::SignedVarint { bits: 16 },
+            "i32" => Schema::SignedVarint { bits: 32 },
+            "i64" => Schema::SignedVarint { bits: 64 },
+            "u128" => Schema::U128,
+            "i128" => Schema::I128,
+            "f32" => Schema::F32,
+            "f64" => Schema::F64,
+            "string" => Schema::String,
+            "delta" => Schema::DeltaVec,
+            _ => return Err(format!("unknown type `{}`", name)),
+        });
+    }
+
+    let object = value.as_object().ok_or_else(|| format!("expected a schema, got {}", value))?;
+    if let Some(len) = object.get("bytes") {
+        Ok(Schema::ByteArray(as_usize(len)?))
+    } else if let Some(array) = object.get("array") {
+        match array.as_array().map(Vec::as_slice) {
+            Some([len, inner]) => Ok(Schema::Array(as_usize(len)?, Box::new(from_value(inner)?))),
+            _ => Err(format!("expected `[length, schema]`, got {}", array)),
+        }
+    } else if let Some(inner) = object.get("vec") {
+        Ok(Schema::Vec(Box::new(from_value(inner)?)))
+    } else if let Some(inner) = object.get("nonzero") {
+        Ok(Schema::NonZero(Box::new(from_value(inner)?)))
+    } else if let Some(items) = object.get("tuple") {
+        let items = items.as_array().ok_or_else(|| format!("expected an array, got {}", items))?;
+        Ok(Schema::Tuple(items.iter().map(from_value).collect::<Result<_, _>>()?))
+    } else if let Some(name) = object.get("struct") {
+        let name = name.as_str().ok_or_else(|| format!("expected a struct name, got {}", name))?;
+        let fields = match object.get("fields") {
+            Some(Value::Array(fields)) => fields.iter().map(field_from_value).collect::<Result<_, _>>()?,
+            Some(other) => return Err(format!("expected an array of fields, got {}", other)),
+            None => Vec::new(),
+        };
+        let reserve = object.get("reserve").map(as_usize).transpose()?.unwrap_or(0);
+        Ok(Schema::Struct(StructSchema { name: name.into(), fields, reserve }))
+    } else {
+        Err(format!("expected a schema, got {}", value))
+    }
+}
+
+fn field_from_value(value: &Value) -> Result<FieldSchema, String> {
+    let name = value["name"].as_str().ok_or_else(|| format!("expected a field name, got {}", value))?;
+    let bits = match value.get("bits") {
+        Some(bits) => Some(bits.as_u64().and_then(|bits| u32::try_from(bits).ok()).ok_or("invalid `bits`")?),
+        None => None,
+    };
+    Ok(FieldSchema { name: name.into(), schema: from_value(&value["schema"])?, bits })
+}
+
+fn as_usize(value: &Value) -> Result<usize, String> {
+    value
+        .as_u64()
+        .and_then(|len| usize::try_from(len).ok())
+        .ok_or_else(|| format!("expected a length, got {}", value))
+}
@@ -0,0 +1,91 @@
+use jaguar::{JaguarSchema, JaguarSerialize};
+use jaguar_json::{decode, encode, format_data, parse_data, Encoding};
+use serde_json::json;
+
+#[derive(JaguarSerialize, JaguarSchema)]
+#[jaguar(reserve = 2)]
+struct Order {
+    owner: [u8; 4],
+    #[jaguar(bits = 2)]
+    side: u8,
+    #[jaguar(bits = 1)]
+    post_only: bool,
+    price: f64,
+    levels: [i16; 3],
+    fills: Vec<(u64, String)>,
+    #[jaguar(delta)]
+    slots: Vec<u64>,
+    total: u128,
+}
+
+fn order() -> Order {
+    Order {
+        owner: [1, 2, 3, 4],
+        side: 2,
+        post_only: true,
+        price: f64::INFINITY,
+        levels: [-1, 0, 300],
+        fills: vec![(7, "bid".into())],
+        slots: vec![100, 105],
+        total: u128::MAX,
+    }
+}
+
+#[test]
+fn test_roundtrip() {
+    let data = jaguar::serialize(&order()).unwrap();
+    let (value, len) = decode(&Order::schema(), &data).unwrap();
+    assert_eq!(len, data.len());
+    assert_eq!(
+        value,
+        json!({
+            "owner": [1, 2, 3, 4],
+            "side": 2,
+            "post_only": true,
+            "price": "inf",
+            "levels": [-1, 0, 300],
+            "fills": [[7, "bid"]],
+            "slots": [100, 105],
+            "total": u128::MAX.to_string(),
+        })
+    );
+    assert_eq!(encode(&Order::schema(), &value).unwrap(), data);
+}
+
+#[test]
+fn test_errors() {
+    let data = jaguar::serialize(&order()).unwrap();
+    assert_eq!(decode(&Order::schema(), &data[..6]).unwrap_err(), "buffer too small at byte 6");
+
+    let (mut value, _) = decode(&Order::schema(), &data).unwrap();
+    value["fills"][0][1] = json!(5);
+    assert_eq!(encode(&Order::schema(), &value).unwrap_err(), "$.fills[0][1]: expected a string, got 5");
+    value["levels"] = json!([1, 2]);
+    assert_eq!(
+        encode(&Order::schema(), &value).unwrap_err(),
+        "$.levels: expected an array of 3 elements, got [1,2]"
+    );
+    value.as_object_mut().unwrap().remove("levels");
+    assert_eq!(encode(&Order::schema(), &value).unwrap_err(), "$: missing field `levels` of `Order`");
+    value["level"] = json!([1, 2, 3]);
+    assert_eq!(encode(&Order::schema(), &value).unwrap_err(), "$: unknown field `level` in `Order`");
+    assert!(encode(&u8::schema(), &json!(256)).is_err());
+    assert!(encode(&i8::schema(), &json!(-129)).is_err());
+
+    let huge = [0xFF, 0xFF, 0xFF, 0x0F];
+    assert_eq!(decode(&Vec::<()>::schema(), &huge).unwrap_err(), "invalid length at byte 4");
+    assert_eq!(decode(&Vec::<u64>::schema(), &huge).unwrap_err(), "buffer too small at byte 4");
+    assert_eq!(decode(&Vec::<()>::schema(), &[2]).unwrap(), (json!([[], []]), 1));
+}
+
+#[test]
+fn test_data_encodings() {
+    assert_eq!(parse_data("0xac02 ff", Encoding::Hex).unwrap(), [0xAC, 0x02, 0xFF]);
+    assert!(parse_data("abc", Encoding::Hex).is_err());
+    assert!(parse_data("zz", Encoding::Hex).is_err());
+    assert!(parse_data("aé1", Encoding::Hex).is_err());
+    assert!(parse_data("éé", Encoding::Hex).is_err());
+    assert_eq!(parse_data("rAL/", Encoding::Base64).unwrap(), [0xAC, 0x02, 0xFF]);
+    assert_eq!(format_data(&[0xAC, 0x02, 0xFF], Encoding::Hex), "ac02ff");
+    assert_eq!(format_data(&[0xAC, 0x02, 0xFF], Encoding::Base64), "rAL/");
+}
@@ -0,0 +1,513 @@
+//! Diffs and migrations between two versions of an account layout.
+//!
+//! Adding a field to an on-chain account means rewriting every existing
+//! account in the new layout. Instead of writing that byte surgery by hand,
+//! keep the old struct around, derive `JaguarSchema` on both versions and
+//! build a [`Migration`] from the two schemas. It decodes bytes in the old
+//! layout and writes them in the new one.
+//!
+//! Fields are matched by name, at any depth, so they can be reordered freely.
+//! Fields only in the new layout are written as zero (`0`, `false`, empty
+//! strings and sequences, zero bytes), fields only in the old layout are
+//! dropped, and `reserve` padding is resized. Fields without a zero value,
+//! like `NonZeroU64`s, can't be added. A field may also change type, as long
+//! as its values carry over:
+//!
+//! - integers to integers of any width or signedness, including `bits`
+//!   fields and `NonZero*` integers. Values that don't fit, including zeros
+//!   written as `NonZero*` integers, fail with `InvalidData` when applied;
+//! - `f32` and `f64` to each other, rounding to `f32`;
+//! - arrays, `[u8; N]` and `Vec`s (including `delta` ones) to each other,
+//!   element by element. A `Vec` whose length doesn't match an array fails
+//!   with `InvalidLength` when applied;
+//! - tuples of the same arity and structs, recursively.
+//!
+//! Any other change fails [`Migration::new`] with [`Incompatible`], so a
+//! broken upgrade is caught when the migration is built rather than on the
+//! first account. [`Migration::changes`] lists every difference, for review
+//! before deploying.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```ignore
+//! use jaguar::JaguarSchema;
+//! use jaguar_migrate::Migration;
+//!
+//! let migration = Migration::new(VaultV1::schema(), VaultV2::schema())?;
+//! for change in migration.changes() {
+//!     println!("{:?}", change);
+//! }
+//! let upgraded = migration.apply(&account_data)?;
+//! ```
+//!
+//! Bytes after the old value, such as an account's unused space, are ignored.
+
+use jaguar::bits::BitField;
+use jaguar::schema::{FieldSchema, Schema, StructSchema};
+use jaguar::{JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+use std::fmt;
+
+/// A difference between two layouts, at a dotted field path like
+/// `position.amount`. Elements of sequences are written `orders[]`, and of
+/// tuples `pair.0`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A field only in the new layout, written as zero.
+    Added { path: String },
+    /// A field only in the old layout, dropped.
+    Removed { path: String },
+    /// A value whose type changed, converted on the way.
+    Retyped { path: String, old: Schema, new: Schema },
+    /// A field whose `bits` width changed, or that became (un)packed.
+    Repacked { path: String, old: Option<u32>, new: Option<u32> },
+    /// A struct whose `reserve` padding changed.
+    Reserve { path: String, old: usize, new: usize },
+}
+
+/// A change between layouts whose values can't be carried over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Incompatible {
+    pub path: String,
+    /// `None` for an added field with no zero value to write.
+    pub old: Option<Box<Schema>>,
+    pub new: Box<Schema>,
+}
+
+impl fmt::Display for Incompatible {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "the value" } else { &self.path };
+        match &self.old {
+            Some(old) => write!(f, "cannot migrate `{}` from {:?} to {:?}", path, old, self.new),
+            None => write!(f, "cannot add `{}` as {:?}, which has no zero value", path, self.new),
+        }
+    }
+}
+
+impl std::error::Error for Incompatible {}
+
+/// Rewrites data from one layout into another.
+#[derive(Debug, Clone)]
+pub struct Migration {
+    old: Schema,
+    new: Schema,
+    changes: Vec<Change>,
+}
+
+impl Migration {
+    /// Builds the migration from `old` to `new`, failing if any value can't
+    /// be carried over.
+    pub fn new(old: Schema, new: Schema) -> Result<Self, Incompatible> {
+        let changes = diff(&old, &new)?;
+        Ok(Self { old, new, changes })
+    }
+
+    /// Every difference between the layouts, in new field order followed by
+    /// removed fields.
+    #[inline]
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Returns `true` if both layouts are identical.
+    #[inline]
+    pub fn is_noop(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Decodes a value in the old layout from the front of `data` and returns
+    /// it encoded in the new layout.
+    pub fn apply(&self, data: &[u8]) -> Result<Vec<u8>, SerError> {
+        let mut ser = JaguarSerializer::with_capacity(data.len());
+        self.apply_into(data, &mut ser)?;
+        Ok(ser.finish())
+    }
+
+    /// Like [`apply`](Self::apply), writing to `ser`.
+    pub fn apply_into(&self, data: &[u8], ser: &mut JaguarSerializer) -> Result<(), SerError> {
+        let value = decode(&self.old, &mut JaguarDeserializer::new(data))?;
+        encode(&self.new, &self.old, &value, ser)
+    }
+}
+
+/// Lists the differences between two layouts, failing if any value can't be
+/// carried over.
+pub fn diff(old: &Schema, new: &Schema) -> Result<Vec<Change>, Incompatible> {
+    let mut changes = Vec::new();
+    check(old, new, "", &mut changes)?;
+    Ok(changes)
+}
+
+const U8: &Schema = &Schema::U8;
+const U64: &Schema = &Schema::Varint { bits: 64 };
+const EMPTY: &StructSchema = &StructSchema { name: String::new(), fields: Vec::new(), reserve: 0 };
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// The fixed length, if any, and element type of a sequence.
+fn sequence(schema: &Schema) -> Option<(Option<usize>, &Schema)> {
+    match schema {
+        Schema::ByteArray(len) => Some((Some(*len), U8)),
+        Schema::Array(len, inner) => Some((Some(*len), inner)),
+        Schema::Vec(inner) => Some((None, inner)),
+        Schema::DeltaVec => Some((None, U64)),
+        _ => None,
+    }
+}
+
+fn is_int(schema: &Schema) -> bool {
+    matches!(
+        schema,
+        Schema::U8 | Schema::Varint { .. } | Schema::SignedVarint { .. } | Schema::U128 | Schema::I128
+    ) || matches!(schema, Schema::NonZero(inner) if is_int(inner))
+}
+
+/// Returns whether `encode_default` can write a value of `schema`.
+fn has_zero(schema: &Schema) -> bool {
+    match schema {
+        Schema::NonZero(_) => false,
+        Schema::Array(len, inner) => *len == 0 || has_zero(inner),
+        Schema::Tuple(schemas) => schemas.iter().all(has_zero),
+        Schema::Struct(def) => def.fields.iter().all(|field| has_zero(&field.schema)),
+        _ => true,
+    }
+}
+
+fn check(old: &Schema, new: &Schema, path: &str, changes: &mut Vec<Change>) -> Result<(), Incompatible> {
+    if old == new {
+        return Ok(());
+    }
+    let incompatible =
+        || Incompatible { path: path.to_string(), old: Some(Box::new(old.clone())), new: Box::new(new.clone()) };
+
+    match (old, new) {
+        (Schema::Struct(old), Schema::Struct(new)) => return check_struct(old, new, path, changes),
+        (Schema::NonZero(old), Schema::NonZero(new)) => return check(old, new, path, changes),
+        (Schema::Tuple(old), Schema::Tuple(new)) if old.len() == new.len() => {
+            for (index, (old, new)) in old.iter().zip(new).enumerate() {
+                check(old, new, &join(path, &index.to_string()), changes)?;
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    if let (Some((old_len, old_item)), Some((new_len, new_item))) = (sequence(old), sequence(new)) {
+        if matches!((old_len, new_len), (Some(old_len), Some(new_len)) if old_len != new_len) {
+            return Err(incompatible());
+        }
+        if !matches!((old, new), (Schema::Array(..), Schema::Array(..)) | (Schema::Vec(_), Schema::Vec(_))) {
+            changes.push(Change::Retyped { path: path.to_string(), old: old.clone(), new: new.clone() });
+        }
+        return check(old_item, new_item, &format!("{}[]", path), changes);
+    }
+
+    let floats = matches!(old, Schema::F32 | Schema::F64) && matches!(new, Schema::F32 | Schema::F64);
+    if !(floats || is_int(old) && is_int(new)) {
+        return Err(incompatible());
+    }
+    changes.push(Change::Retyped { path: path.to_string(), old: old.clone(), new: new.clone() });
+    Ok(())
+}
+
+fn check_struct(
+    old: &StructSchema,
+    new: &StructSchema,
+    path: &str,
+    changes: &mut Vec<Change>,
+) -> Result<(), Incompatible> {
+    for field in &new.fields {
+        let path = join(path, &field.name);
+        let Some(old_field) = old.fields.iter().find(|old| old.name == field.name) else {
+            if !has_zero(&field.schema) {
+                return Err(Incompatible { path, old: None, new: Box::new(field.schema.clone()) });
+            }
+            changes.push(Change::Added { path });
+            continue;
+        };
+        check(&old_field.schema, &field.schema, &path, changes)?;
+        if old_field.bits != field.bits {
+            changes.push(Change::Repacked { path, old: old_field.bits, new: field.bits });
+        }
+    }
+    for field in &old.fields {
+        if !new.fields.iter().any(|new| new.name == field.name) {
+            changes.push(Change::Removed { path: join(path, &field.name) });
+        }
+    }
+    if old.reserve != new.reserve {
+        changes.push(Change::Reserve { path: path.to_string(), old: old.reserve, new: new.reserve });
+    }
+    Ok(())
+}
+
+/// A decoded value, independent of its exact type.
+enum Value {
+    UInt(u128),
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    /// Sequences and tuples.
+    List(Vec<Value>),
+    /// Fields in the order of the old layout.
+    Struct(Vec<Value>),
+}
+
+fn decode(schema: &Schema, de: &mut JaguarDeserializer<'_>) -> Result<Value, SerError> {
+    Ok(match schema {
+        Schema::U8 => Value::UInt(de.read_u8()?.into()),
+        Schema::Bool => Value::Bool(bool::deserialize(de)?),
+        Schema::Varint { bits: 16 } => Value::UInt(u16::deserialize(de)?.into()),
+        Schema::Varint { bits: 32 } => Value::UInt(u32::deserialize(de)?.into()),
+        Schema::Varint { .. } => Value::UInt(u64::deserialize(de)?.into()),
+        Schema::SignedVarint { bits: 8 } => Value::Int(i8::deserialize(de)?.into()),
+        Schema::SignedVarint { bits: 16 } => Value::Int(i16::deserialize(de)?.into()),
+        Schema::SignedVarint { bits: 32 } => Value::Int(i32::deserialize(de)?.into()),
+        Schema::SignedVarint { .. } => Value::Int(i64::deserialize(de)?.into()),
+        Schema::U128 => Value::UInt(u128::deserialize(de)?),
+        Schema::I128 => Value::Int(i128::deserialize(de)?),
+        Schema::F32 => Value::Float(f32::deserialize(de)?.into()),
+        Schema::F64 => Value::Float(f64::deserialize(de)?),
+        Schema::String => Value::Str(String::deserialize(de)?),
+        Schema::ByteArray(len) => {
+            let bytes = de.peek_bytes(*len)?.iter().map(|&byte| Value::UInt(byte.into())).collect();
+            de.skip(*len)?;
+            Value::List(bytes)
+        }
+        Schema::Array(len, inner) => {
+            if de.read_varint()? != *len as u64 {
+                return Err(SerError::InvalidLength);
+            }
+            decode_items(inner, *len as u64, de)?
+        }
+        Schema::Vec(inner) => {
+            let len = de.read_varint()?;
+            decode_items(inner, len, de)?
+        }
+        Schema::DeltaVec => Value::List(de.read_u64_vec_delta()?.into_iter().map(|v| Value::UInt(v.into())).collect()),
+        Schema::NonZero(inner) => match decode(inner, de)? {
+            Value::UInt(0) | Value::Int(0) => return Err(SerError::InvalidData),
+            value => value,
+        },
+        Schema::Tuple(schemas) => {
+            Value::List(schemas.iter().map(|schema| decode(schema, de)).collect::<Result<_, _>>()?)
+        }
+        Schema::Struct(def) => de.nested(|de| decode_struct(def, de))?,
+    })
+}
+
+fn decode_items(schema: &Schema, len: u64, de: &mut JaguarDeserializer<'_>) -> Result<Value, SerError> {
+    let len = de.check_len(len, schema.is_zero_sized())?;
+    de.nested(|de| {
+        let mut items = Vec::new();
+        for _ in 0..len {
+            items.push(decode(schema, de)?);
+        }
+        Ok(Value::List(items))
+    })
+}
+
+/// Converts the raw bits of a `#[jaguar(bits = N)]` field.
+fn bit_field(schema: &Schema, bits: u64) -> Result<Value, SerError> {
+    match schema {
+        Schema::Bool => Ok(Value::Bool(bool::from_bits(bits)?)),
+        _ => Ok(Value::UInt(bits.into())),
+    }
+}
+
+fn decode_struct(def: &StructSchema, de: &mut JaguarDeserializer<'_>) -> Result<Value, SerError> {
+    let mut values = Vec::with_capacity(def.fields.len());
+    let mut fields = def.fields.iter().peekable();
+    while let Some(first) = fields.next() {
+        let Some(bits) = first.bits else {
+            values.push(decode(&first.schema, de)?);
+            continue;
+        };
+
+        let mut reader = de.bit_reader();
+        values.push(bit_field(&first.schema, reader.read_bits(bits)?)?);
+        while let Some(next) = fields.next_if(|next| next.bits.is_some()) {
+            let bits = next.bits.unwrap_or_default();
+            values.push(bit_field(&next.schema, reader.read_bits(bits)?)?);
+        }
+        reader.finish()?;
+    }
+    de.skip(def.reserve)?;
+    Ok(Value::Struct(values))
+}
+
+/// `value` as an unsigned integer of `bits` bits.
+fn uint(value: &Value, bits: u32) -> Result<u128, SerError> {
+    let value = match *value {
+        Value::UInt(value) => value,
+        Value::Int(value) => u128::try_from(value).map_err(|_| SerError::InvalidData)?,
+        _ => return Err(SerError::InvalidData),
+    };
+    if bits < 128 && value >> bits != 0 {
+        return Err(SerError::InvalidData);
+    }
+    Ok(value)
+}
+
+/// `value` as a signed integer of `bits` bits.
+fn int(value: &Value, bits: u32) -> Result<i128, SerError> {
+    let value = match *value {
+        Value::Int(value) => value,
+        Value::UInt(value) => i128::try_from(value).map_err(|_| SerError::InvalidData)?,
+        _ => return Err(SerError::InvalidData),
+    };
+    if bits < 128 && !(-(1i128 << (bits - 1))..1i128 << (bits - 1)).contains(&value) {
+        return Err(SerError::InvalidData);
+    }
+    Ok(value)
+}
+
+/// The items of a sequence, checked against the fixed length of an array.
+fn items(value: &Value, len: Option<usize>) -> Result<&[Value], SerError> {
+    match value {
+        Value::List(items) if len.is_none_or(|len| len == items.len()) => Ok(items),
+        Value::List(_) => Err(SerError::InvalidLength),
+        _ => Err(SerError::InvalidData),
+    }
+}
+
+fn encode(new: &Schema, old: &Schema, value: &Value, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+    match new {
+        Schema::U8 => ser.write_u8(uint(value, 8)? as u8)?,
+        Schema::Bool => match value {
+            Value::Bool(value) => ser.write_bool(*value)?,
+            _ => return Err(SerError::InvalidData),
+        },
+        Schema::Varint { bits } => ser.write_varint(uint(value, *bits)? as u64)?,
+        Schema::SignedVarint { bits } => ser.write_signed_varint(int(value, *bits)? as i64)?,
+        Schema::U128 => uint(value, 128)?.serialize(ser)?,
+        Schema::I128 => int(value, 128)?.serialize(ser)?,
+        Schema::F32 | Schema::F64 => {
+            let Value::Float(value) = *value else {
+                return Err(SerError::InvalidData);
+            };
+            match new {
+                Schema::F32 => ser.write_f32(value as f32)?,
+                _ => ser.write_f64(value)?,
+            }
+        }
+        Schema::String => match value {
+            Value::Str(value) => ser.write_str(value)?,
+            _ => return Err(SerError::InvalidData),
+        },
+        Schema::ByteArray(len) => {
+            for item in items(value, Some(*len))? {
+                ser.write_u8(uint(item, 8)? as u8)?;
+            }
+        }
+        Schema::Array(_, inner) | Schema::Vec(inner) => {
+            let len = match new {
+                Schema::Array(len, _) => Some(*len),
+                _ => None,
+            };
+            let items = items(value, len)?;
+            let (_, old) = sequence(old).ok_or(SerError::InvalidData)?;
+            ser.write_varint(items.len() as u64)?;
+            for item in items {
+                encode(inner, old, item, ser)?;
+            }
+        }
+        Schema::DeltaVec => {
+            let items = items(value, None)?
+                .iter()
+                .map(|item| uint(item, 64).map(|item| item as u64))
+                .collect::<Result<Vec<_>, _>>()?;
+            ser.write_u64_slice_delta(&items)?;
+        }
+        Schema::NonZero(_) if matches!(value, Value::UInt(0) | Value::Int(0)) => return Err(SerError::InvalidData),
+        Schema::NonZero(inner) => encode(inner, old, value, ser)?,
+        Schema::Tuple(schemas) => {
+            let (Schema::Tuple(old), Value::List(items)) = (old, value) else {
+                return Err(SerError::InvalidData);
+            };
+            for ((new, old), item) in schemas.iter().zip(old).zip(items) {
+                encode(new, old, item, ser)?;
+            }
+        }
+        Schema::Struct(def) => {
+            let (Schema::Struct(old), Value::Struct(values)) = (old, value) else {
+                return Err(SerError::InvalidData);
+            };
+            encode_struct(def, old, values, ser)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the zero value of `schema`, for fields added by a migration.
+fn encode_default(schema: &Schema, ser: &mut JaguarSerializer) -> Result<(), SerError> {
+    match schema {
+        Schema::U8 => ser.write_u8(0),
+        Schema::Bool => ser.write_bool(false),
+        Schema::Varint { .. } | Schema::Vec(_) | Schema::DeltaVec => ser.write_varint(0),
+        Schema::SignedVarint { .. } => ser.write_signed_varint(0),
+        Schema::U128 => 0u128.serialize(ser),
+        Schema::I128 => 0i128.serialize(ser),
+        Schema::F32 => ser.write_f32(0.0),
+        Schema::F64 => ser.write_f64(0.0),
+        Schema::String => ser.write_str(""),
+        Schema::ByteArray(len) => ser.write_padding(*len),
+        Schema::Array(len, inner) => {
+            ser.write_varint(*len as u64)?;
+            (0..*len).try_for_each(|_| encode_default(inner, ser))
+        }
+        // rejected by `diff`, which never lets such a field be added
+        Schema::NonZero(_) => Err(SerError::InvalidData),
+        Schema::Tuple(schemas) => schemas.iter().try_for_each(|schema| encode_default(schema, ser)),
+        Schema::Struct(def) => encode_struct(def, EMPTY, &[], ser),
+    }
+}
+
+/// The raw bits of a `#[jaguar(bits = N)]` field, zero if it was added.
+fn bit_value(field: &FieldSchema, value: Option<&Value>) -> Result<u64, SerError> {
+    match (&field.schema, value) {
+        (_, None) => Ok(0),
+        (Schema::Bool, Some(Value::Bool(value))) => Ok(value.to_bits()),
+        (_, Some(value)) => Ok(uint(value, field.bits.unwrap_or_default())? as u64),
+    }
+}
+
+fn encode_struct(
+    new: &StructSchema,
+    old: &StructSchema,
+    values: &[Value],
+    ser: &mut JaguarSerializer,
+) -> Result<(), SerError> {
+    let old_field = |name: &str| {
+        let index = old.fields.iter().position(|field| field.name == name)?;
+        Some((&old.fields[index].schema, values.get(index)?))
+    };
+
+    let mut fields = new.fields.iter().peekable();
+    while let Some(first) = fields.next() {
+        let Some(bits) = first.bits else {
+            match old_field(&first.name) {
+                Some((old, value)) => encode(&first.schema, old, value, ser)?,
+                None => encode_default(&first.schema, ser)?,
+            }
+            continue;
+        };
+
+        let mut writer = ser.bit_writer();
+        writer.write_bits(bit_value(first, old_field(&first.name).map(|(_, value)| value))?, bits)?;
+        while let Some(next) = fields.next_if(|next| next.bits.is_some()) {
+            let bits = next.bits.unwrap_or_default();
+            writer.write_bits(bit_value(next, old_field(&next.name).map(|(_, value)| value))?, bits)?;
+        }
+        writer.finish()?;
+    }
+    ser.write_padding(new.reserve)
+}
@@ -0,0 +1,169 @@
+use jaguar::schema::Schema;
+use jaguar::{JaguarSchema, JaguarSerialize, SerError};
+use jaguar_migrate::{diff, Change, Incompatible, Migration};
+use std::num::{NonZeroU32, NonZeroU64};
+
+#[derive(JaguarSerialize, JaguarSchema)]
+struct PositionV1 {
+    market: [u8; 4],
+    amount: u32,
+}
+
+#[derive(JaguarSerialize, JaguarSchema)]
+#[jaguar(reserve = 4)]
+struct VaultV1 {
+    #[jaguar(bits = 1)]
+    frozen: bool,
+    #[jaguar(bits = 7)]
+    version: u8,
+    owner: [u8; 4],
+    balance: u32,
+    label: String,
+    positions: Vec<PositionV1>,
+    history: Vec<u64>,
+    legacy: i16,
+}
+
+#[derive(JaguarSerialize, JaguarSchema, Default)]
+struct PositionV2 {
+    amount: u64,
+    market: [u8; 4],
+    leverage: u8,
+}
+
+#[derive(JaguarSerialize, JaguarSchema, Default)]
+#[jaguar(reserve = 2)]
+struct VaultV2 {
+    #[jaguar(bits = 1)]
+    frozen: bool,
+    #[jaguar(bits = 3)]
+    version: u8,
+    #[jaguar(bits = 4)]
+    bump: u8,
+    owner: [u8; 4],
+    label: String,
+    balance: i64,
+    positions: Vec<PositionV2>,
+    #[jaguar(delta)]
+    history: Vec<u64>,
+    authority: [u8; 4],
+    fee: f64,
+}
+
+#[derive(JaguarSchema)]
+#[allow(dead_code)]
+struct LabelAsNumber {
+    label: u64,
+}
+
+#[derive(JaguarSchema)]
+#[allow(dead_code)]
+struct WithNonce {
+    label: u64,
+    nonce: NonZeroU64,
+}
+
+#[test]
+fn test_migrate_vault() {
+    let old = VaultV1 {
+        frozen: true,
+        version: 3,
+        owner: [1, 2, 3, 4],
+        balance: 1_000,
+        label: "main".into(),
+        positions: vec![PositionV1 { market: *b"SOL ", amount: 250 }],
+        history: vec![10, 20, 40],
+        legacy: -7,
+    };
+    let new = VaultV2 {
+        frozen: true,
+        version: 3,
+        owner: [1, 2, 3, 4],
+        label: "main".into(),
+        balance: 1_000,
+        positions: vec![PositionV2 { amount: 250, market: *b"SOL ", leverage: 0 }],
+        history: vec![10, 20, 40],
+        ..Default::default()
+    };
+
+    let migration = Migration::new(VaultV1::schema(), VaultV2::schema()).unwrap();
+    let mut data = jaguar::serialize(&old).unwrap();
+    data.extend_from_slice(&[0; 16]);
+    assert_eq!(migration.apply(&data).unwrap(), jaguar::serialize(&new).unwrap());
+
+    assert_eq!(
+        migration.changes(),
+        [
+            Change::Repacked { path: "version".into(), old: Some(7), new: Some(3) },
+            Change::Added { path: "bump".into() },
+            Change::Retyped {
+                path: "balance".into(),
+                old: Schema::Varint { bits: 32 },
+                new: Schema::SignedVarint { bits: 64 }
+            },
+            Change::Retyped {
+                path: "positions[].amount".into(),
+                old: Schema::Varint { bits: 32 },
+                new: Schema::Varint { bits: 64 }
+            },
+            Change::Added { path: "positions[].leverage".into() },
+            Change::Retyped {
+                path: "history".into(),
+                old: Schema::Vec(Box::new(Schema::Varint { bits: 64 })),
+                new: Schema::DeltaVec
+            },
+            Change::Added { path: "authority".into() },
+            Change::Added { path: "fee".into() },
+            Change::Removed { path: "legacy".into() },
+            Change::Reserve { path: String::new(), old: 4, new: 2 },
+        ]
+    );
+}
+
+#[test]
+fn test_migrate_out_of_range() {
+    let migration = Migration::new(VaultV1::schema(), VaultV2::schema()).unwrap();
+    let old = VaultV1 {
+        frozen: false,
+        version: 8,
+        owner: [0; 4],
+        balance: 0,
+        label: String::new(),
+        positions: Vec::new(),
+        history: Vec::new(),
+        legacy: 0,
+    };
+    assert_eq!(migration.apply(&jaguar::serialize(&old).unwrap()), Err(SerError::InvalidData));
+
+    let migration = Migration::new(Schema::Vec(Box::new(Schema::U8)), Schema::ByteArray(2)).unwrap();
+    assert_eq!(migration.apply(&jaguar::serialize(&vec![1u8, 2]).unwrap()).unwrap(), [1, 2]);
+    assert_eq!(migration.apply(&jaguar::serialize(&vec![1u8]).unwrap()), Err(SerError::InvalidLength));
+
+    let migration = Migration::new(Vec::<()>::schema(), Vec::<()>::schema()).unwrap();
+    assert_eq!(migration.apply(&[2]).unwrap(), [2]);
+    assert_eq!(migration.apply(&[0xFF, 0xFF, 0xFF, 0x0F]), Err(SerError::InvalidLength));
+}
+
+#[test]
+fn test_migrate_incompatible() {
+    assert!(Migration::new(VaultV1::schema(), VaultV1::schema()).unwrap().is_noop());
+    assert_eq!(
+        diff(&Schema::Tuple(vec![Schema::String, Schema::F32]), &Schema::Tuple(vec![Schema::String, Schema::U8])),
+        Err(Incompatible { path: "1".into(), old: Some(Box::new(Schema::F32)), new: Box::new(Schema::U8) })
+    );
+
+    let err = Migration::new(VaultV1::schema(), LabelAsNumber::schema()).unwrap_err();
+    assert_eq!(err.path, "label");
+    let err = diff(&Schema::ByteArray(4), &Schema::ByteArray(8)).unwrap_err();
+    assert_eq!(err.to_string(), "cannot migrate `the value` from ByteArray(4) to ByteArray(8)");
+
+    // a `NonZero*` field has no zero value to add it with
+    let err = Migration::new(LabelAsNumber::schema(), WithNonce::schema()).unwrap_err();
+    assert_eq!(err, Incompatible { path: "nonce".into(), old: None, new: Box::new(NonZeroU64::schema()) });
+    assert_eq!(err.to_string(), "cannot add `nonce` as NonZero(Varint { bits: 64 }), which has no zero value");
+    let migration = Migration::new(u32::schema(), NonZeroU32::schema()).unwrap();
+    assert_eq!(migration.apply(&[5]).unwrap(), [5]);
+    assert_eq!(migration.apply(&[0]), Err(SerError::InvalidData));
+    let migration = Migration::new(NonZeroU32::schema(), u64::schema()).unwrap();
+    assert_eq!(migration.apply(&[0]), Err(SerError::InvalidData));
+}
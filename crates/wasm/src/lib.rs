@@ -0,0 +1,441 @@
+//! WebAssembly bindings for Jaguar, so browser frontends produce the exact
+//! bytes the on-chain program expects.
+//!
+//! [`Writer`] and [`Reader`] expose the raw primitives (varints, strings,
+//! floats, ...). [`Codec`] encodes and decodes whole values described by a
+//! schema in the JSON form of [`schema::to_json`], which Rust code generates
+//! from any type deriving `JaguarSchema`.
+//!
+//! Values map to JavaScript like `jaguar-codegen`'s TypeScript output:
+//! integers of up to 32 bits are numbers, wider ones `BigInt`s, `[u8; N]` is
+//! a `Uint8Array`, sequences and tuples are arrays and structs are objects.
+//!
+//! --------
+//!
+//! ### Usage
+//!
+//! ```js
+//! import init, { Codec, Writer } from "jaguar-wasm";
+//!
+//! await init();
+//! const codec = new Codec(orderSchemaJson);
+//! const bytes = codec.encode({ id: 7n, price: 1.5, label: "bid" });
+//! const order = codec.decode(bytes);
+//!
+//! const writer = new Writer();
+//! writer.varint(300n);
+//! writer.string("memo");
+//! const raw = writer.finish();
+//! ```
+
+use jaguar::bits::BitField;
+use jaguar::schema::{Schema, StructSchema};
+use jaguar::{JaguarDeserialize, JaguarDeserializer, JaguarSerialize, JaguarSerializer, SerError};
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+pub use jaguar_json::schema;
+
+/// Writes Jaguar primitives into a growing buffer.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Writer {
+    ser: JaguarSerializer,
+}
+
+#[wasm_bindgen]
+impl Writer {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Writer {
+        Self::default()
+    }
+
+    pub fn u8(&mut self, value: u8) -> Result<(), JsError> {
+        Ok(self.ser.write_u8(value)?)
+    }
+
+    pub fn bool(&mut self, value: bool) -> Result<(), JsError> {
+        Ok(self.ser.write_bool(value)?)
+    }
+
+    /// Writes an unsigned varint, as used for `u16`, `u32` and `u64`.
+    pub fn varint(&mut self, value: u64) -> Result<(), JsError> {
+        Ok(self.ser.write_varint(value)?)
+    }
+
+    /// Writes a zigzag varint, as used for `i8` through `i64`.
+    #[wasm_bindgen(js_name = signedVarint)]
+    pub fn signed_varint(&mut self, value: i64) -> Result<(), JsError> {
+        Ok(self.ser.write_signed_varint(value)?)
+    }
+
+    pub fn f32(&mut self, value: f32) -> Result<(), JsError> {
+        Ok(self.ser.write_f32(value)?)
+    }
+
+    pub fn f64(&mut self, value: f64) -> Result<(), JsError> {
+        Ok(self.ser.write_f64(value)?)
+    }
+
+    /// Writes a length-prefixed UTF-8 string.
+    pub fn string(&mut self, value: &str) -> Result<(), JsError> {
+        Ok(self.ser.write_str(value)?)
+    }
+
+    /// Writes length-prefixed bytes.
+    pub fn bytes(&mut self, value: &[u8]) -> Result<(), JsError> {
+        Ok(self.ser.write_bytes(value)?)
+    }
+
+    /// Writes bytes as-is, e.g. a `[u8; 32]` public key.
+    pub fn raw(&mut self, value: &[u8]) -> Result<(), JsError> {
+        Ok(self.ser.write_raw(value)?)
+    }
+
+    /// Writes `len` zero bytes.
+    pub fn padding(&mut self, len: usize) -> Result<(), JsError> {
+        Ok(self.ser.write_padding(len)?)
+    }
+
+    /// Returns the written bytes.
+    pub fn finish(self) -> Vec<u8> {
+        self.ser.finish()
+    }
+}
+
+/// Reads Jaguar primitives from a copy of the input.
+#[wasm_bindgen]
+pub struct Reader {
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl Reader {
+    fn read<T>(&mut self, read: impl FnOnce(&mut JaguarDeserializer<'_>) -> Result<T, SerError>) -> Result<T, JsError> {
+        let mut de = JaguarDeserializer::new(&self.data[self.pos..]);
+        let value = read(&mut de)?;
+        self.pos += de.position();
+        Ok(value)
+    }
+}
+
+#[wasm_bindgen]
+impl Reader {
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: Vec<u8>) -> Reader {
+        Self { data, pos: 0 }
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the number of unread bytes.
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn u8(&mut self) -> Result<u8, JsError> {
+        self.read(|de| de.read_u8())
+    }
+
+    pub fn bool(&mut self) -> Result<bool, JsError> {
+        self.read(|de| de.read_bool())
+    }
+
+    pub fn varint(&mut self) -> Result<u64, JsError> {
+        self.read(|de| de.read_varint())
+    }
+
+    #[wasm_bindgen(js_name = signedVarint)]
+    pub fn signed_varint(&mut self) -> Result<i64, JsError> {
+        self.read(|de| de.read_signed_varint())
+    }
+
+    pub fn f32(&mut self) -> Result<f32, JsError> {
+        self.read(|de| de.read_f32())
+    }
+
+    pub fn f64(&mut self) -> Result<f64, JsError> {
+        self.read(|de| de.read_f64())
+    }
+
+    pub fn string(&mut self) -> Result<String, JsError> {
+        self.read(|de| de.read_str().map(String::from))
+    }
+
+    pub fn bytes(&mut self) -> Result<Vec<u8>, JsError> {
+        self.read(|de| de.read_bytes().map(<[u8]>::to_vec))
+    }
+
+    /// Reads `len` bytes written as-is.
+    pub fn raw(&mut self, len: usize) -> Result<Vec<u8>, JsError> {
+        self.read(|de| {
+            let bytes = de.peek_bytes(len)?.to_vec();
+            de.skip(len)?;
+            Ok(bytes)
+        })
+    }
+}
+
+/// Encodes and decodes values of a single schema-described type.
+#[wasm_bindgen]
+pub struct Codec {
+    schema: Schema,
+}
+
+impl Codec {
+    /// Creates a codec directly from a schema.
+    pub fn from_schema(schema: Schema) -> Self {
+        Self { schema }
+    }
+}
+
+#[wasm_bindgen]
+impl Codec {
+    /// Creates a codec from the JSON form of a schema.
+    #[wasm_bindgen(constructor)]
+    pub fn new(schema: &str) -> Result<Codec, JsError> {
+        schema::from_json(schema)
+            .map(Self::from_schema)
+            .map_err(|err| JsError::new(&err))
+    }
+
+    pub fn encode(&self, value: &JsValue) -> Result<Vec<u8>, JsError> {
+        let mut ser = JaguarSerializer::new();
+        encode_value(&self.schema, value, &mut ser)?;
+        Ok(ser.finish())
+    }
+
+    pub fn decode(&self, data: &[u8]) -> Result<JsValue, JsError> {
+        decode_value(&self.schema, &mut JaguarDeserializer::new(data))
+    }
+}
+
+fn expected(what: &str, value: &JsValue) -> JsError {
+    JsError::new(&format!("expected {}, got {:?}", what, value))
+}
+
+/// Converts a number or `BigInt` to an integer of `bits` bits.
+fn to_uint(value: &JsValue, bits: u32) -> Result<u128, JsError> {
+    let int = if let Some(number) = value.as_f64() {
+        if number < 0.0 || number.fract() != 0.0 || number > 9_007_199_254_740_991.0 {
+            return Err(expected("a non-negative safe integer", value));
+        }
+        number as u128
+    } else {
+        u128::try_from(value.clone()).map_err(|_| expected("an unsigned integer", value))?
+    };
+    if bits < 128 && int >> bits != 0 {
+        return Err(expected(&format!("a {}-bit integer", bits), value));
+    }
+    Ok(int)
+}
+
+/// Converts a number or `BigInt` to a signed integer of `bits` bits.
+fn to_int(value: &JsValue, bits: u32) -> Result<i128, JsError> {
+    let int = if let Some(number) = value.as_f64() {
+        if number.fract() != 0.0 || number.abs() > 9_007_199_254_740_991.0 {
+            return Err(expected("a safe integer", value));
+        }
+        number as i128
+    } else {
+        i128::try_from(value.clone()).map_err(|_| expected("a signed integer", value))?
+    };
+    if bits < 128 && (int >> (bits - 1) != 0 && int >> (bits - 1) != -1) {
+        return Err(expected(&format!("a {}-bit signed integer", bits), value));
+    }
+    Ok(int)
+}
+
+fn to_array(value: &JsValue) -> Result<&Array, JsError> {
+    value.dyn_ref::<Array>().ok_or_else(|| expected("an array", value))
+}
+
+fn encode_value(schema: &Schema, value: &JsValue, ser: &mut JaguarSerializer) -> Result<(), JsError> {
+    match schema {
+        Schema::U8 => ser.write_u8(to_uint(value, 8)? as u8)?,
+        Schema::Bool => ser.write_bool(value.as_bool().ok_or_else(|| expected("a boolean", value))?)?,
+        Schema::Varint { bits } => ser.write_varint(to_uint(value, *bits)? as u64)?,
+        Schema::SignedVarint { bits } => ser.write_signed_varint(to_int(value, *bits)? as i64)?,
+        Schema::U128 => to_uint(value, 128)?.serialize(ser)?,
+        Schema::I128 => to_int(value, 128)?.serialize(ser)?,
+        Schema::F32 => ser.write_f32(value.as_f64().ok_or_else(|| expected("a number", value))? as f32)?,
+        Schema::F64 => ser.write_f64(value.as_f64().ok_or_else(|| expected("a number", value))?)?,
+        Schema::String => ser.write_str(&value.as_string().ok_or_else(|| expected("a string", value))?)?,
+        Schema::ByteArray(len) => {
+            let bytes = value.dyn_ref::<Uint8Array>().ok_or_else(|| expected("a Uint8Array", value))?;
+            if bytes.length() as usize != *len {
+                return Err(expected(&format!("{} bytes", len), value));
+            }
+            ser.write_raw(&bytes.to_vec())?;
+        }
+        Schema::Array(len, inner) => {
+            let items = to_array(value)?;
+            if items.length() as usize != *len {
+                return Err(expected(&format!("{} elements", len), value));
+            }
+            encode_items(inner, items, ser)?;
+        }
+        Schema::Vec(inner) => encode_items(inner, to_array(value)?, ser)?,
+        Schema::NonZero(inner) => {
+            if value.is_falsy() {
+                return Err(expected("a non-zero integer", value));
+            }
+            encode_value(inner, value, ser)?;
+        }
+        Schema::DeltaVec => {
+            let items = to_array(value)?
+                .iter()
+                .map(|item| to_uint(&item, 64).map(|int| int as u64))
+                .collect::<Result<Vec<_>, _>>()?;
+            ser.write_u64_slice_delta(&items)?;
+        }
+        Schema::Tuple(schemas) => {
+            let items = to_array(value)?;
+            if items.length() as usize != schemas.len() {
+                return Err(expected(&format!("a {}-tuple", schemas.len()), value));
+            }
+            for (index, schema) in schemas.iter().enumerate() {
+                encode_value(schema, &items.get(index as u32), ser)?;
+            }
+        }
+        Schema::Struct(def) => encode_struct(def, value, ser)?,
+    }
+    Ok(())
+}
+
+fn encode_items(schema: &Schema, items: &Array, ser: &mut JaguarSerializer) -> Result<(), JsError> {
+    ser.write_varint(items.length() as u64)?;
+    for item in items.iter() {
+        encode_value(schema, &item, ser)?;
+    }
+    Ok(())
+}
+
+/// The raw bits of a `#[jaguar(bits = N)]` field.
+fn bit_value(schema: &Schema, value: &JsValue) -> Result<u64, JsError> {
+    match schema {
+        Schema::Bool => Ok(value.as_bool().ok_or_else(|| expected("a boolean", value))?.to_bits()),
+        Schema::U8 => Ok(to_uint(value, 8)? as u64),
+        Schema::Varint { bits } => Ok(to_uint(value, *bits)? as u64),
+        _ => Err(JsError::new("only booleans and unsigned integers can be bit-packed")),
+    }
+}
+
+fn encode_struct(def: &StructSchema, value: &JsValue, ser: &mut JaguarSerializer) -> Result<(), JsError> {
+    let field = |name: &str| Reflect::get(value, &JsValue::from_str(name)).map_err(|_| expected("an object", value));
+
+    let mut fields = def.fields.iter().peekable();
+    while let Some(first) = fields.next() {
+        let Some(bits) = first.bits else {
+            encode_value(&first.schema, &field(&first.name)?, ser)?;
+            continue;
+        };
+
+        let mut writer = ser.bit_writer();
+        writer.write_bits(bit_value(&first.schema, &field(&first.name)?)?, bits)?;
+        while let Some(next) = fields.next_if(|next| next.bits.is_some()) {
+            let bits = next.bits.unwrap_or_default();
+            writer.write_bits(bit_value(&next.schema, &field(&next.name)?)?, bits)?;
+        }
+        writer.finish()?;
+    }
+    Ok(ser.write_padding(def.reserve)?)
+}
+
+fn decode_value(schema: &Schema, de: &mut JaguarDeserializer<'_>) -> Result<JsValue, JsError> {
+    Ok(match schema {
+        Schema::U8 => u8::deserialize(de)?.into(),
+        Schema::Bool => bool::deserialize(de)?.into(),
+        Schema::Varint { bits: 16 } => u16::deserialize(de)?.into(),
+        Schema::Varint { bits: 32 } => u32::deserialize(de)?.into(),
+        Schema::Varint { .. } => u64::deserialize(de)?.into(),
+        Schema::SignedVarint { bits: 8 } => i8::deserialize(de)?.into(),
+        Schema::SignedVarint { bits: 16 } => i16::deserialize(de)?.into(),
+        Schema::SignedVarint { bits: 32 } => i32::deserialize(de)?.into(),
+        Schema::SignedVarint { .. } => i64::deserialize(de)?.into(),
+        Schema::U128 => u128::deserialize(de)?.into(),
+        Schema::I128 => i128::deserialize(de)?.into(),
+        Schema::F32 => f32::deserialize(de)?.into(),
+        Schema::F64 => f64::deserialize(de)?.into(),
+        Schema::String => String::deserialize(de)?.into(),
+        Schema::ByteArray(len) => {
+            let bytes = Uint8Array::from(de.peek_bytes(*len)?);
+            de.skip(*len)?;
+            bytes.into()
+        }
+        Schema::Array(len, inner) => {
+            if de.read_varint()? != *len as u64 {
+                return Err(SerError::InvalidLength.into());
+            }
+            decode_items(inner, *len as u64, de)?
+        }
+        Schema::Vec(inner) => {
+            let len = de.read_varint()?;
+            decode_items(inner, len, de)?
+        }
+        Schema::DeltaVec => de.read_u64_vec_delta()?.into_iter().map(JsValue::from).collect::<Array>().into(),
+        Schema::NonZero(inner) => {
+            let value = decode_value(inner, de)?;
+            if value.is_falsy() {
+                return Err(SerError::InvalidData.into());
+            }
+            value
+        }
+        Schema::Tuple(schemas) => schemas
+            .iter()
+            .map(|schema| decode_value(schema, de))
+            .collect::<Result<Array, _>>()?
+            .into(),
+        Schema::Struct(def) => decode_struct(def, de)?,
+    })
+}
+
+fn decode_items(schema: &Schema, len: u64, de: &mut JaguarDeserializer<'_>) -> Result<JsValue, JsError> {
+    let len = de.check_len(len, schema.is_zero_sized())?;
+    let items = Array::new();
+    for _ in 0..len {
+        items.push(&decode_value(schema, de)?);
+    }
+    Ok(items.into())
+}
+
+/// Converts the raw bits of a `#[jaguar(bits = N)]` field back.
+fn bit_field(schema: &Schema, bits: u64) -> Result<JsValue, JsError> {
+    Ok(match schema {
+        Schema::Bool => bool::from_bits(bits)?.into(),
+        Schema::U8 => u8::from_bits(bits)?.into(),
+        Schema::Varint { bits: 16 } => u16::from_bits(bits)?.into(),
+        Schema::Varint { bits: 32 } => u32::from_bits(bits)?.into(),
+        Schema::Varint { .. } => bits.into(),
+        _ => return Err(JsError::new("only booleans and unsigned integers can be bit-packed")),
+    })
+}
+
+fn decode_struct(def: &StructSchema, de: &mut JaguarDeserializer<'_>) -> Result<JsValue, JsError> {
+    let object = Object::new();
+    let set = |name: &str, value: JsValue| {
+        Reflect::set(&object, &JsValue::from_str(name), &value).map_err(|_| JsError::new("cannot set field"))
+    };
+
+    let mut fields = def.fields.iter().peekable();
+    while let Some(first) = fields.next() {
+        let Some(bits) = first.bits else {
+            set(&first.name, decode_value(&first.schema, de)?)?;
+            continue;
+        };
+
+        let mut reader = de.bit_reader();
+        set(&first.name, bit_field(&first.schema, reader.read_bits(bits)?)?)?;
+        while let Some(next) = fields.next_if(|next| next.bits.is_some()) {
+            let bits = next.bits.unwrap_or_default();
+            set(&next.name, bit_field(&next.schema, reader.read_bits(bits)?)?)?;
+        }
+        reader.finish()?;
+    }
+    de.skip(def.reserve)?;
+    Ok(object.into())
+}
@@ -0,0 +1,76 @@
+//! The parts of the bindings that do not touch JavaScript values, run natively.
+
+use jaguar::{JaguarSchema, JaguarSerialize, JaguarSerializer};
+use jaguar_wasm::{schema, Codec, Reader, Writer};
+
+// only the schema is used
+#[allow(dead_code)]
+#[derive(JaguarSchema)]
+#[jaguar(reserve = 8)]
+struct Order {
+    owner: [u8; 32],
+    #[jaguar(bits = 2)]
+    side: u8,
+    #[jaguar(bits = 1)]
+    post_only: bool,
+    price: f64,
+    levels: [i16; 3],
+    fills: Vec<(u64, String)>,
+    #[jaguar(delta)]
+    slots: Vec<u64>,
+    total: u128,
+}
+
+#[test]
+fn test_writer_matches_serializer() {
+    let mut writer = Writer::new();
+    writer.u8(7).unwrap();
+    writer.varint(u64::MAX).unwrap();
+    writer.signed_varint(-300).unwrap();
+    writer.bool(true).unwrap();
+    writer.f32(1.0).unwrap();
+    writer.f64(2.5).unwrap();
+    writer.string("memo").unwrap();
+    writer.bytes(&[1, 2]).unwrap();
+    writer.raw(&[9; 4]).unwrap();
+    writer.padding(2).unwrap();
+    let data = writer.finish();
+
+    let mut ser = JaguarSerializer::new();
+    7u8.serialize(&mut ser).unwrap();
+    u64::MAX.serialize(&mut ser).unwrap();
+    (-300i64).serialize(&mut ser).unwrap();
+    true.serialize(&mut ser).unwrap();
+    1.0f32.serialize(&mut ser).unwrap();
+    2.5f64.serialize(&mut ser).unwrap();
+    String::from("memo").serialize(&mut ser).unwrap();
+    ser.write_bytes(&[1, 2]).unwrap();
+    [9u8; 4].serialize(&mut ser).unwrap();
+    ser.write_padding(2).unwrap();
+    assert_eq!(data, ser.finish());
+
+    let mut reader = Reader::new(data);
+    assert_eq!(reader.u8().unwrap(), 7);
+    assert_eq!(reader.varint().unwrap(), u64::MAX);
+    assert_eq!(reader.signed_varint().unwrap(), -300);
+    assert!(reader.bool().unwrap());
+    assert_eq!(reader.f32().unwrap(), 1.0);
+    assert_eq!(reader.f64().unwrap(), 2.5);
+    assert_eq!(reader.string().unwrap(), "memo");
+    assert_eq!(reader.bytes().unwrap(), [1, 2]);
+    assert_eq!(reader.raw(4).unwrap(), [9; 4]);
+    assert_eq!(reader.remaining(), 2);
+}
+
+#[test]
+fn test_schema_json_roundtrip() {
+    let json = schema::to_json(&Order::schema());
+    assert!(json.starts_with(r#"{"fields":[{"name":"owner","schema":{"bytes":32}}"#));
+    assert!(json.contains(r#"{"bits":2,"name":"side","schema":"u8"}"#));
+    assert!(json.contains(r#"{"name":"fills","schema":{"vec":{"tuple":["u64","string"]}}}"#));
+    assert_eq!(schema::from_json(&json).unwrap(), Order::schema());
+    Codec::new(&json).unwrap();
+
+    assert_eq!(schema::from_json(r#""u24""#).unwrap_err(), "unknown type `u24`");
+    assert!(schema::from_json(r#"{"array": [3]}"#).is_err());
+}
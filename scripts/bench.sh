@@ -0,0 +1,12 @@
+#!/usr/bin/env bash
+
+# Builds the bench program, reports compute units per codec and shape, then
+# runs the off-chain criterion benches.
+
+set -ex
+
+cd "$(dirname "$0")/.."
+
+cargo build-sbf --manifest-path crates/bench/Cargo.toml
+SBF_OUT_DIR="$PWD/target/deploy" cargo test -p jaguar-bench --test cu -- --ignored --nocapture
+cargo bench -p jaguar-bench --bench codecs
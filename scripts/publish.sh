@@ -5,6 +5,7 @@ set -ex
 workspace_crates=(
     jaguar
     jaguar-derive
+    jaguar-anchor
 )
 
 for crate in "${workspace_crates[@]}"; do